@@ -0,0 +1,81 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use wasm_encoder::{
+    CodeSection, Function, IndirectNameMap, Instruction, NameMap, NameSection, ValType,
+};
+
+/// The number of locals, labels, and names used by the pathological inputs
+/// below, chosen to be well over 2^16.
+const COUNT: u32 = 500_000;
+
+fn locals(c: &mut Criterion) {
+    // Alternating types is the worst case for local compression since no two
+    // adjacent locals can be merged.
+    let types = (0..COUNT)
+        .map(|i| {
+            if i % 2 == 0 {
+                ValType::I32
+            } else {
+                ValType::I64
+            }
+        })
+        .collect::<Vec<_>>();
+    c.bench_function("encode/locals-alternating", |b| {
+        b.iter(|| Function::new_with_locals_types(types.iter().copied()))
+    });
+
+    let types = vec![ValType::I32; COUNT as usize];
+    c.bench_function("encode/locals-uniform", |b| {
+        b.iter(|| Function::new_with_locals_types(types.iter().copied()))
+    });
+}
+
+fn instructions(c: &mut Criterion) {
+    c.bench_function("encode/local-get-large-indices", |b| {
+        b.iter(|| {
+            let mut func = Function::new([(COUNT, ValType::I32)]);
+            for i in 0..COUNT {
+                func.instruction(&Instruction::LocalGet(i));
+                func.instruction(&Instruction::Drop);
+            }
+            func.instruction(&Instruction::End);
+            let mut code = CodeSection::new();
+            code.function(&func);
+            code
+        })
+    });
+
+    c.bench_function("encode/deep-branches", |b| {
+        b.iter(|| {
+            let mut func = Function::new([]);
+            for _ in 0..COUNT {
+                func.instruction(&Instruction::Block(wasm_encoder::BlockType::Empty));
+            }
+            for i in 0..COUNT {
+                func.instruction(&Instruction::Br(COUNT - i - 1));
+                func.instruction(&Instruction::End);
+            }
+            func.instruction(&Instruction::End);
+            func
+        })
+    });
+}
+
+fn names(c: &mut Criterion) {
+    let names = (0..COUNT).map(|i| format!("l{i}")).collect::<Vec<_>>();
+    c.bench_function("encode/local-names", |b| {
+        b.iter(|| {
+            let mut locals = NameMap::new();
+            for (i, name) in names.iter().enumerate() {
+                locals.append(i as u32, name);
+            }
+            let mut map = IndirectNameMap::new();
+            map.append(0, &locals);
+            let mut section = NameSection::new();
+            section.locals(&map);
+            section
+        })
+    });
+}
+
+criterion_group!(benches, locals, instructions, names);
+criterion_main!(benches);
@@ -129,6 +129,12 @@ impl Component {
     }
 
     /// Write a section to this component.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `section` is 4GiB or larger, which can't be encoded. The
+    /// sizes of component sections, including nested modules and components,
+    /// aren't checked as they're built.
     pub fn section(&mut self, section: &impl ComponentSection) -> &mut Self {
         self.bytes.push(section.id());
         section.encode(&mut self.bytes);
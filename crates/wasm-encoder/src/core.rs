@@ -10,6 +10,7 @@ mod linking;
 mod memories;
 mod names;
 mod producers;
+mod sorted;
 mod start;
 mod tables;
 mod tags;
@@ -27,6 +28,7 @@ pub use linking::*;
 pub use memories::*;
 pub use names::*;
 pub use producers::*;
+pub use sorted::*;
 pub use start::*;
 pub use tables::*;
 pub use tags::*;
@@ -141,6 +143,13 @@ impl Module {
     /// only defined once. While this is a potential footgun, it also allows you
     /// to use this crate to easily construct test cases for bad Wasm module
     /// encodings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `section` is 4GiB or larger, which can't be encoded. Only
+    /// code and data sections built with their `try_` methods, such as
+    /// [`CodeSection::try_function`](crate::CodeSection::try_function), are
+    /// guaranteed to fit.
     pub fn section(&mut self, section: &impl Section) -> &mut Self {
         self.bytes.push(section.id());
         section.encode(&mut self.bytes);
@@ -1,4 +1,7 @@
-use crate::{encode_section, Encode, HeapType, Section, SectionId, ValType};
+use crate::limits::{check_section, check_size};
+use crate::{
+    encode_section, encoding_size, Encode, HeapType, Section, SectionId, SizeLimitError, ValType,
+};
 use std::borrow::Cow;
 
 /// An encoder for the code section.
@@ -101,6 +104,26 @@ impl CodeSection {
         self.num_added += 1;
         self
     }
+
+    /// Like [`CodeSection::function`], but returns an error instead of
+    /// panicking if the function's body or this section would be too large
+    /// to encode.
+    ///
+    /// The section is left unchanged if an error is returned.
+    pub fn try_function(&mut self, func: &Function) -> Result<&mut Self, SizeLimitError> {
+        self.try_raw(&func.bytes)
+    }
+
+    /// Like [`CodeSection::raw`], but returns an error instead of panicking
+    /// if the function's body or this section would be too large to encode.
+    ///
+    /// The section is left unchanged if an error is returned.
+    pub fn try_raw(&mut self, data: &[u8]) -> Result<&mut Self, SizeLimitError> {
+        check_size(|| format!("function body {}", self.num_added), data.len())?;
+        let added = encoding_size(data.len() as u32) + data.len();
+        check_section("code", self.num_added, self.bytes.len(), added)?;
+        Ok(self.raw(data))
+    }
 }
 
 impl Encode for CodeSection {
@@ -912,7 +935,7 @@ impl Encode for Instruction<'_> {
                 l.encode(sink);
             }
             Instruction::BrOnNull(l) => {
-                sink.push(0xD4);
+                sink.push(0xD5);
                 l.encode(sink);
             }
             Instruction::BrOnNonNull(l) => {
@@ -1313,7 +1336,7 @@ impl Encode for Instruction<'_> {
                 sink.push(0xd2);
                 f.encode(sink);
             }
-            Instruction::RefAsNonNull => sink.push(0xD3),
+            Instruction::RefAsNonNull => sink.push(0xD4),
 
             // Bulk memory instructions.
             Instruction::TableInit { elem_index, table } => {
@@ -2891,4 +2914,97 @@ mod tests {
 
         assert_eq!(f1.bytes, f2.bytes)
     }
+
+    #[test]
+    fn many_locals_and_labels() {
+        use super::*;
+        use wasmparser::Operator;
+
+        // More locals and labels than fit in a 16-bit index to ensure these
+        // are encoded as full LEB128 indices.
+        const N: u32 = (1 << 16) + 10;
+
+        let mut f = Function::new_with_locals_types((0..N).map(|i| {
+            if i % 2 == 0 {
+                ValType::I32
+            } else {
+                ValType::I64
+            }
+        }));
+        for _ in 0..N {
+            f.instruction(&Instruction::Block(BlockType::Empty));
+        }
+        f.instruction(&Instruction::LocalGet(N - 1));
+        f.instruction(&Instruction::Drop);
+        f.instruction(&Instruction::Br(N - 1));
+        for _ in 0..N {
+            f.instruction(&Instruction::End);
+        }
+        f.instruction(&Instruction::End);
+
+        // A code section containing just this one function.
+        let mut bytes = vec![1];
+        f.encode(&mut bytes);
+        let body = wasmparser::CodeSectionReader::new(&bytes, 0)
+            .unwrap()
+            .into_iter()
+            .next()
+            .unwrap()
+            .unwrap();
+        let locals = body.get_locals_reader().unwrap();
+        assert_eq!(locals.get_count(), N);
+        let ops = body
+            .get_operators_reader()
+            .unwrap()
+            .into_iter()
+            .collect::<wasmparser::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(ops.len(), 2 * N as usize + 4);
+        assert!(matches!(
+            ops[N as usize],
+            Operator::LocalGet { local_index } if local_index == N - 1
+        ));
+        assert!(matches!(
+            ops[N as usize + 2],
+            Operator::Br { relative_depth } if relative_depth == N - 1
+        ));
+    }
+
+    #[test]
+    fn typed_function_references_validate() {
+        use super::*;
+        use crate::{CodeSection, FunctionSection, Module, RefType, TypeSection};
+
+        let ref_null_t = ValType::Ref(RefType {
+            nullable: true,
+            heap_type: HeapType::TypedFunc(0),
+        });
+        let mut types = TypeSection::new();
+        types.function([], []);
+        types.function([ref_null_t], []);
+        let mut funcs = FunctionSection::new();
+        funcs.function(1);
+        let mut f = Function::new([]);
+        f.instruction(&Instruction::Block(BlockType::Empty));
+        f.instruction(&Instruction::LocalGet(0));
+        f.instruction(&Instruction::BrOnNull(0));
+        f.instruction(&Instruction::CallRef(HeapType::TypedFunc(0)));
+        f.instruction(&Instruction::End);
+        f.instruction(&Instruction::LocalGet(0));
+        f.instruction(&Instruction::RefAsNonNull);
+        f.instruction(&Instruction::CallRef(HeapType::TypedFunc(0)));
+        f.instruction(&Instruction::End);
+        let mut code = CodeSection::new();
+        code.function(&f);
+
+        let mut module = Module::new();
+        module.section(&types).section(&funcs).section(&code);
+        let wasm = module.finish();
+
+        let mut validator = wasmparser::Validator::new_with_features(wasmparser::WasmFeatures {
+            function_references: true,
+            ..Default::default()
+        });
+        validator.validate_all(&wasm).unwrap();
+    }
 }
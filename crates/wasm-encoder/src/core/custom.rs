@@ -1,6 +1,9 @@
 use crate::{encoding_size, Encode, Section, SectionId};
 
 /// A custom section holding arbitrary data.
+///
+/// The name and data together must be smaller than 4GiB, or encoding this
+/// section panics.
 #[derive(Clone, Debug)]
 pub struct CustomSection<'a> {
     /// The name of this custom section.
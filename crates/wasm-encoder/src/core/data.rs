@@ -1,4 +1,5 @@
-use crate::{encode_section, encoding_size, ConstExpr, Encode, Section, SectionId};
+use crate::limits::{check_section, check_size};
+use crate::{encode_section, encoding_size, ConstExpr, Encode, Section, SectionId, SizeLimitError};
 
 /// An encoder for the data section.
 ///
@@ -18,6 +19,7 @@ use crate::{encode_section, encoding_size, ConstExpr, Encode, Section, SectionId
 ///     maximum: None,
 ///     memory64: false,
 ///     shared: false,
+///     page_size_log2: None,
 /// });
 ///
 /// let mut data = DataSection::new();
@@ -86,26 +88,7 @@ impl DataSection {
         D: IntoIterator<Item = u8>,
         D::IntoIter: ExactSizeIterator,
     {
-        match segment.mode {
-            DataSegmentMode::Passive => {
-                self.bytes.push(0x01);
-            }
-            DataSegmentMode::Active {
-                memory_index: 0,
-                offset,
-            } => {
-                self.bytes.push(0x00);
-                offset.encode(&mut self.bytes);
-            }
-            DataSegmentMode::Active {
-                memory_index,
-                offset,
-            } => {
-                self.bytes.push(0x02);
-                memory_index.encode(&mut self.bytes);
-                offset.encode(&mut self.bytes);
-            }
-        }
+        self.encode_mode(segment.mode);
 
         let data = segment.data.into_iter();
         data.len().encode(&mut self.bytes);
@@ -115,6 +98,35 @@ impl DataSection {
         self
     }
 
+    /// Like [`DataSection::segment`], but returns an error instead of
+    /// panicking if the segment's data or this section would be too large to
+    /// encode.
+    ///
+    /// The section is left unchanged if an error is returned.
+    pub fn try_segment<D>(&mut self, segment: DataSegment<D>) -> Result<&mut Self, SizeLimitError>
+    where
+        D: IntoIterator<Item = u8>,
+        D::IntoIter: ExactSizeIterator,
+    {
+        let data = segment.data.into_iter();
+        let len = data.len();
+        check_size(|| format!("data segment {}", self.num_added), len)?;
+
+        // Encode the segment's mode first to learn the full size of the
+        // segment, undoing that if the section would become too large.
+        let start = self.bytes.len();
+        self.encode_mode(segment.mode);
+        let added = self.bytes.len() - start + encoding_size(len as u32) + len;
+        if let Err(e) = check_section("data", self.num_added, start, added) {
+            self.bytes.truncate(start);
+            return Err(e);
+        }
+        len.encode(&mut self.bytes);
+        self.bytes.extend(data);
+        self.num_added += 1;
+        Ok(self)
+    }
+
     /// Define an active data segment.
     pub fn active<D>(&mut self, memory_index: u32, offset: &ConstExpr, data: D) -> &mut Self
     where
@@ -144,6 +156,29 @@ impl DataSection {
         })
     }
 
+    fn encode_mode(&mut self, mode: DataSegmentMode) {
+        match mode {
+            DataSegmentMode::Passive => {
+                self.bytes.push(0x01);
+            }
+            DataSegmentMode::Active {
+                memory_index: 0,
+                offset,
+            } => {
+                self.bytes.push(0x00);
+                offset.encode(&mut self.bytes);
+            }
+            DataSegmentMode::Active {
+                memory_index,
+                offset,
+            } => {
+                self.bytes.push(0x02);
+                memory_index.encode(&mut self.bytes);
+                offset.encode(&mut self.bytes);
+            }
+        }
+    }
+
     /// Copy an already-encoded data segment into this data section.
     pub fn raw(&mut self, already_encoded_data_segment: &[u8]) -> &mut Self {
         self.bytes.extend_from_slice(already_encoded_data_segment);
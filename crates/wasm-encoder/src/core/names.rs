@@ -42,6 +42,7 @@ enum Subsection {
     Global = 7,
     Element = 8,
     Data = 9,
+    Tag = 11,
 }
 
 impl NameSection {
@@ -145,6 +146,14 @@ impl NameSection {
         names.encode(&mut self.bytes);
     }
 
+    /// Appends a subsection for the names of all tags in this wasm module.
+    ///
+    /// This section should come after the data name subsection (if present).
+    pub fn tags(&mut self, names: &NameMap) {
+        self.subsection_header(Subsection::Tag, names.size());
+        names.encode(&mut self.bytes);
+    }
+
     fn subsection_header(&mut self, id: Subsection, len: usize) {
         self.bytes.push(id as u8);
         len.encode(&mut self.bytes);
@@ -0,0 +1,255 @@
+use crate::{EntityType, ExportKind, ExportSection, ImportSection};
+use std::collections::btree_map::{BTreeMap, Entry};
+use std::fmt;
+
+/// An error returned when an export or import is added to [`SortedExports`]
+/// or [`SortedImports`] with a name that was already added.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DuplicateName {
+    /// An export with this name was already added.
+    Export(String),
+    /// An import with this module and field name was already added.
+    Import(String, String),
+}
+
+impl fmt::Display for DuplicateName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateName::Export(name) => write!(f, "duplicate export name `{name}`"),
+            DuplicateName::Import(module, field) => {
+                write!(f, "duplicate import `{module}` `{field}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DuplicateName {}
+
+/// A set of exports which is emitted as an export section in a canonical
+/// order, sorted by name.
+///
+/// Unlike [`ExportSection`], which emits exports in the order they're
+/// defined, this produces the same section regardless of the order in which
+/// exports were added. Duplicate names are reported as they're added rather
+/// than later when the module is validated.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_encoder::{ExportKind, Module, SortedExports};
+///
+/// let mut exports = SortedExports::new();
+/// exports.export("foo", ExportKind::Memory, 0).unwrap();
+/// exports.export("bar", ExportKind::Memory, 0).unwrap();
+/// assert!(exports.export("foo", ExportKind::Memory, 0).is_err());
+///
+/// let mut module = Module::new();
+/// module.section(&exports.section());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SortedExports {
+    exports: BTreeMap<String, (ExportKind, u32)>,
+}
+
+impl SortedExports {
+    /// Create a new, empty set of exports.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of exports in the set.
+    pub fn len(&self) -> u32 {
+        self.exports.len() as u32
+    }
+
+    /// Determines if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.exports.is_empty()
+    }
+
+    /// Add an export to the set.
+    ///
+    /// Returns an error, leaving the set unchanged, if an export named
+    /// `name` was already added.
+    pub fn export(
+        &mut self,
+        name: &str,
+        kind: ExportKind,
+        index: u32,
+    ) -> Result<&mut Self, DuplicateName> {
+        match self.exports.entry(name.to_string()) {
+            Entry::Occupied(_) => return Err(DuplicateName::Export(name.to_string())),
+            Entry::Vacant(v) => {
+                v.insert((kind, index));
+            }
+        }
+        Ok(self)
+    }
+
+    /// Encode the exports as an export section, sorted by name.
+    pub fn section(&self) -> ExportSection {
+        let mut section = ExportSection::new();
+        for (name, (kind, index)) in self.exports.iter() {
+            section.export(name, *kind, *index);
+        }
+        section
+    }
+}
+
+/// A set of imports which is emitted as an import section in a canonical
+/// order, sorted by module name and then by field name.
+///
+/// Unlike [`ImportSection`], which emits imports in the order they're
+/// defined, this produces the same section regardless of the order in which
+/// imports were added. Duplicate names are reported as they're added.
+///
+/// Note that since imports are the first items of their index spaces,
+/// reordering them changes their indices. Use [`SortedImports::index`] once
+/// all imports have been added to find the final index of an import.
+///
+/// # Example
+///
+/// ```rust
+/// use wasm_encoder::{EntityType, Module, SortedImports};
+///
+/// let mut imports = SortedImports::new();
+/// imports.import("env", "g", EntityType::Function(0)).unwrap();
+/// imports.import("env", "f", EntityType::Function(0)).unwrap();
+/// assert_eq!(imports.index("env", "f"), Some(0));
+/// assert_eq!(imports.index("env", "g"), Some(1));
+///
+/// let mut module = Module::new();
+/// module.section(&imports.section());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SortedImports {
+    imports: BTreeMap<(String, String), EntityType>,
+}
+
+impl SortedImports {
+    /// Create a new, empty set of imports.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of imports in the set.
+    pub fn len(&self) -> u32 {
+        self.imports.len() as u32
+    }
+
+    /// Determines if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.imports.is_empty()
+    }
+
+    /// Add an import to the set.
+    ///
+    /// Returns an error, leaving the set unchanged, if an import with the
+    /// same `module` and `field` names was already added.
+    pub fn import(
+        &mut self,
+        module: &str,
+        field: &str,
+        ty: impl Into<EntityType>,
+    ) -> Result<&mut Self, DuplicateName> {
+        match self.imports.entry((module.to_string(), field.to_string())) {
+            Entry::Occupied(_) => {
+                return Err(DuplicateName::Import(module.to_string(), field.to_string()))
+            }
+            Entry::Vacant(v) => {
+                v.insert(ty.into());
+            }
+        }
+        Ok(self)
+    }
+
+    /// Returns the index of an import within the index space of its kind
+    /// once the imports are sorted, or `None` if there's no such import.
+    ///
+    /// Adding more imports may change the index of an import, so this should
+    /// only be used once all imports have been added.
+    pub fn index(&self, module: &str, field: &str) -> Option<u32> {
+        let mut counts = [0; 5];
+        for ((m, f), ty) in self.imports.iter() {
+            let count = &mut counts[kind(ty)];
+            if m == module && f == field {
+                return Some(*count);
+            }
+            *count += 1;
+        }
+        None
+    }
+
+    /// Encode the imports as an import section, sorted by module name and
+    /// then by field name.
+    pub fn section(&self) -> ImportSection {
+        let mut section = ImportSection::new();
+        for ((module, field), ty) in self.imports.iter() {
+            section.import(module, field, *ty);
+        }
+        section
+    }
+}
+
+/// Returns a distinct number for each kind of entity, which have separate
+/// index spaces.
+fn kind(ty: &EntityType) -> usize {
+    match ty {
+        EntityType::Function(_) => 0,
+        EntityType::Table(_) => 1,
+        EntityType::Memory(_) => 2,
+        EntityType::Global(_) => 3,
+        EntityType::Tag(_) => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MemoryType, Module};
+
+    #[test]
+    fn exports_are_sorted() {
+        let mut a = SortedExports::new();
+        a.export("b", ExportKind::Func, 1).unwrap();
+        a.export("a", ExportKind::Func, 0).unwrap();
+        let mut b = SortedExports::new();
+        b.export("a", ExportKind::Func, 0).unwrap();
+        b.export("b", ExportKind::Func, 1).unwrap();
+
+        let mut module_a = Module::new();
+        module_a.section(&a.section());
+        let mut module_b = Module::new();
+        module_b.section(&b.section());
+        assert_eq!(module_a.finish(), module_b.finish());
+
+        assert_eq!(
+            a.export("a", ExportKind::Table, 0).unwrap_err(),
+            DuplicateName::Export("a".to_string())
+        );
+        assert_eq!(a.len(), 2);
+    }
+
+    #[test]
+    fn import_indices_follow_sorted_order() {
+        let memory = MemoryType {
+            minimum: 1,
+            maximum: None,
+            memory64: false,
+            shared: false,
+            page_size_log2: None,
+        };
+        let mut imports = SortedImports::new();
+        imports.import("b", "f", EntityType::Function(0)).unwrap();
+        imports.import("a", "m", memory).unwrap();
+        imports.import("a", "g", EntityType::Function(0)).unwrap();
+        imports.import("a", "h", EntityType::Function(0)).unwrap();
+        assert!(imports.import("a", "m", memory).is_err());
+
+        assert_eq!(imports.index("a", "g"), Some(0));
+        assert_eq!(imports.index("a", "h"), Some(1));
+        assert_eq!(imports.index("a", "m"), Some(0));
+        assert_eq!(imports.index("b", "f"), Some(2));
+        assert_eq!(imports.index("b", "g"), None);
+    }
+}
@@ -82,9 +82,9 @@ impl Encode for RefType {
         }
 
         if self.nullable {
-            sink.push(0x6C);
+            sink.push(0x63);
         } else {
-            sink.push(0x6B);
+            sink.push(0x64);
         }
         self.heap_type.encode(sink);
     }
@@ -105,6 +105,30 @@ pub enum HeapType {
     Extern,
     /// A reference to a particular index in a table.
     TypedFunc(u32),
+    /// The top type of internal references, part of the GC proposal. When
+    /// nullable, equivalent to `anyref`.
+    Any,
+    /// References which can be compared for equality, part of the GC
+    /// proposal. When nullable, equivalent to `eqref`.
+    Eq,
+    /// A reference to any struct, part of the GC proposal. When nullable,
+    /// equivalent to `structref`.
+    Struct,
+    /// A reference to any array, part of the GC proposal. When nullable,
+    /// equivalent to `arrayref`.
+    Array,
+    /// An unboxed 31-bit integer, part of the GC proposal. When nullable,
+    /// equivalent to `i31ref`.
+    I31,
+    /// The bottom type of the `any` hierarchy, part of the GC proposal. When
+    /// nullable, equivalent to `nullref`.
+    None,
+    /// The bottom type of the `func` hierarchy, part of the GC proposal. When
+    /// nullable, equivalent to `nullfuncref`.
+    NoFunc,
+    /// The bottom type of the `extern` hierarchy, part of the GC proposal.
+    /// When nullable, equivalent to `nullexternref`.
+    NoExtern,
 }
 
 impl Encode for HeapType {
@@ -115,6 +139,14 @@ impl Encode for HeapType {
             // Note that this is encoded as a signed type rather than unsigned
             // as it's decoded as an s33
             HeapType::TypedFunc(i) => i64::from(*i).encode(sink),
+            HeapType::Any => sink.push(0x6E),
+            HeapType::Eq => sink.push(0x6D),
+            HeapType::Struct => sink.push(0x6B),
+            HeapType::Array => sink.push(0x6A),
+            HeapType::I31 => sink.push(0x6C),
+            HeapType::None => sink.push(0x71),
+            HeapType::NoFunc => sink.push(0x73),
+            HeapType::NoExtern => sink.push(0x72),
         }
     }
 }
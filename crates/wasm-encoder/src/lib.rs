@@ -72,11 +72,15 @@
 
 mod component;
 mod core;
+mod limits;
 mod raw;
+mod reserve;
 
 pub use self::component::*;
 pub use self::core::*;
+pub use self::limits::*;
 pub use self::raw::*;
+pub use self::reserve::*;
 
 /// Implemented by types that can be encoded into a byte sink.
 pub trait Encode {
@@ -0,0 +1,156 @@
+use crate::encoding_size;
+use std::fmt;
+
+/// An error returned when an item is too large to be encoded.
+///
+/// The binary format encodes the sizes of sections, function bodies, and data
+/// segments as `u32`s, so none of them can be 4GiB or larger. Methods such as
+/// [`CodeSection::try_function`](crate::CodeSection::try_function) and
+/// [`DataSection::try_segment`](crate::DataSection::try_segment) return this
+/// error, leaving the section unchanged, rather than panicking like their
+/// unchecked counterparts.
+///
+/// Only the code and data sections, which hold the bulk of most modules, have
+/// these checked methods. Other sections, custom sections and the sections of
+/// components aren't checked, and [`Module::section`](crate::Module::section)
+/// and [`Component::section`](crate::Component::section) panic if they're
+/// given a section too large to encode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizeLimitError {
+    item: String,
+    size: u64,
+}
+
+impl SizeLimitError {
+    fn new(item: String, size: u64) -> SizeLimitError {
+        SizeLimitError { item, size }
+    }
+
+    /// A description of the item which was too large, such as `data
+    /// segment 3` or `code section`.
+    pub fn item(&self) -> &str {
+        &self.item
+    }
+
+    /// The size, in bytes or items, that the item would have had.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl fmt::Display for SizeLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is too large to encode: its size of {} exceeds the limit of {}",
+            self.item,
+            self.size,
+            u32::MAX
+        )
+    }
+}
+
+impl std::error::Error for SizeLimitError {}
+
+/// Checks that an item of `size` bytes can be encoded with a `u32` size.
+pub(crate) fn check_size(item: impl FnOnce() -> String, size: usize) -> Result<(), SizeLimitError> {
+    if size > u32::MAX as usize {
+        return Err(SizeLimitError::new(item(), size as u64));
+    }
+    Ok(())
+}
+
+/// Checks that one more item can be added to a section which currently holds
+/// `count` items encoded in `len` bytes, with `added` more bytes for the new
+/// item.
+pub(crate) fn check_section(
+    section: &str,
+    count: u32,
+    len: usize,
+    added: usize,
+) -> Result<(), SizeLimitError> {
+    let count = match count.checked_add(1) {
+        Some(count) => count,
+        None => {
+            return Err(SizeLimitError::new(
+                format!("{section} section item count"),
+                u64::from(count) + 1,
+            ))
+        }
+    };
+    let size = (encoding_size(count) as u64)
+        .saturating_add(len as u64)
+        .saturating_add(added as u64);
+    if size > u64::from(u32::MAX) {
+        return Err(SizeLimitError::new(format!("{section} section"), size));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CodeSection, ConstExpr, DataSection, Encode, Function};
+
+    #[test]
+    fn checked_builders_match_unchecked() {
+        let offset = ConstExpr::i32_const(8);
+        let mut a = DataSection::new();
+        a.passive([1, 2, 3]).active(1, &offset, [4]);
+        let mut b = DataSection::new();
+        b.try_segment(crate::DataSegment {
+            mode: crate::DataSegmentMode::Passive,
+            data: [1, 2, 3],
+        })
+        .unwrap()
+        .try_segment(crate::DataSegment {
+            mode: crate::DataSegmentMode::Active {
+                memory_index: 1,
+                offset: &offset,
+            },
+            data: [4],
+        })
+        .unwrap();
+        let (mut a_bytes, mut b_bytes) = (Vec::new(), Vec::new());
+        a.encode(&mut a_bytes);
+        b.encode(&mut b_bytes);
+        assert_eq!(a_bytes, b_bytes);
+
+        let func = Function::new([]);
+        let mut a = CodeSection::new();
+        a.function(&func).raw(&[0x00, 0x0b]);
+        let mut b = CodeSection::new();
+        b.try_function(&func)
+            .unwrap()
+            .try_raw(&[0x00, 0x0b])
+            .unwrap();
+        let (mut a_bytes, mut b_bytes) = (Vec::new(), Vec::new());
+        a.encode(&mut a_bytes);
+        b.encode(&mut b_bytes);
+        assert_eq!(a_bytes, b_bytes);
+    }
+
+    #[test]
+    fn limits() {
+        assert!(check_size(|| unreachable!(), u32::MAX as usize).is_ok());
+        assert!(check_section("code", 0, 0, u32::MAX as usize - 1).is_ok());
+
+        let err = check_section("code", 10, u32::MAX as usize - 10, 10).unwrap_err();
+        assert_eq!(err.item(), "code section");
+        assert_eq!(err.size(), u64::from(u32::MAX) + 1);
+
+        let err = check_section("data", u32::MAX, 0, 0).unwrap_err();
+        assert_eq!(err.item(), "data section item count");
+
+        if usize::BITS > 32 {
+            let err = check_size(|| "function body 3".to_string(), 1 << 32).unwrap_err();
+            assert_eq!(err.item(), "function body 3");
+            assert_eq!(err.size(), 1 << 32);
+            assert_eq!(
+                err.to_string(),
+                "function body 3 is too large to encode: \
+                 its size of 4294967296 exceeds the limit of 4294967295"
+            );
+        }
+    }
+}
@@ -0,0 +1,119 @@
+/// A fixed-width placeholder for a `u32` in a byte sink, which is patched once
+/// its value is known.
+///
+/// Section sizes, function body sizes, and item counts are normally encoded
+/// with the fewest LEB128 bytes possible, which requires knowing them before
+/// the content that follows is written. A `Reservation` instead writes a
+/// padded 5-byte LEB128 encoding, which is wide enough for any `u32`. This
+/// lets producers that stream their output, rather than buffering whole
+/// sections, fill in sizes and counts after the fact.
+///
+/// # Example
+///
+/// ```
+/// use wasm_encoder::{Module, Reservation, SectionId};
+///
+/// let mut bytes = Module::HEADER.to_vec();
+/// bytes.push(SectionId::Type.into());
+/// let size = Reservation::new(&mut bytes);
+/// let count = Reservation::new(&mut bytes);
+///
+/// // Stream out the types, counting them as they are written.
+/// let mut n = 0;
+/// for _ in 0..3 {
+///     bytes.extend([0x60, 0x00, 0x00]); // (func)
+///     n += 1;
+/// }
+///
+/// count.patch(&mut bytes, n);
+/// size.patch_len(&mut bytes);
+/// assert!(wasmparser::validate(&bytes).is_ok());
+/// ```
+#[derive(Clone, Copy, Debug)]
+#[must_use = "a reservation must be patched to produce a valid encoding"]
+pub struct Reservation {
+    offset: usize,
+}
+
+impl Reservation {
+    /// The number of bytes reserved for the placeholder.
+    pub const WIDTH: usize = 5;
+
+    /// Reserves a placeholder at the end of `sink`.
+    pub fn new(sink: &mut Vec<u8>) -> Reservation {
+        let offset = sink.len();
+        sink.extend([0x80, 0x80, 0x80, 0x80, 0x00]);
+        Reservation { offset }
+    }
+
+    /// The offset in the sink at which the placeholder starts.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The offset in the sink just past the end of the placeholder.
+    pub fn end(&self) -> usize {
+        self.offset + Self::WIDTH
+    }
+
+    /// Overwrites the placeholder in `sink` with the padded LEB128 encoding
+    /// of `value`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sink` is too short to contain this reservation.
+    pub fn patch(self, sink: &mut [u8], value: u32) {
+        let bytes = &mut sink[self.offset..self.end()];
+        let mut value = value;
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if i < Self::WIDTH - 1 {
+                *byte |= 0x80;
+            }
+        }
+    }
+
+    /// Overwrites the placeholder in `sink` with the number of bytes that
+    /// follow it, which is what section and function body sizes encode.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sink` is too short to contain this reservation, or if more
+    /// than `u32::MAX` bytes follow it.
+    pub fn patch_len(self, sink: &mut [u8]) {
+        let len = sink.len() - self.end();
+        let len = u32::try_from(len).expect("length doesn't fit in a `u32`");
+        self.patch(sink, len);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patch_round_trips() {
+        for value in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, u32::MAX] {
+            let mut sink = vec![0xff];
+            let r = Reservation::new(&mut sink);
+            assert_eq!(r.offset(), 1);
+            assert_eq!(sink.len(), 1 + Reservation::WIDTH);
+            r.patch(&mut sink, value);
+
+            let mut slice = &sink[1..];
+            let read = leb128::read::unsigned(&mut slice).unwrap();
+            assert_eq!(read, u64::from(value));
+            assert!(slice.is_empty());
+        }
+    }
+
+    #[test]
+    fn patch_len_counts_following_bytes() {
+        let mut sink = Vec::new();
+        let r = Reservation::new(&mut sink);
+        sink.extend([1, 2, 3]);
+        r.patch_len(&mut sink);
+        assert_eq!(sink, [0x83, 0x80, 0x80, 0x80, 0x00, 1, 2, 3]);
+    }
+}
@@ -563,6 +563,7 @@ impl<'a> ModuleNames<'a> {
                 wasmparser::Name::Global(m) => section.globals(&name_map(&m)?),
                 wasmparser::Name::Element(m) => section.elements(&name_map(&m)?),
                 wasmparser::Name::Data(m) => section.types(&name_map(&m)?),
+                wasmparser::Name::Tag(m) => section.tags(&name_map(&m)?),
                 wasmparser::Name::Unknown { .. } => {} // wasm-encoder doesn't support it
             }
         }
@@ -635,6 +636,7 @@ impl<'a> ComponentNames<'a> {
                 wasmparser::ComponentName::CoreGlobals(m) => section.core_globals(&name_map(&m)?),
                 wasmparser::ComponentName::CoreMemories(m) => section.core_memories(&name_map(&m)?),
                 wasmparser::ComponentName::CoreTables(m) => section.core_tables(&name_map(&m)?),
+                wasmparser::ComponentName::CoreTags(m) => section.core_tags(&name_map(m)?),
                 wasmparser::ComponentName::CoreModules(m) => section.core_modules(&name_map(&m)?),
                 wasmparser::ComponentName::CoreInstances(m) => {
                     section.core_instances(&name_map(&m)?)
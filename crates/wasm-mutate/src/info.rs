@@ -30,6 +30,9 @@ pub struct ModuleInfo<'a> {
     pub code: Option<usize>,
     pub start: Option<usize>,
 
+    /// The offset in the input Wasm of the contents of the code section.
+    pub code_section_offset: usize,
+
     pub exports_count: u32,
     elements_count: u32,
     data_segments_count: u32,
@@ -50,6 +53,8 @@ pub struct ModuleInfo<'a> {
     // function idx to type idx
     pub function_map: Vec<u32>,
     pub global_types: Vec<PrimitiveTypeInfo>,
+    // whether each global, in the same order as `global_types`, is mutable
+    pub global_mutability: Vec<bool>,
     pub table_elem_types: Vec<PrimitiveTypeInfo>,
     pub memory_types: Vec<wasmparser::MemoryType>,
 
@@ -80,6 +85,7 @@ impl<'a> ModuleInfo<'a> {
                     size: _,
                 } => {
                     info.code = Some(info.raw_sections.len());
+                    info.code_section_offset = range.start;
                     info.section(SectionId::Code.into(), range.clone(), input_wasm);
                     parser.skip_section();
                     // update slice, bypass the section
@@ -109,6 +115,7 @@ impl<'a> ModuleInfo<'a> {
                                 info.imported_functions_count += 1;
                             }
                             wasmparser::TypeRef::Global(ty) => {
+                                info.global_mutability.push(ty.mutable);
                                 let ty = PrimitiveTypeInfo::try_from(ty.content_type).unwrap();
                                 info.global_types.push(ty);
                                 info.imported_globals_count += 1;
@@ -164,7 +171,7 @@ impl<'a> ModuleInfo<'a> {
 
                     for ty in reader {
                         let ty = ty?;
-                        // We only need the type of the global, not necessarily if is mutable or not
+                        info.global_mutability.push(ty.ty.mutable);
                         let ty = PrimitiveTypeInfo::try_from(ty.ty.content_type).unwrap();
                         info.global_types.push(ty);
                     }
@@ -13,22 +13,27 @@ mod error;
 mod info;
 mod module;
 mod mutators;
+mod stats;
 
 pub use error::*;
+pub use stats::{MutatorStats, Stats};
 
 use crate::mutators::{
-    add_function::AddFunctionMutator, add_type::AddTypeMutator, codemotion::CodemotionMutator,
-    custom::AddCustomSectionMutator, custom::CustomSectionMutator,
+    add_function::AddFunctionMutator, add_type::AddTypeMutator,
+    branch_retarget::BranchRetargetMutator, code_injection::CodeInjectionMutator,
+    codemotion::CodemotionMutator, custom::AddCustomSectionMutator, custom::CustomSectionMutator,
     custom::ReorderCustomSectionMutator, function_body_unreachable::FunctionBodyUnreachable,
-    modify_const_exprs::ConstExpressionMutator, modify_data::ModifyDataMutator,
-    peephole::PeepholeMutator, remove_export::RemoveExportMutator, remove_item::RemoveItemMutator,
-    remove_section::RemoveSection, rename_export::RenameExportMutator, snip_function::SnipMutator,
-    Item,
+    memarg::MemArgMutator, modify_const_exprs::ConstExpressionMutator,
+    modify_data::ModifyDataMutator, peephole::PeepholeMutator, remove_export::RemoveExportMutator,
+    remove_item::RemoveItemMutator, remove_section::RemoveSection,
+    rename_export::RenameExportMutator, return_value::ReturnValueMutator,
+    snip_function::SnipMutator, types::DuplicateTypeMutator, types::PermuteTypesMutator, Item,
 };
 use info::ModuleInfo;
 use mutators::Mutator;
-use rand::{rngs::SmallRng, Rng, SeedableRng};
-use std::sync::Arc;
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
 
 #[cfg(feature = "clap")]
 use clap::Parser;
@@ -110,11 +115,27 @@ pub struct WasmMutate<'wasm> {
     #[cfg_attr(feature = "clap", clap(long))]
     reduce: bool,
 
+    /// Choose which function and operator to mutate by hashing them with the
+    /// seed instead of drawing them from the RNG. This keeps the same seed
+    /// mutating the same logical site across small edits to the rest of the
+    /// Wasm module.
+    #[cfg_attr(feature = "clap", clap(long))]
+    stable_site_selection: bool,
+
     // Note: this is only exposed via the programmatic interface, not via the
     // CLI.
     #[cfg_attr(feature = "clap", clap(skip = None))]
     raw_mutate_func: Option<Arc<dyn Fn(&mut Vec<u8>, usize) -> Result<()>>>,
 
+    // Note: these are only exposed via the programmatic interface, not via the
+    // CLI.
+    #[cfg_attr(feature = "clap", clap(skip = None))]
+    restrict_functions: Option<Vec<u32>>,
+    #[cfg_attr(feature = "clap", clap(skip = None))]
+    restrict_range: Option<Range<usize>>,
+    #[cfg_attr(feature = "clap", clap(skip = None))]
+    stats: Option<Arc<Mutex<Stats>>>,
+
     #[cfg_attr(feature = "clap", clap(skip = None))]
     rng: Option<SmallRng>,
 
@@ -129,7 +150,11 @@ impl Default for WasmMutate<'_> {
             seed,
             preserve_semantics: false,
             reduce: false,
+            stable_site_selection: false,
             raw_mutate_func: None,
+            restrict_functions: None,
+            restrict_range: None,
+            stats: None,
             fuel: u64::MAX,
             rng: None,
             info: None,
@@ -147,6 +172,11 @@ impl<'wasm> WasmMutate<'wasm> {
         self
     }
 
+    /// Get the RNG seed that has been configured with [`WasmMutate::seed`].
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
     /// Configure whether we will only perform semantics-preserving
     /// transformations on the Wasm module.
     pub fn preserve_semantics(&mut self, preserve_semantics: bool) -> &mut Self {
@@ -170,6 +200,23 @@ impl<'wasm> WasmMutate<'wasm> {
         self
     }
 
+    /// Configure whether mutation sites are chosen by hashing instead of at
+    /// random.
+    ///
+    /// By default the function and operator to mutate are drawn from the RNG,
+    /// so adding or removing an unrelated function changes which site a given
+    /// seed selects. With stable site selection every candidate function is
+    /// hashed along with the seed and the contents of its body, leaving out
+    /// the function indices in calls and `ref.func` instructions, every
+    /// candidate operator is hashed along with its function's hash and its
+    /// position in the body, and the candidate with the smallest hash is
+    /// chosen. Replaying a seed against a slightly different module then
+    /// tends to mutate the same logical site as before.
+    pub fn stable_site_selection(&mut self, stable: bool) -> &mut Self {
+        self.stable_site_selection = stable;
+        self
+    }
+
     /// Set a custom raw mutation function.
     ///
     /// This is used when we need some underlying raw bytes, for example when
@@ -190,6 +237,135 @@ impl<'wasm> WasmMutate<'wasm> {
         self
     }
 
+    /// Restrict mutations to the bodies of the given functions.
+    ///
+    /// Function indices are in the module's function index space, which
+    /// includes imported functions. Once a restriction is configured only
+    /// mutators which change function bodies are used, so all mutations are
+    /// focused on the functions of interest. This can be combined with
+    /// [`WasmMutate::restrict_to_range`], in which case both restrictions
+    /// apply.
+    pub fn restrict_to_functions(&mut self, functions: &[u32]) -> &mut Self {
+        self.restrict_functions = Some(functions.to_vec());
+        self
+    }
+
+    /// Restrict mutations to the bodies of functions which overlap the given
+    /// byte range of the input Wasm.
+    ///
+    /// Like [`WasmMutate::restrict_to_functions`] only mutators which change
+    /// function bodies are used once a restriction is configured.
+    pub fn restrict_to_range(&mut self, range: Range<usize>) -> &mut Self {
+        self.restrict_range = Some(range);
+        self
+    }
+
+    /// Record statistics about the mutators used by [`WasmMutate::run`] into
+    /// `stats`.
+    ///
+    /// Each run records which mutators were applicable to the input, which
+    /// one was chosen and whether it succeeded. Each mutated module produced
+    /// by the returned iterator is then also validated, recording whether it
+    /// was valid, which makes iterating over mutations more expensive. The
+    /// same `stats` can be shared between many runs, and many `WasmMutate`s,
+    /// to collect statistics over a whole fuzzing campaign.
+    pub fn stats(&mut self, stats: Option<Arc<Mutex<Stats>>>) -> &mut Self {
+        self.stats = stats;
+        self
+    }
+
+    pub(crate) fn is_restricted(&self) -> bool {
+        self.restrict_functions.is_some() || self.restrict_range.is_some()
+    }
+
+    /// Returns whether the `idx`th function body in the code section, whose
+    /// range within the code section is `range`, may be mutated.
+    pub(crate) fn function_in_scope(&self, idx: u32, range: Range<usize>) -> bool {
+        if let Some(functions) = &self.restrict_functions {
+            if !functions.contains(&(idx + self.info().num_imported_functions())) {
+                return false;
+            }
+        }
+        if let Some(allowed) = &self.restrict_range {
+            let offset = self.info().code_section_offset;
+            if offset + range.start >= allowed.end || offset + range.end <= allowed.start {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Chooses the index within the code section of a function body to
+    /// mutate, respecting any configured restrictions.
+    pub(crate) fn choose_function(&mut self) -> Result<u32> {
+        let code_section = self.info().get_code_section();
+        let reader = wasmparser::CodeSectionReader::new(code_section.data, 0)?;
+        if self.stable_site_selection {
+            let mut chosen = None;
+            for (i, body) in reader.into_iter().enumerate() {
+                let body = body?;
+                if !self.function_in_scope(i as u32, body.range()) {
+                    continue;
+                }
+                let hash = self.site_hash(&site_contents(code_section.data, &body)?);
+                match chosen {
+                    Some((min, _)) if min <= hash => {}
+                    _ => chosen = Some((hash, i as u32)),
+                }
+            }
+            return chosen
+                .map(|(_, i)| i)
+                .ok_or_else(Error::no_mutations_applicable);
+        }
+        if !self.is_restricted() {
+            let count = reader.count();
+            return Ok(self.rng().gen_range(0..count));
+        }
+        let mut candidates = Vec::new();
+        for (i, body) in reader.into_iter().enumerate() {
+            if self.function_in_scope(i as u32, body?.range()) {
+                candidates.push(i as u32);
+            }
+        }
+        candidates
+            .choose(self.rng())
+            .copied()
+            .ok_or_else(Error::no_mutations_applicable)
+    }
+
+    /// Chooses the position of an operator to mutate within the `function`th
+    /// function body of the code section, which has `count` operators.
+    pub(crate) fn choose_operator(&mut self, function: u32, count: usize) -> Result<usize> {
+        if !self.stable_site_selection {
+            return Ok(self.rng().gen_range(0..count));
+        }
+        let code_section = self.info().get_code_section();
+        let body = wasmparser::CodeSectionReader::new(code_section.data, 0)?
+            .into_iter()
+            .nth(function as usize)
+            .unwrap()?;
+        let function_hash = self.site_hash(&site_contents(code_section.data, &body)?);
+        Ok((0..count)
+            .min_by_key(|i| {
+                let site = [function_hash.to_le_bytes(), (*i as u64).to_le_bytes()];
+                self.site_hash(&site.concat())
+            })
+            .unwrap())
+    }
+
+    /// Hashes `site` along with the seed for stable site selection.
+    ///
+    /// This uses FNV-1a rather than the standard library's hasher so that a
+    /// seed selects the same sites on every platform and Rust version.
+    fn site_hash(&self, site: &[u8]) -> u64 {
+        let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+        for byte in self.seed.to_le_bytes().iter().chain(site) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        hash
+    }
+
     pub(crate) fn consume_fuel(&mut self, qt: u64) -> Result<()> {
         if qt > self.fuel {
             log::info!("Out of fuel");
@@ -213,6 +389,11 @@ impl<'wasm> WasmMutate<'wasm> {
             &SnipMutator,
             &CodemotionMutator,
             &FunctionBodyUnreachable,
+            &BranchRetargetMutator,
+            &CodeInjectionMutator { max_depth: 4 },
+            &ReturnValueMutator,
+            &MemArgMutator::Align,
+            &MemArgMutator::Offset,
             &AddCustomSectionMutator,
             &ReorderCustomSectionMutator,
             &CustomSectionMutator,
@@ -221,6 +402,8 @@ impl<'wasm> WasmMutate<'wasm> {
                 max_results: 20,
             },
             &AddFunctionMutator,
+            &DuplicateTypeMutator,
+            &PermuteTypesMutator,
             &RemoveSection::Custom,
             &RemoveSection::Empty,
             &ConstExpressionMutator::Global,
@@ -239,19 +422,53 @@ impl<'wasm> WasmMutate<'wasm> {
             },
         ];
 
+        let stats = self.stats.clone();
+        if let Some(stats) = &stats {
+            let mut stats = stats.lock().unwrap();
+            stats.runs += 1;
+            for m in MUTATORS {
+                if self.can_use(*m) {
+                    stats.mutator(&m.name()).applicable += 1;
+                }
+            }
+        }
+
         // Attempt all mutators, but start at an arbitrary index.
         let start = self.rng().gen_range(0..MUTATORS.len());
         for m in MUTATORS.iter().cycle().skip(start).take(MUTATORS.len()) {
-            let can_mutate = m.can_mutate(self);
+            let can_mutate = self.can_use(*m);
             log::trace!("Can `{}` mutate? {}", m.name(), can_mutate);
             if !can_mutate {
                 continue;
             }
             log::debug!("attempting to mutate with `{}`", m.name());
+            // Only the first applicable mutator is attempted and its result
+            // is returned either way, so this is counted once per run.
+            if let Some(stats) = &stats {
+                stats.lock().unwrap().mutator(&m.name()).chosen += 1;
+            }
             match m.mutate(self) {
                 Ok(iter) => {
                     log::debug!("mutator `{}` succeeded", m.name());
-                    return Ok(Box::new(iter.into_iter().map(|r| r.map(|m| m.finish()))));
+                    let iter = iter.into_iter().map(|r| r.map(|m| m.finish()));
+                    let stats = match stats {
+                        Some(stats) => stats,
+                        None => return Ok(Box::new(iter)),
+                    };
+                    let name = m.name();
+                    stats.lock().unwrap().mutator(&name).succeeded += 1;
+                    return Ok(Box::new(iter.inspect(move |result| {
+                        if let Ok(wasm) = result {
+                            let valid = stats::validates(wasm);
+                            let mut stats = stats.lock().unwrap();
+                            let m = stats.mutator(&name);
+                            if valid {
+                                m.valid += 1;
+                            } else {
+                                m.invalid += 1;
+                            }
+                        }
+                    })));
                 }
                 Err(e) => {
                     log::debug!("mutator `{}` failed: {}", m.name(), e);
@@ -263,6 +480,11 @@ impl<'wasm> WasmMutate<'wasm> {
         Err(Error::no_mutations_applicable())
     }
 
+    /// Returns whether `m` may be used to mutate the input.
+    fn can_use(&self, m: &dyn Mutator) -> bool {
+        m.can_mutate(self) && (!self.is_restricted() || m.is_code_mutator())
+    }
+
     fn setup(&mut self, input_wasm: &'wasm [u8]) -> Result<()> {
         self.info = Some(ModuleInfo::new(input_wasm)?);
         self.rng = Some(SmallRng::seed_from_u64(self.seed));
@@ -318,13 +540,50 @@ impl<'wasm> WasmMutate<'wasm> {
     }
 }
 
-#[cfg(test)]
-pub(crate) fn validate(bytes: &[u8]) {
-    let mut validator = wasmparser::Validator::new_with_features(wasmparser::WasmFeatures {
+/// Returns the contents of `body`, within the code section `data`, which
+/// are hashed for stable site selection.
+///
+/// This is the body's encoding with the function indices of calls and
+/// `ref.func` instructions left out, since they change when functions are
+/// added or removed elsewhere in the module.
+fn site_contents(data: &[u8], body: &wasmparser::FunctionBody<'_>) -> Result<Vec<u8>> {
+    use wasmparser::Operator;
+
+    let mut contents = Vec::new();
+    // The start of the bytes not yet copied to `contents`, or `None` while
+    // skipping an index.
+    let mut copied = Some(body.range().start);
+    for item in body.get_operators_reader()?.into_iter_with_offsets() {
+        let (op, offset) = item?;
+        if let Some(start) = copied {
+            contents.extend_from_slice(&data[start..offset]);
+        }
+        copied = Some(offset);
+        if let Operator::Call { .. } | Operator::ReturnCall { .. } | Operator::RefFunc { .. } = op {
+            // Keep only the opcode, which is a single byte for each of these.
+            contents.push(data[offset]);
+            copied = None;
+        }
+    }
+    if let Some(start) = copied {
+        contents.extend_from_slice(&data[start..body.range().end]);
+    }
+    Ok(contents)
+}
+
+/// The proposals enabled when validating mutated modules.
+pub(crate) fn validator_features() -> wasmparser::WasmFeatures {
+    wasmparser::WasmFeatures {
         memory64: true,
         multi_memory: true,
+        threads: true,
         ..Default::default()
-    });
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn validate(bytes: &[u8]) {
+    let mut validator = wasmparser::Validator::new_with_features(validator_features());
     let err = match validator.validate_all(bytes) {
         Ok(_) => return,
         Err(e) => e,
@@ -336,3 +595,96 @@ pub(crate) fn validate(bytes: &[u8]) {
 
     panic!("wasm failed to validate: {} (written to test.wasm)", err);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::WasmMutate;
+
+    #[test]
+    fn stable_site_selection() {
+        let original = wat::parse_str(
+            r#"
+            (module
+                (func (result i32) i32.const 1)
+                (func (result i32) i32.const 2)
+                (func (result i32) call 0 call 1 i32.add)
+            )
+            "#,
+        )
+        .unwrap();
+        // The same module with an unrelated function inserted at the front,
+        // which shifts the indices of the calls.
+        let edited = wat::parse_str(
+            r#"
+            (module
+                (func)
+                (func (result i32) i32.const 1)
+                (func (result i32) i32.const 2)
+                (func (result i32) call 1 call 2 i32.add)
+            )
+            "#,
+        )
+        .unwrap();
+
+        for seed in 0..100 {
+            let mut mutate = WasmMutate::default();
+            mutate.seed(seed).stable_site_selection(true);
+
+            mutate.setup(&original).unwrap();
+            let function = mutate.choose_function().unwrap();
+            let operator = mutate.choose_operator(function, 2).unwrap();
+
+            mutate.setup(&edited).unwrap();
+            let edited_function = mutate.choose_function().unwrap();
+            if edited_function == 0 {
+                continue;
+            }
+            assert_eq!(edited_function, function + 1);
+            assert_eq!(
+                mutate.choose_operator(edited_function, 2).unwrap(),
+                operator
+            );
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+                (func (export "f") (param i32) (result i32)
+                    local.get 0
+                    i32.const 1
+                    i32.add
+                )
+            )
+            "#,
+        )
+        .unwrap();
+
+        let stats = std::sync::Arc::new(std::sync::Mutex::new(crate::Stats::default()));
+        for seed in 0..20 {
+            let mut mutate = WasmMutate::default();
+            mutate.seed(seed).stats(Some(stats.clone()));
+            let iter = match mutate.run(&wasm) {
+                Ok(iter) => iter,
+                Err(_) => continue,
+            };
+            for result in iter.take(5) {
+                drop(result);
+            }
+        }
+
+        let stats = stats.lock().unwrap();
+        assert_eq!(stats.runs, 20);
+        let chosen = stats.mutators.values().map(|m| m.chosen).sum::<u64>();
+        // Each run chooses a single mutator, whether or not it succeeds.
+        assert_eq!(chosen, stats.runs);
+        for m in stats.mutators.values() {
+            assert!(m.applicable >= m.chosen);
+            assert!(m.chosen >= m.succeeded);
+            assert_eq!(m.invalid, 0);
+        }
+        assert!(stats.mutators.values().any(|m| m.valid > 0));
+    }
+}
@@ -22,9 +22,12 @@
 
 pub mod add_function;
 pub mod add_type;
+pub mod branch_retarget;
+pub mod code_injection;
 pub mod codemotion;
 pub mod custom;
 pub mod function_body_unreachable;
+pub mod memarg;
 pub mod modify_const_exprs;
 pub mod modify_data;
 pub mod peephole;
@@ -32,9 +35,12 @@ pub mod remove_export;
 pub mod remove_item;
 pub mod remove_section;
 pub mod rename_export;
+pub mod return_value;
 pub mod snip_function;
 pub mod start;
+pub mod types;
 
+mod synthesize;
 mod translate;
 pub use self::translate::Item;
 use self::translate::{DefaultTranslator, Translator};
@@ -95,9 +101,20 @@ pub trait Mutator {
         config: &'a mut WasmMutate,
     ) -> Result<Box<dyn Iterator<Item = Result<Module>> + 'a>>;
 
+    /// Does this mutator only change the body of a function chosen with
+    /// [`WasmMutate::choose_function`] (or otherwise filtered with
+    /// [`WasmMutate::function_in_scope`])?
+    ///
+    /// When mutations are restricted to certain functions or byte ranges only
+    /// these mutators are used.
+    fn is_code_mutator(&self) -> bool {
+        false
+    }
+
     /// What is this mutator's name?
     ///
-    /// This is only used for debugging and logging purposes.
+    /// This is used for debugging and logging purposes, and to key the
+    /// [`Stats`](crate::Stats) collected for each mutator.
     fn name(&self) -> Cow<'static, str> {
         return std::any::type_name::<Self>().into();
     }
@@ -0,0 +1,299 @@
+//! Mutator that changes the target of a `br`, `br_if`, or `br_table` to a
+//! different in-scope label with the same label types.
+
+use super::translate::{self, DefaultTranslator};
+use super::Mutator;
+use crate::module::{PrimitiveTypeInfo, TypeInfo};
+use crate::{Error, Result, WasmMutate};
+use rand::prelude::SliceRandom;
+use std::borrow::Cow;
+use wasm_encoder::{CodeSection, Function, Instruction, Module};
+use wasmparser::{BlockType, CodeSectionReader, Operator};
+
+/// Retargets a single branch to another enclosing label whose types are
+/// identical to the original label's, which keeps the function valid.
+#[derive(Clone, Copy)]
+pub struct BranchRetargetMutator;
+
+/// A branch target which can be retargeted.
+struct Site {
+    /// Index of the branch instruction within the function body.
+    op: usize,
+    /// For `br_table`, the index of the target in the table where the default
+    /// target is at index `len`. Unused for `br` and `br_if`.
+    target: usize,
+    /// Relative depths that the branch could be changed to.
+    candidates: Vec<u32>,
+}
+
+impl BranchRetargetMutator {
+    fn label_types(
+        config: &WasmMutate,
+        ty: BlockType,
+        is_loop: bool,
+    ) -> Result<Vec<PrimitiveTypeInfo>> {
+        Ok(match ty {
+            BlockType::Empty | BlockType::Type(_) if is_loop => Vec::new(),
+            BlockType::Empty => Vec::new(),
+            BlockType::Type(t) => vec![t.into()],
+            BlockType::FuncType(idx) => match config.info().types_map.get(idx as usize) {
+                Some(TypeInfo::Func(f)) if is_loop => f.params.clone(),
+                Some(TypeInfo::Func(f)) => f.returns.clone(),
+                None => return Err(Error::other("block type index out of bounds")),
+            },
+        })
+    }
+
+    /// Finds all branch targets in `ops` which have at least one other label
+    /// in scope with the same types.
+    fn find_sites(
+        config: &WasmMutate,
+        ops: &[Operator<'_>],
+        results: Vec<PrimitiveTypeInfo>,
+    ) -> Result<Vec<Site>> {
+        let mut labels = vec![results];
+        let mut sites = Vec::new();
+
+        let candidates = |labels: &[Vec<PrimitiveTypeInfo>], depth: u32| -> Vec<u32> {
+            let depth = depth as usize;
+            if depth >= labels.len() {
+                return Vec::new();
+            }
+            let ty = &labels[labels.len() - 1 - depth];
+            (0..labels.len())
+                .filter(|d| *d != depth && labels[labels.len() - 1 - d] == *ty)
+                .map(|d| d as u32)
+                .collect()
+        };
+
+        for (i, op) in ops.iter().enumerate() {
+            match op {
+                Operator::Block { blockty }
+                | Operator::If { blockty }
+                | Operator::Try { blockty } => {
+                    labels.push(Self::label_types(config, *blockty, false)?);
+                }
+                Operator::Loop { blockty } => {
+                    labels.push(Self::label_types(config, *blockty, true)?);
+                }
+                Operator::End | Operator::Delegate { .. } => {
+                    labels.pop();
+                }
+                Operator::Br { relative_depth } | Operator::BrIf { relative_depth } => {
+                    let candidates = candidates(&labels, *relative_depth);
+                    if !candidates.is_empty() {
+                        sites.push(Site {
+                            op: i,
+                            target: 0,
+                            candidates,
+                        });
+                    }
+                }
+                Operator::BrTable { targets } => {
+                    let depths = targets
+                        .targets()
+                        .chain(Some(Ok(targets.default())))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    for (target, depth) in depths.into_iter().enumerate() {
+                        let candidates = candidates(&labels, depth);
+                        if !candidates.is_empty() {
+                            sites.push(Site {
+                                op: i,
+                                target,
+                                candidates,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(sites)
+    }
+}
+
+impl Mutator for BranchRetargetMutator {
+    fn mutate<'a>(
+        &self,
+        config: &'a mut WasmMutate,
+    ) -> Result<Box<dyn Iterator<Item = Result<Module>> + 'a>> {
+        let code_section = config.info().get_code_section();
+        let reader = CodeSectionReader::new(code_section.data, 0)?;
+        let function_to_mutate = config.choose_function()?;
+        let ftype = match config
+            .info()
+            .get_functype_idx(function_to_mutate + config.info().num_imported_functions())
+        {
+            TypeInfo::Func(f) => f.clone(),
+        };
+
+        let mut codes = CodeSection::new();
+        for (i, func) in reader.into_iter().enumerate() {
+            config.consume_fuel(1)?;
+            let f = func?;
+
+            if i as u32 != function_to_mutate {
+                codes.raw(&code_section.data[f.range().start..f.range().end]);
+                continue;
+            }
+
+            let mut reader = f.get_operators_reader()?;
+            reader.allow_memarg64(true);
+            let ops = reader.into_iter().collect::<Result<Vec<_>, _>>()?;
+            let sites = Self::find_sites(config, &ops, ftype.returns.clone())?;
+            let site = match sites.choose(config.rng()) {
+                Some(site) => site,
+                None => return Err(Error::no_mutations_applicable()),
+            };
+            let new_depth = *site.candidates.choose(config.rng()).unwrap();
+            log::trace!(
+                "Retargeting branch at instruction {} of function {} to depth {}",
+                site.op,
+                function_to_mutate,
+                new_depth
+            );
+
+            let locals = f
+                .get_locals_reader()?
+                .into_iter()
+                .map(|local| {
+                    let (count, ty) = local?;
+                    Ok((count, translate::ty(&mut DefaultTranslator, &ty)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let mut func = Function::new(locals);
+            for (j, op) in ops.iter().enumerate() {
+                if j != site.op {
+                    func.instruction(&translate::op(&mut DefaultTranslator, op)?);
+                    continue;
+                }
+                let insn = match op {
+                    Operator::Br { .. } => Instruction::Br(new_depth),
+                    Operator::BrIf { .. } => Instruction::BrIf(new_depth),
+                    Operator::BrTable { targets } => {
+                        let mut depths = targets.targets().collect::<Result<Vec<_>, _>>()?;
+                        let mut default = targets.default();
+                        match depths.get_mut(site.target) {
+                            Some(depth) => *depth = new_depth,
+                            None => default = new_depth,
+                        }
+                        Instruction::BrTable(Cow::Owned(depths), default)
+                    }
+                    _ => unreachable!(),
+                };
+                func.instruction(&insn);
+            }
+            codes.function(&func);
+        }
+
+        Ok(Box::new(std::iter::once(Ok(config
+            .info()
+            .replace_section(config.info().code.unwrap(), &codes)))))
+    }
+
+    fn can_mutate(&self, config: &WasmMutate) -> bool {
+        !config.preserve_semantics && !config.reduce && config.info().has_nonempty_code()
+    }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BranchRetargetMutator;
+
+    #[test]
+    fn test_retarget_br() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (func (result i32)
+                    block (result i32)
+                        block (result i32)
+                            i32.const 1
+                            br 0
+                        end
+                    end
+                )
+            )
+        "#,
+            BranchRetargetMutator,
+            r#"
+            (module
+                (func (result i32)
+                    block (result i32)
+                        block (result i32)
+                            i32.const 1
+                            br 2
+                        end
+                    end
+                )
+            )
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_retarget_br_table() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (func (param i32)
+                    block
+                        block
+                            local.get 0
+                            br_table 0 1 0
+                        end
+                    end
+                )
+            )
+        "#,
+            BranchRetargetMutator,
+            r#"
+            (module
+                (func (param i32)
+                    block
+                        block
+                            local.get 0
+                            br_table 0 1 2
+                        end
+                    end
+                )
+            )
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_loop_label_uses_params() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (func
+                    block
+                        loop (result i32)
+                            br 1
+                        end
+                        drop
+                    end
+                )
+            )
+        "#,
+            BranchRetargetMutator,
+            r#"
+            (module
+                (func
+                    block
+                        loop (result i32)
+                            br 0
+                        end
+                        drop
+                    end
+                )
+            )
+        "#,
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! Mutator that injects randomly synthesized code into a function body.
+
+use super::synthesize::CodeSynthesizer;
+use super::translate::{self, DefaultTranslator};
+use super::Mutator;
+use crate::module::{PrimitiveTypeInfo, TypeInfo};
+use crate::{Result, WasmMutate};
+use wasm_encoder::{CodeSection, Function, Module};
+use wasmparser::CodeSectionReader;
+
+/// Inserts a random stack-neutral instruction sequence before a random
+/// instruction of a function.
+///
+/// A sequence which pops and pushes nothing is valid anywhere in a function
+/// body, so every instruction boundary is a candidate. When semantics are
+/// preserved the injected code only computes pure values that are then
+/// dropped.
+#[derive(Clone, Copy)]
+pub struct CodeInjectionMutator {
+    /// The maximum nesting depth of the injected expressions.
+    pub max_depth: u32,
+}
+
+impl Mutator for CodeInjectionMutator {
+    fn mutate<'a>(
+        &self,
+        config: &'a mut WasmMutate,
+    ) -> Result<Box<dyn Iterator<Item = Result<Module>> + 'a>> {
+        let code_section = config.info().get_code_section();
+        let reader = CodeSectionReader::new(code_section.data, 0)?;
+        let function_to_mutate = config.choose_function()?;
+        let ftype = match config
+            .info()
+            .get_functype_idx(function_to_mutate + config.info().num_imported_functions())
+        {
+            TypeInfo::Func(f) => f.clone(),
+        };
+
+        let mut codes = CodeSection::new();
+        for (i, func) in reader.into_iter().enumerate() {
+            config.consume_fuel(1)?;
+            let f = func?;
+
+            if i as u32 != function_to_mutate {
+                codes.raw(&code_section.data[f.range().start..f.range().end]);
+                continue;
+            }
+
+            let mut local_types = ftype.params.clone();
+            let mut locals = Vec::new();
+            for local in f.get_locals_reader()? {
+                let (count, ty) = local?;
+                local_types.extend((0..count).map(|_| PrimitiveTypeInfo::from(ty)));
+                locals.push((count, translate::ty(&mut DefaultTranslator, &ty)?));
+            }
+
+            let mut reader = f.get_operators_reader()?;
+            reader.allow_memarg64(true);
+            let ops = reader.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+            let synth = CodeSynthesizer::new(config, local_types, self.max_depth);
+            let mut injected = Vec::new();
+            synth.sequence(config.rng(), &[], &[], &mut injected);
+            let at = config.choose_operator(function_to_mutate, ops.len())?;
+            log::trace!(
+                "Injecting {:?} before instruction {} of function {}",
+                injected,
+                at,
+                function_to_mutate
+            );
+
+            let mut func = Function::new(locals);
+            for (j, op) in ops.iter().enumerate() {
+                if j == at {
+                    for insn in injected.iter() {
+                        func.instruction(insn);
+                    }
+                }
+                func.instruction(&translate::op(&mut DefaultTranslator, op)?);
+            }
+            codes.function(&func);
+        }
+
+        Ok(Box::new(std::iter::once(Ok(config
+            .info()
+            .replace_section(config.info().code.unwrap(), &codes)))))
+    }
+
+    fn can_mutate(&self, config: &WasmMutate) -> bool {
+        !config.reduce && config.info().has_nonempty_code()
+    }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CodeInjectionMutator;
+
+    #[test]
+    fn test_inject_local_get() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (func (param i32)
+                    nop
+                )
+            )
+        "#,
+            CodeInjectionMutator { max_depth: 2 },
+            r#"
+            (module
+                (func (param i32)
+                    local.get 0
+                    drop
+                    nop
+                )
+            )
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_inject_global_set() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (global (mut i64) (i64.const 0))
+                (func
+                    nop
+                )
+            )
+        "#,
+            CodeInjectionMutator { max_depth: 2 },
+            r#"
+            (module
+                (global (mut i64) (i64.const 0))
+                (func
+                    nop
+                    global.get 0
+                    global.set 0
+                )
+            )
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_inject_preserving_semantics() {
+        let mut config = crate::WasmMutate::default();
+        config.preserve_semantics(true);
+        config.match_mutation(
+            r#"
+            (module
+                (global (mut f32) (f32.const 0))
+                (func
+                    nop
+                )
+            )
+        "#,
+            CodeInjectionMutator { max_depth: 2 },
+            r#"
+            (module
+                (global (mut f32) (f32.const 0))
+                (func
+                    global.get 0
+                    drop
+                    nop
+                )
+            )
+        "#,
+        );
+    }
+}
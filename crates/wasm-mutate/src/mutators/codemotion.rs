@@ -31,7 +31,7 @@ use crate::{
     },
     Error, Result, WasmMutate,
 };
-use rand::{prelude::SliceRandom, Rng};
+use rand::prelude::SliceRandom;
 use wasm_encoder::{CodeSection, Function, Module, ValType};
 use wasmparser::{CodeSectionReader, FunctionBody};
 
@@ -66,7 +66,7 @@ impl CodemotionMutator {
 
         let sectionreader = CodeSectionReader::new(original_code_section.data, 0)?;
         let function_count = sectionreader.count();
-        let function_to_mutate = config.rng().gen_range(0..function_count);
+        let function_to_mutate = config.choose_function()?;
 
         // This split strategy will avoid very often mutating the first function
         // and very rarely mutating the last function
@@ -75,6 +75,9 @@ impl CodemotionMutator {
         for fidx in (function_to_mutate..function_count).chain(0..function_to_mutate) {
             config.consume_fuel(1)?;
             let reader = all_readers[fidx as usize].clone();
+            if !config.function_in_scope(fidx, reader.range()) {
+                continue;
+            }
             let mut operatorreader = reader.get_operators_reader()?;
             operatorreader.allow_memarg64(true);
 
@@ -164,6 +167,10 @@ impl Mutator for CodemotionMutator {
     fn can_mutate<'a>(&self, config: &'a WasmMutate) -> bool {
         config.info().has_code() && config.info().num_local_functions() > 0
     }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
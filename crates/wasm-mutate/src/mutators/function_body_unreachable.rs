@@ -2,7 +2,6 @@
 
 use crate::{Result, WasmMutate};
 
-use rand::Rng;
 use wasm_encoder::{CodeSection, Function, Instruction, Module};
 use wasmparser::CodeSectionReader;
 
@@ -22,8 +21,7 @@ impl Mutator for FunctionBodyUnreachable {
         let code_section = config.info().get_code_section();
         let reader = CodeSectionReader::new(code_section.data, 0)?;
 
-        let count = reader.count();
-        let function_to_mutate = config.rng().gen_range(0..count);
+        let function_to_mutate = config.choose_function()?;
 
         for (i, f) in reader.into_iter().enumerate() {
             config.consume_fuel(1)?;
@@ -50,6 +48,10 @@ impl Mutator for FunctionBodyUnreachable {
     fn can_mutate<'a>(&self, config: &'a WasmMutate) -> bool {
         !config.preserve_semantics && config.info().has_nonempty_code()
     }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,317 @@
+//! Mutators that perturb the alignment hint or static offset of a memory
+//! access's `memarg` immediate.
+
+use super::translate::{self, DefaultTranslator, Translator};
+use super::Mutator;
+use crate::{Error, Result, WasmMutate};
+use rand::Rng;
+use wasm_encoder::{CodeSection, Function, MemArg, Module};
+use wasmparser::{CodeSectionReader, Operator};
+
+/// Changes a single `memarg` of a load, store, or other memory access within
+/// the bounds that are still valid for the instruction.
+#[derive(Clone, Copy)]
+pub enum MemArgMutator {
+    /// Pick a different alignment hint no larger than the access's natural
+    /// alignment. This does not change the semantics of the module.
+    Align,
+    /// Pick a different static offset, within the range of the accessed
+    /// memory's index type.
+    Offset,
+}
+
+struct MemArgTranslator<'cfg, 'wasm> {
+    config: &'cfg mut WasmMutate<'wasm>,
+    kind: MemArgMutator,
+    /// Whether the instruction currently being translated is an atomic
+    /// instruction, whose alignment must always be the natural alignment.
+    atomic: bool,
+    /// The number of candidate `memarg`s seen so far.
+    seen: u32,
+    /// The index of the candidate `memarg` to mutate, if any.
+    target: Option<u32>,
+}
+
+impl MemArgTranslator<'_, '_> {
+    fn is_candidate(&self, arg: &wasmparser::MemArg) -> bool {
+        match self.kind {
+            MemArgMutator::Align => !self.atomic && arg.max_align > 0,
+            MemArgMutator::Offset => !self.config.reduce || arg.offset > 0,
+        }
+    }
+
+    fn new_offset(&mut self, arg: &wasmparser::MemArg) -> Result<u64> {
+        let memory64 = match self.config.info().memory_types.get(arg.memory as usize) {
+            Some(ty) => ty.memory64,
+            None => return Err(Error::other("memory index out of bounds")),
+        };
+        if self.config.reduce {
+            return Ok(self.config.rng().gen_range(0..arg.offset));
+        }
+        let max = if memory64 { u64::MAX } else { u32::MAX.into() };
+        let rng = self.config.rng();
+        loop {
+            let offset = match rng.gen_range(0..4) {
+                0 => 0,
+                // Straddle a wasm page boundary.
+                1 => {
+                    let page = rng.gen_range(0..=16u64) << 16;
+                    page.wrapping_add(rng.gen_range(0..16)).wrapping_sub(8) & max
+                }
+                // Close to the maximum encodable offset.
+                2 => max - rng.gen_range(0..16),
+                _ => rng.gen_range(0..=max),
+            };
+            if offset != arg.offset {
+                return Ok(offset);
+            }
+        }
+    }
+}
+
+impl Translator for MemArgTranslator<'_, '_> {
+    fn as_obj(&mut self) -> &mut dyn Translator {
+        self
+    }
+
+    fn translate_memarg(&mut self, arg: &wasmparser::MemArg) -> Result<MemArg> {
+        if !self.is_candidate(arg) {
+            return translate::memarg(self.as_obj(), arg);
+        }
+        let idx = self.seen;
+        self.seen += 1;
+        if self.target != Some(idx) {
+            return translate::memarg(self.as_obj(), arg);
+        }
+
+        let mut new = translate::memarg(self.as_obj(), arg)?;
+        match self.kind {
+            MemArgMutator::Align => {
+                let max_align = u32::from(arg.max_align);
+                let align = self.config.rng().gen_range(0..max_align);
+                // Skip over the original alignment so a change is always made.
+                new.align = if align >= u32::from(arg.align) {
+                    align + 1
+                } else {
+                    align
+                };
+            }
+            MemArgMutator::Offset => new.offset = self.new_offset(arg)?,
+        }
+        log::trace!("Replacing {:?} with {:?}", arg, new);
+        Ok(new)
+    }
+}
+
+/// Returns whether `op` is an atomic instruction from the threads proposal.
+fn is_atomic(op: &Operator<'_>) -> bool {
+    macro_rules! is_atomic {
+        ($(@$proposal:ident $op:ident $({ $($arg:ident: $argty:ty),* })? => $visit:ident)*) => {
+            match op {
+                $(
+                    Operator::$op { .. } => is_atomic!(@proposal $proposal),
+                )*
+            }
+        };
+        (@proposal threads) => (true);
+        (@proposal $proposal:ident) => (false);
+    }
+    wasmparser::for_each_operator!(is_atomic)
+}
+
+impl Mutator for MemArgMutator {
+    fn mutate<'a>(
+        &self,
+        config: &'a mut WasmMutate,
+    ) -> Result<Box<dyn Iterator<Item = Result<Module>> + 'a>> {
+        let code_section = config.info().get_code_section();
+        let reader = CodeSectionReader::new(code_section.data, 0)?;
+        let function_to_mutate = config.choose_function()?;
+
+        let mut codes = CodeSection::new();
+        for (i, func) in reader.into_iter().enumerate() {
+            config.consume_fuel(1)?;
+            let f = func?;
+
+            if i as u32 != function_to_mutate {
+                codes.raw(&code_section.data[f.range().start..f.range().end]);
+                continue;
+            }
+
+            let mut reader = f.get_operators_reader()?;
+            reader.allow_memarg64(true);
+            let ops = reader.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+            // First count the candidate `memarg`s in this function and then
+            // pick one of them to mutate while re-encoding the function.
+            let mut translator = MemArgTranslator {
+                config,
+                kind: *self,
+                atomic: false,
+                seen: 0,
+                target: None,
+            };
+            for op in ops.iter() {
+                translator.atomic = is_atomic(op);
+                translate::op(&mut translator, op)?;
+            }
+            if translator.seen == 0 {
+                return Err(Error::no_mutations_applicable());
+            }
+            translator.target = Some(translator.config.rng().gen_range(0..translator.seen));
+            translator.seen = 0;
+
+            let locals = f
+                .get_locals_reader()?
+                .into_iter()
+                .map(|local| {
+                    let (count, ty) = local?;
+                    Ok((count, DefaultTranslator.translate_ty(&ty)?))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let mut func = Function::new(locals);
+            for op in ops.iter() {
+                translator.atomic = is_atomic(op);
+                func.instruction(&translate::op(&mut translator, op)?);
+            }
+            codes.function(&func);
+        }
+
+        Ok(Box::new(std::iter::once(Ok(config
+            .info()
+            .replace_section(config.info().code.unwrap(), &codes)))))
+    }
+
+    fn can_mutate(&self, config: &WasmMutate) -> bool {
+        let allowed = match self {
+            MemArgMutator::Align => !config.reduce,
+            MemArgMutator::Offset => !config.preserve_semantics,
+        };
+        allowed && config.info().num_memories() > 0 && config.info().has_nonempty_code()
+    }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MemArgMutator;
+
+    #[test]
+    fn test_align() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (memory 1)
+                (func (param i32) (result i32)
+                    local.get 0
+                    i32.load
+                )
+            )
+        "#,
+            MemArgMutator::Align,
+            r#"
+            (module
+                (memory 1)
+                (func (param i32) (result i32)
+                    local.get 0
+                    i32.load align=1
+                )
+            )
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_align_skips_atomics() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (memory 1 1 shared)
+                (func (param i32) (result i32)
+                    local.get 0
+                    i32.atomic.load
+                    local.get 0
+                    i64.load8_u
+                    drop
+                    local.get 0
+                    i32.load16_s
+                    i32.add
+                )
+            )
+        "#,
+            MemArgMutator::Align,
+            r#"
+            (module
+                (memory 1 1 shared)
+                (func (param i32) (result i32)
+                    local.get 0
+                    i32.atomic.load
+                    local.get 0
+                    i64.load8_u
+                    drop
+                    local.get 0
+                    i32.load16_s align=1
+                    i32.add
+                )
+            )
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (memory 1)
+                (func (param i32)
+                    local.get 0
+                    i32.const 0
+                    i32.store offset=8
+                )
+            )
+        "#,
+            MemArgMutator::Offset,
+            r#"
+            (module
+                (memory 1)
+                (func (param i32)
+                    local.get 0
+                    i32.const 0
+                    i32.store
+                )
+            )
+        "#,
+        );
+    }
+
+    #[test]
+    fn test_offset_reduce() {
+        let mut config = crate::WasmMutate::default();
+        config.reduce(true);
+        config.match_mutation(
+            r#"
+            (module
+                (memory 1)
+                (func (param i32) (result i64)
+                    local.get 0
+                    i64.load offset=1
+                )
+            )
+        "#,
+            MemArgMutator::Offset,
+            r#"
+            (module
+                (memory 1)
+                (func (param i32) (result i64)
+                    local.get 0
+                    i64.load
+                )
+            )
+        "#,
+        );
+    }
+}
@@ -110,7 +110,7 @@ impl PeepholeMutator {
         let code_section = config.info().get_code_section();
         let sectionreader = CodeSectionReader::new(code_section.data, 0)?;
         let function_count = sectionreader.count();
-        let mut function_to_mutate = config.rng().gen_range(0..function_count);
+        let mut function_to_mutate = config.choose_function()?;
 
         let mut visited_functions = 0;
 
@@ -122,6 +122,11 @@ impl PeepholeMutator {
             }
 
             let reader = readers[function_to_mutate as usize].clone();
+            if !config.function_in_scope(function_to_mutate, reader.range()) {
+                function_to_mutate = (function_to_mutate + 1) % function_count;
+                visited_functions += 1;
+                continue;
+            }
             let mut operatorreader = reader.get_operators_reader()?;
             operatorreader.allow_memarg64(true);
             let mut localsreader = reader.get_locals_reader()?;
@@ -130,7 +135,8 @@ impl PeepholeMutator {
                 .collect::<wasmparser::Result<Vec<OperatorAndByteOffset>>>()?;
             let operatorscount = operators.len();
 
-            let mut opcode_to_mutate = config.rng().gen_range(0..operatorscount);
+            let mut opcode_to_mutate =
+                config.choose_operator(function_to_mutate, operatorscount)?;
             log::trace!(
                 "Selecting operator {}/{} from function {}",
                 opcode_to_mutate,
@@ -445,6 +451,10 @@ impl Mutator for PeepholeMutator {
     fn can_mutate<'a>(&self, config: &'a WasmMutate) -> bool {
         config.info().has_code() && config.info().num_local_functions() > 0
     }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
 }
 
 impl Debug for Box<dyn CodeMutator> {
@@ -0,0 +1,507 @@
+//! Mutator that perturbs the values returned by an exported function.
+
+use super::Mutator;
+use crate::info::ModuleInfo;
+use crate::module::{PrimitiveTypeInfo, TypeInfo};
+use crate::{Error, Result, WasmMutate};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use wasm_encoder::{
+    CodeSection, ExportKind, ExportSection, Function, FunctionSection, HeapType, Instruction,
+    Module, SectionId, ValType,
+};
+use wasmparser::{
+    CodeSectionReader, ConstExpr, ElementItems, ElementSectionReader, ExportSectionReader,
+    ExternalKind, FunctionBody, FunctionSectionReader, GlobalSectionReader, Operator,
+};
+
+/// Changes the results of a random exported function, leaving its behavior
+/// otherwise intact.
+///
+/// A wrapper function is appended to the module which calls the exported
+/// function and then either changes one of the results, for example by
+/// xor-ing an integer with a constant, or swaps two results of the same
+/// type. The export is then repointed at the wrapper while the original
+/// function keeps its index, so calls and references to it elsewhere in the
+/// module are unaffected. This doesn't preserve semantics, but produces a
+/// variant which only differs in what the export returns, which is useful
+/// for checking that a differential fuzzing harness compares results.
+///
+/// Functions whose export is the only thing declaring them for `ref.func`
+/// are left alone, since repointing the export would leave those `ref.func`
+/// instructions invalid.
+#[derive(Clone, Copy)]
+pub struct ReturnValueMutator;
+
+impl Mutator for ReturnValueMutator {
+    fn mutate<'a>(
+        &self,
+        config: &'a mut WasmMutate,
+    ) -> Result<Box<dyn Iterator<Item = Result<Module>> + 'a>> {
+        let num_imported = config.info().num_imported_functions();
+        let code_section = config.info().get_code_section();
+        let bodies = CodeSectionReader::new(code_section.data, 0)?
+            .into_iter()
+            .map(|body| Ok(body?.range()))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Find the exports of locally-defined functions with results which
+        // are in scope for mutation.
+        let undeclarable = declared_only_by_export(config.info(), code_section.data, &bodies)?;
+        let mut candidates = Vec::new();
+        let exports = ExportSectionReader::new(config.info().get_exports_section().data, 0)?;
+        for (i, export) in exports.into_iter().enumerate() {
+            let export = export?;
+            if export.kind != ExternalKind::Func || export.index < num_imported {
+                continue;
+            }
+            if undeclarable.contains(&export.index) {
+                continue;
+            }
+            let defined = export.index - num_imported;
+            if !config.function_in_scope(defined, bodies[defined as usize].clone()) {
+                continue;
+            }
+            let TypeInfo::Func(ty) = config.info().get_functype_idx(export.index);
+            if !ty.returns.is_empty() {
+                candidates.push((i, export.index));
+            }
+        }
+        if candidates.is_empty() {
+            return Err(Error::no_mutations_applicable());
+        }
+        let (export_idx, func_idx) = candidates[config.rng().gen_range(0..candidates.len())];
+        let TypeInfo::Func(ty) = config.info().get_functype_idx(func_idx).clone();
+
+        // Either swap two results of the same type, if there are any, or
+        // change a single result.
+        let mut order = (0..ty.returns.len()).collect::<Vec<_>>();
+        let mut swaps = Vec::new();
+        for i in 0..ty.returns.len() {
+            for j in i + 1..ty.returns.len() {
+                if ty.returns[i] == ty.returns[j] {
+                    swaps.push((i, j));
+                }
+            }
+        }
+        let perturbed = if !swaps.is_empty() && config.rng().gen() {
+            let (i, j) = swaps[config.rng().gen_range(0..swaps.len())];
+            log::trace!("swapping results {i} and {j} of function {func_idx}");
+            order.swap(i, j);
+            None
+        } else {
+            let i = config.rng().gen_range(0..ty.returns.len());
+            log::trace!("changing result {i} of function {func_idx}");
+            Some(i)
+        };
+
+        // The wrapper is appended to the end of the function index space with
+        // the same type as the exported function.
+        let mut functions = FunctionSection::new();
+        let mut type_idx = None;
+        let section = config.info().raw_sections[config.info().functions.unwrap()];
+        let reader = FunctionSectionReader::new(section.data, 0)?;
+        for (i, ty) in reader.into_iter().enumerate() {
+            let ty = ty?;
+            if i as u32 + num_imported == func_idx {
+                type_idx = Some(ty);
+            }
+            functions.function(ty);
+        }
+        functions.function(type_idx.unwrap());
+        let wrapper_idx = config.info().num_functions();
+
+        let num_params = ty.params.len() as u32;
+        let mut wrapper = Function::new_with_locals_types(ty.returns.iter().map(|t| valtype(*t)));
+        for i in 0..num_params {
+            wrapper.instruction(&Instruction::LocalGet(i));
+        }
+        wrapper.instruction(&Instruction::Call(func_idx));
+        for i in (0..ty.returns.len() as u32).rev() {
+            wrapper.instruction(&Instruction::LocalSet(num_params + i));
+        }
+        for (i, result) in order.iter().enumerate() {
+            wrapper.instruction(&Instruction::LocalGet(num_params + *result as u32));
+            if perturbed == Some(i) {
+                perturb(config, &mut wrapper, ty.returns[i]);
+            }
+        }
+        wrapper.instruction(&Instruction::End);
+
+        let mut codes = CodeSection::new();
+        for range in bodies.iter() {
+            config.consume_fuel(1)?;
+            codes.raw(&code_section.data[range.start..range.end]);
+        }
+        codes.function(&wrapper);
+
+        // Only the chosen export is repointed, any other exports of the same
+        // function still refer to the original.
+        let mut exports = ExportSection::new();
+        let reader = ExportSectionReader::new(config.info().get_exports_section().data, 0)?;
+        for (i, export) in reader.into_iter().enumerate() {
+            let export = export?;
+            let (kind, index) = match export.kind {
+                ExternalKind::Func if i == export_idx => (ExportKind::Func, wrapper_idx),
+                ExternalKind::Func => (ExportKind::Func, export.index),
+                ExternalKind::Table => (ExportKind::Table, export.index),
+                ExternalKind::Memory => (ExportKind::Memory, export.index),
+                ExternalKind::Global => (ExportKind::Global, export.index),
+                ExternalKind::Tag => (ExportKind::Tag, export.index),
+            };
+            exports.export(export.name, kind, index);
+        }
+
+        let module = config
+            .info()
+            .replace_multiple_sections(|_, id, module| match id {
+                x if x == SectionId::Function as u8 => {
+                    module.section(&functions);
+                    true
+                }
+                x if x == SectionId::Export as u8 => {
+                    module.section(&exports);
+                    true
+                }
+                x if x == SectionId::Code as u8 => {
+                    module.section(&codes);
+                    true
+                }
+                _ => false,
+            });
+        Ok(Box::new(std::iter::once(Ok(module))))
+    }
+
+    fn can_mutate(&self, config: &WasmMutate) -> bool {
+        !config.preserve_semantics
+            && !config.reduce
+            && config.info().has_exports()
+            && config.info().has_nonempty_code()
+    }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
+}
+
+/// Changes the value of type `ty` on top of the stack.
+fn perturb(config: &mut WasmMutate, func: &mut Function, ty: PrimitiveTypeInfo) {
+    match ty {
+        PrimitiveTypeInfo::I32 => {
+            let k = config.rng().gen_range(1..=u32::MAX);
+            func.instruction(&Instruction::I32Const(k as i32));
+            func.instruction(&Instruction::I32Xor);
+        }
+        PrimitiveTypeInfo::I64 => {
+            let k = config.rng().gen_range(1..=u64::MAX);
+            func.instruction(&Instruction::I64Const(k as i64));
+            func.instruction(&Instruction::I64Xor);
+        }
+        // Flip bits of the float's encoding rather than negating it, since
+        // negating a NaN only changes its sign bit.
+        PrimitiveTypeInfo::F32 => {
+            let k = config.rng().gen_range(1..=u32::MAX);
+            func.instruction(&Instruction::I32ReinterpretF32);
+            func.instruction(&Instruction::I32Const(k as i32));
+            func.instruction(&Instruction::I32Xor);
+            func.instruction(&Instruction::F32ReinterpretI32);
+        }
+        PrimitiveTypeInfo::F64 => {
+            let k = config.rng().gen_range(1..=u64::MAX);
+            func.instruction(&Instruction::I64ReinterpretF64);
+            func.instruction(&Instruction::I64Const(k as i64));
+            func.instruction(&Instruction::I64Xor);
+            func.instruction(&Instruction::F64ReinterpretI64);
+        }
+        PrimitiveTypeInfo::V128 => {
+            func.instruction(&Instruction::V128Not);
+        }
+        PrimitiveTypeInfo::FuncRef => {
+            func.instruction(&Instruction::Drop);
+            func.instruction(&Instruction::RefNull(HeapType::Func));
+        }
+        PrimitiveTypeInfo::ExternRef => {
+            func.instruction(&Instruction::Drop);
+            func.instruction(&Instruction::RefNull(HeapType::Extern));
+        }
+        PrimitiveTypeInfo::Empty => unreachable!(),
+    }
+}
+
+/// Returns the functions which are used by a `ref.func` in a function body
+/// and declared only by a single export.
+///
+/// A `ref.func` is only valid if its function is also referenced outside of
+/// function bodies, so such an export can't be repointed at a wrapper.
+fn declared_only_by_export(
+    info: &ModuleInfo,
+    code: &[u8],
+    bodies: &[Range<usize>],
+) -> Result<HashSet<u32>> {
+    let mut referenced = HashSet::new();
+    for range in bodies {
+        let body = FunctionBody::new(0, &code[range.start..range.end]);
+        for op in body.get_operators_reader()? {
+            if let Operator::RefFunc { function_index } = op? {
+                referenced.insert(function_index);
+            }
+        }
+    }
+    if referenced.is_empty() {
+        return Ok(referenced);
+    }
+
+    // Collect the declarations of functions outside of function bodies, with
+    // a function appearing once for each of its declarations.
+    let mut declared = Vec::new();
+    if let Some(section) = info.globals {
+        for global in GlobalSectionReader::new(info.raw_sections[section].data, 0)? {
+            const_expr_ref_funcs(&global?.init_expr, &mut declared)?;
+        }
+    }
+    if let Some(section) = info.elements {
+        for element in ElementSectionReader::new(info.raw_sections[section].data, 0)? {
+            match element?.items {
+                ElementItems::Functions(funcs) => {
+                    for func in funcs {
+                        declared.push(func?);
+                    }
+                }
+                ElementItems::Expressions(exprs) => {
+                    for expr in exprs {
+                        const_expr_ref_funcs(&expr?, &mut declared)?;
+                    }
+                }
+            }
+        }
+    }
+    for export in ExportSectionReader::new(info.get_exports_section().data, 0)? {
+        let export = export?;
+        if export.kind == ExternalKind::Func {
+            declared.push(export.index);
+        }
+    }
+
+    let mut declarations = HashMap::new();
+    for func in declared {
+        *declarations.entry(func).or_insert(0) += 1;
+    }
+    referenced.retain(|f| declarations.get(f) == Some(&1));
+    Ok(referenced)
+}
+
+fn const_expr_ref_funcs(expr: &ConstExpr<'_>, funcs: &mut Vec<u32>) -> Result<()> {
+    for op in expr.get_operators_reader() {
+        if let Operator::RefFunc { function_index } = op? {
+            funcs.push(function_index);
+        }
+    }
+    Ok(())
+}
+
+fn valtype(ty: PrimitiveTypeInfo) -> ValType {
+    match ty {
+        PrimitiveTypeInfo::I32 => ValType::I32,
+        PrimitiveTypeInfo::I64 => ValType::I64,
+        PrimitiveTypeInfo::F32 => ValType::F32,
+        PrimitiveTypeInfo::F64 => ValType::F64,
+        PrimitiveTypeInfo::V128 => ValType::V128,
+        PrimitiveTypeInfo::FuncRef => ValType::FUNCREF,
+        PrimitiveTypeInfo::ExternRef => ValType::EXTERNREF,
+        PrimitiveTypeInfo::Empty => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReturnValueMutator;
+    use crate::WasmMutate;
+
+    #[test]
+    fn test_change_result() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (func (export "f") (param f32) (result f32)
+                    local.get 0
+                )
+            )
+            "#,
+            ReturnValueMutator,
+            r#"
+            (module
+                (func (param f32) (result f32)
+                    local.get 0
+                )
+                (func (export "f") (param f32) (result f32)
+                    (local f32)
+                    local.get 0
+                    call 0
+                    local.set 1
+                    local.get 1
+                    i32.reinterpret_f32
+                    i32.const 686018375
+                    i32.xor
+                    f32.reinterpret_i32
+                )
+            )
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_swap_results() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (func (export "f") (result i64 f32 i64)
+                    i64.const 1
+                    f32.const 2
+                    i64.const 3
+                )
+            )
+            "#,
+            ReturnValueMutator,
+            r#"
+            (module
+                (func (result i64 f32 i64)
+                    i64.const 1
+                    f32.const 2
+                    i64.const 3
+                )
+                (func (export "f") (result i64 f32 i64)
+                    (local i64 f32 i64)
+                    call 0
+                    local.set 2
+                    local.set 1
+                    local.set 0
+                    local.get 2
+                    local.get 1
+                    local.get 0
+                )
+            )
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_keeps_callers() {
+        // Only the export is repointed, so `$g`'s call and the table still
+        // refer to the original function.
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (table 1 funcref)
+                (elem (i32.const 0) func $f)
+                (func $f (export "f") (result f64)
+                    f64.const 1
+                )
+                (func $g (result f64)
+                    call $f
+                )
+            )
+            "#,
+            ReturnValueMutator,
+            r#"
+            (module
+                (table 1 funcref)
+                (elem (i32.const 0) func $f)
+                (func $f (result f64)
+                    f64.const 1
+                )
+                (func $g (result f64)
+                    call $f
+                )
+                (func (export "f") (result f64)
+                    (local f64)
+                    call $f
+                    local.set 0
+                    local.get 0
+                    i64.reinterpret_f64
+                    i64.const 2946426486211239052
+                    i64.xor
+                    f64.reinterpret_i64
+                )
+            )
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_skips_ref_func_declared_by_export() {
+        // `$f` is only declared for `ref.func` by its export, so only `$g`'s
+        // export is repointed.
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (func $f (export "f") (result i32)
+                    i32.const 1
+                )
+                (func $g (export "g") (result funcref)
+                    ref.func $f
+                )
+            )
+            "#,
+            ReturnValueMutator,
+            r#"
+            (module
+                (func $f (export "f") (result i32)
+                    i32.const 1
+                )
+                (func $g (result funcref)
+                    ref.func $f
+                )
+                (func (export "g") (result funcref)
+                    (local funcref)
+                    call $g
+                    local.set 0
+                    local.get 0
+                    drop
+                    ref.null func
+                )
+            )
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_restrict_to_functions() {
+        // Only `$g` may be mutated, so its export is the one which changes.
+        WasmMutate::default()
+            .restrict_to_functions(&[1])
+            .match_mutation(
+                r#"
+                (module
+                    (func (export "f") (result f32)
+                        f32.const 1
+                    )
+                    (func (export "g") (result f32)
+                        f32.const 2
+                    )
+                )
+                "#,
+                ReturnValueMutator,
+                r#"
+                (module
+                    (func (export "f") (result f32)
+                        f32.const 1
+                    )
+                    (func (result f32)
+                        f32.const 2
+                    )
+                    (func (export "g") (result f32)
+                        (local f32)
+                        call 1
+                        local.set 0
+                        local.get 0
+                        i32.reinterpret_f32
+                        i32.const 686018375
+                        i32.xor
+                        f32.reinterpret_i32
+                    )
+                )
+                "#,
+            );
+    }
+}
@@ -3,7 +3,6 @@
 use super::Mutator;
 use crate::module::{PrimitiveTypeInfo, TypeInfo};
 use crate::{Result, WasmMutate};
-use rand::Rng;
 use wasm_encoder::{CodeSection, Function, HeapType, Instruction, Module};
 use wasmparser::CodeSectionReader;
 
@@ -19,8 +18,7 @@ impl Mutator for SnipMutator {
         let mut codes = CodeSection::new();
         let code_section = config.info().get_code_section();
         let reader = CodeSectionReader::new(code_section.data, 0)?;
-        let count = reader.count();
-        let function_to_mutate = config.rng().gen_range(0..count);
+        let function_to_mutate = config.choose_function()?;
         let ftype = config
             .info()
             .get_functype_idx(function_to_mutate + config.info().num_imported_functions())
@@ -85,6 +83,10 @@ impl Mutator for SnipMutator {
     fn can_mutate<'a>(&self, config: &'a WasmMutate) -> bool {
         !config.preserve_semantics && config.info().has_nonempty_code()
     }
+
+    fn is_code_mutator(&self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
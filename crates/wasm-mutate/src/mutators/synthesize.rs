@@ -0,0 +1,449 @@
+//! Synthesis of random, valid instruction sequences.
+//!
+//! Mutators that grow function bodies with new code use this to generate
+//! instructions out of whatever the surrounding function has available: its
+//! locals, the module's globals, and the module's functions.
+
+use crate::module::{FuncInfo, PrimitiveTypeInfo, TypeInfo};
+use crate::WasmMutate;
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use wasm_encoder::{HeapType, Instruction};
+
+use PrimitiveTypeInfo::{ExternRef, FuncRef, F32, F64, I32, I64, V128};
+
+/// Generates random instruction sequences with a required stack signature.
+///
+/// None of the generated instructions trap on their own. When semantics
+/// aren't preserved the synthesized code may also call the module's
+/// functions, which can trap or never return, and write to locals and
+/// globals. When semantics are to be preserved only pure instructions are
+/// generated, so the synthesized code never writes to locals or globals and
+/// never calls a function, and therefore can't trap.
+pub(crate) struct CodeSynthesizer {
+    /// The types of the parameters and locals of the function the code is
+    /// synthesized for.
+    locals: Vec<PrimitiveTypeInfo>,
+    /// The type of each global and whether it's mutable.
+    globals: Vec<(PrimitiveTypeInfo, bool)>,
+    /// The type of each function.
+    funcs: Vec<FuncInfo>,
+    /// Whether only pure instructions may be generated.
+    pure: bool,
+    /// The maximum nesting depth of generated expressions.
+    max_depth: u32,
+}
+
+impl CodeSynthesizer {
+    /// Creates a synthesizer for the body of a function with the given
+    /// parameter and local types, within the module being mutated by
+    /// `config`.
+    pub(crate) fn new(
+        config: &WasmMutate,
+        locals: Vec<PrimitiveTypeInfo>,
+        max_depth: u32,
+    ) -> CodeSynthesizer {
+        let info = config.info();
+        let globals = info
+            .global_types
+            .iter()
+            .copied()
+            .zip(info.global_mutability.iter().copied())
+            .collect();
+        let funcs = (0..info.num_functions())
+            .map(|i| match info.get_functype_idx(i) {
+                TypeInfo::Func(f) => f.clone(),
+            })
+            .collect();
+        CodeSynthesizer {
+            locals,
+            globals,
+            funcs,
+            pure: config.preserve_semantics,
+            max_depth,
+        }
+    }
+
+    /// Appends instructions to `out` which pop values of the `params` types
+    /// off of the stack and then push values of the `results` types.
+    ///
+    /// If both `params` and `results` are empty a single statement is
+    /// generated, so the sequence is never empty.
+    pub(crate) fn sequence(
+        &self,
+        rng: &mut impl Rng,
+        params: &[PrimitiveTypeInfo],
+        results: &[PrimitiveTypeInfo],
+        out: &mut Vec<Instruction<'static>>,
+    ) {
+        for ty in params.iter().rev() {
+            self.consume(rng, *ty, out);
+        }
+        if params.is_empty() && results.is_empty() {
+            self.statement(rng, out);
+        }
+        for ty in results {
+            self.expr(rng, *ty, 0, out);
+        }
+    }
+
+    /// Appends a stack-neutral instruction sequence to `out`.
+    fn statement(&self, rng: &mut impl Rng, out: &mut Vec<Instruction<'static>>) {
+        if !self.pure && !self.funcs.is_empty() && rng.gen_ratio(1, 4) {
+            let idx = rng.gen_range(0..self.funcs.len());
+            let ty = &self.funcs[idx];
+            for param in ty.params.iter() {
+                self.expr(rng, *param, 1, out);
+            }
+            out.push(Instruction::Call(idx as u32));
+            for ty in ty.returns.iter().rev() {
+                self.consume(rng, *ty, out);
+            }
+            return;
+        }
+
+        // Prefer types that values are already available for, but otherwise
+        // stick to the MVP value types so no new proposals are required.
+        let available = self
+            .locals
+            .iter()
+            .chain(self.globals.iter().map(|(ty, _)| ty))
+            .copied()
+            .collect::<Vec<_>>();
+        let ty = match available.choose(rng) {
+            Some(ty) if rng.gen() => *ty,
+            _ => *[I32, I64, F32, F64].choose(rng).unwrap(),
+        };
+        self.expr(rng, ty, 0, out);
+        self.consume(rng, ty, out);
+    }
+
+    /// Appends instructions to `out` which pop a value of type `ty` off of
+    /// the stack.
+    fn consume(
+        &self,
+        rng: &mut impl Rng,
+        ty: PrimitiveTypeInfo,
+        out: &mut Vec<Instruction<'static>>,
+    ) {
+        let mut choices = vec![Instruction::Drop];
+        if !self.pure {
+            choices.extend(
+                self.locals
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, t)| **t == ty)
+                    .map(|(i, _)| Instruction::LocalSet(i as u32)),
+            );
+            choices.extend(
+                self.globals
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (t, mutable))| *mutable && *t == ty)
+                    .map(|(i, _)| Instruction::GlobalSet(i as u32)),
+            );
+        }
+        out.push(choices.choose(rng).unwrap().clone());
+    }
+
+    /// Appends instructions to `out` which push a single value of type `ty`.
+    fn expr(
+        &self,
+        rng: &mut impl Rng,
+        ty: PrimitiveTypeInfo,
+        depth: u32,
+        out: &mut Vec<Instruction<'static>>,
+    ) {
+        let numeric = matches!(ty, I32 | I64 | F32 | F64);
+        if depth >= self.max_depth || !numeric || rng.gen() {
+            return self.leaf(rng, ty, out);
+        }
+        if rng.gen_ratio(1, 8) {
+            self.expr(rng, ty, depth + 1, out);
+            self.expr(rng, ty, depth + 1, out);
+            self.expr(rng, I32, depth + 1, out);
+            out.push(Instruction::Select);
+            return;
+        }
+        let (insn, operands) = operator(rng, ty);
+        for operand in operands {
+            self.expr(rng, *operand, depth + 1, out);
+        }
+        out.push(insn);
+    }
+
+    /// Appends a single instruction to `out` which reads a local or global of
+    /// type `ty` or pushes a constant of that type.
+    fn leaf(&self, rng: &mut impl Rng, ty: PrimitiveTypeInfo, out: &mut Vec<Instruction<'static>>) {
+        let mut choices = self
+            .locals
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| **t == ty)
+            .map(|(i, _)| Instruction::LocalGet(i as u32))
+            .collect::<Vec<_>>();
+        choices.extend(
+            self.globals
+                .iter()
+                .enumerate()
+                .filter(|(_, (t, _))| *t == ty)
+                .map(|(i, _)| Instruction::GlobalGet(i as u32)),
+        );
+        match choices.choose(rng) {
+            Some(insn) if rng.gen() => out.push(insn.clone()),
+            _ => out.push(constant(rng, ty)),
+        }
+    }
+}
+
+/// Returns a random constant instruction of type `ty`, biased towards
+/// interesting values.
+fn constant(rng: &mut impl Rng, ty: PrimitiveTypeInfo) -> Instruction<'static> {
+    let interesting = rng.gen();
+    match ty {
+        I32 if interesting => {
+            Instruction::I32Const(*[0, 1, -1, i32::MIN, i32::MAX].choose(rng).unwrap())
+        }
+        I32 => Instruction::I32Const(rng.gen()),
+        I64 if interesting => {
+            Instruction::I64Const(*[0, 1, -1, i64::MIN, i64::MAX].choose(rng).unwrap())
+        }
+        I64 => Instruction::I64Const(rng.gen()),
+        F32 if interesting => Instruction::F32Const(
+            *[0.0, -0.0, 1.0, f32::NAN, f32::INFINITY, f32::NEG_INFINITY]
+                .choose(rng)
+                .unwrap(),
+        ),
+        F32 => Instruction::F32Const(f32::from_bits(rng.gen())),
+        F64 if interesting => Instruction::F64Const(
+            *[0.0, -0.0, 1.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY]
+                .choose(rng)
+                .unwrap(),
+        ),
+        F64 => Instruction::F64Const(f64::from_bits(rng.gen())),
+        V128 => Instruction::V128Const(rng.gen()),
+        FuncRef => Instruction::RefNull(HeapType::Func),
+        ExternRef => Instruction::RefNull(HeapType::Extern),
+        PrimitiveTypeInfo::Empty => unreachable!("no values have the empty type"),
+    }
+}
+
+/// Returns a random non-trapping numeric operator which produces a value of
+/// type `ty`, along with the types of its operands.
+fn operator(
+    rng: &mut impl Rng,
+    ty: PrimitiveTypeInfo,
+) -> (Instruction<'static>, &'static [PrimitiveTypeInfo]) {
+    use Instruction::*;
+
+    const UN_I32: &[PrimitiveTypeInfo] = &[I32];
+    const BIN_I32: &[PrimitiveTypeInfo] = &[I32, I32];
+    const UN_I64: &[PrimitiveTypeInfo] = &[I64];
+    const BIN_I64: &[PrimitiveTypeInfo] = &[I64, I64];
+    const UN_F32: &[PrimitiveTypeInfo] = &[F32];
+    const BIN_F32: &[PrimitiveTypeInfo] = &[F32, F32];
+    const UN_F64: &[PrimitiveTypeInfo] = &[F64];
+    const BIN_F64: &[PrimitiveTypeInfo] = &[F64, F64];
+
+    let choices = match ty {
+        I32 => vec![
+            (I32Eqz, UN_I32),
+            (I32Clz, UN_I32),
+            (I32Ctz, UN_I32),
+            (I32Popcnt, UN_I32),
+            (I32Add, BIN_I32),
+            (I32Sub, BIN_I32),
+            (I32Mul, BIN_I32),
+            (I32And, BIN_I32),
+            (I32Or, BIN_I32),
+            (I32Xor, BIN_I32),
+            (I32Shl, BIN_I32),
+            (I32ShrS, BIN_I32),
+            (I32ShrU, BIN_I32),
+            (I32Rotl, BIN_I32),
+            (I32Rotr, BIN_I32),
+            (I32Eq, BIN_I32),
+            (I32Ne, BIN_I32),
+            (I32LtS, BIN_I32),
+            (I32LtU, BIN_I32),
+            (I32GtS, BIN_I32),
+            (I32GeU, BIN_I32),
+            (I32WrapI64, UN_I64),
+            (I64Eqz, UN_I64),
+            (I64Eq, BIN_I64),
+            (I64LtS, BIN_I64),
+            (I64GtU, BIN_I64),
+            (I32ReinterpretF32, UN_F32),
+            (F32Eq, BIN_F32),
+            (F32Lt, BIN_F32),
+            (F64Ne, BIN_F64),
+            (F64Ge, BIN_F64),
+        ],
+        I64 => vec![
+            (I64Clz, UN_I64),
+            (I64Ctz, UN_I64),
+            (I64Popcnt, UN_I64),
+            (I64Add, BIN_I64),
+            (I64Sub, BIN_I64),
+            (I64Mul, BIN_I64),
+            (I64And, BIN_I64),
+            (I64Or, BIN_I64),
+            (I64Xor, BIN_I64),
+            (I64Shl, BIN_I64),
+            (I64ShrS, BIN_I64),
+            (I64ShrU, BIN_I64),
+            (I64Rotl, BIN_I64),
+            (I64Rotr, BIN_I64),
+            (I64ExtendI32S, UN_I32),
+            (I64ExtendI32U, UN_I32),
+            (I64ReinterpretF64, UN_F64),
+        ],
+        F32 => vec![
+            (F32Abs, UN_F32),
+            (F32Neg, UN_F32),
+            (F32Ceil, UN_F32),
+            (F32Floor, UN_F32),
+            (F32Trunc, UN_F32),
+            (F32Nearest, UN_F32),
+            (F32Sqrt, UN_F32),
+            (F32Add, BIN_F32),
+            (F32Sub, BIN_F32),
+            (F32Mul, BIN_F32),
+            (F32Div, BIN_F32),
+            (F32Min, BIN_F32),
+            (F32Max, BIN_F32),
+            (F32Copysign, BIN_F32),
+            (F32ConvertI32S, UN_I32),
+            (F32ConvertI32U, UN_I32),
+            (F32ConvertI64S, UN_I64),
+            (F32ConvertI64U, UN_I64),
+            (F32ReinterpretI32, UN_I32),
+            (F32DemoteF64, UN_F64),
+        ],
+        F64 => vec![
+            (F64Abs, UN_F64),
+            (F64Neg, UN_F64),
+            (F64Ceil, UN_F64),
+            (F64Floor, UN_F64),
+            (F64Trunc, UN_F64),
+            (F64Nearest, UN_F64),
+            (F64Sqrt, UN_F64),
+            (F64Add, BIN_F64),
+            (F64Sub, BIN_F64),
+            (F64Mul, BIN_F64),
+            (F64Div, BIN_F64),
+            (F64Min, BIN_F64),
+            (F64Max, BIN_F64),
+            (F64Copysign, BIN_F64),
+            (F64ConvertI32S, UN_I32),
+            (F64ConvertI32U, UN_I32),
+            (F64ConvertI64S, UN_I64),
+            (F64ConvertI64U, UN_I64),
+            (F64ReinterpretI64, UN_I64),
+            (F64PromoteF32, UN_F32),
+        ],
+        _ => unreachable!("only numeric types have operators"),
+    };
+    choices.choose(rng).unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CodeSynthesizer;
+    use crate::module::PrimitiveTypeInfo::{self, F32, F64, I32, I64};
+    use crate::WasmMutate;
+    use rand::{rngs::SmallRng, SeedableRng};
+    use wasm_encoder::{
+        CodeSection, Function, FunctionSection, Instruction, Module, RawSection, SectionId,
+        TypeSection, ValType,
+    };
+
+    /// Synthesizes many sequences with various signatures inside the body of
+    /// the single function of a module, and checks that they all validate.
+    fn check_sequences(preserve_semantics: bool) {
+        let wasm = wat::parse_str(
+            r#"
+            (module
+                (global (mut i64) (i64.const 0))
+                (global f32 (f32.const 0))
+                (func (param i32 f64) (result i32)
+                    (local i64)
+                    i32.const 0
+                )
+            )
+        "#,
+        )
+        .unwrap();
+        let mut config = WasmMutate::default();
+        config.preserve_semantics(preserve_semantics);
+        config.setup(&wasm).unwrap();
+        let synth = CodeSynthesizer::new(&config, vec![I32, F64, I64], 4);
+        let mut rng = SmallRng::seed_from_u64(0);
+
+        let signatures: &[(&[PrimitiveTypeInfo], &[PrimitiveTypeInfo])] = &[
+            (&[], &[]),
+            (&[], &[I32]),
+            (&[I64], &[]),
+            (&[F32, I32], &[F64, F64]),
+        ];
+        for _ in 0..100 {
+            for (params, results) in signatures {
+                let mut types = TypeSection::new();
+                types.function([ValType::I32, ValType::F64], [ValType::I32]);
+                let mut funcs = FunctionSection::new();
+                funcs.function(0);
+                let mut func = Function::new([(1, ValType::I64)]);
+                let mut out = Vec::new();
+                synth.sequence(&mut rng, params, results, &mut out);
+                assert!(!out.is_empty());
+                if preserve_semantics {
+                    assert!(!out.iter().any(|insn| matches!(
+                        insn,
+                        Instruction::Call(_) | Instruction::LocalSet(_) | Instruction::GlobalSet(_)
+                    )));
+                }
+
+                // Feed the sequence its parameters with constants and drop
+                // its results.
+                for ty in params.iter() {
+                    func.instruction(&match ty {
+                        I32 => Instruction::I32Const(0),
+                        I64 => Instruction::I64Const(0),
+                        F32 => Instruction::F32Const(0.0),
+                        _ => Instruction::F64Const(0.0),
+                    });
+                }
+                for insn in out.iter() {
+                    func.instruction(insn);
+                }
+                for _ in results.iter() {
+                    func.instruction(&Instruction::Drop);
+                }
+                func.instruction(&Instruction::I32Const(0));
+                func.instruction(&Instruction::End);
+                let mut code = CodeSection::new();
+                code.function(&func);
+
+                let mut module = Module::new();
+                module.section(&types);
+                module.section(&funcs);
+                module.section(&RawSection {
+                    id: SectionId::Global.into(),
+                    data: config.info().get_global_section().data,
+                });
+                module.section(&code);
+                crate::validate(&module.finish());
+            }
+        }
+    }
+
+    #[test]
+    fn sequences_are_valid() {
+        check_sequences(false);
+    }
+
+    #[test]
+    fn pure_sequences_are_valid() {
+        check_sequences(true);
+    }
+}
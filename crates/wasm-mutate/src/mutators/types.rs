@@ -0,0 +1,383 @@
+//! Mutators which add or reorder entries of the type section without
+//! changing what the module means.
+//!
+//! Function types are compared structurally, so a module which refers to an
+//! identical copy of a type, or which lists its types in a different order,
+//! is equivalent to the original. Both mutators here rewrite every type index
+//! in the module, including those in typed references such as `(ref $t)` and
+//! `call_ref`, which exercises how engines canonicalize function types and
+//! compare them for equality, for example in `call_indirect`.
+
+use crate::mutators::translate::ConstExprKind;
+use crate::mutators::{Item, Mutator, Translator};
+use crate::{Error, ModuleInfo, Result, WasmMutate};
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use wasm_encoder::*;
+use wasmparser::{
+    CodeSectionReader, ElementSectionReader, FunctionSectionReader, GlobalSectionReader,
+    ImportSectionReader, TableInit, TableSectionReader, TagSectionReader, TypeSectionReader,
+};
+
+/// Mutator that appends a copy of a random type to the type section and then
+/// changes a random subset of the uses of that type to use the copy instead.
+#[derive(Copy, Clone)]
+pub struct DuplicateTypeMutator;
+
+impl Mutator for DuplicateTypeMutator {
+    fn can_mutate(&self, config: &WasmMutate) -> bool {
+        !config.reduce && config.info().num_types() > 0
+    }
+
+    fn mutate<'a>(
+        &self,
+        config: &'a mut WasmMutate,
+    ) -> Result<Box<dyn Iterator<Item = Result<Module>> + 'a>> {
+        let num_types = config.info().num_types();
+        let original = config.rng().gen_range(0..num_types);
+        let seed = config.rng().gen();
+        log::trace!("duplicating type {}", original);
+
+        let module = RemapTypes {
+            order: (0..num_types).chain([original]).collect(),
+            map: (0..num_types).collect(),
+            duplicate: Some((original, num_types)),
+            defining: None,
+            rng: SmallRng::seed_from_u64(seed),
+        }
+        .rewrite(config.info())?;
+        Ok(Box::new(std::iter::once(Ok(module))))
+    }
+}
+
+/// Mutator that shuffles the entries of the type section, renumbering all
+/// uses of types to match.
+#[derive(Copy, Clone)]
+pub struct PermuteTypesMutator;
+
+impl Mutator for PermuteTypesMutator {
+    fn can_mutate(&self, config: &WasmMutate) -> bool {
+        config.info().num_types() > 1
+    }
+
+    fn mutate<'a>(
+        &self,
+        config: &'a mut WasmMutate,
+    ) -> Result<Box<dyn Iterator<Item = Result<Module>> + 'a>> {
+        let num_types = config.info().num_types();
+        let mut order = (0..num_types).collect::<Vec<_>>();
+        order.shuffle(config.rng());
+        let mut map = vec![0; num_types as usize];
+        for (new, old) in order.iter().enumerate() {
+            map[*old as usize] = new as u32;
+        }
+        log::trace!("permuting types to {:?}", order);
+
+        let module = RemapTypes {
+            order,
+            map,
+            duplicate: None,
+            defining: None,
+            rng: SmallRng::seed_from_u64(0),
+        }
+        .rewrite(config.info())?;
+        Ok(Box::new(std::iter::once(Ok(module))))
+    }
+}
+
+struct RemapTypes {
+    /// The original index of each type in the new type section.
+    order: Vec<u32>,
+    /// The new index of each original type.
+    map: Vec<u32>,
+    /// An original type and the index of its copy, uses of which are
+    /// randomly redirected to the copy.
+    duplicate: Option<(u32, u32)>,
+    /// The new index of the type being defined while translating the type
+    /// section, which may only refer to types defined before it.
+    defining: Option<u32>,
+    rng: SmallRng,
+}
+
+impl RemapTypes {
+    fn rewrite(&mut self, info: &ModuleInfo) -> Result<Module> {
+        let mut module = Module::new();
+        for section in info.raw_sections.iter() {
+            crate::module::match_section_id! {
+                match section.id;
+
+                Custom => {
+                    module.section(section);
+                },
+
+                Type => {
+                    let types = TypeSectionReader::new(section.data, 0)?
+                        .into_iter()
+                        .collect::<Result<Vec<_>, _>>()?;
+                    let mut result = TypeSection::new();
+                    for (new, old) in self.order.clone().into_iter().enumerate() {
+                        self.defining = Some(new as u32);
+                        self.translate_type_def(types[old as usize].clone(), &mut result)?;
+                    }
+                    self.defining = None;
+                    module.section(&result);
+                },
+
+                Import => {
+                    let mut result = ImportSection::new();
+                    for item in ImportSectionReader::new(section.data, 0)? {
+                        let item = item?;
+                        let ty: EntityType = match &item.ty {
+                            wasmparser::TypeRef::Func(ty) => {
+                                EntityType::Function(self.remap(Item::Type, *ty)?)
+                            }
+                            wasmparser::TypeRef::Table(ty) => self.translate_table_type(ty)?.into(),
+                            wasmparser::TypeRef::Memory(ty) => {
+                                self.translate_memory_type(ty)?.into()
+                            }
+                            wasmparser::TypeRef::Global(ty) => {
+                                self.translate_global_type(ty)?.into()
+                            }
+                            wasmparser::TypeRef::Tag(ty) => self.translate_tag_type(ty)?.into(),
+                        };
+                        result.import(item.module, item.name, ty);
+                    }
+                    module.section(&result);
+                },
+
+                Function => {
+                    let mut result = FunctionSection::new();
+                    for ty in FunctionSectionReader::new(section.data, 0)? {
+                        result.function(self.remap(Item::Type, ty?)?);
+                    }
+                    module.section(&result);
+                },
+
+                Table => {
+                    let mut result = TableSection::new();
+                    for table in TableSectionReader::new(section.data, 0)? {
+                        let table = table?;
+                        let ty = self.translate_table_type(&table.ty)?;
+                        match &table.init {
+                            TableInit::RefNull => {
+                                result.table(ty);
+                            }
+                            TableInit::Expr(expr) => {
+                                let init = self.translate_const_expr(
+                                    expr,
+                                    &table.ty.element_type.into(),
+                                    ConstExprKind::TableInit,
+                                )?;
+                                result.table_with_init(ty, &init);
+                            }
+                        }
+                    }
+                    module.section(&result);
+                },
+
+                Memory => {
+                    module.section(section);
+                },
+
+                Global => {
+                    let mut result = GlobalSection::new();
+                    for global in GlobalSectionReader::new(section.data, 0)? {
+                        self.translate_global(global?, &mut result)?;
+                    }
+                    module.section(&result);
+                },
+
+                Export => {
+                    module.section(section);
+                },
+
+                Start => {
+                    module.section(section);
+                },
+
+                Element => {
+                    let mut result = ElementSection::new();
+                    for element in ElementSectionReader::new(section.data, 0)? {
+                        self.translate_element(element?, &mut result)?;
+                    }
+                    module.section(&result);
+                },
+
+                Code => {
+                    let mut result = CodeSection::new();
+                    for body in CodeSectionReader::new(section.data, 0)? {
+                        self.translate_code(body?, &mut result)?;
+                    }
+                    module.section(&result);
+                },
+
+                Data => {
+                    module.section(section);
+                },
+
+                DataCount => {
+                    module.section(section);
+                },
+
+                Tag => {
+                    let mut result = TagSection::new();
+                    for tag in TagSectionReader::new(section.data, 0)? {
+                        result.tag(self.translate_tag_type(&tag?)?);
+                    }
+                    module.section(&result);
+                },
+                _ => panic!("unknown id: {}", section.id),
+            }
+        }
+        Ok(module)
+    }
+}
+
+impl Translator for RemapTypes {
+    fn as_obj(&mut self) -> &mut dyn Translator {
+        self
+    }
+
+    fn remap(&mut self, item: Item, idx: u32) -> Result<u32> {
+        if item != Item::Type {
+            return Ok(idx);
+        }
+        if let Some((original, copy)) = self.duplicate {
+            if idx == original && self.defining.is_none() && self.rng.gen() {
+                return Ok(copy);
+            }
+        }
+        let new = self.map[idx as usize];
+        match self.defining {
+            // Types can only refer to types defined before them, so a
+            // permutation which would move a referenced type after its use
+            // isn't applicable.
+            Some(defining) if new >= defining => Err(Error::no_mutations_applicable()),
+            _ => Ok(new),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DuplicateTypeMutator, PermuteTypesMutator, RemapTypes};
+    use crate::mutators::Translator;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+    use wasm_encoder::HeapType;
+
+    #[test]
+    fn test_duplicate_type() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (type (func (param i32)))
+                (import "m" "f" (func (type 0)))
+                (func (type 0))
+            )
+            "#,
+            DuplicateTypeMutator,
+            r#"
+            (module
+                (type (func (param i32)))
+                (type (func (param i32)))
+                (import "m" "f" (func (type 0)))
+                (func (type 1))
+            )
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_duplicate_type_block_type() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (type (func (param i32) (result i32)))
+                (func (type 0)
+                    local.get 0
+                    block (type 0)
+                    end
+                )
+            )
+            "#,
+            DuplicateTypeMutator,
+            r#"
+            (module
+                (type (func (param i32) (result i32)))
+                (type (func (param i32) (result i32)))
+                (func (type 0)
+                    local.get 0
+                    block (type 1)
+                    end
+                )
+            )
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_permute_types() {
+        crate::mutators::match_mutation(
+            r#"
+            (module
+                (type (func))
+                (type (func (param i32)))
+                (table 1 funcref)
+                (func (type 0)
+                    i32.const 0
+                    i32.const 0
+                    call_indirect (type 1)
+                )
+                (func (type 1))
+            )
+            "#,
+            PermuteTypesMutator,
+            r#"
+            (module
+                (type (func (param i32)))
+                (type (func))
+                (table 1 funcref)
+                (func (type 1)
+                    i32.const 0
+                    i32.const 0
+                    call_indirect (type 0)
+                )
+                (func (type 0))
+            )
+            "#,
+        );
+    }
+
+    #[test]
+    fn test_remap_heap_types() {
+        // Typed references are remapped like every other use of a type, so
+        // `(ref $t)` and `call_ref $t` agree with the rest of the module.
+        let heap_type = wasmparser::HeapType::TypedFunc(0.try_into().unwrap());
+        let mut permute = RemapTypes {
+            order: vec![1, 0],
+            map: vec![1, 0],
+            duplicate: None,
+            defining: None,
+            rng: SmallRng::seed_from_u64(0),
+        };
+        assert_eq!(
+            permute.translate_heapty(&heap_type).unwrap(),
+            HeapType::TypedFunc(1)
+        );
+
+        let mut duplicate = RemapTypes {
+            order: vec![0, 0],
+            map: vec![0],
+            duplicate: Some((0, 1)),
+            defining: None,
+            rng: SmallRng::seed_from_u64(0),
+        };
+        let remapped = (0..100)
+            .map(|_| duplicate.translate_heapty(&heap_type).unwrap())
+            .collect::<Vec<_>>();
+        assert!(remapped.contains(&HeapType::TypedFunc(0)));
+        assert!(remapped.contains(&HeapType::TypedFunc(1)));
+    }
+}
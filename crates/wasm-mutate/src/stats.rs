@@ -0,0 +1,57 @@
+//! Statistics about the mutators used over a fuzzing campaign.
+
+use std::collections::BTreeMap;
+
+/// Statistics collected across runs of [`WasmMutate`](crate::WasmMutate)
+/// configured with [`WasmMutate::stats`](crate::WasmMutate::stats).
+///
+/// Fuzzers can use these to find mutators which are rarely applicable or
+/// which often fail, for example to tune which mutations are worthwhile for
+/// their inputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// The number of runs recorded.
+    pub runs: u64,
+    /// Statistics for each mutator, keyed by the mutator's name.
+    pub mutators: BTreeMap<String, MutatorStats>,
+}
+
+/// Statistics about a single mutator, part of [`Stats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MutatorStats {
+    /// The number of runs in which this mutator could have been used for the
+    /// input.
+    pub applicable: u64,
+    /// The number of runs in which this mutator was chosen. Only one mutator
+    /// is chosen in each run, and a run which can't use its chosen mutator
+    /// fails rather than trying another.
+    pub chosen: u64,
+    /// The number of times this mutator was chosen and successfully produced
+    /// mutations, rather than returning an error.
+    pub succeeded: u64,
+    /// The number of mutated modules this mutator produced which validated.
+    pub valid: u64,
+    /// The number of mutated modules this mutator produced which failed to
+    /// validate.
+    pub invalid: u64,
+}
+
+/// Returns whether a mutated module validates.
+pub(crate) fn validates(wasm: &[u8]) -> bool {
+    let mut validator = wasmparser::Validator::new_with_features(crate::validator_features());
+    validator.validate_all(wasm).is_ok()
+}
+
+impl Stats {
+    /// Returns the statistics of the mutator called `name`, creating them if
+    /// necessary.
+    pub(crate) fn mutator(&mut self, name: &str) -> &mut MutatorStats {
+        if !self.mutators.contains_key(name) {
+            self.mutators
+                .insert(name.to_string(), MutatorStats::default());
+        }
+        self.mutators.get_mut(name).unwrap()
+    }
+}
@@ -68,3 +68,102 @@ fn integration_test() {
         elapsed.subsec_millis()
     );
 }
+
+fn function_bodies(wasm: &[u8]) -> Vec<Vec<u8>> {
+    for payload in wasmparser::Parser::new(0).parse_all(wasm) {
+        if let wasmparser::Payload::CodeSectionStart { range, .. } = payload.unwrap() {
+            let reader = wasmparser::CodeSectionReader::new(&wasm[range.clone()], range.start);
+            return reader
+                .unwrap()
+                .into_iter()
+                .map(|body| wasm[body.unwrap().range()].to_vec())
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+#[test]
+fn restrict_to_functions() {
+    let _ = env_logger::try_init();
+
+    let wat = r#"
+        (module
+            (import "" "" (func))
+            (func (result i32)
+                i32.const 1
+                i32.const 2
+                i32.add)
+            (func (result i32)
+                i32.const 3
+                i32.const 4
+                i32.add)
+            (func (result i32)
+                i32.const 5
+                i32.const 6
+                i32.add)
+        )
+    "#;
+    let original = &wat::parse_str(wat).unwrap();
+    let original_bodies = function_bodies(original);
+
+    // Function 2 is the second defined function since function 0 is imported.
+    let mut mutated_any = false;
+    for seed in 0..100 {
+        let mut mutator = WasmMutate::default();
+        mutator.seed(seed).restrict_to_functions(&[2]);
+        let mutated = match mutator.run(original) {
+            Ok(mut it) => match it.next() {
+                Some(Ok(mutated)) => mutated,
+                Some(Err(e)) if matches!(e.kind(), ErrorKind::NoMutationsApplicable) => continue,
+                Some(Err(e)) => panic!("{}", e),
+                None => continue,
+            },
+            Err(e) if matches!(e.kind(), ErrorKind::NoMutationsApplicable) => continue,
+            Err(e) => panic!("{}", e),
+        };
+        let bodies = function_bodies(&mutated);
+        assert_eq!(bodies.len(), 3);
+        assert_eq!(bodies[0], original_bodies[0]);
+        assert_eq!(bodies[2], original_bodies[2]);
+        mutated_any |= bodies[1] != original_bodies[1];
+    }
+    assert!(mutated_any);
+}
+
+#[test]
+fn restrict_to_range() {
+    let wat = r#"
+        (module
+            (func (result i32)
+                i32.const 1
+                i32.const 2
+                i32.add)
+            (func (result i32)
+                i32.const 3
+                i32.const 4
+                i32.add)
+        )
+    "#;
+    let original = &wat::parse_str(wat).unwrap();
+    let original_bodies = function_bodies(original);
+
+    // Only the last byte of the module, which is the `end` of the last
+    // function, is in range.
+    let range = original.len() - 1..original.len();
+    for seed in 0..100 {
+        let mut mutator = WasmMutate::default();
+        mutator.seed(seed).restrict_to_range(range.clone());
+        let mutated = match mutator
+            .run(original)
+            .and_then(|mut it| it.next().transpose())
+        {
+            Ok(Some(mutated)) => mutated,
+            Ok(None) => continue,
+            Err(e) if matches!(e.kind(), ErrorKind::NoMutationsApplicable) => continue,
+            Err(e) => panic!("{}", e),
+        };
+        let bodies = function_bodies(&mutated);
+        assert_eq!(bodies[0], original_bodies[0]);
+    }
+}
@@ -119,6 +119,14 @@ pub struct Module {
     /// The predicted size of the effective type of this module, based on this
     /// module's size of the types of imports/exports.
     type_size: u32,
+
+    /// The address and length of the entropy that this module was generated
+    /// from, which `decisions` are relative to.
+    entropy_start: usize,
+    entropy_len: usize,
+
+    /// The parts of the entropy consumed for each part of this module.
+    decisions: Vec<Decision>,
 }
 
 impl<'a> Arbitrary<'a> for Module {
@@ -196,6 +204,9 @@ impl Module {
             code: Vec::new(),
             data: Vec::new(),
             type_size: 0,
+            entropy_start: 0,
+            entropy_len: 0,
+            decisions: Vec::new(),
         }
     }
 }
@@ -233,6 +244,61 @@ impl<'a> Arbitrary<'a> for MaybeInvalidModule {
     }
 }
 
+/// A record of the input entropy that was consumed to generate one part of a
+/// module.
+///
+/// Generation consumes entropy from the front of the input in order, so a
+/// shrinker can use these ranges to predictably make a module structurally
+/// smaller. Truncating the input at the start of a decision's range causes that
+/// part of the module, and everything generated after it, to be generated
+/// minimally. Zeroing the bytes in a range makes loops in that part of the
+/// module stop as early as their configured minimums allow.
+///
+/// Ranges are offsets relative to the first byte of entropy given to
+/// [`Module::new`]. For [`Module`]'s own `Arbitrary` implementation this is the
+/// start of the input, but other configurations may consume entropy for
+/// themselves before generating the module. Lengths, such as those of names
+/// and data segments, are taken from the end of the input and are not
+/// included in these ranges.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decision {
+    /// What was generated from this part of the entropy.
+    pub kind: DecisionKind,
+    /// The range of entropy consumed.
+    pub range: Range<usize>,
+}
+
+/// The part of a module that a [`Decision`] generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecisionKind {
+    /// The set of types.
+    Types,
+    /// The set of imports.
+    Imports,
+    /// The set of locally-defined tags.
+    Tags,
+    /// The set of locally-defined functions and their types.
+    Funcs,
+    /// The set of locally-defined tables.
+    Tables,
+    /// The set of locally-defined memories.
+    Memories,
+    /// The set of locally-defined globals.
+    Globals,
+    /// The set of exports.
+    Exports,
+    /// The start function.
+    Start,
+    /// The set of element segments.
+    Elems,
+    /// The set of data segments.
+    Data,
+    /// The body of the locally-defined function with the given index in the
+    /// function index space.
+    FuncBody(u32),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub(crate) enum Type {
     Func(Rc<FuncType>),
@@ -345,35 +411,77 @@ pub(crate) enum GlobalInitExpr {
 impl Module {
     fn build(&mut self, u: &mut Unstructured, allow_invalid: bool) -> Result<()> {
         self.valtypes = configured_valtypes(&*self.config);
+        self.entropy_start = u.peek_bytes(0).unwrap().as_ptr() as usize;
+        self.entropy_len = u.len();
 
         // We attempt to figure out our available imports *before* creating the types section here,
         // because the types for the imports are already well-known (specified by the user) and we
         // must have those populated for all function/etc. imports, no matter what.
         //
         // This can affect the available capacity for types and such.
-        if self.arbitrary_imports_from_available(u)? {
-            self.arbitrary_types(u)?;
+        if self.record(u, DecisionKind::Imports, |m, u| {
+            m.arbitrary_imports_from_available(u)
+        })? {
+            self.record(u, DecisionKind::Types, |m, u| m.arbitrary_types(u))?;
         } else {
-            self.arbitrary_types(u)?;
-            self.arbitrary_imports(u)?;
+            self.record(u, DecisionKind::Types, |m, u| m.arbitrary_types(u))?;
+            self.record(u, DecisionKind::Imports, |m, u| m.arbitrary_imports(u))?;
         }
 
         self.should_encode_types = !self.types.is_empty() || u.arbitrary()?;
         self.should_encode_imports = !self.imports.is_empty() || u.arbitrary()?;
 
-        self.arbitrary_tags(u)?;
-        self.arbitrary_funcs(u)?;
-        self.arbitrary_tables(u)?;
-        self.arbitrary_memories(u)?;
-        self.arbitrary_globals(u)?;
-        self.arbitrary_exports(u)?;
-        self.arbitrary_start(u)?;
-        self.arbitrary_elems(u)?;
-        self.arbitrary_data(u)?;
+        self.record(u, DecisionKind::Tags, |m, u| m.arbitrary_tags(u))?;
+        self.record(u, DecisionKind::Funcs, |m, u| m.arbitrary_funcs(u))?;
+        self.record(u, DecisionKind::Tables, |m, u| m.arbitrary_tables(u))?;
+        self.record(u, DecisionKind::Memories, |m, u| m.arbitrary_memories(u))?;
+        self.record(u, DecisionKind::Globals, |m, u| m.arbitrary_globals(u))?;
+        self.record(u, DecisionKind::Exports, |m, u| m.arbitrary_exports(u))?;
+        self.record(u, DecisionKind::Start, |m, u| m.arbitrary_start(u))?;
+        self.record(u, DecisionKind::Elems, |m, u| m.arbitrary_elems(u))?;
+        self.record(u, DecisionKind::Data, |m, u| m.arbitrary_data(u))?;
         self.arbitrary_code(u, allow_invalid)?;
         Ok(())
     }
 
+    /// Runs `f` to generate one part of this module, recording the range of
+    /// entropy that it consumed as a `Decision` of the given `kind`.
+    fn record<T>(
+        &mut self,
+        u: &mut Unstructured,
+        kind: DecisionKind,
+        f: impl FnOnce(&mut Self, &mut Unstructured) -> Result<T>,
+    ) -> Result<T> {
+        let start = self.entropy_offset(u);
+        let ret = f(self, u)?;
+        let end = self.entropy_offset(u);
+        self.decisions.push(Decision {
+            kind,
+            range: start..end,
+        });
+        Ok(ret)
+    }
+
+    /// Returns the offset of the next byte that `u` will consume from the
+    /// front of its data, relative to where this module's entropy started.
+    fn entropy_offset(&self, u: &Unstructured) -> usize {
+        // Exhausted data may no longer point into the original input, so
+        // treat it as being at the end of it.
+        if u.is_empty() {
+            return self.entropy_len;
+        }
+        u.peek_bytes(0).unwrap().as_ptr() as usize - self.entropy_start
+    }
+
+    /// Returns the record of which parts of the input entropy were used to
+    /// generate which parts of this module, in the order that they were
+    /// generated.
+    ///
+    /// See [`Decision`] for how this can be used to shrink the input.
+    pub fn decisions(&self) -> &[Decision] {
+        &self.decisions
+    }
+
     fn arbitrary_types(&mut self, u: &mut Unstructured) -> Result<()> {
         // NB: It isn't guaranteed that `self.types.is_empty()` because when
         // available imports are configured, we may add eagerly specigfic types
@@ -675,6 +783,7 @@ impl Module {
                         maximum: memory_ty.maximum,
                         memory64: memory_ty.memory64,
                         shared: memory_ty.shared,
+                        page_size_log2: None,
                     };
                     let entity = EntityType::Memory(memory_ty);
                     let type_size = entity.size();
@@ -1168,9 +1277,16 @@ impl Module {
     fn arbitrary_code(&mut self, u: &mut Unstructured, allow_invalid: bool) -> Result<()> {
         self.code.reserve(self.num_defined_funcs);
         let mut allocs = CodeBuilderAllocations::new(self);
-        for (_, ty) in self.funcs[self.funcs.len() - self.num_defined_funcs..].iter() {
+        let first = self.funcs.len() - self.num_defined_funcs;
+        for (i, (_, ty)) in self.funcs[first..].iter().enumerate() {
+            let start = self.entropy_offset(u);
             let body = self.arbitrary_func_body(u, ty, &mut allocs, allow_invalid)?;
+            let end = self.entropy_offset(u);
             self.code.push(body);
+            self.decisions.push(Decision {
+                kind: DecisionKind::FuncBody((first + i) as u32),
+                range: start..end,
+            });
         }
         Ok(())
     }
@@ -1467,6 +1583,7 @@ pub(crate) fn arbitrary_memtype(u: &mut Unstructured, config: &dyn Config) -> Re
         maximum,
         memory64,
         shared,
+        page_size_log2: None,
     })
 }
 
@@ -59,7 +59,8 @@ mod config;
 mod core;
 
 pub use crate::core::{
-    ConfiguredModule, InstructionKind, InstructionKinds, MaybeInvalidModule, Module,
+    ConfiguredModule, Decision, DecisionKind, InstructionKind, InstructionKinds,
+    MaybeInvalidModule, Module,
 };
 use arbitrary::{Result, Unstructured};
 pub use component::{Component, ConfiguredComponent};
@@ -1,8 +1,8 @@
 use arbitrary::{Arbitrary, Unstructured};
 use rand::{rngs::SmallRng, RngCore, SeedableRng};
 use std::collections::HashMap;
-use wasm_smith::{Config, ConfiguredModule, Module, SwarmConfig};
-use wasmparser::{Parser, TypeRef, ValType, Validator, WasmFeatures};
+use wasm_smith::{Config, ConfiguredModule, DecisionKind, Module, SwarmConfig};
+use wasmparser::{Parser, Payload, TypeRef, ValType, Validator, WasmFeatures};
 
 #[test]
 fn smoke_test_module() {
@@ -215,6 +215,51 @@ fn smoke_test_no_trapping_mode() {
     }
 }
 
+#[test]
+fn zeroing_decision_shrinks_module() {
+    fn num_exports(module: &Module) -> u32 {
+        Parser::new(0)
+            .parse_all(&module.to_bytes())
+            .filter_map(|payload| match payload.unwrap() {
+                Payload::ExportSection(reader) => Some(reader.count()),
+                _ => None,
+            })
+            .sum()
+    }
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut buf = vec![0; 2048];
+    let mut shrunk = 0;
+    for _ in 0..1024 {
+        rng.fill_bytes(&mut buf);
+        let module = match Module::arbitrary(&mut Unstructured::new(&buf)) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        let decisions = module.decisions();
+        for pair in decisions.windows(2) {
+            assert!(pair[0].range.end <= pair[1].range.start);
+        }
+        if num_exports(&module) == 0 {
+            continue;
+        }
+
+        // Zeroing the entropy used for exports shouldn't change anything
+        // generated before them, but should leave no exports behind.
+        let exports = decisions
+            .iter()
+            .position(|d| d.kind == DecisionKind::Exports)
+            .unwrap();
+        let mut zeroed = buf.clone();
+        zeroed[decisions[exports].range.clone()].fill(0);
+        let smaller = Module::arbitrary(&mut Unstructured::new(&zeroed)).unwrap();
+        assert_eq!(num_exports(&smaller), 0);
+        assert_eq!(decisions[..exports], smaller.decisions()[..exports]);
+        shrunk += 1;
+    }
+    assert!(shrunk > 0);
+}
+
 fn wasm_features() -> WasmFeatures {
     WasmFeatures {
         multi_memory: true,
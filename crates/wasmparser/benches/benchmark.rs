@@ -218,7 +218,10 @@ fn read_all_wasm(wasm: &[u8]) -> Result<()> {
             | DataCountSection { .. }
             | UnknownSection { .. }
             | CustomSection { .. }
+            | CustomSectionStart { .. }
+            | CustomSectionChunk { .. }
             | CodeSectionStart { .. }
+            | SkippedCodeSectionChunk { .. }
             | End(_) => {}
         }
     }
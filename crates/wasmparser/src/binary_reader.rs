@@ -14,12 +14,15 @@
  */
 
 use crate::{limits::*, *};
-use std::convert::TryInto;
-use std::error::Error;
-use std::fmt;
-use std::marker;
-use std::ops::Range;
-use std::str;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+use core::marker;
+use core::ops::Range;
+use core::str;
 
 const WASM_MAGIC_NUMBER: &[u8; 4] = b"\0asm";
 
@@ -37,12 +40,15 @@ pub(crate) struct BinaryReaderErrorInner {
     pub(crate) message: String,
     pub(crate) offset: usize,
     pub(crate) needed_hint: Option<usize>,
+    pub(crate) section: Option<&'static str>,
+    pub(crate) items: Vec<(&'static str, u32)>,
 }
 
 /// The result for `BinaryReader` operations.
-pub type Result<T, E = BinaryReaderError> = std::result::Result<T, E>;
+pub type Result<T, E = BinaryReaderError> = core::result::Result<T, E>;
 
-impl Error for BinaryReaderError {}
+#[cfg(feature = "std")]
+impl std::error::Error for BinaryReaderError {}
 
 impl fmt::Display for BinaryReaderError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -63,6 +69,8 @@ impl BinaryReaderError {
                 message,
                 offset,
                 needed_hint: None,
+                section: None,
+                items: Vec::new(),
             }),
         }
     }
@@ -79,6 +87,8 @@ impl BinaryReaderError {
                 message: "unexpected end-of-file".to_string(),
                 offset,
                 needed_hint: Some(needed_hint),
+                section: None,
+                items: Vec::new(),
             }),
         }
     }
@@ -92,6 +102,72 @@ impl BinaryReaderError {
     pub fn offset(&self) -> usize {
         self.inner.offset
     }
+
+    /// Records that this error occurred within the section named `section`,
+    /// such as `"code"`.
+    ///
+    /// If a section was already recorded then it's kept.
+    pub fn with_section(mut self, section: &'static str) -> Self {
+        self.inner.section.get_or_insert(section);
+        self
+    }
+
+    /// Records that this error occurred within the item `index` of kind
+    /// `kind`, such as function 37.
+    ///
+    /// Items are expected to be added from the innermost outwards, so the
+    /// item added here encloses all previously added items.
+    pub fn with_item(mut self, kind: &'static str, index: u32) -> Self {
+        self.inner.items.insert(0, (kind, index));
+        self
+    }
+
+    /// Get the name of the section in which this error occurred, if known.
+    pub fn section(&self) -> Option<&'static str> {
+        self.inner.section
+    }
+
+    /// Get the items in which this error occurred, from the outermost to the
+    /// innermost, such as `[("function", 37), ("operator", 142)]`.
+    pub fn items(&self) -> &[(&'static str, u32)] {
+        &self.inner.items
+    }
+
+    /// Renders where this error occurred, including the section and items
+    /// it's within when known.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let wasm = wat::parse_str(r#"
+    ///     (module
+    ///         (func)
+    ///         (func (result i32) nop nop))
+    /// "#).unwrap();
+    /// let err = match wasmparser::validate(&wasm) {
+    ///     Ok(_) => unreachable!(),
+    ///     Err(e) => e,
+    /// };
+    /// assert_eq!(err.location(), "at offset 0x21, in code section, function 1, operator 2");
+    ///
+    /// let wasm = wat::parse_str("(module (global i32 (i32.const 0)) (global i32 (i64.const 0)))").unwrap();
+    /// let err = match wasmparser::validate(&wasm) {
+    ///     Ok(_) => unreachable!(),
+    ///     Err(e) => e,
+    /// };
+    /// assert_eq!(err.section(), Some("global"));
+    /// assert_eq!(err.items(), [("entry", 1)]);
+    /// ```
+    pub fn location(&self) -> String {
+        let mut location = format!("at offset 0x{:x}", self.inner.offset);
+        if let Some(section) = self.inner.section {
+            location.push_str(&format!(", in {section} section"));
+        }
+        for (kind, index) in self.inner.items.iter() {
+            location.push_str(&format!(", {kind} {index}"));
+        }
+        location
+    }
 }
 
 /// A binary reader of the WebAssembly structures and types.
@@ -973,8 +1049,8 @@ impl<'a> BinaryReader<'a> {
             0xd0 => visitor.visit_ref_null(self.read()?),
             0xd1 => visitor.visit_ref_is_null(),
             0xd2 => visitor.visit_ref_func(self.read_var_u32()?),
-            0xd3 => visitor.visit_ref_as_non_null(),
-            0xd4 => visitor.visit_br_on_null(self.read_var_u32()?),
+            0xd4 => visitor.visit_ref_as_non_null(),
+            0xd5 => visitor.visit_br_on_null(self.read_var_u32()?),
             0xd6 => visitor.visit_br_on_non_null(self.read_var_u32()?),
 
             0xfc => self.visit_0xfc_operator(pos, visitor)?,
@@ -0,0 +1,186 @@
+use crate::{BinaryReaderError, Encoding, Parser, Payload, Result, TypeRef};
+
+/// The number of imported and locally-defined items in one index space of a
+/// core WebAssembly module.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ItemCount {
+    /// The number of items which are imported.
+    pub imported: u32,
+    /// The number of items which are defined locally in the module.
+    pub defined: u32,
+}
+
+impl ItemCount {
+    /// Returns the total number of items in this index space.
+    pub fn total(&self) -> u64 {
+        u64::from(self.imported) + u64::from(self.defined)
+    }
+}
+
+/// The sizes of the index spaces of a core WebAssembly module, as declared by
+/// its import section and the counts of its definition sections.
+///
+/// This is intended for embedders which need to size host data structures
+/// before deciding whether to fully validate a module. Only the header of
+/// each section is read, except for the import section whose entries are
+/// read to classify them. Nothing is validated beyond what's required to
+/// read those, so the counts of an invalid module may be arbitrary.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IndexSpaceCounts {
+    /// The functions index space.
+    pub funcs: ItemCount,
+    /// The tables index space.
+    pub tables: ItemCount,
+    /// The memories index space.
+    pub memories: ItemCount,
+    /// The globals index space.
+    pub globals: ItemCount,
+    /// The tags index space.
+    pub tags: ItemCount,
+}
+
+impl IndexSpaceCounts {
+    /// Scans the sections of the core wasm module `data` to count the items
+    /// in each of its index spaces.
+    ///
+    /// Scanning stops at the first section which can't contribute to any
+    /// index space, so sections such as the code section are never read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `data` is a component rather than a core module,
+    /// or if any of the sections read are malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmparser::IndexSpaceCounts;
+    /// # let wasm = wat::parse_str(r#"
+    /// #     (module
+    /// #         (import "" "f" (func))
+    /// #         (import "" "g" (global i32))
+    /// #         (func)
+    /// #         (func)
+    /// #         (memory 1))
+    /// # "#).unwrap();
+    ///
+    /// let counts = IndexSpaceCounts::new(&wasm).unwrap();
+    /// assert_eq!(counts.funcs.imported, 1);
+    /// assert_eq!(counts.funcs.defined, 2);
+    /// assert_eq!(counts.memories.total(), 1);
+    /// assert_eq!(counts.globals.imported, 1);
+    /// ```
+    pub fn new(data: &[u8]) -> Result<IndexSpaceCounts> {
+        let mut counts = IndexSpaceCounts::default();
+        for payload in Parser::new(0).parse_all(data) {
+            match payload? {
+                Payload::Version {
+                    encoding: Encoding::Component,
+                    range,
+                    ..
+                } => {
+                    return Err(BinaryReaderError::new(
+                        "expected a core wasm module, found a component",
+                        range.start,
+                    ));
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let count = match import?.ty {
+                            TypeRef::Func(_) => &mut counts.funcs,
+                            TypeRef::Table(_) => &mut counts.tables,
+                            TypeRef::Memory(_) => &mut counts.memories,
+                            TypeRef::Global(_) => &mut counts.globals,
+                            TypeRef::Tag(_) => &mut counts.tags,
+                        };
+                        count.imported += 1;
+                    }
+                }
+                Payload::FunctionSection(reader) => counts.funcs.defined = reader.count(),
+                Payload::TableSection(reader) => counts.tables.defined = reader.count(),
+                Payload::MemorySection(reader) => counts.memories.defined = reader.count(),
+                Payload::GlobalSection(reader) => counts.globals.defined = reader.count(),
+                Payload::TagSection(reader) => counts.tags.defined = reader.count(),
+
+                // These sections can't define anything, but may come before
+                // or among the sections which do.
+                Payload::Version { .. } | Payload::TypeSection(_) | Payload::CustomSection(_) => {}
+
+                // Anything else comes after all of the sections which define
+                // items, so there's no need to keep going.
+                _ => break,
+            }
+        }
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_imports_and_definitions() {
+        let wasm = wat::parse_str(
+            r#"
+                (module
+                    (import "" "t" (table 1 funcref))
+                    (import "" "m" (memory 1))
+                    (import "" "f" (func))
+                    (import "" "e" (tag))
+                    (table 1 funcref)
+                    (table 1 funcref)
+                    (global i32 (i32.const 0))
+                    (tag)
+                    (tag)
+                    (tag)
+                    (func)
+                    (export "f" (func 0)))
+            "#,
+        )
+        .unwrap();
+        let counts = IndexSpaceCounts::new(&wasm).unwrap();
+        assert_eq!(
+            counts,
+            IndexSpaceCounts {
+                funcs: ItemCount {
+                    imported: 1,
+                    defined: 1
+                },
+                tables: ItemCount {
+                    imported: 1,
+                    defined: 2
+                },
+                memories: ItemCount {
+                    imported: 1,
+                    defined: 0
+                },
+                globals: ItemCount {
+                    imported: 0,
+                    defined: 1
+                },
+                tags: ItemCount {
+                    imported: 1,
+                    defined: 3
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_components() {
+        let wasm = wat::parse_str("(component)").unwrap();
+        assert!(IndexSpaceCounts::new(&wasm).is_err());
+    }
+
+    #[test]
+    fn stops_before_code() {
+        let wasm = [
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+            0x03, 0x02, 0x01, 0x00, // function section: 1 function
+            0x0a, 0x03, 0x01, 0xff, 0xff, // code section: malformed body
+        ];
+        let counts = IndexSpaceCounts::new(&wasm).unwrap();
+        assert_eq!(counts.funcs.defined, 1);
+    }
+}
@@ -22,8 +22,18 @@
 //! If you need random access to the entire WebAssembly data-structure,
 //! this is not the right library for you. You could however, build such
 //! a data-structure using this library.
+//!
+//! # `no_std` support
+//!
+//! This crate can be used without the standard library, only requiring
+//! `alloc`, by disabling its default `std` feature. The parser and the
+//! section readers are then available, but the [`Validator`] and the other
+//! types related to validation are not.
 
 #![deny(missing_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 /// A helper macro to conveniently iterate over all opcodes recognized by this
 /// crate. This can be used to work with either the [`Operator`] enumeration or
@@ -699,14 +709,21 @@ macro_rules! bail {
 }
 
 pub use crate::binary_reader::{BinaryReader, BinaryReaderError, Result};
+pub use crate::counts::*;
 pub use crate::parser::*;
 pub use crate::readers::*;
+#[cfg(feature = "std")]
 pub use crate::resources::*;
+#[cfg(feature = "std")]
 pub use crate::validator::*;
 
 mod binary_reader;
+mod counts;
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 mod limits;
 mod parser;
 mod readers;
+#[cfg(feature = "std")]
 mod resources;
+#[cfg(feature = "std")]
 mod validator;
@@ -7,11 +7,13 @@ use crate::{
     GlobalSectionReader, ImportSectionReader, InstanceSectionReader, MemorySectionReader, Result,
     SectionLimited, TableSectionReader, TagSectionReader, TypeSectionReader,
 };
-use std::convert::TryInto;
-use std::fmt;
-use std::iter;
-use std::ops::Range;
+use alloc::vec::Vec;
+use core::convert::TryInto;
+use core::fmt;
+use core::iter;
+use core::ops::Range;
 
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) const WASM_MODULE_VERSION: u16 = 0x1;
 
 // Note that this started at `0xa` and we're incrementing up from there. When
@@ -20,6 +22,7 @@ pub(crate) const WASM_MODULE_VERSION: u16 = 0x1;
 // * [????-??-??] 0xa - original version
 // * [2022-01-05] 0xb - `export` introduces an alias
 // * [2022-02-06] 0xc - `export` has an optional type ascribed to it
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 pub(crate) const WASM_COMPONENT_VERSION: u16 = 0xc;
 
 /// The supported encoding formats for the parser.
@@ -46,6 +49,89 @@ pub struct Parser {
     offset: u64,
     max_size: u64,
     encoding: Encoding,
+    stream_custom_sections: Option<u32>,
+    skip_function_bodies: bool,
+    limits: Limits,
+    depth: u32,
+    declared_items: u64,
+}
+
+/// Configurable limits on the size of the modules and components accepted by
+/// a [`Parser`] or a [`Validator`](crate::Validator).
+///
+/// Limits on sections are checked as each section starts, before any space is
+/// reserved for its items, so they can be used to bound the memory used to
+/// process untrusted inputs, such as when fuzzing. They're in addition to the
+/// fixed limits, such as on the number of functions, that the validator
+/// always imposes. By default there are no additional limits.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// The maximum number of items in any one section.
+    pub max_section_items: u32,
+    /// The maximum depth of modules and components nested within a
+    /// component, where those directly within the top-level component are at
+    /// a depth of 1.
+    pub max_nesting_depth: u32,
+    /// The maximum total number of items, such as types, functions and
+    /// exports, declared across all sections, including those of nested
+    /// modules and components.
+    pub max_declared_items: u64,
+    /// The maximum depth of blocks, loops, `if`s and `try`s nested within a
+    /// function body.
+    ///
+    /// This is only checked by the validator, since the parser doesn't
+    /// decode function bodies.
+    pub max_control_depth: u32,
+}
+
+impl Default for Limits {
+    fn default() -> Limits {
+        Limits {
+            max_section_items: u32::MAX,
+            max_nesting_depth: u32::MAX,
+            max_declared_items: u64::MAX,
+            max_control_depth: u32::MAX,
+        }
+    }
+}
+
+impl Limits {
+    /// Checks that the section named `name` doesn't have too many items.
+    pub(crate) fn check_section(&self, name: &str, count: u32, offset: usize) -> Result<()> {
+        if count > self.max_section_items {
+            bail!(
+                offset,
+                "{name} section has {count} items, exceeding the limit of {}",
+                self.max_section_items
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that the `total` number of declared items is within the limit.
+    pub(crate) fn check_declared_items(&self, total: u64, offset: usize) -> Result<()> {
+        if total > self.max_declared_items {
+            bail!(
+                offset,
+                "declared items exceed the limit of {}",
+                self.max_declared_items
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks that a module or component nested at `depth` is within the
+    /// limit.
+    pub(crate) fn check_nesting_depth(&self, depth: u32, offset: usize) -> Result<()> {
+        if depth > self.max_nesting_depth {
+            bail!(
+                offset,
+                "nesting depth exceeds the limit of {}",
+                self.max_nesting_depth
+            );
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -53,6 +139,8 @@ enum State {
     Header,
     SectionStart,
     FunctionBody { remaining: u32, len: u32 },
+    SkippedFunctionBodies { len: u32 },
+    CustomSection { len: u32 },
 }
 
 /// A successful return payload from [`Parser::parse`].
@@ -189,6 +277,18 @@ pub enum Payload<'a> {
     /// parsed, it's only been outlined. You'll need to process the
     /// `FunctionBody` provided to test whether it parses and/or is valid.
     CodeSectionEntry(FunctionBody<'a>),
+    /// A chunk of a code section whose function bodies are being skipped.
+    ///
+    /// This is only returned with [`Parser::set_skip_function_bodies`]
+    /// enabled, for the bytes of the code section which weren't yet available
+    /// when [`Payload::CodeSectionStart`] was returned. Each chunk is as large
+    /// as the input given to [`Parser::parse`] allows, and is at least one
+    /// byte.
+    SkippedCodeSectionChunk {
+        /// The range of bytes, relative to the start of the original data
+        /// stream, that were skipped.
+        range: Range<usize>,
+    },
 
     /// A core module section was received and the provided parser can be
     /// used to parse the nested module.
@@ -267,6 +367,44 @@ pub enum Payload<'a> {
     /// A module or component custom section was received.
     CustomSection(CustomSectionReader<'a>),
 
+    /// The start of a custom section whose contents are streamed was received.
+    ///
+    /// This is only returned for custom sections large enough to be streamed
+    /// as configured with [`Parser::set_custom_section_streaming`], and
+    /// otherwise [`Payload::CustomSection`] is returned. Only the name of the
+    /// section needs to be resident in memory, and the rest of its contents
+    /// are returned in the [`Payload::CustomSectionChunk`] payloads which
+    /// follow this one.
+    CustomSectionStart {
+        /// The name of the custom section.
+        name: &'a str,
+        /// The offset, relative to the start of the original data stream,
+        /// at which the section's data, following its name, starts.
+        data_offset: usize,
+        /// The range of bytes, relative to the start of the original data
+        /// stream, that the contents of this section reside in, including
+        /// its name.
+        range: Range<usize>,
+        /// The size, in bytes, of the section's data following its name.
+        ///
+        /// This is provided so the section can be skipped with
+        /// [`Parser::skip_section`], in which case the caller must skip this
+        /// many bytes before feeding bytes into `Parser` again.
+        size: u32,
+    },
+    /// A chunk of the data of a custom section which is being streamed.
+    ///
+    /// Chunks follow a [`Payload::CustomSectionStart`] and together cover all
+    /// of the section's data, in order. Each chunk is as large as the input
+    /// given to [`Parser::parse`] allows, and is at least one byte.
+    CustomSectionChunk {
+        /// This chunk of the section's data.
+        data: &'a [u8],
+        /// The range of bytes, relative to the start of the original data
+        /// stream, that this chunk resides in.
+        range: Range<usize>,
+    },
+
     /// An unknown section was found.
     ///
     /// This variant is returned for all unknown sections encountered. This
@@ -329,9 +467,133 @@ impl Parser {
             max_size: u64::MAX,
             // Assume the encoding is a module until we know otherwise
             encoding: Encoding::Module,
+            stream_custom_sections: None,
+            skip_function_bodies: false,
+            limits: Limits::default(),
+            depth: 0,
+            declared_items: 0,
         }
     }
 
+    /// Configures whether the contents of large custom sections are streamed.
+    ///
+    /// By default the entirety of a custom section must be resident in memory
+    /// before [`Payload::CustomSection`] is returned, which forces consumers
+    /// that stream their input to buffer sections such as embedded assets or
+    /// DWARF debug information in full.
+    ///
+    /// When `min_size` is `Some`, custom sections whose contents are at least
+    /// that many bytes are instead returned as a
+    /// [`Payload::CustomSectionStart`] followed by any number of
+    /// [`Payload::CustomSectionChunk`] payloads, each of which contains as
+    /// much of the section's data as was available. Smaller custom sections
+    /// are still returned as [`Payload::CustomSection`]. This setting is
+    /// inherited by the parsers of nested modules and components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmparser::{Chunk, Parser, Payload};
+    ///
+    /// # let wasm = &b"\0asm\x01\0\0\0\0\x06\x03big\x01\x02"[..];
+    /// let mut parser = Parser::new(0);
+    /// parser.set_custom_section_streaming(Some(1));
+    ///
+    /// // Feed the module in one byte at a time.
+    /// let mut data = Vec::<u8>::new();
+    /// let mut offset = 0;
+    /// for i in 0..=wasm.len() {
+    ///     let eof = i == wasm.len();
+    ///     loop {
+    ///         let (payload, consumed) = match parser.parse(&wasm[offset..i], eof).unwrap() {
+    ///             Chunk::NeedMoreData(_) => break,
+    ///             Chunk::Parsed { payload, consumed } => (payload, consumed),
+    ///         };
+    ///         offset += consumed;
+    ///         match payload {
+    ///             Payload::CustomSectionStart { name, .. } => assert_eq!(name, "big"),
+    ///             Payload::CustomSectionChunk { data: chunk, .. } => data.extend(chunk),
+    ///             Payload::End(_) => break,
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    /// assert_eq!(data, [1, 2]);
+    /// ```
+    pub fn set_custom_section_streaming(&mut self, min_size: Option<u32>) {
+        self.stream_custom_sections = min_size;
+    }
+
+    /// Configures whether the function bodies of the code section are
+    /// skipped.
+    ///
+    /// When enabled, [`Payload::CodeSectionStart`] is returned for the code
+    /// section but no [`Payload::CodeSectionEntry`] payloads follow it.
+    /// Instead the bytes of the section are consumed without being looked at,
+    /// as many as are available along with the start payload and the rest in
+    /// [`Payload::SkippedCodeSectionChunk`] payloads, so the section needn't
+    /// be resident in memory. This is useful for consumers which only care
+    /// about a module's declarations, such as its imports and exports.
+    ///
+    /// Note that a [`Validator`](crate::Validator) can't be used to validate
+    /// the payloads of a parser with this enabled. This setting is inherited
+    /// by the parsers of nested modules and components.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmparser::{Parser, Payload};
+    ///
+    /// # let wasm = &b"\0asm\x01\0\0\0\x01\x04\x01\x60\0\0\x03\x02\x01\0\x0a\x04\x01\x02\0\x0b"[..];
+    /// let mut parser = Parser::new(0);
+    /// parser.set_skip_function_bodies(true);
+    /// for payload in parser.parse_all(wasm) {
+    ///     match payload.unwrap() {
+    ///         Payload::CodeSectionStart { count, size, .. } => assert_eq!((count, size), (1, 3)),
+    ///         Payload::CodeSectionEntry(_) => unreachable!(),
+    ///         _ => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn set_skip_function_bodies(&mut self, skip: bool) {
+        self.skip_function_bodies = skip;
+    }
+
+    /// Configures the limits on the size of the modules and components this
+    /// parser accepts.
+    ///
+    /// A section with too many items, or one that brings the total number of
+    /// declared items over the limit, is reported as an error instead of
+    /// being returned. These limits are inherited by the parsers of nested
+    /// modules and components, which continue counting declared items from
+    /// the total of their parent. [`Parser::parse_all`] adds the items of a
+    /// nested module or component back to the total of its parent when it
+    /// ends, but when nested parsers are driven by hand each one only counts
+    /// the items declared by its ancestors and itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmparser::{Limits, Parser};
+    ///
+    /// let wasm = wat::parse_str("(module (func) (func) (func))").unwrap();
+    /// let mut parser = Parser::new(0);
+    /// parser.set_limits(Limits {
+    ///     max_section_items: 2,
+    ///     ..Default::default()
+    /// });
+    /// let err = parser.parse_all(&wasm).find_map(|p| p.err()).unwrap();
+    /// assert_eq!(err.message(), "function section has 3 items, exceeding the limit of 2");
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Returns the limits used for this parser.
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
     /// Attempts to parse a chunk of data.
     ///
     /// This method will attempt to parse the next incremental portion of a
@@ -433,6 +695,9 @@ impl Parser {
     ///                 // and its locals
     ///             }
     ///
+    ///             // Only returned if function bodies are skipped.
+    ///             SkippedCodeSectionChunk { .. } => { /* ... */ }
+    ///
     ///             // Sections for WebAssembly components
     ///             ModuleSection { .. } => { /* ... */ }
     ///             InstanceSection(_) => { /* ... */ }
@@ -448,6 +713,10 @@ impl Parser {
     ///
     ///             CustomSection(_) => { /* ... */ }
     ///
+    ///             // Only returned if custom section streaming is enabled.
+    ///             CustomSectionStart { .. } => { /* ... */ }
+    ///             CustomSectionChunk { .. } => { /* ... */ }
+    ///
     ///             // most likely you'd return an error here
     ///             UnknownSection { id, .. } => { /* ... */ }
     ///
@@ -568,9 +837,24 @@ impl Parser {
                     return Err(BinaryReaderError::new("section too large", len_pos));
                 }
 
-                match (self.encoding, id) {
+                let payload = match (self.encoding, id) {
                     // Sections for both modules and components.
-                    (_, 0) => section(reader, len, CustomSectionReader::new, CustomSection),
+                    (_, CUSTOM_SECTION) if matches!(self.stream_custom_sections, Some(min) if len >= min) =>
+                    {
+                        let start = reader.original_position();
+                        let name = delimited(reader, &mut len, |r| r.read_string())?;
+                        let data_offset = reader.original_position();
+                        self.state = State::CustomSection { len };
+                        Ok(CustomSectionStart {
+                            name,
+                            data_offset,
+                            range: start..data_offset + len as usize,
+                            size: len,
+                        })
+                    }
+                    (_, CUSTOM_SECTION) => {
+                        section(reader, len, CustomSectionReader::new, CustomSection)
+                    }
 
                     // Module sections
                     (Encoding::Module, TYPE_SECTION) => {
@@ -605,10 +889,18 @@ impl Parser {
                         let start = reader.original_position();
                         let count = delimited(reader, &mut len, |r| r.read_var_u32())?;
                         let range = start..reader.original_position() + len as usize;
-                        self.state = State::FunctionBody {
-                            remaining: count,
-                            len,
-                        };
+                        if self.skip_function_bodies {
+                            let available = reader.bytes_remaining().min(len as usize);
+                            reader.read_bytes(available)?;
+                            self.state = State::SkippedFunctionBodies {
+                                len: len - available as u32,
+                            };
+                        } else {
+                            self.state = State::FunctionBody {
+                                remaining: count,
+                                len,
+                            };
+                        }
                         Ok(CodeSectionStart {
                             count,
                             range,
@@ -639,10 +931,18 @@ impl Parser {
 
                         let range =
                             reader.original_position()..reader.original_position() + len as usize;
+                        self.limits
+                            .check_nesting_depth(self.depth + 1, range.start)?;
+                        self.declare_items(1, range.start)?;
                         self.max_size -= u64::from(len);
                         self.offset += u64::from(len);
                         let mut parser = Parser::new(usize_to_u64(reader.original_position()));
                         parser.max_size = len.into();
+                        parser.stream_custom_sections = self.stream_custom_sections;
+                        parser.skip_function_bodies = self.skip_function_bodies;
+                        parser.limits = self.limits;
+                        parser.depth = self.depth + 1;
+                        parser.declared_items = self.declared_items;
 
                         Ok(match id {
                             1 => ModuleSection { parser, range },
@@ -703,7 +1003,9 @@ impl Parser {
                             range,
                         })
                     }
-                }
+                }?;
+                self.check_limits(&payload)?;
+                Ok(payload)
             }
 
             // Once we hit 0 remaining incrementally parsed items, with 0
@@ -753,9 +1055,92 @@ impl Parser {
                 };
                 Ok(CodeSectionEntry(body))
             }
+
+            // Skipping function bodies works like streaming a custom section,
+            // except that the skipped bytes aren't returned.
+            State::SkippedFunctionBodies { len: 0 } => {
+                self.state = State::SectionStart;
+                self.parse_reader(reader, eof)
+            }
+            State::SkippedFunctionBodies { len } => {
+                let offset = reader.original_position();
+                let available = reader.bytes_remaining().min(len as usize);
+                let skipped = reader.read_bytes(available.max(1))?.len();
+                self.state = State::SkippedFunctionBodies {
+                    len: len - skipped as u32,
+                };
+                Ok(SkippedCodeSectionChunk {
+                    range: offset..offset + skipped,
+                })
+            }
+
+            // Once all of a streamed custom section's data has been returned
+            // go back to parsing sections.
+            State::CustomSection { len: 0 } => {
+                self.state = State::SectionStart;
+                self.parse_reader(reader, eof)
+            }
+
+            // Otherwise return as much of the section's data as is available,
+            // requiring at least one byte so progress is always made.
+            State::CustomSection { len } => {
+                let offset = reader.original_position();
+                let available = reader.bytes_remaining().min(len as usize);
+                let data = reader.read_bytes(available.max(1))?;
+                self.state = State::CustomSection {
+                    len: len - data.len() as u32,
+                };
+                Ok(CustomSectionChunk {
+                    data,
+                    range: offset..offset + data.len(),
+                })
+            }
         }
     }
 
+    /// Checks the configured limits for the section in `payload`.
+    fn check_limits(&mut self, payload: &Payload<'_>) -> Result<()> {
+        use Payload::*;
+
+        let (name, count, offset) = match payload {
+            TypeSection(s) => ("type", s.count(), s.range().start),
+            ImportSection(s) => ("import", s.count(), s.range().start),
+            FunctionSection(s) => ("function", s.count(), s.range().start),
+            TableSection(s) => ("table", s.count(), s.range().start),
+            MemorySection(s) => ("memory", s.count(), s.range().start),
+            TagSection(s) => ("tag", s.count(), s.range().start),
+            GlobalSection(s) => ("global", s.count(), s.range().start),
+            ExportSection(s) => ("export", s.count(), s.range().start),
+            ElementSection(s) => ("element", s.count(), s.range().start),
+            DataSection(s) => ("data", s.count(), s.range().start),
+            // The bodies of the code section define functions which were
+            // already declared by the function section, so they don't count
+            // towards the total again.
+            CodeSectionStart { count, range, .. } => {
+                return self.limits.check_section("code", *count, range.start);
+            }
+            InstanceSection(s) => ("core instance", s.count(), s.range().start),
+            CoreTypeSection(s) => ("core type", s.count(), s.range().start),
+            ComponentInstanceSection(s) => ("instance", s.count(), s.range().start),
+            ComponentAliasSection(s) => ("alias", s.count(), s.range().start),
+            ComponentTypeSection(s) => ("type", s.count(), s.range().start),
+            ComponentCanonicalSection(s) => ("function", s.count(), s.range().start),
+            ComponentImportSection(s) => ("import", s.count(), s.range().start),
+            ComponentExportSection(s) => ("export", s.count(), s.range().start),
+            _ => return Ok(()),
+        };
+        self.limits.check_section(name, count, offset)?;
+        self.declare_items(count, offset)
+    }
+
+    /// Records that `count` more items were declared, checking the configured
+    /// limit on the total.
+    fn declare_items(&mut self, count: u32, offset: usize) -> Result<()> {
+        self.declared_items += u64::from(count);
+        self.limits
+            .check_declared_items(self.declared_items, offset)
+    }
+
     /// Convenience function that can be used to parse a module or component
     /// that is entirely resident in memory.
     ///
@@ -796,7 +1181,10 @@ impl Parser {
                     cur = parser.clone();
                 }
                 Payload::End(_) => match stack.pop() {
-                    Some(p) => cur = p,
+                    Some(mut p) => {
+                        p.declared_items = cur.declared_items;
+                        cur = p;
+                    }
                     None => done = true,
                 },
 
@@ -807,25 +1195,26 @@ impl Parser {
         })
     }
 
-    /// Skip parsing the code section entirely.
+    /// Skip parsing the code section, or a streamed custom section, entirely.
     ///
     /// This function can be used to indicate, after receiving
-    /// `CodeSectionStart`, that the section will not be parsed.
+    /// `CodeSectionStart` or `CustomSectionStart`, that the section will not
+    /// be parsed.
     ///
     /// The caller will be responsible for skipping `size` bytes (found in the
-    /// `CodeSectionStart` payload). Bytes should only be fed into `parse`
-    /// after the `size` bytes have been skipped.
+    /// `CodeSectionStart` or `CustomSectionStart` payload). Bytes should only
+    /// be fed into `parse` after the `size` bytes have been skipped.
     ///
     /// # Panics
     ///
     /// This function will panic if the parser is not in a state where it's
-    /// parsing the code section.
+    /// parsing the code section or a streamed custom section.
     ///
     /// # Examples
     ///
     /// ```
     /// use wasmparser::{Result, Parser, Chunk, Payload::*};
-    /// use std::ops::Range;
+    /// use core::ops::Range;
     ///
     /// fn objdump_headers(mut wasm: &[u8]) -> Result<()> {
     ///     let mut parser = Parser::new(0);
@@ -864,6 +1253,7 @@ impl Parser {
     pub fn skip_section(&mut self) {
         let skip = match self.state {
             State::FunctionBody { remaining: _, len } => len,
+            State::CustomSection { len } => len,
             _ => panic!("wrong state to call `skip_section`"),
         };
         self.offset += u64::from(skip);
@@ -982,6 +1372,7 @@ impl Payload<'_> {
             DataCountSection { range, .. } => Some((DATA_COUNT_SECTION, range.clone())),
             CodeSectionStart { range, .. } => Some((CODE_SECTION, range.clone())),
             CodeSectionEntry(_) => None,
+            SkippedCodeSectionChunk { .. } => None,
 
             ModuleSection { range, .. } => Some((COMPONENT_MODULE_SECTION, range.clone())),
             InstanceSection(s) => Some((COMPONENT_CORE_INSTANCE_SECTION, s.range())),
@@ -996,6 +1387,8 @@ impl Payload<'_> {
             ComponentExportSection(s) => Some((COMPONENT_EXPORT_SECTION, s.range())),
 
             CustomSection(c) => Some((CUSTOM_SECTION, c.range())),
+            CustomSectionStart { range, .. } => Some((CUSTOM_SECTION, range.clone())),
+            CustomSectionChunk { .. } => None,
 
             UnknownSection { id, range, .. } => Some((*id, range.clone())),
 
@@ -1047,6 +1440,10 @@ impl fmt::Debug for Payload<'_> {
                 .field("size", size)
                 .finish(),
             CodeSectionEntry(_) => f.debug_tuple("CodeSectionEntry").field(&"...").finish(),
+            SkippedCodeSectionChunk { range } => f
+                .debug_struct("SkippedCodeSectionChunk")
+                .field("range", range)
+                .finish(),
 
             // Component sections
             ModuleSection { parser: _, range } => f
@@ -1086,6 +1483,22 @@ impl fmt::Debug for Payload<'_> {
                 .finish(),
 
             CustomSection(c) => f.debug_tuple("CustomSection").field(c).finish(),
+            CustomSectionStart {
+                name,
+                data_offset,
+                range,
+                size,
+            } => f
+                .debug_struct("CustomSectionStart")
+                .field("name", name)
+                .field("data_offset", data_offset)
+                .field("range", range)
+                .field("size", size)
+                .finish(),
+            CustomSectionChunk { range, .. } => f
+                .debug_struct("CustomSectionChunk")
+                .field("range", range)
+                .finish(),
 
             UnknownSection { id, range, .. } => f
                 .debug_struct("UnknownSection")
@@ -1299,6 +1712,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn streamed_custom_section() {
+        let mut p = parser_after_header();
+        p.set_custom_section_streaming(Some(3));
+
+        // Sections smaller than the threshold aren't streamed.
+        assert_matches!(
+            p.parse(&[0, 2, 1, b'a'], false),
+            Ok(Chunk::Parsed {
+                consumed: 4,
+                payload: Payload::CustomSection(_),
+            }),
+        );
+
+        let mut p = parser_after_header();
+        p.set_custom_section_streaming(Some(3));
+        assert_matches!(p.parse(&[0, 5, 1], false), Ok(Chunk::NeedMoreData(1)),);
+        assert_matches!(
+            p.parse(&[0, 5, 1, b'a'], false),
+            Ok(Chunk::Parsed {
+                consumed: 4,
+                payload: Payload::CustomSectionStart {
+                    name: "a",
+                    data_offset: 12,
+                    range: Range { start: 10, end: 15 },
+                    size: 3,
+                },
+            }),
+        );
+        assert_matches!(p.parse(&[], false), Ok(Chunk::NeedMoreData(1)));
+        assert_matches!(
+            p.parse(&[1, 2], false),
+            Ok(Chunk::Parsed {
+                consumed: 2,
+                payload: Payload::CustomSectionChunk {
+                    data: &[1, 2],
+                    range: Range { start: 12, end: 14 },
+                },
+            }),
+        );
+        assert!(p.parse(&[], true).is_err());
+        assert_matches!(
+            p.parse(&[3, 4], false),
+            Ok(Chunk::Parsed {
+                consumed: 1,
+                payload: Payload::CustomSectionChunk {
+                    data: &[3],
+                    range: Range { start: 14, end: 15 },
+                },
+            }),
+        );
+        assert_matches!(
+            p.parse(&[], true),
+            Ok(Chunk::Parsed {
+                consumed: 0,
+                payload: Payload::End(15),
+            }),
+        );
+    }
+
+    #[test]
+    fn streamed_custom_section_skip() {
+        let mut p = parser_after_header();
+        p.set_custom_section_streaming(Some(0));
+        assert_matches!(
+            p.parse(&[0, 3, 0], false),
+            Ok(Chunk::Parsed {
+                consumed: 3,
+                payload: Payload::CustomSectionStart { size: 2, .. },
+            }),
+        );
+        p.skip_section();
+        assert_matches!(
+            p.parse(&[], true),
+            Ok(Chunk::Parsed {
+                consumed: 0,
+                payload: Payload::End(13),
+            }),
+        );
+    }
+
     #[test]
     fn function_section() {
         assert!(parser_after_header().parse(&[10], true).is_err());
@@ -1409,6 +1903,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn skipped_function_bodies() {
+        let skipping = || {
+            let mut p = parser_after_header();
+            p.set_skip_function_bodies(true);
+            p
+        };
+        assert_matches!(
+            skipping().parse(&[10, 4], false),
+            Ok(Chunk::NeedMoreData(1))
+        );
+
+        // Bytes which aren't available yet are skipped in chunks.
+        let mut p = skipping();
+        assert_matches!(
+            p.parse(&[10, 4, 1, 2], false),
+            Ok(Chunk::Parsed {
+                consumed: 4,
+                payload: Payload::CodeSectionStart {
+                    count: 1,
+                    range: Range { start: 10, end: 14 },
+                    size: 3,
+                },
+            }),
+        );
+        assert_matches!(p.parse(&[], false), Ok(Chunk::NeedMoreData(1)));
+        assert_matches!(
+            p.parse(&[0], false),
+            Ok(Chunk::Parsed {
+                consumed: 1,
+                payload: Payload::SkippedCodeSectionChunk {
+                    range: Range { start: 12, end: 13 },
+                },
+            }),
+        );
+        assert!(p.clone().parse(&[], true).is_err());
+        assert_matches!(
+            p.parse(&[11, 0], false),
+            Ok(Chunk::Parsed {
+                consumed: 1,
+                payload: Payload::SkippedCodeSectionChunk {
+                    range: Range { start: 13, end: 14 },
+                },
+            }),
+        );
+        assert_matches!(
+            p.parse(&[], true),
+            Ok(Chunk::Parsed {
+                consumed: 0,
+                payload: Payload::End(14),
+            }),
+        );
+
+        let mut p = skipping();
+        assert_matches!(
+            p.parse(&[10, 4, 1, 2, 0, 11], false),
+            Ok(Chunk::Parsed {
+                consumed: 6,
+                payload: Payload::CodeSectionStart {
+                    count: 1,
+                    size: 3,
+                    ..
+                },
+            }),
+        );
+        assert_matches!(
+            p.parse(&[], true),
+            Ok(Chunk::Parsed {
+                consumed: 0,
+                payload: Payload::End(14),
+            }),
+        );
+    }
+
     #[test]
     fn single_module() {
         let mut p = parser_after_component_header();
@@ -1493,4 +2061,75 @@ mod tests {
             "section too large",
         );
     }
+
+    /// Parses `wat` with `limits`, returning the error message if any.
+    fn parse_with_limits(wat: &str, limits: Limits) -> Option<String> {
+        let wasm = wat::parse_str(wat).unwrap();
+        let mut parser = Parser::new(0);
+        parser.set_limits(limits);
+        let err = parser.parse_all(&wasm).find_map(|p| p.err());
+        err.map(|e| e.message().to_string())
+    }
+
+    #[test]
+    fn limit_section_items() {
+        let limits = |max_section_items| Limits {
+            max_section_items,
+            ..Default::default()
+        };
+        let wat = "(module (func) (func) (func))";
+        assert_eq!(parse_with_limits(wat, limits(3)), None);
+        assert_eq!(
+            parse_with_limits(wat, limits(2)).unwrap(),
+            "function section has 3 items, exceeding the limit of 2"
+        );
+
+        // The limits apply to the sections of nested modules too.
+        let wat = "(component (core module (global i32 (i32.const 0)) (global i32 (i32.const 0))))";
+        assert_eq!(parse_with_limits(wat, limits(2)), None);
+        assert_eq!(
+            parse_with_limits(wat, limits(1)).unwrap(),
+            "global section has 2 items, exceeding the limit of 1"
+        );
+    }
+
+    #[test]
+    fn limit_nesting_depth() {
+        let limits = |max_nesting_depth| Limits {
+            max_nesting_depth,
+            ..Default::default()
+        };
+        let wat = "(component (component (component (core module))))";
+        assert_eq!(parse_with_limits(wat, limits(3)), None);
+        assert_eq!(
+            parse_with_limits(wat, limits(2)).unwrap(),
+            "nesting depth exceeds the limit of 2"
+        );
+        assert_eq!(parse_with_limits("(module)", limits(0)), None);
+    }
+
+    #[test]
+    fn limit_declared_items() {
+        let limits = |max_declared_items| Limits {
+            max_declared_items,
+            ..Default::default()
+        };
+
+        // The code section's bodies aren't counted again.
+        let wat = r#"(module (type (func)) (func) (func) (export "f" (func 0)))"#;
+        assert_eq!(parse_with_limits(wat, limits(4)), None);
+        assert_eq!(
+            parse_with_limits(wat, limits(3)).unwrap(),
+            "declared items exceed the limit of 3"
+        );
+
+        // The items of a nested module are added to the total of its parent
+        // once it ends, so a later sibling continues counting from there.
+        let wat = "(component (core module (func)) (core module (func)))";
+        assert_eq!(parse_with_limits(wat, limits(6)), None);
+        assert_eq!(
+            parse_with_limits(wat, limits(5)).unwrap(),
+            "declared items exceed the limit of 5"
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use crate::{BinaryReader, BinaryReaderError, NameMap, Result, Subsection, Subsections};
-use std::ops::Range;
+use core::ops::Range;
 
 /// Type used to iterate and parse the contents of the `component-name` custom
 /// section in compnents, similar to the `name` section of core modules.
@@ -17,6 +17,7 @@ pub enum ComponentName<'a> {
     CoreGlobals(NameMap<'a>),
     CoreMemories(NameMap<'a>),
     CoreTables(NameMap<'a>),
+    CoreTags(NameMap<'a>),
     CoreModules(NameMap<'a>),
     CoreInstances(NameMap<'a>),
     CoreTypes(NameMap<'a>),
@@ -63,6 +64,7 @@ impl<'a> Subsection<'a> for ComponentName<'a> {
                         0x01 => ComponentName::CoreTables,
                         0x02 => ComponentName::CoreMemories,
                         0x03 => ComponentName::CoreGlobals,
+                        0x04 => ComponentName::CoreTags,
                         0x10 => ComponentName::CoreTypes,
                         0x11 => ComponentName::CoreModules,
                         0x12 => ComponentName::CoreInstances,
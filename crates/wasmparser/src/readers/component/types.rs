@@ -3,6 +3,7 @@ use crate::{
     BinaryReader, ComponentAlias, ComponentImport, ComponentTypeRef, FromReader, FuncType, Import,
     Result, SectionLimited, Type, TypeRef,
 };
+use alloc::boxed::Box;
 
 /// Represents the kind of an outer core alias in a WebAssembly component.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -185,6 +186,7 @@ impl PrimitiveValType {
         })
     }
 
+    #[cfg(feature = "std")]
     pub(crate) fn requires_realloc(&self) -> bool {
         matches!(self, Self::String)
     }
@@ -379,7 +381,7 @@ impl ComponentFuncResult<'_> {
         }
 
         match self {
-            Self::Unnamed(ty) => Either::Left(std::iter::once(ty).map(|ty| (None, ty))),
+            Self::Unnamed(ty) => Either::Left(core::iter::once(ty).map(|ty| (None, ty))),
             Self::Named(vec) => Either::Right(vec.iter().map(|(n, ty)| (Some(*n), ty))),
         }
     }
@@ -0,0 +1,92 @@
+use crate::{BinaryReader, BinaryReaderError, FromReader, Result, SectionLimited};
+use alloc::format;
+
+/// A reader for the `metadata.code.branch_hint` custom section, which hints
+/// whether branches are likely to be taken.
+///
+/// This section is described by the [branch hinting proposal].
+///
+/// [branch hinting proposal]: https://github.com/WebAssembly/branch-hinting/blob/main/proposals/branch-hinting/Overview.md
+///
+/// # Examples
+///
+/// ```
+/// use wasmparser::BranchHintSectionReader;
+///
+/// // Function 1 has a hint that the branch at offset 4 is taken.
+/// let data = [0x01, 0x01, 0x01, 0x04, 0x01, 0x01];
+/// let reader = BranchHintSectionReader::new(&data, 0).unwrap();
+/// for func in reader {
+///     let func = func.unwrap();
+///     for hint in func.hints {
+///         let hint = hint.unwrap();
+///         assert_eq!((func.func, hint.func_offset, hint.taken), (1, 4, true));
+///     }
+/// }
+/// ```
+pub type BranchHintSectionReader<'a> = SectionLimited<'a, BranchHintFunction<'a>>;
+
+/// The branch hints of a single function in the `metadata.code.branch_hint`
+/// custom section.
+#[derive(Debug, Clone)]
+pub struct BranchHintFunction<'a> {
+    /// The index of the function the hints are for.
+    pub func: u32,
+    /// The hints for the branches in the function, ordered by offset.
+    pub hints: SectionLimited<'a, BranchHint>,
+}
+
+impl<'a> FromReader<'a> for BranchHintFunction<'a> {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let func = reader.read_var_u32()?;
+        let hints = reader.skip(|reader| {
+            // FIXME(#188) ideally shouldn't need to skip here
+            for _ in 0..reader.read_var_u32()? {
+                reader.read::<BranchHint>()?;
+            }
+            Ok(())
+        })?;
+        Ok(BranchHintFunction {
+            func,
+            hints: SectionLimited::new(hints.remaining_buffer(), hints.original_position())?,
+        })
+    }
+}
+
+/// A hint for a single branch instruction, such as `br_if` or `if`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BranchHint {
+    /// The offset of the branch instruction, relative to the start of the
+    /// function's body.
+    pub func_offset: u32,
+    /// Whether the branch is likely to be taken.
+    pub taken: bool,
+}
+
+impl<'a> FromReader<'a> for BranchHint {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let func_offset = reader.read_var_u32()?;
+        let size_offset = reader.original_position();
+        match reader.read_var_u32()? {
+            1 => {}
+            _ => {
+                return Err(BinaryReaderError::new(
+                    "branch hint size must be 1",
+                    size_offset,
+                ))
+            }
+        }
+        let value_offset = reader.original_position();
+        let taken = match reader.read_u8()? {
+            0 => false,
+            1 => true,
+            v => {
+                return Err(BinaryReaderError::new(
+                    format!("invalid branch hint value: {v:#x}"),
+                    value_offset,
+                ))
+            }
+        };
+        Ok(BranchHint { func_offset, taken })
+    }
+}
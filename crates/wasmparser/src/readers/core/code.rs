@@ -13,12 +13,61 @@
  * limitations under the License.
  */
 
-use crate::{BinaryReader, FromReader, OperatorsReader, Result, SectionLimited, ValType};
-use std::ops::Range;
+use crate::{
+    BinaryReader, BinaryReaderError, FromReader, OperatorsReader, Result, SectionLimited, ValType,
+};
+use core::ops::Range;
 
 /// A reader for the code section of a WebAssembly module.
 pub type CodeSectionReader<'a> = SectionLimited<'a, FunctionBody<'a>>;
 
+impl<'a> CodeSectionReader<'a> {
+    /// Checks that this code section matches a function section which
+    /// declared `function_count` functions, without decoding any function
+    /// bodies.
+    ///
+    /// This verifies that the section has exactly `function_count` entries,
+    /// that the declared size of each body fits within the section, and that
+    /// there's no trailing data after the last body. Streaming compilers can
+    /// use this to reject a malformed module before spawning work for each
+    /// function.
+    ///
+    /// A count mismatch is reported at the offset of the section. Any other
+    /// error is reported at the offset of the body whose size is invalid.
+    ///
+    /// # Examples
+    /// ```
+    /// use wasmparser::CodeSectionReader;
+    /// // Two empty bodies, each `(locals 0) end`.
+    /// let data: &[u8] = &[0x02, 0x02, 0x00, 0x0b, 0x02, 0x00, 0x0b];
+    /// let reader = CodeSectionReader::new(data, 100).unwrap();
+    /// assert!(reader.verify_entries(2).is_ok());
+    /// assert_eq!(reader.verify_entries(1).unwrap_err().offset(), 100);
+    ///
+    /// // The size of the second body extends past the end of the section.
+    /// let data: &[u8] = &[0x02, 0x02, 0x00, 0x0b, 0x05, 0x00, 0x0b];
+    /// let reader = CodeSectionReader::new(data, 100).unwrap();
+    /// assert_eq!(reader.verify_entries(2).unwrap_err().offset(), 104);
+    /// ```
+    pub fn verify_entries(&self, function_count: u32) -> Result<()> {
+        if self.count() != function_count {
+            return Err(BinaryReaderError::new(
+                "function and code section have inconsistent lengths",
+                self.range().start,
+            ));
+        }
+        let mut bodies = self.clone().into_iter();
+        loop {
+            let offset = bodies.original_position();
+            match bodies.next() {
+                None => return Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(BinaryReaderError::new(e.message(), offset)),
+            }
+        }
+    }
+}
+
 /// Represents a WebAssembly function body.
 #[derive(Debug, Clone)]
 pub struct FunctionBody<'a> {
@@ -70,6 +119,51 @@ impl<'a> FunctionBody<'a> {
         Ok(OperatorsReader::new(reader))
     }
 
+    /// Gets an operators reader for this function body which starts reading
+    /// at the original (module-relative) `offset` provided.
+    ///
+    /// This can be used to resume decoding in the middle of a function, for
+    /// example to decode a handful of instructions around an offset without
+    /// decoding the entire body. The `offset` must be an instruction boundary
+    /// previously recorded from this function body, such as one returned by
+    /// [`OperatorsReader::read_with_offset`]. Offsets which are not on an
+    /// instruction boundary are not detected and will decode garbage.
+    ///
+    /// An error is returned if `offset` lies outside of the operators of this
+    /// function body.
+    ///
+    /// # Examples
+    /// ```
+    /// use wasmparser::{Operator, CodeSectionReader};
+    /// # let data: &[u8] = &[
+    /// #     0x01, 0x05, 0x00, /* offset = 23 */ 0x01, 0x01, 0x01, 0x0b];
+    /// let code_reader = CodeSectionReader::new(data, 20).unwrap();
+    /// for body in code_reader {
+    ///     let body = body.expect("function body");
+    ///     let mut op_reader = body.get_operators_reader_at(25).expect("op reader");
+    ///     assert!(matches!(op_reader.read_with_offset(), Ok((Operator::Nop, 25))));
+    ///     assert!(matches!(op_reader.read_with_offset(), Ok((Operator::End, 26))));
+    ///     assert!(op_reader.eof());
+    /// }
+    /// ```
+    pub fn get_operators_reader_at(&self, offset: usize) -> Result<OperatorsReader<'a>> {
+        let mut reader = self.reader.clone();
+        Self::skip_locals(&mut reader)?;
+        let start = reader.original_position();
+        let end = reader.range().end;
+        if offset < start || offset >= end {
+            return Err(BinaryReaderError::fmt(
+                format_args!(
+                    "offset {offset:#x} is outside of the function body's operators \
+                     ({start:#x}..{end:#x})"
+                ),
+                offset,
+            ));
+        }
+        reader.position += offset - start;
+        Ok(OperatorsReader::new(reader))
+    }
+
     /// Gets the range of the function body.
     pub fn range(&self) -> Range<usize> {
         self.reader.range()
@@ -144,3 +238,55 @@ impl<'a> Iterator for LocalsIterator<'a> {
         (count, Some(count))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Parser, Payload};
+
+    fn first_body(wasm: &[u8]) -> FunctionBody<'_> {
+        for payload in Parser::new(0).parse_all(wasm) {
+            if let Payload::CodeSectionEntry(body) = payload.unwrap() {
+                return body;
+            }
+        }
+        panic!("no function body");
+    }
+
+    #[test]
+    fn operators_reader_at_resumes_on_boundaries() {
+        let wasm = wat::parse_str(
+            "(module (func (local i32 i64) i32.const 1 i32.const 300 i32.add drop))",
+        )
+        .unwrap();
+        let body = first_body(&wasm);
+        let mut all = Vec::new();
+        let mut reader = body.get_operators_reader().unwrap();
+        while !reader.eof() {
+            all.push(reader.read_with_offset().unwrap());
+        }
+        for (i, (_, offset)) in all.iter().enumerate() {
+            let mut reader = body.get_operators_reader_at(*offset).unwrap();
+            for (op, offset) in &all[i..] {
+                let (actual, actual_offset) = reader.read_with_offset().unwrap();
+                assert_eq!(format!("{actual:?}"), format!("{op:?}"));
+                assert_eq!(actual_offset, *offset);
+            }
+            assert!(reader.eof());
+        }
+    }
+
+    #[test]
+    fn operators_reader_at_rejects_offsets_outside_operators() {
+        let wasm = wat::parse_str("(module (func (local i32) nop))").unwrap();
+        let body = first_body(&wasm);
+        let start = body.get_operators_reader().unwrap().original_position();
+        let end = body.range().end;
+        // The start of the body, which holds the locals.
+        assert!(body.get_operators_reader_at(body.range().start).is_err());
+        assert!(body.get_operators_reader_at(start - 1).is_err());
+        assert!(body.get_operators_reader_at(end).is_err());
+        assert!(body.get_operators_reader_at(start).is_ok());
+        assert!(body.get_operators_reader_at(end - 1).is_ok());
+    }
+}
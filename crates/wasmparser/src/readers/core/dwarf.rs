@@ -0,0 +1,120 @@
+use crate::{CustomSectionReader, Parser, Payload, Result};
+use alloc::vec::Vec;
+
+/// The DWARF debugging information of a WebAssembly module, which is stored
+/// in custom sections named `.debug_info`, `.debug_line`, and so on.
+///
+/// The sections can be collected while parsing a module by passing each
+/// custom section to [`DebugSections::add`], or all at once with
+/// [`DebugSections::parse`]. The contents of a section are then looked up by
+/// name with [`DebugSections::get`], which returns an empty slice for
+/// missing sections, as expected by DWARF readers such as the `gimli` crate:
+///
+/// ```ignore
+/// let sections = wasmparser::DebugSections::parse(&wasm)?;
+/// let dwarf = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+///     Ok(gimli::EndianSlice::new(sections.get(id.name()), gimli::LittleEndian))
+/// })?;
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// use wasmparser::DebugSections;
+///
+/// let wasm = wat::parse_str(r#"
+///     (module
+///         (@custom ".debug_info" "info")
+///         (@custom ".debug_line" "line")
+///         (@custom "name" "\00\02\01m"))
+/// "#).unwrap();
+/// let sections = DebugSections::parse(&wasm).unwrap();
+/// assert_eq!(sections.get(".debug_info"), b"info");
+/// assert_eq!(sections.get(".debug_str"), b"");
+/// let names = sections.iter().map(|s| s.name).collect::<Vec<_>>();
+/// assert_eq!(names, [".debug_info", ".debug_line"]);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct DebugSections<'a> {
+    sections: Vec<DebugSection<'a>>,
+}
+
+/// A custom section containing DWARF debugging information, as collected in
+/// [`DebugSections`].
+#[derive(Debug, Copy, Clone)]
+pub struct DebugSection<'a> {
+    /// The name of the section, such as `.debug_info`.
+    pub name: &'a str,
+    /// The contents of the section.
+    pub data: &'a [u8],
+    /// The offset of `data` within the original wasm binary.
+    pub data_offset: usize,
+}
+
+impl<'a> DebugSections<'a> {
+    /// Creates an empty set of debug sections.
+    pub fn new() -> DebugSections<'a> {
+        DebugSections::default()
+    }
+
+    /// Collects the debug sections of the module or component `wasm`.
+    ///
+    /// Only the sections of `wasm` itself are collected, not those of any
+    /// modules or components nested within it.
+    pub fn parse(wasm: &'a [u8]) -> Result<DebugSections<'a>> {
+        let mut sections = DebugSections::new();
+        let mut depth = 0;
+        for payload in Parser::new(0).parse_all(wasm) {
+            match payload? {
+                Payload::ModuleSection { .. } | Payload::ComponentSection { .. } => depth += 1,
+                Payload::End(_) => depth -= 1,
+                Payload::CustomSection(reader) if depth == 0 => {
+                    sections.add(&reader);
+                }
+                _ => {}
+            }
+        }
+        Ok(sections)
+    }
+
+    /// Adds the custom section `reader` if it contains debugging information,
+    /// returning whether it was added.
+    ///
+    /// If a section with the same name was already added then the first one
+    /// is kept and this returns `false`.
+    pub fn add(&mut self, reader: &CustomSectionReader<'a>) -> bool {
+        if !reader.name().starts_with(".debug_") || self.section(reader.name()).is_some() {
+            return false;
+        }
+        self.sections.push(DebugSection {
+            name: reader.name(),
+            data: reader.data(),
+            data_offset: reader.data_offset(),
+        });
+        true
+    }
+
+    /// Returns the section named `name`, if any.
+    pub fn section(&self, name: &str) -> Option<&DebugSection<'a>> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+
+    /// Returns the contents of the section named `name`, or an empty slice if
+    /// there's no such section.
+    pub fn get(&self, name: &str) -> &'a [u8] {
+        match self.section(name) {
+            Some(section) => section.data,
+            None => &[],
+        }
+    }
+
+    /// Returns an iterator over the sections, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &DebugSection<'a>> + '_ {
+        self.sections.iter()
+    }
+
+    /// Returns whether there are no debug sections.
+    pub fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+}
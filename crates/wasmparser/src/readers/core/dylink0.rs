@@ -0,0 +1,157 @@
+use crate::{BinaryReader, BinaryReaderError, Result, Subsection, Subsections, SymbolInfo};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// A reader for the `dylink.0` custom section of a WebAssembly module, which
+/// describes the module as a shared library for dynamic linking.
+///
+/// This section is described by the [tool conventions].
+///
+/// [tool conventions]: https://github.com/WebAssembly/tool-conventions/blob/main/DynamicLinking.md
+///
+/// # Examples
+///
+/// ```
+/// use wasmparser::{Dylink0SectionReader, Dylink0Subsection};
+///
+/// // A `needed` subsection with one library.
+/// let data = b"\x02\x09\x01\x07libc.so";
+/// let reader = Dylink0SectionReader::new(data, 0);
+/// for subsection in reader {
+///     match subsection.unwrap() {
+///         Dylink0Subsection::Needed(libs) => assert_eq!(libs, ["libc.so"]),
+///         _ => unreachable!(),
+///     }
+/// }
+/// ```
+pub type Dylink0SectionReader<'a> = Subsections<'a, Dylink0Subsection<'a>>;
+
+/// The names of the symbol flags of [`ExportInfo`] and [`ImportInfo`], as
+/// written in the text format, with the bit of each.
+pub const DYLINK_0_SYMBOL_FLAGS: &[(&str, u32)] = &[
+    ("binding-weak", SymbolInfo::WASM_SYM_BINDING_WEAK),
+    ("binding-local", SymbolInfo::WASM_SYM_BINDING_LOCAL),
+    ("visibility-hidden", SymbolInfo::WASM_SYM_VISIBILITY_HIDDEN),
+    ("undefined", SymbolInfo::WASM_SYM_UNDEFINED),
+    ("exported", SymbolInfo::WASM_SYM_EXPORTED),
+    ("explicit-name", SymbolInfo::WASM_SYM_EXPLICIT_NAME),
+    ("no-strip", SymbolInfo::WASM_SYM_NO_STRIP),
+    ("tls", SymbolInfo::WASM_SYM_TLS),
+    ("absolute", SymbolInfo::WASM_SYM_ABSOLUTE),
+];
+
+/// A subsection of the `dylink.0` custom section.
+#[derive(Debug, Clone)]
+pub enum Dylink0Subsection<'a> {
+    /// The memory and table space the module requires.
+    MemInfo(MemInfo),
+    /// The shared libraries the module depends on.
+    Needed(Vec<&'a str>),
+    /// Extra information about the exports of the module.
+    ExportInfo(Vec<ExportInfo<'a>>),
+    /// Extra information about the imports of the module.
+    ImportInfo(Vec<ImportInfo<'a>>),
+    /// An unknown subsection.
+    Unknown {
+        /// The identifier for this subsection.
+        ty: u8,
+        /// The contents of this subsection.
+        data: &'a [u8],
+        /// The range of bytes, relative to the start of the original data
+        /// stream, that the contents of this subsection reside in.
+        range: Range<usize>,
+    },
+}
+
+/// The memory and table space a module requires, from the `mem-info`
+/// subsection of the `dylink.0` custom section.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MemInfo {
+    /// The size of the module's data, in bytes.
+    pub memory_size: u32,
+    /// The alignment of the module's data, as a power of two.
+    pub memory_alignment: u32,
+    /// The number of table elements the module requires.
+    pub table_size: u32,
+    /// The alignment of the module's table elements, as a power of two.
+    pub table_alignment: u32,
+}
+
+/// Extra information about an export, from the `export-info` subsection of
+/// the `dylink.0` custom section.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExportInfo<'a> {
+    /// The name of the export.
+    pub name: &'a str,
+    /// The symbol flags of the export, a combination of the `WASM_SYM_*`
+    /// constants of [`SymbolInfo`].
+    pub flags: u32,
+}
+
+/// Extra information about an import, from the `import-info` subsection of
+/// the `dylink.0` custom section.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImportInfo<'a> {
+    /// The module name of the import.
+    pub module: &'a str,
+    /// The field name of the import.
+    pub field: &'a str,
+    /// The symbol flags of the import, a combination of the `WASM_SYM_*`
+    /// constants of [`SymbolInfo`].
+    pub flags: u32,
+}
+
+impl<'a> Subsection<'a> for Dylink0Subsection<'a> {
+    fn from_reader(id: u8, mut reader: BinaryReader<'a>) -> Result<Self> {
+        let data = reader.remaining_buffer();
+        let offset = reader.original_position();
+        let subsection = match id {
+            1 => Dylink0Subsection::MemInfo(MemInfo {
+                memory_size: reader.read_var_u32()?,
+                memory_alignment: reader.read_var_u32()?,
+                table_size: reader.read_var_u32()?,
+                table_alignment: reader.read_var_u32()?,
+            }),
+            2 => Dylink0Subsection::Needed(
+                (0..reader.read_var_u32()?)
+                    .map(|_| reader.read_string())
+                    .collect::<Result<_>>()?,
+            ),
+            3 => Dylink0Subsection::ExportInfo(
+                (0..reader.read_var_u32()?)
+                    .map(|_| {
+                        Ok(ExportInfo {
+                            name: reader.read_string()?,
+                            flags: reader.read_var_u32()?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            4 => Dylink0Subsection::ImportInfo(
+                (0..reader.read_var_u32()?)
+                    .map(|_| {
+                        Ok(ImportInfo {
+                            module: reader.read_string()?,
+                            field: reader.read_string()?,
+                            flags: reader.read_var_u32()?,
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            ty => {
+                return Ok(Dylink0Subsection::Unknown {
+                    ty,
+                    data,
+                    range: offset..offset + data.len(),
+                })
+            }
+        };
+        if !reader.eof() {
+            return Err(BinaryReaderError::new(
+                "trailing data at the end of a dylink.0 subsection",
+                reader.original_position(),
+            ));
+        }
+        Ok(subsection)
+    }
+}
@@ -0,0 +1,445 @@
+use crate::{
+    BinaryReader, BinaryReaderError, FromReader, Result, SectionLimited, Subsection, Subsections,
+};
+use alloc::format;
+use core::ops::Range;
+
+/// The version of the `linking` custom section that can be read.
+const VERSION: u32 = 2;
+
+/// A reader for the `linking` custom section of a WebAssembly object file, as
+/// produced by LLVM and consumed by linkers such as `wasm-ld`.
+///
+/// This section is described by the [tool conventions] and contains the
+/// symbol table of the object file along with other information needed to
+/// link it.
+///
+/// [tool conventions]: https://github.com/WebAssembly/tool-conventions/blob/main/Linking.md
+///
+/// # Examples
+///
+/// ```
+/// use wasmparser::{Linking, LinkingSectionReader, SymbolInfo};
+///
+/// // Version 2, followed by a symbol table with one defined function.
+/// let data = [0x02, 0x08, 0x06, 0x01, 0x00, 0x00, 0x00, 0x01, b'f'];
+/// let reader = LinkingSectionReader::new(&data, 0).unwrap();
+/// for subsection in reader.subsections() {
+///     if let Linking::SymbolTable(symbols) = subsection.unwrap() {
+///         for symbol in symbols {
+///             match symbol.unwrap() {
+///                 SymbolInfo::Func { index, name, .. } => {
+///                     assert_eq!(index, 0);
+///                     assert_eq!(name, Some("f"));
+///                 }
+///                 _ => unreachable!(),
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct LinkingSectionReader<'a> {
+    version: u32,
+    subsections: Subsections<'a, Linking<'a>>,
+    range: Range<usize>,
+}
+
+impl<'a> LinkingSectionReader<'a> {
+    /// Creates a new reader for the `linking` custom section contents
+    /// starting at `offset` within the original wasm file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the section's version isn't supported.
+    pub fn new(data: &'a [u8], offset: usize) -> Result<Self> {
+        let mut reader = BinaryReader::new_with_offset(data, offset);
+        let range = reader.range();
+        let version = reader.read_var_u32()?;
+        if version != VERSION {
+            return Err(BinaryReaderError::new(
+                format!("unsupported linking section version: {version}"),
+                offset,
+            ));
+        }
+        let subsections = Subsections::new(reader.remaining_buffer(), reader.original_position());
+        Ok(LinkingSectionReader {
+            version,
+            subsections,
+            range,
+        })
+    }
+
+    /// Returns the version of this section.
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Returns the range, as byte offsets, of this section within the original
+    /// wasm binary.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns an iterator over the subsections of this section.
+    pub fn subsections(&self) -> Subsections<'a, Linking<'a>> {
+        self.subsections.clone()
+    }
+}
+
+/// A subsection of the `linking` custom section.
+#[derive(Debug, Clone)]
+pub enum Linking<'a> {
+    /// Extra information about the data segments.
+    SegmentInfo(SectionLimited<'a, Segment<'a>>),
+    /// The initialization functions to call on startup.
+    InitFuncs(SectionLimited<'a, InitFunc>),
+    /// The COMDAT groups of the object file.
+    ComdatInfo(SectionLimited<'a, Comdat<'a>>),
+    /// The symbol table of the object file.
+    SymbolTable(SectionLimited<'a, SymbolInfo<'a>>),
+    /// An unknown subsection.
+    Unknown {
+        /// The identifier for this subsection.
+        ty: u8,
+        /// The contents of this subsection.
+        data: &'a [u8],
+        /// The range of bytes, relative to the start of the original data
+        /// stream, that the contents of this subsection reside in.
+        range: Range<usize>,
+    },
+}
+
+impl<'a> Subsection<'a> for Linking<'a> {
+    fn from_reader(id: u8, reader: BinaryReader<'a>) -> Result<Self> {
+        let data = reader.remaining_buffer();
+        let offset = reader.original_position();
+        Ok(match id {
+            5 => Linking::SegmentInfo(SectionLimited::new(data, offset)?),
+            6 => Linking::InitFuncs(SectionLimited::new(data, offset)?),
+            7 => Linking::ComdatInfo(SectionLimited::new(data, offset)?),
+            8 => Linking::SymbolTable(SectionLimited::new(data, offset)?),
+            ty => Linking::Unknown {
+                ty,
+                data,
+                range: offset..offset + data.len(),
+            },
+        })
+    }
+}
+
+/// Extra information about a data segment.
+#[derive(Debug, Copy, Clone)]
+pub struct Segment<'a> {
+    /// The name of the segment.
+    pub name: &'a str,
+    /// The alignment of the segment, as a power of two.
+    pub alignment: u32,
+    /// The flags of the segment, a combination of the `WASM_SEGMENT_FLAG_*`
+    /// constants.
+    pub flags: u32,
+}
+
+impl Segment<'_> {
+    /// The segment contains only null-terminated strings, which allows the
+    /// linker to merge them.
+    pub const WASM_SEGMENT_FLAG_STRINGS: u32 = 0x1;
+    /// The segment contains thread-local data.
+    pub const WASM_SEGMENT_FLAG_TLS: u32 = 0x2;
+}
+
+impl<'a> FromReader<'a> for Segment<'a> {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let name = reader.read_string()?;
+        let alignment = reader.read_var_u32()?;
+        let flags = reader.read_var_u32()?;
+        Ok(Segment {
+            name,
+            alignment,
+            flags,
+        })
+    }
+}
+
+/// An initialization function to call on startup.
+#[derive(Debug, Copy, Clone)]
+pub struct InitFunc {
+    /// The priority of the function, where functions with a lower priority
+    /// are called first.
+    pub priority: u32,
+    /// The index of the function's symbol in the symbol table.
+    pub symbol_index: u32,
+}
+
+impl<'a> FromReader<'a> for InitFunc {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let priority = reader.read_var_u32()?;
+        let symbol_index = reader.read_var_u32()?;
+        Ok(InitFunc {
+            priority,
+            symbol_index,
+        })
+    }
+}
+
+/// A COMDAT group, of which a linker only includes one copy with the same
+/// name.
+#[derive(Debug, Clone)]
+pub struct Comdat<'a> {
+    /// The name of the group.
+    pub name: &'a str,
+    /// The flags of the group, which are currently unused.
+    pub flags: u32,
+    /// The items in the group.
+    pub symbols: SectionLimited<'a, ComdatSymbol>,
+}
+
+impl<'a> FromReader<'a> for Comdat<'a> {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let name = reader.read_string()?;
+        let flags = reader.read_var_u32()?;
+        // FIXME(#188) ideally shouldn't need to skip here
+        let symbols = reader.skip(|reader| {
+            let count = reader.read_var_u32()?;
+            for _ in 0..count {
+                reader.read_u8()?;
+                reader.read_var_u32()?;
+            }
+            Ok(())
+        })?;
+        Ok(Comdat {
+            name,
+            flags,
+            symbols: SectionLimited::new(symbols.remaining_buffer(), symbols.original_position())?,
+        })
+    }
+}
+
+/// An item in a [`Comdat`] group.
+#[derive(Debug, Copy, Clone)]
+pub struct ComdatSymbol {
+    /// The kind of the item.
+    pub kind: ComdatSymbolKind,
+    /// The index of the item, in the index space of its kind.
+    pub index: u32,
+}
+
+impl<'a> FromReader<'a> for ComdatSymbol {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let offset = reader.original_position();
+        let kind = match reader.read_u8()? {
+            0 => ComdatSymbolKind::Data,
+            1 => ComdatSymbolKind::Func,
+            2 => ComdatSymbolKind::Global,
+            3 => ComdatSymbolKind::Event,
+            4 => ComdatSymbolKind::Table,
+            5 => ComdatSymbolKind::Section,
+            k => {
+                return Err(BinaryReaderError::new(
+                    format!("invalid comdat symbol kind: {k}"),
+                    offset,
+                ))
+            }
+        };
+        let index = reader.read_var_u32()?;
+        Ok(ComdatSymbol { kind, index })
+    }
+}
+
+/// The kind of an item in a [`Comdat`] group.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ComdatSymbolKind {
+    /// A data segment.
+    Data,
+    /// A function.
+    Func,
+    /// A global.
+    Global,
+    /// An event, or tag.
+    Event,
+    /// A table.
+    Table,
+    /// A custom section.
+    Section,
+}
+
+/// A symbol in the symbol table of the `linking` custom section.
+///
+/// The `flags` of each symbol are a combination of the `WASM_SYM_*`
+/// constants.
+#[derive(Debug, Copy, Clone)]
+pub enum SymbolInfo<'a> {
+    /// A function symbol.
+    Func {
+        /// The flags of the symbol.
+        flags: u32,
+        /// The index of the function.
+        index: u32,
+        /// The name of the symbol, which is only present for defined symbols
+        /// or with [`SymbolInfo::WASM_SYM_EXPLICIT_NAME`].
+        name: Option<&'a str>,
+    },
+    /// A data symbol.
+    Data {
+        /// The flags of the symbol.
+        flags: u32,
+        /// The name of the symbol.
+        name: &'a str,
+        /// Where the symbol is defined, which is `None` for undefined symbols.
+        symbol: Option<DefinedDataSymbol>,
+    },
+    /// A global symbol.
+    Global {
+        /// The flags of the symbol.
+        flags: u32,
+        /// The index of the global.
+        index: u32,
+        /// The name of the symbol, which is only present for defined symbols
+        /// or with [`SymbolInfo::WASM_SYM_EXPLICIT_NAME`].
+        name: Option<&'a str>,
+    },
+    /// A symbol for a custom section, used by relocations in debug info.
+    Section {
+        /// The flags of the symbol.
+        flags: u32,
+        /// The index of the section.
+        section: u32,
+    },
+    /// An event, or tag, symbol.
+    Event {
+        /// The flags of the symbol.
+        flags: u32,
+        /// The index of the tag.
+        index: u32,
+        /// The name of the symbol, which is only present for defined symbols
+        /// or with [`SymbolInfo::WASM_SYM_EXPLICIT_NAME`].
+        name: Option<&'a str>,
+    },
+    /// A table symbol.
+    Table {
+        /// The flags of the symbol.
+        flags: u32,
+        /// The index of the table.
+        index: u32,
+        /// The name of the symbol, which is only present for defined symbols
+        /// or with [`SymbolInfo::WASM_SYM_EXPLICIT_NAME`].
+        name: Option<&'a str>,
+    },
+}
+
+impl SymbolInfo<'_> {
+    /// The symbol is weak, and can be overridden by a strong symbol with the
+    /// same name.
+    pub const WASM_SYM_BINDING_WEAK: u32 = 0x1;
+    /// The symbol is local to the object file.
+    pub const WASM_SYM_BINDING_LOCAL: u32 = 0x2;
+    /// The symbol isn't exported from the linked module.
+    pub const WASM_SYM_VISIBILITY_HIDDEN: u32 = 0x4;
+    /// The symbol isn't defined by the object file.
+    pub const WASM_SYM_UNDEFINED: u32 = 0x10;
+    /// The symbol is exported from the linked module.
+    pub const WASM_SYM_EXPORTED: u32 = 0x20;
+    /// The symbol has a name even though it's undefined, rather than taking
+    /// the name of its import.
+    pub const WASM_SYM_EXPLICIT_NAME: u32 = 0x40;
+    /// The symbol must not be removed by the linker.
+    pub const WASM_SYM_NO_STRIP: u32 = 0x80;
+    /// The symbol is thread-local.
+    pub const WASM_SYM_TLS: u32 = 0x100;
+    /// The symbol's address is absolute rather than relative to its segment.
+    pub const WASM_SYM_ABSOLUTE: u32 = 0x200;
+
+    /// Returns the flags of this symbol.
+    pub fn flags(&self) -> u32 {
+        match self {
+            SymbolInfo::Func { flags, .. }
+            | SymbolInfo::Data { flags, .. }
+            | SymbolInfo::Global { flags, .. }
+            | SymbolInfo::Section { flags, .. }
+            | SymbolInfo::Event { flags, .. }
+            | SymbolInfo::Table { flags, .. } => *flags,
+        }
+    }
+}
+
+impl<'a> FromReader<'a> for SymbolInfo<'a> {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let offset = reader.original_position();
+        let kind = reader.read_u8()?;
+        let flags = reader.read_var_u32()?;
+
+        let defined = flags & SymbolInfo::WASM_SYM_UNDEFINED == 0;
+        let explicit_name = flags & SymbolInfo::WASM_SYM_EXPLICIT_NAME != 0;
+        let index_and_name = |reader: &mut BinaryReader<'a>| -> Result<_> {
+            let index = reader.read_var_u32()?;
+            let name = if defined || explicit_name {
+                Some(reader.read_string()?)
+            } else {
+                None
+            };
+            Ok((index, name))
+        };
+
+        Ok(match kind {
+            0 => {
+                let (index, name) = index_and_name(reader)?;
+                SymbolInfo::Func { flags, index, name }
+            }
+            1 => {
+                let name = reader.read_string()?;
+                let symbol = if defined { Some(reader.read()?) } else { None };
+                SymbolInfo::Data {
+                    flags,
+                    name,
+                    symbol,
+                }
+            }
+            2 => {
+                let (index, name) = index_and_name(reader)?;
+                SymbolInfo::Global { flags, index, name }
+            }
+            3 => SymbolInfo::Section {
+                flags,
+                section: reader.read_var_u32()?,
+            },
+            4 => {
+                let (index, name) = index_and_name(reader)?;
+                SymbolInfo::Event { flags, index, name }
+            }
+            5 => {
+                let (index, name) = index_and_name(reader)?;
+                SymbolInfo::Table { flags, index, name }
+            }
+            k => {
+                return Err(BinaryReaderError::new(
+                    format!("invalid symbol kind: {k}"),
+                    offset,
+                ))
+            }
+        })
+    }
+}
+
+/// Where a defined data symbol is located.
+#[derive(Debug, Copy, Clone)]
+pub struct DefinedDataSymbol {
+    /// The index of the data segment that contains the symbol.
+    pub index: u32,
+    /// The offset of the symbol within the segment.
+    pub offset: u64,
+    /// The size of the symbol in bytes.
+    pub size: u64,
+}
+
+impl<'a> FromReader<'a> for DefinedDataSymbol {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let index = reader.read_var_u32()?;
+        let offset = reader.read_var_u64()?;
+        let size = reader.read_var_u64()?;
+        Ok(DefinedDataSymbol {
+            index,
+            offset,
+            size,
+        })
+    }
+}
@@ -14,6 +14,7 @@
  */
 
 use crate::{BinaryReader, BinaryReaderError, Result, ValType};
+use alloc::boxed::Box;
 
 /// Represents a block type.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -0,0 +1,193 @@
+use crate::{BinaryReader, BinaryReaderError, FromReader, Result, SectionLimited};
+use alloc::format;
+use core::ops::Range;
+
+/// A reader for a `reloc.*` custom section of a WebAssembly object file,
+/// such as `reloc.CODE`, which lists the relocations to apply to another
+/// section when linking.
+///
+/// This section is described by the [tool conventions].
+///
+/// [tool conventions]: https://github.com/WebAssembly/tool-conventions/blob/main/Linking.md
+///
+/// # Examples
+///
+/// ```
+/// use wasmparser::{RelocSectionReader, RelocationType};
+///
+/// // Section 5, followed by one `R_WASM_MEMORY_ADDR_SLEB` relocation at
+/// // offset 1 of symbol 2 with an addend of -1.
+/// let data = [0x05, 0x01, 0x04, 0x01, 0x02, 0x7f];
+/// let reader = RelocSectionReader::new(&data, 0).unwrap();
+/// assert_eq!(reader.section_index(), 5);
+/// let entry = reader.entries().into_iter().next().unwrap().unwrap();
+/// assert_eq!(entry.ty, RelocationType::MemoryAddrSleb);
+/// assert_eq!((entry.offset, entry.index, entry.addend), (1, 2, -1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RelocSectionReader<'a> {
+    section: u32,
+    entries: SectionLimited<'a, RelocationEntry>,
+    range: Range<usize>,
+}
+
+impl<'a> RelocSectionReader<'a> {
+    /// Creates a new reader for the `reloc.*` custom section contents
+    /// starting at `offset` within the original wasm file.
+    pub fn new(data: &'a [u8], offset: usize) -> Result<Self> {
+        let mut reader = BinaryReader::new_with_offset(data, offset);
+        let range = reader.range();
+        let section = reader.read_var_u32()?;
+        let entries = SectionLimited::new(reader.remaining_buffer(), reader.original_position())?;
+        Ok(RelocSectionReader {
+            section,
+            entries,
+            range,
+        })
+    }
+
+    /// Returns the index of the section that the relocations apply to.
+    pub fn section_index(&self) -> u32 {
+        self.section
+    }
+
+    /// Returns the range, as byte offsets, of this section within the original
+    /// wasm binary.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns a reader for the relocations in this section.
+    pub fn entries(&self) -> SectionLimited<'a, RelocationEntry> {
+        self.entries.clone()
+    }
+}
+
+/// A relocation in a `reloc.*` custom section.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RelocationEntry {
+    /// The type of the relocation.
+    pub ty: RelocationType,
+    /// The offset of the value to rewrite, relative to the start of the
+    /// contents of the section that the relocation applies to.
+    pub offset: u32,
+    /// The index of the symbol used, or of the type for
+    /// [`RelocationType::TypeIndexLeb`].
+    pub index: u32,
+    /// The addend to add to the address, which is zero for relocations
+    /// without one.
+    pub addend: i64,
+}
+
+impl<'a> FromReader<'a> for RelocationEntry {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let type_offset = reader.original_position();
+        let code = reader.read_u8()?;
+        let ty = match RelocationType::from_code(code) {
+            Some(ty) => ty,
+            None => {
+                return Err(BinaryReaderError::new(
+                    format!("unknown relocation type: {code}"),
+                    type_offset,
+                ))
+            }
+        };
+        let offset = reader.read_var_u32()?;
+        let index = reader.read_var_u32()?;
+        let addend = if !ty.has_addend() {
+            0
+        } else if ty.is_64() {
+            reader.read_var_i64()?
+        } else {
+            i64::from(reader.read_var_i32()?)
+        };
+        Ok(RelocationEntry {
+            ty,
+            offset,
+            index,
+            addend,
+        })
+    }
+}
+
+macro_rules! relocation_types {
+    ($($name:ident = $code:tt $reloc:ident $(, $flag:ident)*;)*) => {
+        /// The type of a relocation, which determines the kind of value that
+        /// is rewritten and how it's encoded.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum RelocationType {
+            $(
+                #[doc = concat!("`", stringify!($reloc), "`")]
+                $name = $code,
+            )*
+        }
+
+        impl RelocationType {
+            /// Returns the relocation type with the given code, if any.
+            pub fn from_code(code: u8) -> Option<RelocationType> {
+                match code {
+                    $($code => Some(RelocationType::$name),)*
+                    _ => None,
+                }
+            }
+
+            /// Returns the name of this relocation type used by the tool
+            /// conventions, such as `R_WASM_FUNCTION_INDEX_LEB`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(RelocationType::$name => stringify!($reloc),)*
+                }
+            }
+
+            /// Returns whether relocations of this type have an addend.
+            pub fn has_addend(&self) -> bool {
+                match self {
+                    $(RelocationType::$name => relocation_types!(@has addend $($flag)*),)*
+                }
+            }
+
+            /// Returns whether this relocation type rewrites a 64-bit value,
+            /// in which case its addend is also 64 bits.
+            pub fn is_64(&self) -> bool {
+                match self {
+                    $(RelocationType::$name => relocation_types!(@has is64 $($flag)*),)*
+                }
+            }
+        }
+    };
+
+    (@has $want:ident) => (false);
+    (@has addend addend $($rest:ident)*) => (true);
+    (@has is64 is64 $($rest:ident)*) => (true);
+    (@has $want:ident $other:ident $($rest:ident)*) => (relocation_types!(@has $want $($rest)*));
+}
+
+relocation_types! {
+    FunctionIndexLeb = 0 R_WASM_FUNCTION_INDEX_LEB;
+    TableIndexSleb = 1 R_WASM_TABLE_INDEX_SLEB;
+    TableIndexI32 = 2 R_WASM_TABLE_INDEX_I32;
+    MemoryAddrLeb = 3 R_WASM_MEMORY_ADDR_LEB, addend;
+    MemoryAddrSleb = 4 R_WASM_MEMORY_ADDR_SLEB, addend;
+    MemoryAddrI32 = 5 R_WASM_MEMORY_ADDR_I32, addend;
+    TypeIndexLeb = 6 R_WASM_TYPE_INDEX_LEB;
+    GlobalIndexLeb = 7 R_WASM_GLOBAL_INDEX_LEB;
+    FunctionOffsetI32 = 8 R_WASM_FUNCTION_OFFSET_I32, addend;
+    SectionOffsetI32 = 9 R_WASM_SECTION_OFFSET_I32, addend;
+    EventIndexLeb = 10 R_WASM_EVENT_INDEX_LEB;
+    MemoryAddrRelSleb = 11 R_WASM_MEMORY_ADDR_REL_SLEB, addend;
+    TableIndexRelSleb = 12 R_WASM_TABLE_INDEX_REL_SLEB;
+    GlobalIndexI32 = 13 R_WASM_GLOBAL_INDEX_I32;
+    MemoryAddrLeb64 = 14 R_WASM_MEMORY_ADDR_LEB64, addend, is64;
+    MemoryAddrSleb64 = 15 R_WASM_MEMORY_ADDR_SLEB64, addend, is64;
+    MemoryAddrI64 = 16 R_WASM_MEMORY_ADDR_I64, addend, is64;
+    MemoryAddrRelSleb64 = 17 R_WASM_MEMORY_ADDR_REL_SLEB64, addend, is64;
+    TableIndexSleb64 = 18 R_WASM_TABLE_INDEX_SLEB64, is64;
+    TableIndexI64 = 19 R_WASM_TABLE_INDEX_I64, is64;
+    TableNumberLeb = 20 R_WASM_TABLE_NUMBER_LEB;
+    MemoryAddrTlsSleb = 21 R_WASM_MEMORY_ADDR_TLS_SLEB, addend;
+    FunctionOffsetI64 = 22 R_WASM_FUNCTION_OFFSET_I64, addend, is64;
+    MemoryAddrLocrelI32 = 23 R_WASM_MEMORY_ADDR_LOCREL_I32, addend;
+    TableIndexRelSleb64 = 24 R_WASM_TABLE_INDEX_REL_SLEB64, is64;
+    MemoryAddrTlsSleb64 = 25 R_WASM_MEMORY_ADDR_TLS_SLEB64, addend, is64;
+    FunctionIndexI32 = 26 R_WASM_FUNCTION_INDEX_I32;
+}
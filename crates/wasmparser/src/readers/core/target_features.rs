@@ -0,0 +1,66 @@
+use crate::{BinaryReader, BinaryReaderError, FromReader, Result, SectionLimited};
+use alloc::format;
+
+/// A reader for the `target_features` custom section of a WebAssembly module,
+/// which lists the features, such as proposals, that the module was compiled
+/// with.
+///
+/// This section is described by the [tool conventions].
+///
+/// [tool conventions]: https://github.com/WebAssembly/tool-conventions/blob/main/Linking.md#target-features-section
+///
+/// # Examples
+///
+/// ```
+/// use wasmparser::{TargetFeaturePrefix, TargetFeaturesSectionReader};
+///
+/// let data = b"\x02+\x0bbulk-memory-\x07atomics";
+/// let reader = TargetFeaturesSectionReader::new(data, 0).unwrap();
+/// let features = reader.into_iter().collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(features[0].prefix, TargetFeaturePrefix::Used);
+/// assert_eq!(features[0].name, "bulk-memory");
+/// assert_eq!(features[1].prefix, TargetFeaturePrefix::Disallowed);
+/// assert_eq!(features[1].name, "atomics");
+/// ```
+pub type TargetFeaturesSectionReader<'a> = SectionLimited<'a, TargetFeature<'a>>;
+
+/// A feature listed in the `target_features` custom section.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TargetFeature<'a> {
+    /// How the feature is used by the module.
+    pub prefix: TargetFeaturePrefix,
+    /// The name of the feature, such as `bulk-memory`.
+    pub name: &'a str,
+}
+
+/// How a feature in the `target_features` custom section is used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TargetFeaturePrefix {
+    /// The module uses the feature, written as `+`.
+    Used,
+    /// The module must not be linked with modules that use the feature,
+    /// written as `-`.
+    Disallowed,
+    /// The feature must be used by every module it's linked with, written as
+    /// `=`.
+    Required,
+}
+
+impl<'a> FromReader<'a> for TargetFeature<'a> {
+    fn from_reader(reader: &mut BinaryReader<'a>) -> Result<Self> {
+        let offset = reader.original_position();
+        let prefix = match reader.read_u8()? {
+            b'+' => TargetFeaturePrefix::Used,
+            b'-' => TargetFeaturePrefix::Disallowed,
+            b'=' => TargetFeaturePrefix::Required,
+            p => {
+                return Err(BinaryReaderError::new(
+                    format!("invalid target feature prefix: {p:#x}"),
+                    offset,
+                ))
+            }
+        };
+        let name = reader.read_string()?;
+        Ok(TargetFeature { prefix, name })
+    }
+}
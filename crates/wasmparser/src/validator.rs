@@ -14,8 +14,8 @@
  */
 
 use crate::{
-    limits::*, BinaryReaderError, Encoding, FromReader, FunctionBody, HeapType, Parser, Payload,
-    Result, SectionLimited, ValType, WASM_COMPONENT_VERSION, WASM_MODULE_VERSION,
+    limits::*, BinaryReaderError, Encoding, FromReader, FunctionBody, HeapType, Limits, Parser,
+    Payload, Result, SectionLimited, ValType, WASM_COMPONENT_VERSION, WASM_MODULE_VERSION,
 };
 use std::mem;
 use std::ops::Range;
@@ -108,6 +108,76 @@ fn combine_type_sizes(a: u32, b: u32, offset: usize) -> Result<u32> {
 /// the validation context up to that point. This enables applications to check
 /// the types of functions and learn how many globals there are, for example.
 ///
+/// A `Validator` only retains the declarations of a module, such as its types,
+/// function signatures and globals, and never the contents of function
+/// bodies. Each body is returned as a [`FuncToValidate`] and once it has been
+/// validated nothing about it is kept, so a module streamed from a `Read` can
+/// be validated with memory proportional to its declarations rather than to
+/// the size of its code.
+///
+/// # Examples
+///
+/// Validating a stream of bytes, validating each function as soon as it's
+/// received and discarding the input as it's consumed:
+///
+/// ```
+/// use std::io::Read;
+/// use anyhow::Result;
+/// use wasmparser::{Chunk, FuncValidatorAllocations, Parser, ValidPayload, Validator};
+///
+/// fn validate(mut reader: impl Read) -> Result<()> {
+///     let mut buf = Vec::new();
+///     let mut eof = false;
+///     let mut parser = Parser::new(0);
+///     let mut stack = Vec::new();
+///     let mut validator = Validator::new();
+///     let mut allocs = FuncValidatorAllocations::default();
+///
+///     loop {
+///         let (payload, consumed) = match parser.parse(&buf, eof)? {
+///             Chunk::NeedMoreData(hint) => {
+///                 let len = buf.len();
+///                 buf.resize(len + hint as usize, 0);
+///                 let n = reader.read(&mut buf[len..])?;
+///                 buf.truncate(len + n);
+///                 eof = n == 0;
+///                 continue;
+///             }
+///             Chunk::Parsed { consumed, payload } => (payload, consumed),
+///         };
+///
+///         match validator.payload(&payload)? {
+///             ValidPayload::Ok => {}
+///             ValidPayload::Parser(nested) => {
+///                 stack.push(parser.clone());
+///                 parser = nested;
+///             }
+///             ValidPayload::Func(func, body) => {
+///                 let mut func = func.into_validator(allocs);
+///                 func.validate(&body)?;
+///                 allocs = func.into_allocations();
+///             }
+///             ValidPayload::End(_) => match stack.pop() {
+///                 Some(parent) => parser = parent,
+///                 None => break,
+///             },
+///         }
+///
+///         buf.drain(..consumed);
+///     }
+///     Ok(())
+/// }
+///
+/// let wasm = wat::parse_str(r#"
+///     (module
+///         (func (export "f") (result i32) i32.const 1)
+///         (func (result i32) call 0))
+/// "#)?;
+/// validate(&wasm[..])?;
+/// assert!(validate(&wat::parse_str("(module (func (result i32)))")?[..]).is_err());
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
 /// [core]: https://webassembly.github.io/spec/core/valid/index.html
 #[derive(Default)]
 pub struct Validator {
@@ -127,6 +197,13 @@ pub struct Validator {
     /// Enabled WebAssembly feature flags, dictating what's valid and what
     /// isn't.
     features: WasmFeatures,
+
+    /// Configured limits on the size of the module or component.
+    limits: Limits,
+
+    /// The total number of items declared so far, checked against
+    /// `limits.max_declared_items`.
+    declared_items: u64,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -289,6 +366,80 @@ impl WasmFeatures {
             }
         }
     }
+
+    /// Determines the features required to validate the module or component
+    /// `bytes`.
+    ///
+    /// The returned features have only those features enabled without which
+    /// `bytes` would fail to validate, for example `simd` if a `v128` is used
+    /// or `memory64` if a 64-bit memory is defined. This includes features
+    /// which are enabled by default, so a module with no floating-point
+    /// operations or types won't have `floats` enabled.
+    ///
+    /// This works by validating `bytes` once with all features enabled and
+    /// then once more per feature, so it's rather more expensive than
+    /// validation itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` isn't valid even with all features
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmparser::WasmFeatures;
+    ///
+    /// let wasm = wat::parse_str(r#"
+    ///     (module
+    ///         (memory i64 1)
+    ///         (func (param v128) (result v128) local.get 0))
+    /// "#).unwrap();
+    /// let features = WasmFeatures::detect(&wasm).unwrap();
+    /// assert!(features.memory64 && features.simd);
+    /// assert!(!features.threads && !features.floats && !features.multi_value);
+    /// ```
+    pub fn detect(bytes: &[u8]) -> Result<WasmFeatures> {
+        // Features which build on others are listed before them, so they're
+        // disabled first when unused.
+        let flags: [fn(&mut WasmFeatures) -> &mut bool; 18] = [
+            |f| &mut f.component_model,
+            |f| &mut f.mutable_global,
+            |f| &mut f.saturating_float_to_int,
+            |f| &mut f.sign_extension,
+            |f| &mut f.function_references,
+            |f| &mut f.reference_types,
+            |f| &mut f.multi_value,
+            |f| &mut f.memory_control,
+            |f| &mut f.bulk_memory,
+            |f| &mut f.relaxed_simd,
+            |f| &mut f.simd,
+            |f| &mut f.threads,
+            |f| &mut f.tail_call,
+            |f| &mut f.floats,
+            |f| &mut f.multi_memory,
+            |f| &mut f.exceptions,
+            |f| &mut f.memory64,
+            |f| &mut f.extended_const,
+        ];
+        let mut features = WasmFeatures::default();
+        for flag in flags {
+            *flag(&mut features) = true;
+        }
+        Validator::new_with_features(features).validate_all(bytes)?;
+
+        for flag in flags {
+            let mut without = features;
+            *flag(&mut without) = false;
+            if Validator::new_with_features(without)
+                .validate_all(bytes)
+                .is_ok()
+            {
+                features = without;
+            }
+        }
+        Ok(features)
+    }
 }
 
 impl Default for WasmFeatures {
@@ -365,6 +516,73 @@ impl Validator {
         &self.features
     }
 
+    /// Configures the limits on the size of the modules and components
+    /// accepted by this validator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmparser::{Limits, Validator, WasmFeatures};
+    ///
+    /// let wasm = wat::parse_str("(module (func) (func) (func))").unwrap();
+    /// let mut validator = Validator::new();
+    /// validator.set_limits(Limits {
+    ///     max_section_items: 2,
+    ///     ..Default::default()
+    /// });
+    /// let err = match validator.validate_all(&wasm) {
+    ///     Ok(_) => unreachable!(),
+    ///     Err(e) => e,
+    /// };
+    /// assert_eq!(err.message(), "function section has 3 items, exceeding the limit of 2");
+    ///
+    /// let wasm = wat::parse_str("(component (component (component)))").unwrap();
+    /// let mut validator = Validator::new_with_features(WasmFeatures {
+    ///     component_model: true,
+    ///     ..Default::default()
+    /// });
+    /// validator.set_limits(Limits {
+    ///     max_nesting_depth: 1,
+    ///     ..Default::default()
+    /// });
+    /// let err = match validator.validate_all(&wasm) {
+    ///     Ok(_) => unreachable!(),
+    ///     Err(e) => e,
+    /// };
+    /// assert_eq!(err.message(), "nesting depth exceeds the limit of 1");
+    /// ```
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Returns the limits used for this validator.
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    /// Checks the configured limits for a section named `name` declaring
+    /// `count` items.
+    fn check_limits(&mut self, name: &str, count: u32, offset: usize) -> Result<()> {
+        self.limits.check_section(name, count, offset)?;
+        self.declare_items(count, offset)
+    }
+
+    /// Records that `count` more items were declared, checking the configured
+    /// limit on the total.
+    fn declare_items(&mut self, count: u32, offset: usize) -> Result<()> {
+        self.declared_items += u64::from(count);
+        self.limits
+            .check_declared_items(self.declared_items, offset)
+    }
+
+    /// Checks the configured nesting depth limit before a nested module or
+    /// component starts.
+    fn check_nesting_depth(&mut self, offset: usize) -> Result<()> {
+        let depth = u32::try_from(self.components.len()).unwrap_or(u32::MAX);
+        self.limits.check_nesting_depth(depth, offset)?;
+        self.declare_items(1, offset)
+    }
+
     /// Validates an entire in-memory module or component with this validator.
     ///
     /// This function will internally create a [`Parser`] to parse the `bytes`
@@ -466,6 +684,12 @@ impl Validator {
                 let func_validator = self.code_section_entry(body)?;
                 return Ok(ValidPayload::Func(func_validator, body.clone()));
             }
+            SkippedCodeSectionChunk { range } => {
+                bail!(
+                    range.start,
+                    "function bodies must not be skipped to validate"
+                )
+            }
             DataSection(s) => self.data_section(s)?,
 
             // Component sections
@@ -489,7 +713,8 @@ impl Validator {
 
             End(offset) => return Ok(ValidPayload::End(self.end(*offset)?)),
 
-            CustomSection { .. } => {} // no validation for custom sections
+            // no validation for custom sections
+            CustomSection { .. } | CustomSectionStart { .. } | CustomSectionChunk { .. } => {}
             UnknownSection { id, range, .. } => self.unknown_section(*id, range)?,
         }
         Ok(ValidPayload::Ok)
@@ -887,12 +1112,14 @@ impl Validator {
         let state = self.module.as_mut().unwrap();
 
         let (index, ty) = state.next_code_index_and_type(offset)?;
-        Ok(FuncToValidate::new(
+        let mut func = FuncToValidate::new(
             index,
             ty,
             ValidatorResources(state.module.arc().clone()),
             &self.features,
-        ))
+        );
+        func.max_control_depth = self.limits.max_control_depth;
+        Ok(func)
     }
 
     /// Validates [`Payload::DataSection`](crate::Payload).
@@ -916,6 +1143,7 @@ impl Validator {
     /// This method should only be called when parsing a component.
     pub fn module_section(&mut self, range: &Range<usize>) -> Result<()> {
         self.state.ensure_component("module", range.start)?;
+        self.check_nesting_depth(range.start)?;
 
         let current = self.components.last_mut().unwrap();
         check_max(
@@ -989,6 +1217,7 @@ impl Validator {
     /// This method should only be called when parsing a component.
     pub fn component_section(&mut self, range: &Range<usize>) -> Result<()> {
         self.state.ensure_component("component", range.start)?;
+        self.check_nesting_depth(range.start)?;
 
         let current = self.components.last_mut().unwrap();
         check_max(
@@ -1273,7 +1502,7 @@ impl Validator {
         &mut self,
         order: Order,
         section: &SectionLimited<'a, T>,
-        name: &str,
+        name: &'static str,
         validate_section: impl FnOnce(
             &mut ModuleState,
             &WasmFeatures,
@@ -1295,6 +1524,8 @@ impl Validator {
         let offset = section.range().start;
         self.state.ensure_module(name, offset)?;
 
+        self.check_limits(name, section.count(), offset)?;
+
         let state = self.module.as_mut().unwrap();
         state.update_order(order, offset)?;
 
@@ -1306,9 +1537,11 @@ impl Validator {
             offset,
         )?;
 
-        for item in section.clone().into_iter_with_offsets() {
-            let (offset, item) = item?;
-            validate_item(state, &self.features, &mut self.types, item, offset)?;
+        for (index, item) in (0..).zip(section.clone().into_iter_with_offsets()) {
+            let (offset, item) =
+                item.map_err(|e| e.with_item("entry", index).with_section(name))?;
+            validate_item(state, &self.features, &mut self.types, item, offset)
+                .map_err(|e| e.with_item("entry", index).with_section(name))?;
         }
 
         Ok(())
@@ -1317,7 +1550,7 @@ impl Validator {
     fn process_component_section<'a, T>(
         &mut self,
         section: &SectionLimited<'a, T>,
-        name: &str,
+        name: &'static str,
         validate_section: impl FnOnce(
             &mut Vec<ComponentState>,
             &mut TypeAlloc,
@@ -1345,6 +1578,7 @@ impl Validator {
         }
 
         self.state.ensure_component(name, offset)?;
+        self.check_limits(name, section.count(), offset)?;
         validate_section(
             &mut self.components,
             &mut self.types,
@@ -1352,15 +1586,17 @@ impl Validator {
             offset,
         )?;
 
-        for item in section.clone().into_iter_with_offsets() {
-            let (offset, item) = item?;
+        for (index, item) in (0..).zip(section.clone().into_iter_with_offsets()) {
+            let (offset, item) =
+                item.map_err(|e| e.with_item("entry", index).with_section(name))?;
             validate_item(
                 &mut self.components,
                 &mut self.types,
                 &self.features,
                 item,
                 offset,
-            )?;
+            )
+            .map_err(|e| e.with_item("entry", index).with_section(name))?;
         }
 
         Ok(())
@@ -1369,9 +1605,104 @@ impl Validator {
 
 #[cfg(test)]
 mod tests {
-    use crate::{GlobalType, MemoryType, RefType, TableType, ValType, Validator, WasmFeatures};
+    use crate::{
+        GlobalType, Limits, MemoryType, RefType, TableType, ValType, Validator, WasmFeatures,
+    };
     use anyhow::Result;
 
+    /// Validates `wat` with `limits`, returning the error message if any.
+    fn validate_with_limits(wat: &str, limits: Limits) -> Option<String> {
+        let bytes = wat::parse_str(wat).unwrap();
+        let mut validator = Validator::new_with_features(WasmFeatures {
+            component_model: true,
+            ..Default::default()
+        });
+        validator.set_limits(limits);
+        validator
+            .validate_all(&bytes)
+            .err()
+            .map(|e| e.message().to_string())
+    }
+
+    #[test]
+    fn limit_section_items() {
+        let wat = "(module (func) (func) (func))";
+        let limits = |max_section_items| Limits {
+            max_section_items,
+            ..Default::default()
+        };
+        assert_eq!(validate_with_limits(wat, limits(3)), None);
+        assert_eq!(
+            validate_with_limits(wat, limits(2)).unwrap(),
+            "function section has 3 items, exceeding the limit of 2"
+        );
+
+        let wat = r#"(component (import "a" (func)) (import "b" (func)) (import "c" (func)))"#;
+        assert_eq!(validate_with_limits(wat, limits(3)), None);
+        assert_eq!(
+            validate_with_limits(wat, limits(2)).unwrap(),
+            "import section has 3 items, exceeding the limit of 2"
+        );
+    }
+
+    #[test]
+    fn limit_nesting_depth() {
+        let wat = "(component (component (component (core module))))";
+        let limits = |max_nesting_depth| Limits {
+            max_nesting_depth,
+            ..Default::default()
+        };
+        assert_eq!(validate_with_limits(wat, limits(3)), None);
+        assert_eq!(
+            validate_with_limits(wat, limits(2)).unwrap(),
+            "nesting depth exceeds the limit of 2"
+        );
+        assert_eq!(
+            validate_with_limits(wat, limits(0)).unwrap(),
+            "nesting depth exceeds the limit of 0"
+        );
+    }
+
+    #[test]
+    fn limit_declared_items() {
+        let limits = |max_declared_items| Limits {
+            max_declared_items,
+            ..Default::default()
+        };
+
+        // One type, two functions and an export.
+        let wat = r#"(module (type (func)) (func) (func) (export "f" (func 0)))"#;
+        assert_eq!(validate_with_limits(wat, limits(4)), None);
+        assert_eq!(
+            validate_with_limits(wat, limits(3)).unwrap(),
+            "declared items exceed the limit of 3"
+        );
+
+        // Each nested module counts as an item, along with its type and
+        // function, and the items of sibling modules add up.
+        let wat = "(component (core module (func)) (core module (func)))";
+        assert_eq!(validate_with_limits(wat, limits(6)), None);
+        assert_eq!(
+            validate_with_limits(wat, limits(5)).unwrap(),
+            "declared items exceed the limit of 5"
+        );
+    }
+
+    #[test]
+    fn limit_control_depth() {
+        let wat = "(module (func block loop i32.const 0 if nop end end end))";
+        let limits = |max_control_depth| Limits {
+            max_control_depth,
+            ..Default::default()
+        };
+        assert_eq!(validate_with_limits(wat, limits(3)), None);
+        assert_eq!(
+            validate_with_limits(wat, limits(2)).unwrap(),
+            "control frames are nested more than the limit of 2 deep"
+        );
+        assert_eq!(validate_with_limits("(module (func nop))", limits(0)), None);
+    }
+
     #[test]
     fn test_module_type_information() -> Result<()> {
         let bytes = wat::parse_str(
@@ -1389,7 +1389,7 @@ impl ComponentState {
                 }
                 ExternalKind::Tag => insert_export(
                     export.name,
-                    EntityType::Tag(self.core_function_at(export.index, offset)?),
+                    EntityType::Tag(self.tag_at(export.index, offset)?),
                     &mut inst_exports,
                     &mut type_size,
                     offset,
@@ -2041,6 +2041,13 @@ impl ComponentState {
         }
     }
 
+    fn tag_at(&self, idx: u32, offset: usize) -> Result<TypeId> {
+        match self.core_tags.get(idx as usize) {
+            Some(t) => Ok(*t),
+            None => bail!(offset, "unknown tag {idx}: tag index out of bounds"),
+        }
+    }
+
     fn table_at(&self, idx: u32, offset: usize) -> Result<&TableType> {
         match self.core_tables.get(idx as usize) {
             Some(t) => Ok(t),
@@ -14,6 +14,7 @@ pub struct FuncToValidate<T> {
     index: u32,
     ty: u32,
     features: WasmFeatures,
+    pub(crate) max_control_depth: u32,
 }
 
 impl<T: WasmModuleResources> FuncToValidate<T> {
@@ -32,6 +33,7 @@ impl<T: WasmModuleResources> FuncToValidate<T> {
             index,
             ty,
             features: *features,
+            max_control_depth: u32::MAX,
         }
     }
 
@@ -54,9 +56,11 @@ impl<T: WasmModuleResources> FuncToValidate<T> {
             index,
             ty,
             features,
+            max_control_depth,
         } = self;
-        let validator =
+        let mut validator =
             OperatorValidator::new_func(ty, 0, &features, &resources, allocs.0).unwrap();
+        validator.max_control_depth = max_control_depth;
         FuncValidator {
             validator,
             resources,
@@ -89,14 +93,29 @@ impl<T: WasmModuleResources> FuncValidator<T> {
     ///
     /// You may not end up using this in final implementations because you'll
     /// often want to interleave validation with parsing.
+    ///
+    /// Errors returned record the index of this function and, for errors in
+    /// its body, the index of the offending operator.
     pub fn validate(&mut self, body: &FunctionBody<'_>) -> Result<()> {
+        let index = self.index;
         let mut reader = body.get_binary_reader();
-        self.read_locals(&mut reader)?;
+        self.read_locals(&mut reader)
+            .map_err(|e| e.with_item("function", index).with_section("code"))?;
         reader.allow_memarg64(self.validator.features.memory64);
+        let mut operator = 0;
         while !reader.eof() {
-            reader.visit_operator(&mut self.visitor(reader.original_position()))??;
+            reader
+                .visit_operator(&mut self.visitor(reader.original_position()))
+                .and_then(|r| r)
+                .map_err(|e| {
+                    e.with_item("operator", operator)
+                        .with_item("function", index)
+                        .with_section("code")
+                })?;
+            operator += 1;
         }
         self.finish(reader.original_position())
+            .map_err(|e| e.with_item("function", index).with_section("code"))
     }
 
     /// Reads the local definitions from the given `BinaryReader`, often sourced
@@ -51,6 +51,10 @@ pub(crate) struct OperatorValidator {
     /// Offset of the `end` instruction which emptied the `control` stack, which
     /// must be the end of the function.
     end_which_emptied_control: Option<usize>,
+
+    /// The maximum number of frames nested within the function's own frame
+    /// on the `control` stack.
+    pub(crate) max_control_depth: u32,
 }
 
 // No science was performed in the creation of this number, feel free to change
@@ -204,6 +208,7 @@ impl OperatorValidator {
             operands,
             control,
             end_which_emptied_control: None,
+            max_control_depth: u32::MAX,
         }
     }
 
@@ -560,6 +565,15 @@ impl<'resources, R: WasmModuleResources> OperatorValidatorTemp<'_, 'resources, R
     /// breaks interact with this block's type. Additionally the type signature
     /// of the block is specified by `ty`.
     fn push_ctrl(&mut self, kind: FrameKind, ty: BlockType) -> Result<()> {
+        // The function's own frame is at the bottom of the stack and doesn't
+        // count towards the depth.
+        if self.control.len() > self.max_control_depth as usize {
+            bail!(
+                self.offset,
+                "control frames are nested more than the limit of {} deep",
+                self.max_control_depth
+            );
+        }
         // Push a new frame which has a snapshot of the height of the current
         // operand stack.
         let height = self.operands.len();
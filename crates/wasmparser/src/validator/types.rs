@@ -1606,14 +1606,23 @@ impl<'a> TypesRef<'a> {
                 ExternalKind::Global => {
                     EntityType::Global(*module.globals.get(export.index as usize)?)
                 }
-                ExternalKind::Tag => EntityType::Tag(
-                    module.types[*module.functions.get(export.index as usize)? as usize],
-                ),
+                ExternalKind::Tag => EntityType::Tag(*module.tags.get(export.index as usize)?),
             }),
             TypesRefKind::Component(_) => None,
         }
     }
 
+    /// Gets the entity type of the core module export named `name`.
+    ///
+    /// Returns `None` if there's no such export or this is the type
+    /// information of a component.
+    pub fn entity_type_of_export(&self, name: &str) -> Option<EntityType> {
+        match &self.kind {
+            TypesRefKind::Module(module) => module.exports.get(name).copied(),
+            TypesRefKind::Component(_) => None,
+        }
+    }
+
     /// Gets the component entity type for the given component import.
     pub fn component_entity_type_of_extern(&self, name: &str) -> Option<ComponentEntityType> {
         match &self.kind {
@@ -1883,6 +1892,45 @@ impl Types {
         self.as_ref().entity_type_from_export(export)
     }
 
+    /// Gets the entity type of the core module export named `name`.
+    ///
+    /// Returns `None` if there's no such export or this is the type
+    /// information of a component.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmparser::types::EntityType;
+    ///
+    /// let wasm = wat::parse_str(r#"
+    ///     (module
+    ///         (tag (export "t") (param i64))
+    ///         (func (export "f") (param i32) (result i32) local.get 0))
+    /// "#).unwrap();
+    /// let features = wasmparser::WasmFeatures {
+    ///     exceptions: true,
+    ///     ..Default::default()
+    /// };
+    /// let types = match wasmparser::Validator::new_with_features(features).validate_all(&wasm) {
+    ///     Ok(types) => types,
+    ///     Err(e) => panic!("{e}"),
+    /// };
+    /// let f = match types.entity_type_of_export("f") {
+    ///     Some(EntityType::Func(id)) => types.type_from_id(id).unwrap(),
+    ///     _ => unreachable!(),
+    /// };
+    /// assert_eq!(f.as_func_type().unwrap().params(), [wasmparser::ValType::I32]);
+    /// let t = match types.entity_type_of_export("t") {
+    ///     Some(EntityType::Tag(id)) => types.type_from_id(id).unwrap(),
+    ///     _ => unreachable!(),
+    /// };
+    /// assert_eq!(t.as_func_type().unwrap().params(), [wasmparser::ValType::I64]);
+    /// assert!(types.entity_type_of_export("g").is_none());
+    /// ```
+    pub fn entity_type_of_export(&self, name: &str) -> Option<EntityType> {
+        self.as_ref().entity_type_of_export(name)
+    }
+
     /// Gets the component entity type for the given component import or export
     /// name.
     pub fn component_entity_type_of_extern(&self, name: &str) -> Option<ComponentEntityType> {
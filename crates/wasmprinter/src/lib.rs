@@ -9,10 +9,14 @@
 #![deny(missing_docs)]
 
 use anyhow::{bail, Context, Result};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Write};
+use std::iter::Peekable;
 use std::mem;
+use std::ops::Range;
 use std::path::Path;
+use std::vec;
 use wasmparser::*;
 
 const MAX_LOCALS: u32 = 50000;
@@ -20,6 +24,9 @@ const MAX_NESTING_TO_PRINT: u32 = 50;
 const MAX_WASM_FUNCTIONS: u32 = 1_000_000;
 
 mod operator;
+mod print;
+
+pub use self::print::*;
 
 /// Reads a WebAssembly `file` from the filesystem and then prints it into an
 /// in-memory `String`.
@@ -42,6 +49,16 @@ pub fn print_bytes(wasm: impl AsRef<[u8]>) -> Result<String> {
 #[derive(Default)]
 pub struct Printer {
     print_offsets: bool,
+    fold_instructions: bool,
+    config: Config,
+    name_style: NameStyle,
+    skeleton: bool,
+    hexdump_unknown_sections: bool,
+    hexdump_sections: HashSet<String>,
+    /// The function to print with [`Printer::print_function`], and its text
+    /// once it has been printed.
+    only_func: Option<u32>,
+    only_func_text: Option<String>,
     printers: HashMap<String, Box<dyn FnMut(&mut Printer, usize, &[u8]) -> Result<()>>>,
     result: String,
     /// The `i`th line in `result` is at offset `lines[i]`.
@@ -49,6 +66,9 @@ pub struct Printer {
     /// The binary offset for the `i`th line is `line_offsets[i]`.
     line_offsets: Vec<Option<usize>>,
     nesting: u32,
+    /// Extra indentation for the `then` and `else` clauses of folded `if`
+    /// instructions, which doesn't affect label depths unlike `nesting`.
+    clause_nesting: u32,
     line: usize,
     group_lines: Vec<usize>,
 }
@@ -71,10 +91,21 @@ struct CoreState {
     table_names: HashMap<u32, Naming>,
     memory_names: HashMap<u32, Naming>,
     global_names: HashMap<u32, Naming>,
+    tag_names: HashMap<u32, Naming>,
     element_names: HashMap<u32, Naming>,
     data_names: HashMap<u32, Naming>,
     module_names: HashMap<u32, Naming>,
     instance_names: HashMap<u32, Naming>,
+    branch_hints: HashMap<u32, Vec<(usize, u8)>>,
+    func_to_type: HashMap<u32, u32>,
+    /// The id of the last non-custom section seen, for the placement of
+    /// custom sections.
+    last_section: Option<u8>,
+    /// Whether the `name` section has been seen. The text format always
+    /// places the `name` section after custom sections which are placed
+    /// relative to another section, so custom sections after it are placed
+    /// `(after last)` instead.
+    after_names: bool,
 }
 
 #[derive(Default)]
@@ -109,11 +140,117 @@ impl State {
     }
 }
 
+/// Options for the layout of the text printed by a [`Printer`], as configured
+/// with [`Printer::config`].
+///
+/// # Examples
+///
+/// ```
+/// use wasmprinter::{Config, Printer};
+///
+/// let wasm = wat::parse_str(r#"
+///     (module
+///         (func (param i32 i32) (result i32)
+///             local.get 0
+///             local.get 1
+///             i32.add)
+///         (func))
+/// "#).unwrap();
+/// let mut printer = Printer::new();
+/// printer.config(Config {
+///     indent: "\t".to_string(),
+///     blank_lines_between_functions: 1,
+///     group_local_gets: true,
+///     ..Config::default()
+/// });
+/// let text = printer.print(&wasm).unwrap();
+/// assert!(text.contains("\t\tlocal.get 0 local.get 1\n\t\ti32.add\n\t)\n\n\t(func"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    /// The string printed for each level of indentation, which is two spaces
+    /// by default.
+    pub indent: String,
+    /// The maximum width of lines, if any.
+    ///
+    /// This limits how many instructions are grouped onto one line with
+    /// [`Config::group_local_gets`], and the strings of data segments which
+    /// would exceed it are split across multiple lines. Other lines may still
+    /// be wider. Defaults to `None`.
+    pub max_width: Option<usize>,
+    /// The number of blank lines printed between consecutive functions.
+    /// Defaults to 0.
+    pub blank_lines_between_functions: u32,
+    /// Whether or not consecutive `local.get` instructions are printed on the
+    /// same line, such as `local.get 0 local.get 1`. Defaults to `false`.
+    ///
+    /// This has no effect when instructions are folded with
+    /// [`Printer::fold_instructions`].
+    pub group_local_gets: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            indent: "  ".to_string(),
+            max_width: None,
+            blank_lines_between_functions: 0,
+            group_local_gets: false,
+        }
+    }
+}
+
+/// How items are identified in the text format, as configured with
+/// [`Printer::name_style`].
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum NameStyle {
+    /// Items named in the `name` or `component-name` sections are printed
+    /// with those names as their identifiers, such as `$main`, and all other
+    /// items are referred to by index.
+    #[default]
+    Names,
+    /// The `name` and `component-name` sections are ignored and all items are
+    /// referred to by index.
+    Indices,
+    /// Like [`NameStyle::Names`], except that items of core modules without
+    /// a name are given synthesized identifiers such as `$#func12`, which
+    /// includes the kind and index of the item.
+    ///
+    /// Note that parsing the printed text will produce a `name` section
+    /// containing the synthesized names.
+    Synthesized,
+}
+
 struct Naming {
     identifier: Option<String>,
     name: String,
 }
 
+/// The number of items of each kind in a core module, for
+/// [`NameStyle::Synthesized`].
+#[derive(Default)]
+struct ItemCounts {
+    types: u32,
+    funcs: u32,
+    tables: u32,
+    memories: u32,
+    globals: u32,
+    tags: u32,
+    elems: u32,
+    datas: u32,
+}
+
+impl ItemCounts {
+    /// Adds the `count` items of a section at `range` to the count selected
+    /// by `field`. Counts are clamped by the size of the section, as every
+    /// item is at least one byte.
+    fn add(&mut self, field: impl FnOnce(&mut Self) -> &mut u32, count: u32, range: Range<usize>) {
+        let count = count.min(u32::try_from(range.len()).unwrap_or(u32::MAX));
+        let total = field(self);
+        *total = total.saturating_add(count);
+    }
+}
+
 impl Printer {
     /// Creates a new `Printer` object that's ready to start printing wasm
     /// binaries to strings.
@@ -123,10 +260,124 @@ impl Printer {
 
     /// Whether or not to print binary offsets of each item as comments in the
     /// text format whenever a newline is printed.
+    ///
+    /// Offsets are printed in hexadecimal as location comments of the form
+    /// `(;@1a2b;)` at the start of each line. These are recognized by
+    /// `wast::LocationComments`, so the printed text can be edited and parsed
+    /// again while still correlating it with the original binary.
     pub fn print_offsets(&mut self, print: bool) {
         self.print_offsets = print;
     }
 
+    /// Whether or not to print the instructions of function bodies as folded
+    /// expressions, such as `(i32.add (local.get 0) (i32.const 1))`, rather
+    /// than as a flat list of instructions.
+    ///
+    /// Blocks, loops and `if` instructions are printed in their folded forms
+    /// too. Instructions whose operands can't be determined, such as
+    /// branches or those whose operands were produced outside of the current
+    /// block, are printed as usual.
+    pub fn fold_instructions(&mut self, fold: bool) {
+        self.fold_instructions = fold;
+    }
+
+    /// Configures the layout of the printed text, such as its indentation.
+    ///
+    /// See [`Config`] for the available options.
+    pub fn config(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    /// Configures how items are identified in the printed text, which by
+    /// default uses the names of the `name` section.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmprinter::{NameStyle, Printer};
+    ///
+    /// let wasm = wat::parse_str("(module (func $f (param $x i32)) (global i32 i32.const 0))").unwrap();
+    /// let mut printer = Printer::new();
+    ///
+    /// let text = printer.print(&wasm).unwrap();
+    /// assert!(text.contains("(func $f (;0;) (type 0) (param $x i32)"));
+    ///
+    /// printer.name_style(NameStyle::Indices);
+    /// let text = printer.print(&wasm).unwrap();
+    /// assert!(text.contains("(func (;0;) (type 0) (param i32)"));
+    ///
+    /// printer.name_style(NameStyle::Synthesized);
+    /// let text = printer.print(&wasm).unwrap();
+    /// assert!(text.contains("(global $#global0 (;0;) i32"));
+    /// ```
+    pub fn name_style(&mut self, style: NameStyle) {
+        self.name_style = style;
+    }
+
+    /// Whether or not to print only the "skeleton" of a module, omitting the
+    /// locals and instructions of function bodies.
+    ///
+    /// Each function is printed with just its name and type, followed by the
+    /// size of its body, and the size of each section is printed as a comment
+    /// where it appears. This is useful to get an overview of large modules
+    /// which would otherwise be dominated by function bodies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmprinter::Printer;
+    ///
+    /// let wasm = wat::parse_str("(module (func (result i32) i32.const 1))").unwrap();
+    /// let mut printer = Printer::new();
+    /// printer.skeleton(true);
+    /// let text = printer.print(&wasm).unwrap();
+    /// assert!(text.contains("(func (;0;) (type 0) (result i32) (; 4 bytes ;))"));
+    /// assert!(text.contains(";; code section: 6 bytes"));
+    /// ```
+    pub fn skeleton(&mut self, skeleton: bool) {
+        self.skeleton = skeleton;
+    }
+
+    /// Whether or not to print custom sections which aren't otherwise
+    /// represented in the text format as `@custom` annotations.
+    ///
+    /// By default such sections are omitted. When enabled, the contents of
+    /// each section are printed as strings of 16 bytes each, with a hex and
+    /// ASCII dump of the bytes in a comment above each string. Sections
+    /// within modules are annotated with their placement, so parsing the
+    /// printed text produces the same binary, including the order of custom
+    /// sections around the `name` section.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmprinter::Printer;
+    ///
+    /// let wasm = wat::parse_str(r#"(module (@custom "hello" "hi!\00"))"#).unwrap();
+    /// let mut printer = Printer::new();
+    /// printer.hexdump_unknown_custom_sections(true);
+    /// let text = printer.print(&wasm).unwrap();
+    /// assert!(text.contains(r#"(@custom "hello" (before first)"#));
+    /// assert!(text.contains(";; 0000: 68 69 21 00"));
+    /// assert!(text.contains(r#""hi!\00""#));
+    /// assert_eq!(wat::parse_str(&text).unwrap(), wasm);
+    /// ```
+    pub fn hexdump_unknown_custom_sections(&mut self, hexdump: bool) {
+        self.hexdump_unknown_sections = hexdump;
+    }
+
+    /// Prints custom sections named `section` as `@custom` annotations with a
+    /// hexdump of their contents, as described in
+    /// [`Printer::hexdump_unknown_custom_sections`].
+    ///
+    /// This applies even to custom sections which would otherwise be printed
+    /// in a structured form, such as the `producers` section, although
+    /// printers registered with [`Printer::add_custom_section_printer`] take
+    /// precedence.
+    pub fn hexdump_custom_section(&mut self, section: &str) {
+        self.hexdump_sections.insert(section.to_string());
+    }
+
     /// Registers a custom `printer` function to get invoked whenever a custom
     /// section of name `section` is seen.
     ///
@@ -160,17 +411,89 @@ impl Printer {
     /// This function takes an entire `wasm` binary blob and will print it to
     /// the WebAssembly Text Format and return the result as a `String`.
     pub fn print(&mut self, wasm: &[u8]) -> Result<String> {
-        self.print_contents(wasm)?;
+        self.print_contents(wasm, None)?;
         Ok(mem::take(&mut self.result))
     }
 
+    /// Prints only the function at `index` of the WebAssembly module `wasm`.
+    ///
+    /// The function is printed as it would be within the module, using the
+    /// names and types of the module, but the bodies of all other functions
+    /// aren't printed at all. An error is returned if `wasm` doesn't define a
+    /// function at `index`, such as when it's imported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let wasm = wat::parse_str(r#"
+    ///     (module
+    ///         (import "" "" (func))
+    ///         (func $first)
+    ///         (func $second (result i32) i32.const 2))
+    /// "#).unwrap();
+    /// let text = wasmprinter::Printer::new().print_function(&wasm, 2).unwrap();
+    /// assert_eq!(
+    ///     text,
+    ///     "(func $second (;2;) (type 1) (result i32)\n  i32.const 2\n)",
+    /// );
+    /// ```
+    pub fn print_function(&mut self, wasm: &[u8], index: u32) -> Result<String> {
+        if let Chunk::Parsed {
+            payload:
+                Payload::Version {
+                    encoding: Encoding::Component,
+                    ..
+                },
+            ..
+        } = Parser::new(0).parse(wasm, true)?
+        {
+            bail!("functions can only be printed from core wasm modules");
+        }
+        self.only_func = Some(index);
+        self.only_func_text = None;
+        let result = self.print_contents(wasm, None);
+        self.only_func = None;
+        self.result.clear();
+        result?;
+        match self.only_func_text.take() {
+            Some(text) => Ok(text),
+            None => bail!("function {index} is not defined in the module"),
+        }
+    }
+
+    /// Prints a WebAssembly binary to the destination `dst`.
+    ///
+    /// Unlike [`Printer::print`] the text isn't built up in memory all at
+    /// once. Instead it's written to `dst` after each section and each
+    /// function body, so large binaries can be printed without holding their
+    /// entire textual representation in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wasmprinter::{Printer, PrintIoWrite};
+    ///
+    /// let wasm = wat::parse_str("(module (func))").unwrap();
+    /// let mut out = Vec::new();
+    /// Printer::new().print_to(&wasm, PrintIoWrite(&mut out)).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(out).unwrap(),
+    ///     "(module\n  (type (;0;) (func))\n  (func (;0;) (type 0))\n)",
+    /// );
+    /// ```
+    pub fn print_to(&mut self, wasm: &[u8], mut dst: impl Print) -> Result<()> {
+        self.result.clear();
+        self.print_contents(wasm, Some(&mut dst))?;
+        self.flush_to(&mut Some(&mut dst))
+    }
+
     /// Get the line-by-line WAT disassembly for the given Wasm, along with the
     /// binary offsets for each line.
     pub fn offsets_and_lines<'a>(
         &'a mut self,
         wasm: &[u8],
     ) -> Result<impl Iterator<Item = (Option<usize>, &'a str)> + 'a> {
-        self.print_contents(wasm)?;
+        self.print_contents(wasm, None)?;
 
         let end = self.result.len();
         let result = &self.result;
@@ -189,11 +512,16 @@ impl Printer {
 
     fn read_names_and_code<'a>(
         &mut self,
+        wasm: &'a [u8],
         mut bytes: &'a [u8],
         mut parser: Parser,
         state: &mut State,
         code: &mut Vec<FunctionBody<'a>>,
     ) -> Result<()> {
+        let use_names = self.name_style != NameStyle::Indices;
+        let synthesize =
+            self.name_style == NameStyle::Synthesized && state.encoding == Encoding::Module;
+        let mut counts = ItemCounts::default();
         loop {
             let payload = match parser.parse(bytes, true)? {
                 Chunk::NeedMoreData(_) => unreachable!(),
@@ -202,6 +530,7 @@ impl Printer {
                     payload
                 }
             };
+            let payload = whole_custom_section(wasm, &mut parser, &mut bytes, payload)?;
 
             match payload {
                 Payload::FunctionSection(s) => {
@@ -213,7 +542,27 @@ impl Printer {
                         );
                     }
                     code.reserve(s.count() as usize);
+                    counts.add(|c| &mut c.funcs, s.count(), s.range());
+                }
+                Payload::ImportSection(s) if synthesize => {
+                    for import in s {
+                        let count = match import?.ty {
+                            TypeRef::Func(_) => &mut counts.funcs,
+                            TypeRef::Table(_) => &mut counts.tables,
+                            TypeRef::Memory(_) => &mut counts.memories,
+                            TypeRef::Global(_) => &mut counts.globals,
+                            TypeRef::Tag(_) => &mut counts.tags,
+                        };
+                        *count = count.saturating_add(1);
+                    }
                 }
+                Payload::TypeSection(s) => counts.add(|c| &mut c.types, s.count(), s.range()),
+                Payload::TableSection(s) => counts.add(|c| &mut c.tables, s.count(), s.range()),
+                Payload::MemorySection(s) => counts.add(|c| &mut c.memories, s.count(), s.range()),
+                Payload::GlobalSection(s) => counts.add(|c| &mut c.globals, s.count(), s.range()),
+                Payload::TagSection(s) => counts.add(|c| &mut c.tags, s.count(), s.range()),
+                Payload::ElementSection(s) => counts.add(|c| &mut c.elems, s.count(), s.range()),
+                Payload::DataSection(s) => counts.add(|c| &mut c.datas, s.count(), s.range()),
                 Payload::CodeSectionEntry(f) => {
                     code.push(f);
                 }
@@ -226,20 +575,35 @@ impl Printer {
                 }
 
                 // Ignore any error associated with the name sections.
-                Payload::CustomSection(c) if c.name() == "name" => {
+                Payload::CustomSection(c) if c.name() == "name" && use_names => {
                     let reader = NameSectionReader::new(c.data(), c.data_offset());
                     drop(self.register_names(state, reader));
                 }
-                Payload::CustomSection(c) if c.name() == "component-name" => {
+                Payload::CustomSection(c) if c.name() == "component-name" && use_names => {
                     let reader = ComponentNameSectionReader::new(c.data(), c.data_offset());
                     drop(self.register_component_names(state, reader));
                 }
+                Payload::CustomSection(c) if c.name() == "metadata.code.branch_hint" => {
+                    drop(self.register_branch_hints(state, &c));
+                }
 
                 Payload::End(_) => break,
                 _ => {}
             }
         }
 
+        if synthesize {
+            let core = &mut state.core;
+            synthesize_names(&mut core.type_names, "type", counts.types);
+            synthesize_names(&mut core.func_names, "func", counts.funcs);
+            synthesize_names(&mut core.table_names, "table", counts.tables);
+            synthesize_names(&mut core.memory_names, "memory", counts.memories);
+            synthesize_names(&mut core.global_names, "global", counts.globals);
+            synthesize_names(&mut core.tag_names, "tag", counts.tags);
+            synthesize_names(&mut core.element_names, "elem", counts.elems);
+            synthesize_names(&mut core.data_names, "data", counts.datas);
+        }
+
         Ok(())
     }
 
@@ -259,7 +623,24 @@ impl Printer {
         Ok(())
     }
 
-    fn print_contents(&mut self, mut bytes: &[u8]) -> Result<()> {
+    /// Writes out everything printed so far to `dst`, if any, so it doesn't
+    /// need to be kept in memory.
+    ///
+    /// This is only done between items, as some printing methods inspect what
+    /// they've printed so far.
+    fn flush_to(&mut self, dst: &mut Option<&mut dyn Print>) -> Result<()> {
+        if let Some(dst) = dst {
+            dst.write_str(&self.result)
+                .context("failed to write the text format")?;
+            self.result.clear();
+            self.lines.clear();
+            self.line_offsets.clear();
+        }
+        Ok(())
+    }
+
+    fn print_contents(&mut self, wasm: &[u8], mut dst: Option<&mut dyn Print>) -> Result<()> {
+        let mut bytes = wasm;
         self.lines.clear();
         self.lines.push(0);
         self.line_offsets.clear();
@@ -280,6 +661,34 @@ impl Printer {
                     payload
                 }
             };
+            let payload = whole_custom_section(wasm, &mut parser, &mut bytes, payload)?;
+            if let (Some((id, _)), Some(state)) = (payload.as_section(), states.last_mut()) {
+                if id != 0 {
+                    state.core.last_section = Some(id);
+                }
+                if let Payload::CustomSection(c) = &payload {
+                    if c.name() == "name" {
+                        state.core.after_names = true;
+                    }
+                }
+            }
+            if self.skeleton {
+                if let (Some((id, range)), Some(state)) = (payload.as_section(), states.last()) {
+                    self.newline(range.start);
+                    match &payload {
+                        Payload::CustomSection(c) => {
+                            self.result.push_str(";; custom section ");
+                            self.print_str(c.name())?;
+                        }
+                        _ => write!(
+                            self.result,
+                            ";; {} section",
+                            section_name(state.encoding, id)
+                        )?,
+                    }
+                    write!(self.result, ": {} bytes", range.len())?;
+                }
+            }
             match payload {
                 Payload::Version { encoding, .. } => {
                     if let Some(e) = expected {
@@ -329,7 +738,7 @@ impl Printer {
                     // print out functions as soon as we hit the function section.
                     code.clear();
                     code_printed = false;
-                    self.read_names_and_code(bytes, parser.clone(), state, &mut code)?;
+                    self.read_names_and_code(wasm, bytes, parser.clone(), state, &mut code)?;
 
                     if len == 1 {
                         if let Some(name) = state.name.as_ref() {
@@ -338,10 +747,60 @@ impl Printer {
                         }
                     }
                 }
+                // Only the types and functions of a module are needed to print
+                // a single function, so nothing else is printed for
+                // `print_function`.
+                Payload::TypeSection(s) if self.only_func.is_some() => {
+                    let state = states.last_mut().unwrap();
+                    for ty in s {
+                        match ty? {
+                            wasmparser::Type::Func(ty) => state.core.types.push(Some(ty)),
+                        }
+                    }
+                }
+                Payload::ImportSection(s) if self.only_func.is_some() => {
+                    Self::ensure_module(&states)?;
+                    let state = states.last_mut().unwrap();
+                    for import in s {
+                        if let TypeRef::Func(ty) = import?.ty {
+                            state.core.func_to_type.insert(state.core.funcs, ty);
+                            state.core.funcs += 1;
+                        }
+                    }
+                }
+                Payload::CustomSection(_)
+                | Payload::TableSection(_)
+                | Payload::MemorySection(_)
+                | Payload::TagSection(_)
+                | Payload::GlobalSection(_)
+                | Payload::ExportSection(_)
+                | Payload::StartSection { .. }
+                | Payload::ElementSection(_)
+                | Payload::DataSection(_)
+                    if self.only_func.is_some() => {}
                 Payload::CustomSection(c) => {
                     let mut printers = mem::take(&mut self.printers);
                     if let Some(printer) = printers.get_mut(c.name()) {
                         printer(self, c.data_offset(), c.data())?;
+                    } else if self.hexdump_sections.contains(c.name()) {
+                        self.print_custom_section_hexdump(states.last().unwrap(), &c)?;
+                    } else if c.name() == "producers" {
+                        self.print_producers_section(&c)?;
+                    } else if c.name() == "target_features"
+                        && states.last().unwrap().encoding == Encoding::Module
+                    {
+                        self.print_target_features_section(&c)?;
+                    } else if c.name() == "dylink.0"
+                        && states.last().unwrap().encoding == Encoding::Module
+                    {
+                        self.print_dylink0_section(&c)?;
+                    } else if self.hexdump_unknown_sections
+                        && !matches!(
+                            c.name(),
+                            "name" | "component-name" | "metadata.code.branch_hint"
+                        )
+                    {
+                        self.print_custom_section_hexdump(states.last().unwrap(), &c)?;
                     }
                     self.printers = printers;
                 }
@@ -358,7 +817,7 @@ impl Printer {
                     if reader.count() == 0 {
                         continue;
                     }
-                    self.print_code(states.last_mut().unwrap(), &code, reader)?;
+                    self.print_code(states.last_mut().unwrap(), &code, reader, &mut dst)?;
                 }
                 Payload::TableSection(s) => {
                     Self::ensure_module(&states)?;
@@ -399,6 +858,11 @@ impl Printer {
                     parser.skip_section();
                 }
                 Payload::CodeSectionEntry(_) => unreachable!(),
+                // Function bodies are never skipped by this parser.
+                Payload::SkippedCodeSectionChunk { .. } => {}
+                // Streamed custom sections are printed whole above, and their
+                // chunks are skipped.
+                Payload::CustomSectionStart { .. } | Payload::CustomSectionChunk { .. } => {}
                 Payload::DataCountSection { .. } => {
                     Self::ensure_module(&states)?;
                     // not part of the text format
@@ -483,8 +947,169 @@ impl Printer {
 
                 Payload::UnknownSection { id, .. } => bail!("found unknown section `{}`", id),
             }
+
+            self.flush_to(&mut dst)?;
+        }
+
+        Ok(())
+    }
+
+    /// Prints a `producers` custom section as a `@producers` annotation.
+    ///
+    /// Sections which fail to parse, or which have field names that can't be
+    /// written as keywords, aren't printed at all.
+    fn print_producers_section(&mut self, section: &CustomSectionReader<'_>) -> Result<()> {
+        let mut fields = Vec::new();
+        let parsed = (|| -> Result<()> {
+            let reader = ProducersSectionReader::new(section.data(), section.data_offset())?;
+            for field in reader {
+                let field = field?;
+                for value in field.values {
+                    let value = value?;
+                    fields.push((field.name, value.name, value.version));
+                }
+            }
+            Ok(())
+        })();
+        if parsed.is_err() || fields.iter().any(|(field, _, _)| !is_keyword(field)) {
+            return Ok(());
+        }
+
+        self.newline(section.range().start);
+        self.start_group("@producers");
+        for (field, name, version) in fields {
+            self.newline_unknown_pos();
+            self.start_group(field);
+            self.result.push(' ');
+            self.print_str(name)?;
+            self.result.push(' ');
+            self.print_str(version)?;
+            self.end_group();
+        }
+        self.end_group();
+        Ok(())
+    }
+
+    /// Prints a `target_features` custom section as a `@target_features`
+    /// annotation.
+    ///
+    /// Sections which fail to parse aren't printed at all.
+    fn print_target_features_section(&mut self, section: &CustomSectionReader<'_>) -> Result<()> {
+        let mut features = Vec::new();
+        let parsed = (|| -> Result<()> {
+            let reader = TargetFeaturesSectionReader::new(section.data(), section.data_offset())?;
+            for feature in reader {
+                let feature = feature?;
+                let policy = match feature.prefix {
+                    TargetFeaturePrefix::Used => "used",
+                    TargetFeaturePrefix::Disallowed => "disallowed",
+                    TargetFeaturePrefix::Required => "required",
+                };
+                features.push((policy, feature.name));
+            }
+            Ok(())
+        })();
+        if parsed.is_err() {
+            return Ok(());
+        }
+
+        self.newline(section.range().start);
+        self.start_group("@target_features");
+        for (policy, name) in features {
+            self.newline_unknown_pos();
+            self.start_group(policy);
+            self.result.push(' ');
+            self.print_str(name)?;
+            self.end_group();
+        }
+        self.end_group();
+        Ok(())
+    }
+
+    /// Prints a `dylink.0` custom section as a `@dylink.0` annotation.
+    ///
+    /// Sections which fail to parse, or which have subsections unknown to
+    /// the text format, aren't printed at all.
+    fn print_dylink0_section(&mut self, section: &CustomSectionReader<'_>) -> Result<()> {
+        let subsections = Dylink0SectionReader::new(section.data(), section.data_offset())
+            .collect::<wasmparser::Result<Vec<_>>>();
+        let subsections = match subsections {
+            Ok(subsections) => subsections,
+            Err(_) => return Ok(()),
+        };
+        if subsections
+            .iter()
+            .any(|s| matches!(s, Dylink0Subsection::Unknown { .. }))
+        {
+            return Ok(());
+        }
+
+        self.newline(section.range().start);
+        self.start_group("@dylink.0");
+        for subsection in subsections {
+            match subsection {
+                Dylink0Subsection::MemInfo(info) => {
+                    self.newline_unknown_pos();
+                    self.start_group("mem-info");
+                    write!(
+                        self.result,
+                        " (memory {} {}) (table {} {})",
+                        info.memory_size,
+                        info.memory_alignment,
+                        info.table_size,
+                        info.table_alignment,
+                    )?;
+                    self.end_group();
+                }
+                Dylink0Subsection::Needed(libs) => {
+                    self.newline_unknown_pos();
+                    self.start_group("needed");
+                    for lib in libs {
+                        self.result.push(' ');
+                        self.print_str(lib)?;
+                    }
+                    self.end_group();
+                }
+                Dylink0Subsection::ExportInfo(infos) => {
+                    for info in infos {
+                        self.newline_unknown_pos();
+                        self.start_group("export-info");
+                        self.result.push(' ');
+                        self.print_str(info.name)?;
+                        self.print_dylink0_flags(info.flags)?;
+                        self.end_group();
+                    }
+                }
+                Dylink0Subsection::ImportInfo(infos) => {
+                    for info in infos {
+                        self.newline_unknown_pos();
+                        self.start_group("import-info");
+                        self.result.push(' ');
+                        self.print_str(info.module)?;
+                        self.result.push(' ');
+                        self.print_str(info.field)?;
+                        self.print_dylink0_flags(info.flags)?;
+                        self.end_group();
+                    }
+                }
+                Dylink0Subsection::Unknown { .. } => unreachable!(),
+            }
         }
+        self.end_group();
+        Ok(())
+    }
 
+    fn print_dylink0_flags(&mut self, mut flags: u32) -> Result<()> {
+        for (name, bit) in DYLINK_0_SYMBOL_FLAGS {
+            if flags & bit != 0 {
+                self.result.push(' ');
+                self.result.push_str(name);
+                flags &= !bit;
+            }
+        }
+        if flags != 0 {
+            write!(self.result, " {:#x}", flags)?;
+        }
         Ok(())
     }
 
@@ -505,6 +1130,38 @@ impl Printer {
         self.result.push(')');
     }
 
+    /// Reads the `metadata.code.branch_hint` section so the hints can be
+    /// printed as annotations on their instructions.
+    ///
+    /// Hints are only registered if the whole section is well-formed and each
+    /// hint is a single 0 or 1 byte, which is all the text format can express.
+    fn register_branch_hints(
+        &mut self,
+        state: &mut State,
+        section: &CustomSectionReader<'_>,
+    ) -> Result<()> {
+        let mut reader = BinaryReader::new_with_offset(section.data(), section.data_offset());
+        let mut branch_hints = HashMap::new();
+        for _ in 0..reader.read_var_u32()? {
+            let func = reader.read_var_u32()?;
+            let mut hints = Vec::new();
+            for _ in 0..reader.read_var_u32()? {
+                let offset = reader.read_var_u32()? as usize;
+                let size = reader.read_var_u32()? as usize;
+                match reader.read_bytes(size)? {
+                    [value @ (0 | 1)] => hints.push((offset, *value)),
+                    _ => bail!("invalid branch hint"),
+                }
+            }
+            branch_hints.insert(func, hints);
+        }
+        if !reader.eof() {
+            bail!("trailing data at the end of the branch hint section");
+        }
+        state.core.branch_hints = branch_hints;
+        Ok(())
+    }
+
     fn register_names(&mut self, state: &mut State, names: NameSectionReader<'_>) -> Result<()> {
         fn indirect_name_map(
             into: &mut HashMap<(u32, u32), Naming>,
@@ -545,6 +1202,7 @@ impl Printer {
                 Name::Global(n) => name_map(&mut state.core.global_names, n, "global")?,
                 Name::Element(n) => name_map(&mut state.core.element_names, n, "elem")?,
                 Name::Data(n) => name_map(&mut state.core.data_names, n, "data")?,
+                Name::Tag(n) => name_map(&mut state.core.tag_names, n, "tag")?,
                 Name::Unknown { .. } => (),
             }
         }
@@ -577,6 +1235,7 @@ impl Printer {
                 ComponentName::CoreGlobals(n) => {
                     name_map(&mut state.core.global_names, n, "core-global")?
                 }
+                ComponentName::CoreTags(n) => name_map(&mut state.core.tag_names, n, "core-tag")?,
                 ComponentName::CoreModules(n) => {
                     name_map(&mut state.core.module_names, n, "core-module")?
                 }
@@ -755,7 +1414,10 @@ impl Printer {
             self.newline(offset);
             self.print_import(state, &import, true)?;
             match import.ty {
-                TypeRef::Func(_) => state.core.funcs += 1,
+                TypeRef::Func(ty) => {
+                    state.core.func_to_type.insert(state.core.funcs, ty);
+                    state.core.funcs += 1;
+                }
                 TypeRef::Table(_) => state.core.tables += 1,
                 TypeRef::Memory(_) => state.core.memories += 1,
                 TypeRef::Tag(_) => state.core.tags += 1,
@@ -826,7 +1488,8 @@ impl Printer {
     fn print_tag_type(&mut self, state: &State, ty: &TagType, index: bool) -> Result<()> {
         self.start_group("tag ");
         if index {
-            write!(self.result, "(;{};) ", state.core.tags)?;
+            self.print_name(&state.core.tag_names, state.core.tags)?;
+            self.result.push(' ');
         }
         self.print_core_functype_idx(state, ty.func_type_idx, None)?;
         Ok(())
@@ -917,117 +1580,448 @@ impl Printer {
         state: &mut State,
         code: &[FunctionBody<'_>],
         funcs: FunctionSectionReader<'_>,
+        dst: &mut Option<&mut dyn Print>,
     ) -> Result<()> {
         if funcs.count() != code.len() as u32 {
             bail!("mismatch in function and code section counts");
         }
-        for (body, ty) in code.iter().zip(funcs) {
-            let mut body = body.get_binary_reader();
-            let offset = body.original_position();
+        for (i, ty) in funcs.clone().into_iter().enumerate() {
+            state
+                .core
+                .func_to_type
+                .insert(state.core.funcs + i as u32, ty?);
+        }
+        for (i, (body, ty)) in code.iter().zip(funcs).enumerate() {
             let ty = ty?;
-            self.newline(offset);
-            self.start_group("func ");
             let func_idx = state.core.funcs;
-            self.print_name(&state.core.func_names, func_idx)?;
-            self.result.push(' ');
-            let params = self
-                .print_core_functype_idx(state, ty, Some(func_idx))?
-                .unwrap_or(0);
-
-            let mut first = true;
-            let mut local_idx = 0;
-            let mut locals = NamedLocalPrinter::new("local");
-            for _ in 0..body.read_var_u32()? {
-                let offset = body.original_position();
-                let cnt = body.read_var_u32()?;
-                let ty = body.read()?;
-                if MAX_LOCALS
-                    .checked_sub(local_idx)
-                    .and_then(|s| s.checked_sub(cnt))
-                    .is_none()
-                {
-                    bail!("function exceeds the maximum number of locals that can be printed");
-                }
-                for _ in 0..cnt {
-                    if first {
-                        self.newline(offset);
-                        first = false;
-                    }
-                    let name = state.core.local_names.get(&(func_idx, params + local_idx));
-                    locals.start_local(name, &mut self.result);
-                    self.print_valtype(ty)?;
-                    locals.end_local(&mut self.result);
-                    local_idx += 1;
-                }
-            }
-            locals.finish(&mut self.result);
-
-            state.core.labels = 0;
-            let nesting_start = self.nesting;
-            body.allow_memarg64(true);
-
-            let mut buf = String::new();
-            let mut op_printer = operator::PrintOperator::new(self, state);
-            while !body.eof() {
-                // TODO
-                let offset = body.original_position();
-                mem::swap(&mut buf, &mut op_printer.printer.result);
-                let op_kind = body.visit_operator(&mut op_printer)??;
-                mem::swap(&mut buf, &mut op_printer.printer.result);
-
-                match op_kind {
-                    // The final `end` in a reader is not printed, it's implied
-                    // in the text format.
-                    operator::OpKind::End if body.eof() => break,
-
-                    // When we start a block we newline to the current
-                    // indentation, then we increase the indentation so further
-                    // instructions are tabbed over.
-                    operator::OpKind::BlockStart => {
-                        op_printer.printer.newline(offset);
-                        op_printer.printer.nesting += 1;
+            if self.name_style == NameStyle::Synthesized && state.encoding == Encoding::Module {
+                Self::synthesize_local_names(state, body, ty, func_idx)?;
+            }
+            if i > 0 && self.only_func.is_none() {
+                for _ in 0..self.config.blank_lines_between_functions {
+                    self.blank_line();
+                }
+            }
+            match self.only_func {
+                // Print the selected function on its own, saving it for
+                // `print_function`, and skip all other functions.
+                Some(only) => {
+                    if only == func_idx && self.only_func_text.is_none() {
+                        let prev_result = mem::take(&mut self.result);
+                        let prev_nesting = mem::replace(&mut self.nesting, 0);
+                        let res = self.print_func(state, body, ty, func_idx);
+                        let mut text = mem::replace(&mut self.result, prev_result);
+                        self.nesting = prev_nesting;
+                        res?;
+                        // Skip the newline that functions are printed after.
+                        text.remove(0);
+                        self.only_func_text = Some(text);
                     }
+                }
+                None if self.skeleton => self.print_func_skeleton(state, body, ty, func_idx)?,
+                None => self.print_func(state, body, ty, func_idx)?,
+            }
+            self.flush_to(dst)?;
 
-                    // `else`/`catch` are special in that it's printed at
-                    // the previous indentation, but it doesn't actually change
-                    // our nesting level.
-                    operator::OpKind::BlockMid => {
-                        op_printer.printer.nesting -= 1;
-                        op_printer.printer.newline(offset);
-                        op_printer.printer.nesting += 1;
-                    }
+            state.core.funcs += 1;
+        }
+        Ok(())
+    }
 
-                    // Exiting a block prints `end` at the previous indentation
-                    // level. `delegate` also ends a block like `end` for `try`.
-                    operator::OpKind::End | operator::OpKind::Delegate
-                        if op_printer.printer.nesting > nesting_start =>
-                    {
-                        op_printer.printer.nesting -= 1;
-                        op_printer.printer.newline(offset);
-                    }
+    /// Prints only the name and type of a function, followed by the size of
+    /// its body, for [`Printer::skeleton`].
+    fn print_func_skeleton(
+        &mut self,
+        state: &mut State,
+        body: &FunctionBody<'_>,
+        ty: u32,
+        func_idx: u32,
+    ) -> Result<()> {
+        self.newline(body.range().start);
+        self.start_group("func ");
+        self.print_name(&state.core.func_names, func_idx)?;
+        self.result.push(' ');
+        self.print_core_functype_idx(state, ty, Some(func_idx))?;
+        write!(self.result, " (; {} bytes ;)", body.range().len())?;
+        self.end_group();
+        Ok(())
+    }
+
+    fn print_func(
+        &mut self,
+        state: &mut State,
+        body: &FunctionBody<'_>,
+        ty: u32,
+        func_idx: u32,
+    ) -> Result<()> {
+        let mut body = body.get_binary_reader();
+        let offset = body.original_position();
+        let func_start = offset;
+        self.newline(offset);
+        self.start_group("func ");
+        self.print_name(&state.core.func_names, func_idx)?;
+        self.result.push(' ');
+        let params = self
+            .print_core_functype_idx(state, ty, Some(func_idx))?
+            .unwrap_or(0);
+
+        let mut first = true;
+        let mut local_idx = 0;
+        let mut locals = NamedLocalPrinter::new("local");
+        for _ in 0..body.read_var_u32()? {
+            let offset = body.original_position();
+            let cnt = body.read_var_u32()?;
+            let ty = body.read()?;
+            if MAX_LOCALS
+                .checked_sub(local_idx)
+                .and_then(|s| s.checked_sub(cnt))
+                .is_none()
+            {
+                bail!("function exceeds the maximum number of locals that can be printed");
+            }
+            for _ in 0..cnt {
+                if first {
+                    self.newline(offset);
+                    first = false;
+                }
+                let name = state.core.local_names.get(&(func_idx, params + local_idx));
+                locals.start_local(name, &mut self.result);
+                self.print_valtype(ty)?;
+                locals.end_local(&mut self.result);
+                local_idx += 1;
+            }
+        }
+        locals.finish(&mut self.result);
+
+        state.core.labels = 0;
+        let nesting_start = self.nesting;
+        body.allow_memarg64(true);
+
+        let hints = state
+            .core
+            .branch_hints
+            .remove(&func_idx)
+            .unwrap_or_default();
+        let mut hints = hints.into_iter().peekable();
+
+        if self.fold_instructions {
+            self.print_folded_body(state, &mut body, func_start, &mut hints)?;
+        } else {
+            self.print_body(state, &mut body, func_start, &mut hints)?;
+        }
+
+        // If this was an invalid function body then the nesting may not
+        // have reset back to normal. Fix that up here and forcibly insert
+        // a newline as well in case the last instruction was something
+        // like an `if` which has a comment after it which could interfere
+        // with the closing paren printed for the func.
+        if self.nesting != nesting_start || self.clause_nesting != 0 {
+            self.nesting = nesting_start;
+            self.clause_nesting = 0;
+            self.newline(body.original_position());
+        }
 
-                    // .. otherwise everything else just has a normal newline
-                    // out in front.
-                    _ => op_printer.printer.newline(offset),
+        self.end_group();
+        Ok(())
+    }
+
+    /// Gives each parameter and local of the function `func_idx` without a
+    /// name a synthesized name, for [`NameStyle::Synthesized`].
+    fn synthesize_local_names(
+        state: &mut State,
+        body: &FunctionBody<'_>,
+        ty: u32,
+        func_idx: u32,
+    ) -> Result<()> {
+        let mut count = match state.core.types.get(ty as usize) {
+            Some(Some(ty)) => ty.params().len() as u32,
+            _ => 0,
+        };
+        for local in body.get_locals_reader()? {
+            let (n, _) = local?;
+            count = count.saturating_add(n);
+        }
+        // Functions with too many locals fail to print later on, so don't
+        // bother naming all of them.
+        let count = count.min(MAX_LOCALS);
+        let names = &mut state.core.local_names;
+        let mut used = (0..count)
+            .filter_map(|i| names.get(&(func_idx, i)))
+            .flat_map(|naming| [naming.name.clone(), naming.identifier().to_string()])
+            .collect();
+        for i in 0..count {
+            names
+                .entry((func_idx, i))
+                .or_insert_with(|| Naming::synthesized("local", i, &mut used));
+        }
+        Ok(())
+    }
+
+    /// Prints the instructions of a function body, one per line.
+    fn print_body(
+        &mut self,
+        state: &mut State,
+        body: &mut BinaryReader<'_>,
+        func_start: usize,
+        hints: &mut Peekable<vec::IntoIter<(usize, u8)>>,
+    ) -> Result<()> {
+        let nesting_start = self.nesting;
+        let group_local_gets = self.config.group_local_gets;
+        // Whether the last instruction printed was a `local.get` which a
+        // following `local.get` can be grouped with.
+        let mut prev_local_get = false;
+        let mut buf = String::new();
+        let mut op_printer = operator::PrintOperator::new(self, state);
+        while !body.eof() {
+            // TODO
+            let offset = body.original_position();
+
+            // Branch hints are offsets relative to the start of the body
+            // and are printed as an annotation before their instruction.
+            // Hints which don't fall on an instruction are dropped.
+            let relative = offset - func_start;
+            while hints.next_if(|(o, _)| *o < relative).is_some() {}
+            if let Some((_, value)) = hints.next_if(|(o, _)| *o == relative) {
+                op_printer.printer.print_branch_hint(offset, value)?;
+                prev_local_get = false;
+            }
+            let local_get = group_local_gets
+                && matches!(body.clone().read_operator()?, Operator::LocalGet { .. });
+            mem::swap(&mut buf, &mut op_printer.printer.result);
+            let op_kind = body.visit_operator(&mut op_printer)??;
+            mem::swap(&mut buf, &mut op_printer.printer.result);
+
+            match op_kind {
+                // The final `end` in a reader is not printed, it's implied
+                // in the text format.
+                operator::OpKind::End if body.eof() => break,
+
+                // When we start a block we newline to the current
+                // indentation, then we increase the indentation so further
+                // instructions are tabbed over.
+                operator::OpKind::BlockStart => {
+                    op_printer.printer.newline(offset);
+                    op_printer.printer.nesting += 1;
+                }
+
+                // `else`/`catch` are special in that it's printed at
+                // the previous indentation, but it doesn't actually change
+                // our nesting level.
+                operator::OpKind::BlockMid => {
+                    op_printer.printer.nesting -= 1;
+                    op_printer.printer.newline(offset);
+                    op_printer.printer.nesting += 1;
+                }
+
+                // Exiting a block prints `end` at the previous indentation
+                // level. `delegate` also ends a block like `end` for `try`.
+                operator::OpKind::End | operator::OpKind::Delegate
+                    if op_printer.printer.nesting > nesting_start =>
+                {
+                    op_printer.printer.nesting -= 1;
+                    op_printer.printer.newline(offset);
+                }
+
+                // Consecutive `local.get`s may be grouped on one line.
+                _ if local_get
+                    && prev_local_get
+                    && op_printer.printer.fits_on_line(buf.len() + 1) =>
+                {
+                    op_printer.printer.result.push(' ');
                 }
-                op_printer.printer.result.push_str(&buf);
-                buf.truncate(0);
+
+                // .. otherwise everything else just has a normal newline
+                // out in front.
+                _ => op_printer.printer.newline(offset),
             }
+            op_printer.printer.result.push_str(&buf);
+            buf.truncate(0);
+            prev_local_get = local_get;
+        }
+        Ok(())
+    }
+
+    /// Prints the instructions of a function body as folded expressions, as
+    /// configured with [`Printer::fold_instructions`].
+    ///
+    /// Instructions with a known number of operands and at most one result
+    /// are held back as pending expressions until they're used as the
+    /// operands of a later instruction. Pending expressions are printed on
+    /// their own, in order, whenever an instruction which can't use them is
+    /// reached, so the order of the instructions is never changed.
+    fn print_folded_body(
+        &mut self,
+        state: &mut State,
+        body: &mut BinaryReader<'_>,
+        func_start: usize,
+        hints: &mut Peekable<vec::IntoIter<(usize, u8)>>,
+    ) -> Result<()> {
+        enum Frame {
+            Folded,
+            FoldedIf,
+            Flat,
+        }
 
-            // If this was an invalid function body then the nesting may not
-            // have reset back to normal. Fix that up here and forcibly insert
-            // a newline as well in case the last instruction was something
-            // like an `if` which has a comment after it which could interfere
-            // with the closing paren printed for the func.
-            if self.nesting != nesting_start {
-                self.nesting = nesting_start;
-                self.newline(body.original_position());
+        fn flush(printer: &mut Printer, pending: &mut Vec<(usize, String)>) {
+            for (offset, expr) in pending.drain(..) {
+                printer.newline(offset);
+                printer.result.push_str(&expr);
             }
+        }
 
-            self.end_group();
+        let nesting_start = self.nesting;
+        let mut frames = Vec::new();
+        // Expressions that haven't been printed yet, along with the offset of
+        // their first instruction.
+        let mut pending = Vec::new();
+        let mut buf = String::new();
+        let mut op_printer = operator::PrintOperator::new(self, state);
+        while !body.eof() {
+            let offset = body.original_position();
+            let relative = offset - func_start;
+            while hints.next_if(|(o, _)| *o < relative).is_some() {}
+            let hint = hints.next_if(|(o, _)| *o == relative).map(|(_, v)| v);
+
+            let op = body.clone().read_operator()?;
+            let arity = match hint {
+                Some(_) => None,
+                None => op_printer.fold_arity(&op),
+            };
+            mem::swap(&mut buf, &mut op_printer.printer.result);
+            let op_kind = body.visit_operator(&mut op_printer)??;
+            mem::swap(&mut buf, &mut op_printer.printer.result);
+            let text = mem::take(&mut buf);
+
+            let printer = &mut *op_printer.printer;
+            if let operator::OpKind::End = op_kind {
+                if body.eof() {
+                    flush(printer, &mut pending);
+                    break;
+                }
+            }
+            if let Some(value) = hint {
+                flush(printer, &mut pending);
+                printer.print_branch_hint(offset, value)?;
+            }
+
+            match op_kind {
+                operator::OpKind::Normal => match arity {
+                    Some((params, results)) if params as usize <= pending.len() && results <= 1 => {
+                        let operands = pending.split_off(pending.len() - params as usize);
+                        let start = operands.first().map_or(offset, |(o, _)| *o);
+                        let mut expr = format!("({text}");
+                        for (_, operand) in operands {
+                            expr.push(' ');
+                            expr.push_str(&operand);
+                        }
+                        expr.push(')');
+                        if results == 1 {
+                            pending.push((start, expr));
+                        } else {
+                            flush(printer, &mut pending);
+                            printer.newline(start);
+                            printer.result.push_str(&expr);
+                        }
+                    }
+                    _ => {
+                        flush(printer, &mut pending);
+                        printer.newline(offset);
+                        printer.result.push_str(&text);
+                    }
+                },
+
+                operator::OpKind::BlockStart => match op {
+                    Operator::Block { .. } | Operator::Loop { .. } if hint.is_none() => {
+                        flush(printer, &mut pending);
+                        printer.newline(offset);
+                        printer.result.push('(');
+                        printer.result.push_str(&text);
+                        printer.nesting += 1;
+                        frames.push(Frame::Folded);
+                    }
+                    Operator::If { .. } if hint.is_none() => {
+                        let condition = pending.pop();
+                        flush(printer, &mut pending);
+                        printer.newline(offset);
+                        printer.result.push('(');
+                        printer.result.push_str(&text);
+                        printer.nesting += 1;
+                        if let Some((start, condition)) = condition {
+                            printer.newline(start);
+                            printer.result.push_str(&condition);
+                        }
+                        printer.newline(offset);
+                        printer.result.push_str("(then");
+                        printer.clause_nesting += 1;
+                        frames.push(Frame::FoldedIf);
+                    }
+                    _ => {
+                        flush(printer, &mut pending);
+                        printer.newline(offset);
+                        printer.result.push_str(&text);
+                        printer.nesting += 1;
+                        frames.push(Frame::Flat);
+                    }
+                },
+
+                operator::OpKind::BlockMid => {
+                    flush(printer, &mut pending);
+                    match frames.last() {
+                        Some(Frame::FoldedIf) => {
+                            printer.clause_nesting -= 1;
+                            printer.newline(offset);
+                            printer.result.push(')');
+                            printer.newline(offset);
+                            printer.result.push_str("(else");
+                            printer.clause_nesting += 1;
+                        }
+                        _ => {
+                            printer.nesting -= 1;
+                            printer.newline(offset);
+                            printer.result.push_str(&text);
+                            printer.nesting += 1;
+                        }
+                    }
+                }
 
-            state.core.funcs += 1;
+                operator::OpKind::End | operator::OpKind::Delegate => {
+                    flush(printer, &mut pending);
+                    match frames.pop() {
+                        Some(Frame::Folded) => {
+                            printer.nesting -= 1;
+                            printer.newline(offset);
+                            printer.result.push(')');
+                        }
+                        Some(Frame::FoldedIf) => {
+                            printer.clause_nesting -= 1;
+                            printer.newline(offset);
+                            printer.result.push(')');
+                            printer.nesting -= 1;
+                            printer.newline(offset);
+                            printer.result.push(')');
+                        }
+                        Some(Frame::Flat) => {
+                            printer.nesting -= 1;
+                            printer.newline(offset);
+                            printer.result.push_str(&text);
+                        }
+                        None => {
+                            printer.newline(offset);
+                            printer.result.push_str(&text);
+                        }
+                    }
+                }
+            }
         }
+        debug_assert!(op_printer.printer.nesting >= nesting_start);
+        Ok(())
+    }
+
+    fn print_branch_hint(&mut self, offset: usize, value: u8) -> Result<()> {
+        self.newline(offset);
+        self.result.push_str("(@metadata.code.branch_hint ");
+        self.print_bytes(&[value])?;
+        self.result.push(')');
         Ok(())
     }
 
@@ -1039,6 +2033,28 @@ impl Printer {
         self.print_newline(None)
     }
 
+    /// Starts a new line which is left empty, without any indentation.
+    fn blank_line(&mut self) {
+        self.result.push('\n');
+        self.lines.push(self.result.len());
+        self.line_offsets.push(None);
+        self.line += 1;
+    }
+
+    /// Returns the width of the current line so far.
+    fn column(&self) -> usize {
+        let start = self.lines.last().copied().unwrap_or(0);
+        self.result.len().saturating_sub(start)
+    }
+
+    /// Returns whether `len` more bytes fit on the current line within the
+    /// configured maximum width.
+    fn fits_on_line(&self, len: usize) -> bool {
+        self.config
+            .max_width
+            .map_or(true, |max| self.column() + len <= max)
+    }
+
     fn print_newline(&mut self, offset: Option<usize>) {
         self.result.push('\n');
 
@@ -1056,8 +2072,8 @@ impl Printer {
         // Clamp the maximum nesting size that we print at something somewhat
         // reasonable to avoid generating hundreds of megabytes of whitespace
         // for small-ish modules that have deep-ish nesting.
-        for _ in 0..self.nesting.min(MAX_NESTING_TO_PRINT) {
-            self.result.push_str("  ");
+        for _ in 0..(self.nesting + self.clause_nesting).min(MAX_NESTING_TO_PRINT) {
+            self.result.push_str(&self.config.indent);
         }
     }
 
@@ -1098,7 +2114,10 @@ impl Printer {
                 self.result.push_str("memory ");
                 self.print_idx(&state.core.memory_names, index)?;
             }
-            ExternalKind::Tag => write!(self.result, "tag {}", index)?,
+            ExternalKind::Tag => {
+                self.result.push_str("tag ");
+                self.print_idx(&state.core.tag_names, index)?;
+            }
         }
         self.result.push(')');
         Ok(())
@@ -1224,7 +2243,7 @@ impl Printer {
                     self.result.push(' ');
                 }
             }
-            self.print_bytes(data.data)?;
+            self.print_wrapped_bytes(data.data, offset)?;
             self.end_group();
         }
         Ok(())
@@ -2238,7 +3257,7 @@ impl Printer {
                     }
                     ExternalKind::Tag => {
                         self.start_group("core tag ");
-                        write!(self.result, "(;{};)", state.core.tags)?;
+                        self.print_name(&state.core.tag_names, state.core.tags)?;
                         self.end_group();
                         state.core.tags += 1;
                     }
@@ -2300,6 +3319,63 @@ impl Printer {
         Ok(())
     }
 
+    /// Prints the custom section `section` as an `@custom` annotation, with a
+    /// hexdump of each row of 16 bytes in a comment.
+    fn print_custom_section_hexdump(
+        &mut self,
+        state: &State,
+        section: &CustomSectionReader<'_>,
+    ) -> Result<()> {
+        self.newline(section.range().start);
+        self.start_group("@custom ");
+        self.print_str(section.name())?;
+        if state.encoding == Encoding::Module {
+            // Custom sections are placed relative to the section before them
+            // so they end up in the same place when the text is parsed.
+            let place = match state.core.last_section {
+                _ if state.core.after_names => "after last",
+                None => "before first",
+                Some(1) => "after type",
+                Some(2) => "after import",
+                Some(3) => "after func",
+                Some(4) => "after table",
+                Some(5) => "after memory",
+                Some(6) => "after global",
+                Some(7) => "after export",
+                Some(8) => "after start",
+                Some(9) => "after elem",
+                Some(10) => "after code",
+                Some(11) => "after data",
+                Some(12) => "before code",
+                Some(13) => "after tag",
+                Some(_) => "after last",
+            };
+            write!(self.result, " ({place})")?;
+        }
+        for (i, row) in section.data().chunks(16).enumerate() {
+            let offset = i * 16;
+            self.newline(section.data_offset() + offset);
+            write!(self.result, ";; {offset:04x}:")?;
+            for byte in row {
+                write!(self.result, " {byte:02x}")?;
+            }
+            for _ in row.len()..16 {
+                self.result.push_str("   ");
+            }
+            self.result.push_str("  ");
+            for byte in row {
+                match *byte {
+                    0x20..=0x7e => self.result.push(*byte as char),
+                    _ => self.result.push('.'),
+                }
+            }
+            self.newline(section.data_offset() + offset);
+            self.print_bytes(row)?;
+        }
+        self.end_group();
+        Ok(())
+    }
+
     fn print_str(&mut self, name: &str) -> Result<()> {
         let mut bytes = [0; 4];
         self.result.push('"');
@@ -2330,6 +3406,48 @@ impl Printer {
         Ok(())
     }
 
+    /// Prints `bytes` as a string like [`Printer::print_bytes`], or as several
+    /// strings on separate lines if it doesn't fit within the configured
+    /// maximum width.
+    fn print_wrapped_bytes(&mut self, bytes: &[u8], offset: usize) -> Result<()> {
+        fn width(byte: u8) -> usize {
+            if (0x20..0x7f).contains(&byte) && byte != b'"' && byte != b'\\' {
+                1
+            } else {
+                3
+            }
+        }
+
+        let total = bytes.iter().map(|b| width(*b)).sum::<usize>() + 2;
+        let max = match self.config.max_width {
+            Some(max) if !self.fits_on_line(total) => max,
+            _ => return self.print_bytes(bytes),
+        };
+        // Don't leave the space printed before the string at the end of the
+        // line.
+        if self.result.ends_with(' ') {
+            self.result.pop();
+        }
+        let mut rest = bytes;
+        while !rest.is_empty() {
+            self.newline(offset);
+            let mut remaining = max.saturating_sub(self.column() + 2);
+            // Always print at least one byte per line to make progress.
+            let mut n = 0;
+            for byte in rest {
+                if n > 0 && width(*byte) > remaining {
+                    break;
+                }
+                remaining = remaining.saturating_sub(width(*byte));
+                n += 1;
+            }
+            let (line, next) = rest.split_at(n);
+            self.print_bytes(line)?;
+            rest = next;
+        }
+        Ok(())
+    }
+
     fn hex_byte(&mut self, byte: u8) {
         fn to_hex(b: u8) -> char {
             if b < 10 {
@@ -2496,6 +3614,26 @@ impl Printer {
 }
 
 impl Naming {
+    /// Creates a name for an item which isn't named in the `name` section,
+    /// such as `#func12`, for [`NameStyle::Synthesized`].
+    ///
+    /// `used` holds the names and identifiers of the other items of the same
+    /// kind. If the name is already among them, such as when the `name`
+    /// section calls another item `#func12`, then it's deduplicated as in
+    /// [`Naming::new`].
+    fn synthesized(group: &str, index: u32, used: &mut HashSet<String>) -> Naming {
+        let name = format!("#{group}{index}");
+        if used.insert(name.clone()) {
+            return Naming {
+                identifier: None,
+                name,
+            };
+        }
+        let naming = Naming::new(&name, index, group, None);
+        used.insert(naming.identifier().to_string());
+        naming
+    }
+
     fn new<'a>(
         name: &'a str,
         index: u32,
@@ -2627,3 +3765,79 @@ fn name_map(into: &mut HashMap<u32, Naming>, names: NameMap<'_>, name: &str) ->
     }
     Ok(())
 }
+
+/// Gives each of the first `count` items in `names` which isn't already named
+/// a synthesized name, for [`NameStyle::Synthesized`].
+fn synthesize_names(names: &mut HashMap<u32, Naming>, group: &str, count: u32) {
+    let mut used = names
+        .values()
+        .flat_map(|naming| [naming.name.clone(), naming.identifier().to_string()])
+        .collect();
+    for i in 0..count {
+        names
+            .entry(i)
+            .or_insert_with(|| Naming::synthesized(group, i, &mut used));
+    }
+}
+
+/// Returns `payload`, except that the start of a streamed custom section is
+/// returned as the whole section, which is available in `wasm` since the
+/// printer always has the whole binary. The rest of the section is then
+/// skipped in `parser` and `bytes`, so none of its chunks follow.
+fn whole_custom_section<'a>(
+    wasm: &'a [u8],
+    parser: &mut Parser,
+    bytes: &mut &'a [u8],
+    payload: Payload<'a>,
+) -> Result<Payload<'a>> {
+    match payload {
+        Payload::CustomSectionStart { range, size, .. } => {
+            *bytes = &bytes[size as usize..];
+            parser.skip_section();
+            let section = CustomSectionReader::new(&wasm[range.clone()], range.start)?;
+            Ok(Payload::CustomSection(section))
+        }
+        payload => Ok(payload),
+    }
+}
+
+/// Returns a description of the section with the `id` in a binary with the
+/// given `encoding`, for [`Printer::skeleton`].
+fn section_name(encoding: Encoding, id: u8) -> Cow<'static, str> {
+    let name = match (encoding, id) {
+        (_, 0) => "custom",
+        (Encoding::Module, 1) => "type",
+        (Encoding::Module, 2) => "import",
+        (Encoding::Module, 3) => "function",
+        (Encoding::Module, 4) => "table",
+        (Encoding::Module, 5) => "memory",
+        (Encoding::Module, 6) => "global",
+        (Encoding::Module, 7) => "export",
+        (Encoding::Module, 8) => "start",
+        (Encoding::Module, 9) => "element",
+        (Encoding::Module, 10) => "code",
+        (Encoding::Module, 11) => "data",
+        (Encoding::Module, 12) => "data count",
+        (Encoding::Module, 13) => "tag",
+        (Encoding::Component, 1) => "core module",
+        (Encoding::Component, 2) => "core instance",
+        (Encoding::Component, 3) => "core type",
+        (Encoding::Component, 4) => "component",
+        (Encoding::Component, 5) => "instance",
+        (Encoding::Component, 6) => "alias",
+        (Encoding::Component, 7) => "type",
+        (Encoding::Component, 8) => "canonical function",
+        (Encoding::Component, 9) => "start",
+        (Encoding::Component, 10) => "import",
+        (Encoding::Component, 11) => "export",
+        _ => return format!("unknown ({id})").into(),
+    };
+    name.into()
+}
+
+/// Returns whether `s` can be printed as a keyword in the text format.
+fn is_keyword(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_lowercase())
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-./:<=>?@\\^_`|~".contains(c))
+}
@@ -1,7 +1,8 @@
-use super::{Printer, State};
+use super::{NameStyle, Naming, Printer, State};
 use anyhow::{bail, Result};
+use std::collections::HashSet;
 use std::fmt::Write;
-use wasmparser::{BlockType, BrTable, HeapType, MemArg, VisitOperator};
+use wasmparser::{BlockType, BrTable, Encoding, HeapType, MemArg, Operator, VisitOperator};
 
 pub struct PrintOperator<'a, 'b> {
     pub(super) printer: &'a mut Printer,
@@ -27,14 +28,27 @@ impl<'a, 'b> PrintOperator<'a, 'b> {
     }
 
     fn blockty(&mut self, ty: BlockType) -> Result<()> {
-        if let Some(name) = self
+        let label = self.state.core.labels;
+        match self
             .state
             .core
             .label_names
-            .get(&(self.state.core.funcs, self.state.core.labels))
+            .get(&(self.state.core.funcs, label))
         {
-            name.write(&mut self.printer.result);
-            self.printer.result.push(' ');
+            Some(name) => {
+                name.write(&mut self.printer.result);
+                self.printer.result.push(' ');
+            }
+            None if self.printer.name_style == NameStyle::Synthesized
+                && self.state.encoding == Encoding::Module =>
+            {
+                // Labels can be shadowed, so they don't need to be
+                // deduplicated against the names of other labels.
+                let used = &mut HashSet::new();
+                Naming::synthesized("label", label, used).write(&mut self.printer.result);
+                self.printer.result.push(' ');
+            }
+            None => {}
         }
         match ty {
             BlockType::Empty => {}
@@ -69,9 +83,8 @@ impl<'a, 'b> PrintOperator<'a, 'b> {
         }
     }
 
-    fn tag_index(&mut self, index: u32) -> Result<()> {
-        write!(self.result(), "{index}")?;
-        Ok(())
+    fn tag_index(&mut self, idx: u32) -> Result<()> {
+        self.printer.print_idx(&self.state.core.tag_names, idx)
     }
 
     fn relative_depth(&mut self, depth: u32) -> Result<()> {
@@ -299,6 +312,12 @@ macro_rules! define_visit {
         $self.push_str(" ");
         $self.printer.print_f64($val.bits())?;
     );
+    // The label of `delegate` is relative to the block enclosing its `try`,
+    // but the `try` is still on the label stack while it's printed.
+    (payload $self:ident Delegate $relative_depth:ident) => (
+        let label = $self.label($relative_depth.saturating_add(1));
+        write!($self.result(), " {} (;{};)", $relative_depth, label)?;
+    );
     (payload $self:ident V128Const $val:ident) => (
         $self.push_str(" i32x4");
         for chunk in $val.bytes().chunks(4) {
@@ -864,3 +883,221 @@ impl<'a> VisitOperator<'a> for PrintOperator<'_, '_> {
 
     wasmparser::for_each_operator!(define_visit);
 }
+
+impl PrintOperator<'_, '_> {
+    /// Returns the number of operands popped and results pushed by `op`, for
+    /// the operators which can be printed as folded expressions.
+    ///
+    /// Control instructions and those whose arity depends on the types of
+    /// labels return `None`, as do the instructions of proposals which aren't
+    /// folded.
+    pub(super) fn fold_arity(&self, op: &Operator<'_>) -> Option<(u32, u32)> {
+        use Operator::*;
+        let state = &*self.state;
+        let func_arity = |ty: u32| match state.core.types.get(ty as usize) {
+            Some(Some(ty)) => Some((ty.params().len() as u32, ty.results().len() as u32)),
+            _ => None,
+        };
+        Some(match op {
+            Nop | DataDrop { .. } | ElemDrop { .. } => (0, 0),
+
+            I32Const { .. }
+            | I64Const { .. }
+            | F32Const { .. }
+            | F64Const { .. }
+            | LocalGet { .. }
+            | GlobalGet { .. }
+            | MemorySize { .. }
+            | RefNull { .. }
+            | RefFunc { .. }
+            | TableSize { .. } => (0, 1),
+
+            Drop | LocalSet { .. } | GlobalSet { .. } => (1, 0),
+
+            LocalTee { .. }
+            | MemoryGrow { .. }
+            | RefIsNull
+            | TableGet { .. }
+            | I32Load { .. }
+            | I64Load { .. }
+            | F32Load { .. }
+            | F64Load { .. }
+            | I32Load8S { .. }
+            | I32Load8U { .. }
+            | I32Load16S { .. }
+            | I32Load16U { .. }
+            | I64Load8S { .. }
+            | I64Load8U { .. }
+            | I64Load16S { .. }
+            | I64Load16U { .. }
+            | I64Load32S { .. }
+            | I64Load32U { .. }
+            | I32Eqz
+            | I64Eqz
+            | I32Clz
+            | I32Ctz
+            | I32Popcnt
+            | I64Clz
+            | I64Ctz
+            | I64Popcnt
+            | F32Abs
+            | F32Neg
+            | F32Ceil
+            | F32Floor
+            | F32Trunc
+            | F32Nearest
+            | F32Sqrt
+            | F64Abs
+            | F64Neg
+            | F64Ceil
+            | F64Floor
+            | F64Trunc
+            | F64Nearest
+            | F64Sqrt
+            | I32WrapI64
+            | I32TruncF32S
+            | I32TruncF32U
+            | I32TruncF64S
+            | I32TruncF64U
+            | I64ExtendI32S
+            | I64ExtendI32U
+            | I64TruncF32S
+            | I64TruncF32U
+            | I64TruncF64S
+            | I64TruncF64U
+            | F32ConvertI32S
+            | F32ConvertI32U
+            | F32ConvertI64S
+            | F32ConvertI64U
+            | F32DemoteF64
+            | F64ConvertI32S
+            | F64ConvertI32U
+            | F64ConvertI64S
+            | F64ConvertI64U
+            | F64PromoteF32
+            | I32ReinterpretF32
+            | I64ReinterpretF64
+            | F32ReinterpretI32
+            | F64ReinterpretI64
+            | I32Extend8S
+            | I32Extend16S
+            | I64Extend8S
+            | I64Extend16S
+            | I64Extend32S
+            | I32TruncSatF32S
+            | I32TruncSatF32U
+            | I32TruncSatF64S
+            | I32TruncSatF64U
+            | I64TruncSatF32S
+            | I64TruncSatF32U
+            | I64TruncSatF64S
+            | I64TruncSatF64U => (1, 1),
+
+            TableSet { .. }
+            | I32Store { .. }
+            | I64Store { .. }
+            | F32Store { .. }
+            | F64Store { .. }
+            | I32Store8 { .. }
+            | I32Store16 { .. }
+            | I64Store8 { .. }
+            | I64Store16 { .. }
+            | I64Store32 { .. } => (2, 0),
+
+            TableGrow { .. }
+            | I32Eq
+            | I32Ne
+            | I32LtS
+            | I32LtU
+            | I32GtS
+            | I32GtU
+            | I32LeS
+            | I32LeU
+            | I32GeS
+            | I32GeU
+            | I64Eq
+            | I64Ne
+            | I64LtS
+            | I64LtU
+            | I64GtS
+            | I64GtU
+            | I64LeS
+            | I64LeU
+            | I64GeS
+            | I64GeU
+            | F32Eq
+            | F32Ne
+            | F32Lt
+            | F32Gt
+            | F32Le
+            | F32Ge
+            | F64Eq
+            | F64Ne
+            | F64Lt
+            | F64Gt
+            | F64Le
+            | F64Ge
+            | I32Add
+            | I32Sub
+            | I32Mul
+            | I32DivS
+            | I32DivU
+            | I32RemS
+            | I32RemU
+            | I32And
+            | I32Or
+            | I32Xor
+            | I32Shl
+            | I32ShrS
+            | I32ShrU
+            | I32Rotl
+            | I32Rotr
+            | I64Add
+            | I64Sub
+            | I64Mul
+            | I64DivS
+            | I64DivU
+            | I64RemS
+            | I64RemU
+            | I64And
+            | I64Or
+            | I64Xor
+            | I64Shl
+            | I64ShrS
+            | I64ShrU
+            | I64Rotl
+            | I64Rotr
+            | F32Add
+            | F32Sub
+            | F32Mul
+            | F32Div
+            | F32Min
+            | F32Max
+            | F32Copysign
+            | F64Add
+            | F64Sub
+            | F64Mul
+            | F64Div
+            | F64Min
+            | F64Max
+            | F64Copysign => (2, 1),
+
+            Select | TypedSelect { .. } => (3, 1),
+
+            MemoryInit { .. }
+            | MemoryCopy { .. }
+            | MemoryFill { .. }
+            | TableInit { .. }
+            | TableCopy { .. }
+            | TableFill { .. } => (3, 0),
+
+            Call { function_index } => func_arity(*state.core.func_to_type.get(function_index)?)?,
+            CallIndirect { type_index, .. } => {
+                let (params, results) = func_arity(*type_index)?;
+                (params + 1, results)
+            }
+
+            _ => return None,
+        })
+    }
+}
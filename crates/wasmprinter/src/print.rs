@@ -0,0 +1,42 @@
+use std::fmt;
+use std::io;
+
+/// A destination that the text format of a WebAssembly binary can be printed
+/// to with [`Printer::print_to`](crate::Printer::print_to).
+///
+/// This is implemented by [`PrintIoWrite`] and [`PrintFmtWrite`], which adapt
+/// types implementing [`io::Write`] and [`fmt::Write`] respectively.
+pub trait Print {
+    /// Writes the string `s` to this destination.
+    fn write_str(&mut self, s: &str) -> io::Result<()>;
+}
+
+impl<T: Print + ?Sized> Print for &mut T {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        (**self).write_str(s)
+    }
+}
+
+/// An adapter to print to a type implementing [`io::Write`], such as a file
+/// or standard output.
+#[derive(Debug)]
+pub struct PrintIoWrite<T>(pub T);
+
+impl<T: io::Write> Print for PrintIoWrite<T> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0.write_all(s.as_bytes())
+    }
+}
+
+/// An adapter to print to a type implementing [`fmt::Write`], such as a
+/// `String`.
+#[derive(Debug)]
+pub struct PrintFmtWrite<T>(pub T);
+
+impl<T: fmt::Write> Print for PrintFmtWrite<T> {
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.0
+            .write_str(s)
+            .map_err(|_| io::Error::other("failed to write string"))
+    }
+}
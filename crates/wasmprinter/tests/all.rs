@@ -21,6 +21,20 @@ fn no_panic() {
     wasmprinter::print_bytes(&bytes).unwrap();
 }
 
+#[test]
+fn delegate_max_depth() {
+    let bytes = wat::parse_str(
+        r#"
+            (module
+                (func try delegate 4294967295)
+            )
+        "#,
+    )
+    .unwrap();
+    let wat = wasmprinter::print_bytes(&bytes).unwrap();
+    assert!(wat.contains("delegate 4294967295 (; INVALID ;)"), "{wat}");
+}
+
 #[test]
 fn code_section_overflow() {
     let bytes = wat::parse_str(
@@ -264,3 +278,194 @@ fn offsets_and_lines_smoke_test() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+fn tag_names() {
+    let mut bytes = wat::parse_str("(module (tag) (func throw 0))").unwrap();
+    // A `name` section with a tag subsection naming tag 0 `e`.
+    bytes.extend_from_slice(b"\x00\x0b\x04name\x0b\x04\x01\x00\x01e");
+    let result = wasmprinter::print_bytes(&bytes).unwrap();
+    assert!(result.contains("(tag $e "), "{}", result);
+    assert!(result.contains("throw $e"), "{}", result);
+}
+
+#[test]
+fn fold_instructions() {
+    const MODULE: &str = r#"
+        (module
+          (func (param i32) (result i32)
+            local.get 0
+            i32.eqz
+            if
+              unreachable
+            end
+            local.get 0
+            i32.const 1
+            local.get 0
+            select
+            call 0
+            drop
+            i32.const 2
+          )
+        )
+    "#;
+    let bytes = wat::parse_str(MODULE).unwrap();
+    let mut printer = wasmprinter::Printer::new();
+    printer.fold_instructions(true);
+    let result = printer.print(&bytes).unwrap();
+    assert!(result.contains("(i32.eqz (local.get 0))"), "{}", result);
+    assert!(
+        result.contains("(drop (call 0 (select (local.get 0) (i32.const 1) (local.get 0))))"),
+        "{}",
+        result
+    );
+    assert_eq!(wat::parse_str(&result).unwrap(), bytes);
+}
+
+#[test]
+fn print_to_matches_print() {
+    let bytes = wat::parse_str(
+        r#"
+            (component
+              (core module $m
+                (func $a (result i32) i32.const 0)
+                (func $b (param i32) local.get 0 drop)
+                (export "a" (func $a))
+              )
+              (core instance (instantiate $m))
+            )
+        "#,
+    )
+    .unwrap();
+    let expected = wasmprinter::print_bytes(&bytes).unwrap();
+
+    let mut io = Vec::new();
+    wasmprinter::Printer::new()
+        .print_to(&bytes, wasmprinter::PrintIoWrite(&mut io))
+        .unwrap();
+    assert_eq!(String::from_utf8(io).unwrap(), expected);
+
+    let mut fmt = String::new();
+    wasmprinter::Printer::new()
+        .print_to(&bytes, wasmprinter::PrintFmtWrite(&mut fmt))
+        .unwrap();
+    assert_eq!(fmt, expected);
+}
+
+#[test]
+fn print_function_errors() {
+    let mut printer = wasmprinter::Printer::new();
+    let bytes = wat::parse_str(r#"(module (import "" "" (func)) (func))"#).unwrap();
+    assert!(printer.print_function(&bytes, 0).is_err());
+    assert!(printer.print_function(&bytes, 2).is_err());
+    assert!(printer.print_function(&bytes, 1).is_ok());
+
+    let bytes = wat::parse_str("(component (core module (func)))").unwrap();
+    assert!(printer.print_function(&bytes, 0).is_err());
+}
+
+#[test]
+fn print_function_skips_other_sections() {
+    let mut bytes = wat::parse_str("(module (func (result i32) i32.const 1))").unwrap();
+    // A data section with one segment of an invalid kind, which fails to
+    // print but isn't needed to print the function.
+    bytes.extend_from_slice(b"\x0b\x02\x01\xff");
+    let mut printer = wasmprinter::Printer::new();
+    assert!(printer.print(&bytes).is_err());
+    let text = printer.print_function(&bytes, 0).unwrap();
+    assert_eq!(text, "(func (;0;) (type 0) (result i32)\n  i32.const 1\n)");
+}
+
+#[test]
+fn config_layout() {
+    let bytes = wat::parse_str(
+        r#"
+            (module
+              (func (param i32 i32 i32 i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                local.get 2
+                local.get 3
+                local.get 4
+                i32.add
+                i32.add
+                i32.add
+                i32.add
+              )
+              (func)
+              (memory 1)
+              (data (i32.const 0) "a fairly long data segment \00\01\02 which needs to be wrapped")
+            )
+        "#,
+    )
+    .unwrap();
+    let mut printer = wasmprinter::Printer::new();
+    printer.config(wasmprinter::Config {
+        indent: " ".to_string(),
+        max_width: Some(32),
+        blank_lines_between_functions: 2,
+        group_local_gets: true,
+    });
+    let result = printer.print(&bytes).unwrap();
+    assert!(
+        result.contains("\n  local.get 0 local.get 1\n  local.get 2 local.get 3\n  local.get 4\n"),
+        "{}",
+        result
+    );
+    assert!(result.contains("\n )\n\n\n (func"), "{}", result);
+    for line in result.lines().filter(|l| l.contains('"')) {
+        assert!(line.len() <= 32, "{}", result);
+        assert!(!line.ends_with(' '), "{}", result);
+    }
+    assert_eq!(wat::parse_str(&result).unwrap(), bytes);
+}
+
+#[test]
+fn synthesized_names_avoid_name_section_names() {
+    let bytes = wat::parse_str(
+        r##"
+        (module
+          (func (@name "#func2"))
+          (func)
+          (func)
+          (func (param i32) (local $l (@name "#local2") i32) (local i32))
+        )
+        "##,
+    )
+    .unwrap();
+    let mut printer = wasmprinter::Printer::new();
+    printer.name_style(wasmprinter::NameStyle::Synthesized);
+    let result = printer.print(&bytes).unwrap();
+    assert!(result.contains("(func $#func1 (;1;)"), "{}", result);
+    assert!(
+        result.contains("(func $#func2<#func2> (@name \"#func2\") (;2;)"),
+        "{}",
+        result
+    );
+    assert!(result.contains("(param $#local0 i32)"), "{}", result);
+    assert!(
+        result.contains("(local $#local2<#local2> (@name \"#local2\") i32)"),
+        "{}",
+        result
+    );
+    wat::parse_str(&result).unwrap();
+}
+
+#[test]
+fn hexdump_custom_sections_after_names() {
+    let bytes = wat::parse_str(
+        r#"
+        (module $m
+          (func $f)
+          (@custom "a" (after code) "1")
+          (@custom "b" "2")
+        )
+        "#,
+    )
+    .unwrap();
+    let mut printer = wasmprinter::Printer::new();
+    printer.hexdump_unknown_custom_sections(true);
+    let result = printer.print(&bytes).unwrap();
+    assert!(result.contains("(@custom \"b\" (after last)"), "{}", result);
+    assert_eq!(wat::parse_str(&result).unwrap(), bytes, "{}", result);
+}
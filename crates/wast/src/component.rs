@@ -11,6 +11,7 @@ mod import;
 mod instance;
 mod item_ref;
 mod module;
+mod print;
 mod resolve;
 mod types;
 mod wast;
@@ -24,5 +25,6 @@ pub use self::import::*;
 pub use self::instance::*;
 pub use self::item_ref::*;
 pub use self::module::*;
+pub use self::resolve::{ComponentNameKind, ComponentNames};
 pub use self::types::*;
 pub use self::wast::*;
@@ -4,17 +4,25 @@ use crate::parser::{Parse, Parser, Result};
 use crate::token::{Id, Index, NameAnnotation, Span};
 
 /// A inline alias for component exported items.
+///
+/// When `CORE` is true this is an alias of a core instance's export, written
+/// as `(alias core export $instance "name")`. The `core` keyword may be
+/// omitted for compatibility with older text.
 #[derive(Debug)]
-pub struct InlineExportAlias<'a> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InlineExportAlias<'a, const CORE: bool> {
     /// The instance to alias the export from.
     pub instance: Index<'a>,
     /// The name of the export to alias.
     pub name: &'a str,
 }
 
-impl<'a> Parse<'a> for InlineExportAlias<'a> {
+impl<'a, const CORE: bool> Parse<'a> for InlineExportAlias<'a, CORE> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         parser.parse::<kw::alias>()?;
+        if CORE {
+            parser.parse::<Option<kw::core>>()?;
+        }
         parser.parse::<kw::export>()?;
         let instance = parser.parse()?;
         let name = parser.parse()?;
@@ -24,6 +32,7 @@ impl<'a> Parse<'a> for InlineExportAlias<'a> {
 
 /// An alias to a component item.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Alias<'a> {
     /// Where this `alias` was defined.
     pub span: Span,
@@ -68,6 +77,32 @@ impl<'a> Alias<'a> {
     }
 }
 
+impl<'a> Alias<'a> {
+    /// Parses the inline form of an alias of a core instance's exported
+    /// table, memory, global, or tag, such as
+    /// `(core memory $m (alias core export $i "memory"))`.
+    ///
+    /// Core functions are instead parsed as [`CoreFunc`](crate::component::CoreFunc).
+    pub fn parse_inline_core_export_alias(parser: Parser<'a>) -> Result<Self> {
+        let span = parser.parse::<kw::core>()?.0;
+        let kind = parser.parse()?;
+        let id = parser.parse()?;
+        let name = parser.parse()?;
+        let alias = parser.parens(|parser| parser.parse::<InlineExportAlias<true>>())?;
+
+        Ok(Self {
+            span,
+            target: AliasTarget::CoreExport {
+                instance: alias.instance,
+                name: alias.name,
+                kind,
+            },
+            id,
+            name,
+        })
+    }
+}
+
 impl<'a> Parse<'a> for Alias<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let span = parser.parse::<kw::alias>()?.0;
@@ -132,6 +167,7 @@ impl<'a> Parse<'a> for Alias<'a> {
 
 /// Represents the kind of instance export alias.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentExportAliasKind {
     /// The alias is to a core module export.
     CoreModule,
@@ -182,6 +218,7 @@ impl<'a> Parse<'a> for ComponentExportAliasKind {
 
 /// Represents the kind of outer alias.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentOuterAliasKind {
     /// The alias is to an outer core module.
     CoreModule,
@@ -222,6 +259,7 @@ impl<'a> Parse<'a> for ComponentOuterAliasKind {
 
 /// The target of a component alias.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum AliasTarget<'a> {
     /// The alias is to an export of a component instance.
     Export {
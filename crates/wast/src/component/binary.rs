@@ -1,6 +1,8 @@
 use crate::component::*;
 use crate::core;
+use crate::encode::check_size;
 use crate::token::{Id, Index, NameAnnotation};
+use crate::Error;
 use wasm_encoder::{
     CanonicalFunctionSection, ComponentAliasSection, ComponentDefinedTypeEncoder,
     ComponentExportSection, ComponentImportSection, ComponentInstanceSection, ComponentNameSection,
@@ -9,12 +11,12 @@ use wasm_encoder::{
     NestedComponentSection, RawSection, SectionId,
 };
 
-pub fn encode(component: &Component<'_>) -> Vec<u8> {
+pub fn encode(component: &Component<'_>) -> Result<Vec<u8>, Error> {
     match &component.kind {
         ComponentKind::Text(fields) => {
-            encode_fields(&component.id, &component.name, fields).finish()
+            Ok(encode_fields(&component.id, &component.name, fields)?.finish())
         }
-        ComponentKind::Binary(bytes) => bytes.iter().flat_map(|b| b.iter().copied()).collect(),
+        ComponentKind::Binary(bytes) => Ok(bytes.iter().flat_map(|b| b.iter().copied()).collect()),
     }
 }
 
@@ -23,15 +25,28 @@ fn encode_fields(
     component_id: &Option<Id<'_>>,
     component_name: &Option<NameAnnotation<'_>>,
     fields: &[ComponentField<'_>],
-) -> wasm_encoder::Component {
+) -> Result<wasm_encoder::Component, Error> {
     let mut e = Encoder::default();
 
+    // A component has only one producers section, so all `@producers`
+    // annotations are merged into the first of them.
+    let mut producers = fields.iter().filter_map(|f| match f {
+        ComponentField::Producers(p) => Some(p),
+        _ => None,
+    });
+    let mut merged = producers.next().cloned();
+    if let Some(merged) = &mut merged {
+        for p in producers {
+            merged.merge(p);
+        }
+    }
+
     for field in fields {
         match field {
-            ComponentField::CoreModule(m) => e.encode_core_module(m),
+            ComponentField::CoreModule(m) => e.encode_core_module(m)?,
             ComponentField::CoreInstance(i) => e.encode_core_instance(i),
             ComponentField::CoreType(t) => e.encode_core_type(t),
-            ComponentField::Component(c) => e.encode_component(c),
+            ComponentField::Component(c) => e.encode_component(c)?,
             ComponentField::Instance(i) => e.encode_instance(i),
             ComponentField::Alias(a) => e.encode_alias(a),
             ComponentField::Type(t) => e.encode_type(t),
@@ -43,13 +58,18 @@ fn encode_fields(
             ComponentField::Import(i) => e.encode_import(i),
             ComponentField::Export(ex) => e.encode_export(ex),
             ComponentField::Custom(c) => e.encode_custom(c),
+            ComponentField::Producers(_) => {
+                if let Some(p) = merged.take() {
+                    e.encode_producers(&p);
+                }
+            }
         }
     }
 
     e.flush(None);
     e.encode_names(component_id, component_name);
 
-    e.component
+    Ok(e.component)
 }
 
 fn encode_core_type(encoder: CoreTypeEncoder, ty: &CoreTypeDef) {
@@ -156,6 +176,7 @@ struct Encoder<'a> {
     core_table_names: Vec<Option<&'a str>>,
     core_memory_names: Vec<Option<&'a str>>,
     core_global_names: Vec<Option<&'a str>>,
+    core_tag_names: Vec<Option<&'a str>>,
     core_type_names: Vec<Option<&'a str>>,
     core_module_names: Vec<Option<&'a str>>,
     core_instance_names: Vec<Option<&'a str>>,
@@ -173,7 +194,17 @@ impl<'a> Encoder<'a> {
         self.component.section(custom);
     }
 
-    fn encode_core_module(&mut self, module: &CoreModule<'a>) {
+    fn encode_producers(&mut self, producers: &core::Producers) {
+        let mut data = Vec::new();
+        crate::encode::Encode::encode(producers, &mut data);
+        self.encode_custom(&Custom {
+            span: producers.span,
+            name: "producers",
+            data: vec![&data],
+        });
+    }
+
+    fn encode_core_module(&mut self, module: &CoreModule<'a>) -> Result<(), Error> {
         // Flush any in-progress section before encoding the module
         self.flush(None);
 
@@ -184,13 +215,16 @@ impl<'a> Encoder<'a> {
             CoreModuleKind::Import { .. } => unreachable!("should be expanded already"),
             CoreModuleKind::Inline { fields } => {
                 // TODO: replace this with a wasm-encoder based encoding (should return `wasm_encoder::Module`)
-                let data = crate::core::binary::encode(&module.id, &module.name, fields);
+                let data =
+                    crate::core::binary::encode(module.span, &module.id, &module.name, fields)?;
+                check_size(module.span, || "core module".to_string(), data.len())?;
                 self.component.section(&RawSection {
                     id: ComponentSectionId::CoreModule.into(),
                     data: &data,
                 });
             }
         }
+        Ok(())
     }
 
     fn encode_core_instance(&mut self, instance: &CoreInstance<'a>) {
@@ -220,7 +254,7 @@ impl<'a> Encoder<'a> {
         self.flush(Some(self.core_types.id()));
     }
 
-    fn encode_component(&mut self, component: &NestedComponent<'a>) {
+    fn encode_component(&mut self, component: &NestedComponent<'a>) -> Result<(), Error> {
         self.component_names
             .push(get_name(&component.id, &component.name));
         // Flush any in-progress section before encoding the component
@@ -229,14 +263,16 @@ impl<'a> Encoder<'a> {
         match &component.kind {
             NestedComponentKind::Import { .. } => unreachable!("should be expanded already"),
             NestedComponentKind::Inline(fields) => {
-                self.component
-                    .section(&NestedComponentSection(&encode_fields(
-                        &component.id,
-                        &component.name,
-                        fields,
-                    )));
+                let nested = encode_fields(&component.id, &component.name, fields)?;
+                check_size(
+                    component.span,
+                    || "nested component".to_string(),
+                    nested.as_slice().len(),
+                )?;
+                self.component.section(&NestedComponentSection(&nested));
             }
         }
+        Ok(())
     }
 
     fn encode_instance(&mut self, instance: &Instance<'a>) {
@@ -436,6 +472,7 @@ impl<'a> Encoder<'a> {
         funcs(&self.core_table_names, ComponentNameSection::core_tables);
         funcs(&self.core_memory_names, ComponentNameSection::core_memories);
         funcs(&self.core_global_names, ComponentNameSection::core_globals);
+        funcs(&self.core_tag_names, ComponentNameSection::core_tags);
         funcs(&self.core_type_names, ComponentNameSection::core_types);
         funcs(&self.core_module_names, ComponentNameSection::core_modules);
         funcs(
@@ -485,7 +522,7 @@ impl<'a> Encoder<'a> {
             core::ExportKind::Global => &mut self.core_global_names,
             core::ExportKind::Table => &mut self.core_table_names,
             core::ExportKind::Memory => &mut self.core_memory_names,
-            core::ExportKind::Tag => unimplemented!(),
+            core::ExportKind::Tag => &mut self.core_tag_names,
         }
     }
 
@@ -573,13 +610,14 @@ impl From<core::HeapType<'_>> for wasm_encoder::HeapType {
             core::HeapType::Extern => Self::Extern,
             core::HeapType::Index(Index::Num(i, _)) => Self::TypedFunc(i),
             core::HeapType::Index(_) => panic!("unresolved index"),
-            core::HeapType::Any
-            | core::HeapType::Eq
-            | core::HeapType::Struct
-            | core::HeapType::Array
-            | core::HeapType::I31 => {
-                todo!("encoding of GC proposal types not yet implemented")
-            }
+            core::HeapType::Any => Self::Any,
+            core::HeapType::Eq => Self::Eq,
+            core::HeapType::Struct => Self::Struct,
+            core::HeapType::Array => Self::Array,
+            core::HeapType::I31 => Self::I31,
+            core::HeapType::None => Self::None,
+            core::HeapType::NoFunc => Self::NoFunc,
+            core::HeapType::NoExtern => Self::NoExtern,
         }
     }
 }
@@ -608,11 +646,23 @@ impl From<core::TableType<'_>> for wasm_encoder::TableType {
 
 impl From<core::MemoryType> for wasm_encoder::MemoryType {
     fn from(ty: core::MemoryType) -> Self {
-        let (minimum, maximum, memory64, shared) = match ty {
-            core::MemoryType::B32 { limits, shared } => {
-                (limits.min.into(), limits.max.map(Into::into), false, shared)
-            }
-            core::MemoryType::B64 { limits, shared } => (limits.min, limits.max, true, shared),
+        let (minimum, maximum, memory64, shared, page_size_log2) = match ty {
+            core::MemoryType::B32 {
+                limits,
+                shared,
+                page_size_log2,
+            } => (
+                limits.min.into(),
+                limits.max.map(Into::into),
+                false,
+                shared,
+                page_size_log2,
+            ),
+            core::MemoryType::B64 {
+                limits,
+                shared,
+                page_size_log2,
+            } => (limits.min, limits.max, true, shared, page_size_log2),
         };
 
         Self {
@@ -620,6 +670,7 @@ impl From<core::MemoryType> for wasm_encoder::MemoryType {
             maximum,
             memory64,
             shared,
+            page_size_log2,
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::annotation;
 use crate::component::*;
+use crate::core;
 use crate::kw;
 use crate::parser::{Parse, Parser, Result};
 use crate::token::Index;
@@ -7,6 +8,7 @@ use crate::token::{Id, NameAnnotation, Span};
 
 /// A parsed WebAssembly component module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Component<'a> {
     /// Where this `component` was defined
     pub span: Span,
@@ -20,6 +22,7 @@ pub struct Component<'a> {
 
 /// The different kinds of ways to define a component.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentKind<'a> {
     /// A component defined in the textual s-expression format.
     Text(Vec<ComponentField<'a>>),
@@ -44,15 +47,20 @@ impl<'a> Component<'a> {
     /// also expand inline exports/imports listed on fields and handle various
     /// other shorthands of the text format.
     ///
-    /// If successful the AST was modified to be ready for binary encoding.
+    /// If successful the AST was modified to be ready for binary encoding. A
+    /// [`ComponentNames`] structure is also returned so if you'd like to do
+    /// your own name lookups on the result you can do so as well.
     ///
     /// # Errors
     ///
     /// If an error happens during resolution, such a name resolution error or
     /// items are found in the wrong order, then an error is returned.
-    pub fn resolve(&mut self) -> std::result::Result<(), crate::Error> {
+    pub fn resolve(&mut self) -> std::result::Result<ComponentNames<'a>, crate::Error> {
         match &mut self.kind {
             ComponentKind::Text(fields) => {
+                // As with core modules, make the names generated while
+                // expanding only depend on this component.
+                crate::gensym::reset();
                 crate::component::expand::expand(fields);
             }
             ComponentKind::Binary(_) => {}
@@ -83,31 +91,17 @@ impl<'a> Component<'a> {
     /// # Errors
     ///
     /// This function can return an error for name resolution errors and other
-    /// expansion-related errors.
+    /// expansion-related errors, or if an item is too large to be encoded.
     pub fn encode(&mut self) -> std::result::Result<Vec<u8>, crate::Error> {
         self.resolve()?;
-        Ok(crate::component::binary::encode(self))
-    }
-
-    pub(crate) fn validate(&self, parser: Parser<'_>) -> Result<()> {
-        let mut starts = 0;
-        if let ComponentKind::Text(fields) = &self.kind {
-            for item in fields.iter() {
-                if let ComponentField::Start(_) = item {
-                    starts += 1;
-                }
-            }
-        }
-        if starts > 1 {
-            return Err(parser.error("multiple start sections found"));
-        }
-        Ok(())
+        crate::component::binary::encode(self)
     }
 }
 
 impl<'a> Parse<'a> for Component<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let _r = parser.register_annotation("custom");
+        let _r = parser.register_annotation("producers");
 
         let span = parser.parse::<kw::component>()?.0;
         let id = parser.parse()?;
@@ -135,6 +129,7 @@ impl<'a> Parse<'a> for Component<'a> {
 /// A listing of all possible fields that can make up a WebAssembly component.
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentField<'a> {
     CoreModule(CoreModule<'a>),
     CoreInstance(CoreInstance<'a>),
@@ -150,6 +145,7 @@ pub enum ComponentField<'a> {
     Import(ComponentImport<'a>),
     Export(ComponentExport<'a>),
     Custom(Custom<'a>),
+    Producers(core::Producers<'a>),
 }
 
 impl<'a> ComponentField<'a> {
@@ -177,6 +173,13 @@ impl<'a> Parse<'a> for ComponentField<'a> {
             if parser.peek2::<kw::func>() {
                 return Ok(Self::CoreFunc(parser.parse()?));
             }
+            if parser.peek2::<kw::table>()
+                || parser.peek2::<kw::memory>()
+                || parser.peek2::<kw::global>()
+                || parser.peek2::<kw::tag>()
+            {
+                return Ok(Self::Alias(Alias::parse_inline_core_export_alias(parser)?));
+            }
         } else {
             if parser.peek::<kw::component>() {
                 return Ok(Self::Component(parser.parse()?));
@@ -205,6 +208,17 @@ impl<'a> Parse<'a> for ComponentField<'a> {
             if parser.peek::<annotation::custom>() {
                 return Ok(Self::Custom(parser.parse()?));
             }
+            if parser.peek::<annotation::producers>() {
+                return Ok(Self::Producers(parser.parse()?));
+            }
+            // The binary format has no section to define values in, so they
+            // can only come from imports, start functions and aliases.
+            if parser.peek::<kw::value>() {
+                return Err(parser.error(
+                    "component values cannot be defined, only imported, \
+                     aliased or returned by a start function",
+                ));
+            }
         }
         Err(parser.error("expected valid component field"))
     }
@@ -212,6 +226,7 @@ impl<'a> Parse<'a> for ComponentField<'a> {
 
 /// A function to call at instantiation time.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Start<'a> {
     /// The function to call.
     pub func: Index<'a>,
@@ -251,6 +266,7 @@ impl<'a> Parse<'a> for Start<'a> {
 
 /// A nested WebAssembly component.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NestedComponent<'a> {
     /// Where this `component` was defined
     pub span: Span,
@@ -267,6 +283,7 @@ pub struct NestedComponent<'a> {
 
 /// The different kinds of ways to define a nested component.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum NestedComponentKind<'a> {
     /// This is actually an inline import of a component
     Import {
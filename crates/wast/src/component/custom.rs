@@ -4,6 +4,7 @@ use crate::token::Span;
 
 /// A custom section within a component.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Custom<'a> {
     /// Where this `@custom` was defined.
     pub span: Span,
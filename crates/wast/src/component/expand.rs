@@ -64,6 +64,10 @@ struct Expander<'a> {
     /// Fields that are appended to the end of the module once everything has
     /// finished.
     component_fields_to_append: Vec<ComponentField<'a>>,
+
+    /// Function types defined so far in this scope, used to reuse an existing
+    /// definition for an inline function type with the same signature.
+    func_type_to_idx: HashMap<FuncKey<'a>, Index<'a>>,
 }
 
 impl<'a> Expander<'a> {
@@ -127,7 +131,10 @@ impl<'a> Expander<'a> {
                 }
                 None
             }
-            ComponentField::Start(_) | ComponentField::Alias(_) | ComponentField::Custom(_) => None,
+            ComponentField::Start(_)
+            | ComponentField::Alias(_)
+            | ComponentField::Custom(_)
+            | ComponentField::Producers(_) => None,
         };
 
         if let Some(expanded) = expanded {
@@ -459,6 +466,7 @@ impl<'a> Expander<'a> {
                         name: None,
                         def: key.to_def(item.span),
                         parent: None,
+                        final_type: None,
                     }));
                     let idx = Index::Id(id);
                     t.index = Some(idx);
@@ -567,7 +575,8 @@ impl<'a> Expander<'a> {
         // If this inline type has already been defined within this context
         // then reuse the previously defined type to avoid injecting too many
         // types into the type index space.
-        if let Some(idx) = inline.key().lookup(self) {
+        let key = inline.key();
+        if let Some(idx) = key.lookup(self) {
             *ty = ComponentValType::Ref(idx);
             return;
         }
@@ -578,6 +587,7 @@ impl<'a> Expander<'a> {
         let id = gensym::gen(span);
 
         self.types_to_prepend.push(inline.into_any_type(span, id));
+        key.insert(self, Index::Id(id));
 
         let idx = Index::Id(id);
         *ty = ComponentValType::Ref(idx);
@@ -609,7 +619,8 @@ impl<'a> Expander<'a> {
         // If this inline type has already been defined within this context
         // then reuse the previously defined type to avoid injecting too many
         // types into the type index space.
-        if let Some(idx) = inline.key().lookup(self) {
+        let key = inline.key();
+        if let Some(idx) = key.lookup(self) {
             let ret = CoreItemRef {
                 idx,
                 kind: kw::r#type(span),
@@ -624,6 +635,7 @@ impl<'a> Expander<'a> {
         let id = gensym::gen(span);
 
         self.types_to_prepend.push(inline.into_any_type(span, id));
+        key.insert(self, Index::Id(id));
 
         let idx = Index::Id(id);
         let ret = CoreItemRef {
@@ -662,7 +674,8 @@ impl<'a> Expander<'a> {
         // If this inline type has already been defined within this context
         // then reuse the previously defined type to avoid injecting too many
         // types into the type index space.
-        if let Some(idx) = inline.key().lookup(self) {
+        let key = inline.key();
+        if let Some(idx) = key.lookup(self) {
             let ret = ItemRef {
                 idx,
                 kind: kw::r#type(span),
@@ -677,6 +690,7 @@ impl<'a> Expander<'a> {
         let id = gensym::gen(span);
 
         self.types_to_prepend.push(inline.into_any_type(span, id));
+        key.insert(self, Index::Id(id));
 
         let idx = Index::Id(id);
         let ret = ItemRef {
@@ -822,10 +836,21 @@ impl<'a> TypeReference<'a> for InstanceType<'a> {
 }
 
 impl<'a> TypeReference<'a> for ComponentFunctionType<'a> {
-    type Key = Todo; // FIXME(#598): should implement this
+    type Key = FuncKey<'a>;
 
     fn key(&self) -> Self::Key {
-        Todo
+        FuncKey {
+            params: self
+                .params
+                .iter()
+                .map(|p| (p.name, ValKey::new(&p.ty)))
+                .collect(),
+            results: self
+                .results
+                .iter()
+                .map(|r| (r.name, ValKey::new(&r.ty)))
+                .collect(),
+        }
     }
 
     fn expand(&mut self, cx: &mut Expander<'a>) {
@@ -845,7 +870,7 @@ impl<'a> TypeReference<'a> for ComponentFunctionType<'a> {
 
 trait TypeKey<'a> {
     fn lookup(&self, cx: &Expander<'a>) -> Option<Index<'a>>;
-    fn insert(&self, cx: &mut Expander<'a>, index: Index<'a>);
+    fn insert(self, cx: &mut Expander<'a>, index: Index<'a>);
 }
 
 struct Todo;
@@ -855,5 +880,39 @@ impl<'a> TypeKey<'a> for Todo {
         None
     }
 
-    fn insert(&self, _cx: &mut Expander<'a>, _index: Index<'a>) {}
+    fn insert(self, _cx: &mut Expander<'a>, _index: Index<'a>) {}
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct FuncKey<'a> {
+    params: Box<[(&'a str, ValKey<'a>)]>,
+    results: Box<[(Option<&'a str>, ValKey<'a>)]>,
+}
+
+impl<'a> TypeKey<'a> for FuncKey<'a> {
+    fn lookup(&self, cx: &Expander<'a>) -> Option<Index<'a>> {
+        cx.func_type_to_idx.get(self).cloned()
+    }
+
+    fn insert(self, cx: &mut Expander<'a>, index: Index<'a>) {
+        cx.func_type_to_idx.entry(self).or_insert(index);
+    }
+}
+
+/// The key of an already-expanded value type, which is either primitive or a
+/// reference to a type defined elsewhere.
+#[derive(Hash, PartialEq, Eq)]
+enum ValKey<'a> {
+    Primitive(PrimitiveValType),
+    Ref(Index<'a>),
+}
+
+impl<'a> ValKey<'a> {
+    fn new(ty: &ComponentValType<'a>) -> ValKey<'a> {
+        match ty {
+            ComponentValType::Inline(ComponentDefinedType::Primitive(p)) => ValKey::Primitive(*p),
+            ComponentValType::Ref(idx) => ValKey::Ref(*idx),
+            ComponentValType::Inline(_) => unreachable!("should be expanded already"),
+        }
+    }
 }
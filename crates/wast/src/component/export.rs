@@ -5,6 +5,7 @@ use crate::token::{Id, Index, NameAnnotation, Span};
 
 /// An entry in a WebAssembly component's export section.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentExport<'a> {
     /// Where this export was defined.
     pub span: Span,
@@ -59,6 +60,7 @@ impl<'a> Parse<'a> for Vec<ComponentExport<'a>> {
 
 /// The kind of exported item.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentExportKind<'a> {
     /// The export is a core module.
     ///
@@ -175,6 +177,7 @@ impl Peek for ComponentExportKind<'_> {
 /// A listing of inline `(export "foo" <url>)` statements on a WebAssembly
 /// component item in its textual format.
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct InlineExport<'a> {
     /// The extra names to export an item as, if any.
     pub names: Vec<(&'a str, Option<&'a str>)>,
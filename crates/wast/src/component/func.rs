@@ -7,6 +7,7 @@ use crate::token::{Id, Index, LParen, NameAnnotation, Span};
 ///
 /// This is a member of both the core alias and canon sections.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CoreFunc<'a> {
     /// Where this `core func` was defined.
     pub span: Span,
@@ -38,6 +39,7 @@ impl<'a> Parse<'a> for CoreFunc<'a> {
 
 /// Represents the kind of core functions.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CoreFuncKind<'a> {
     /// The core function is defined in terms of lowering a component function.
     ///
@@ -46,7 +48,7 @@ pub enum CoreFuncKind<'a> {
     /// The core function is defined in terms of aliasing a module instance export.
     ///
     /// The core function is actually a member of the core alias section.
-    Alias(InlineExportAlias<'a>),
+    Alias(InlineExportAlias<'a, true>),
 }
 
 impl<'a> Parse<'a> for CoreFuncKind<'a> {
@@ -69,6 +71,7 @@ impl<'a> Parse<'a> for CoreFuncKind<'a> {
 ///
 /// This may be a member of the import, alias, or canon sections.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Func<'a> {
     /// Where this `func` was defined.
     pub span: Span,
@@ -104,6 +107,7 @@ impl<'a> Parse<'a> for Func<'a> {
 
 /// Represents the kind of component functions.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum FuncKind<'a> {
     /// A function which is actually defined as an import, such as:
     ///
@@ -128,7 +132,7 @@ pub enum FuncKind<'a> {
     /// The function is defined in terms of aliasing a component instance export.
     ///
     /// The function is actually a member of the alias section.
-    Alias(InlineExportAlias<'a>),
+    Alias(InlineExportAlias<'a, false>),
 }
 
 impl<'a> Parse<'a> for FuncKind<'a> {
@@ -156,6 +160,7 @@ impl<'a> Parse<'a> for FuncKind<'a> {
 ///
 /// This is a member of the canonical section.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CanonicalFunc<'a> {
     /// Where this `func` was defined.
     pub span: Span,
@@ -212,6 +217,7 @@ impl<'a> Parse<'a> for CanonicalFunc<'a> {
 
 /// Possible ways to define a canonical function in the text format.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CanonicalFuncKind<'a> {
     /// A canonical function that is defined in terms of lifting a core function.
     Lift {
@@ -226,6 +232,7 @@ pub enum CanonicalFuncKind<'a> {
 
 /// Information relating to lifting a core function.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CanonLift<'a> {
     /// The core function being lifted.
     pub func: CoreItemRef<'a, kw::func>,
@@ -263,6 +270,7 @@ impl Default for CanonLift<'_> {
 
 /// Information relating to lowering a component function.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CanonLower<'a> {
     /// The function being lowered.
     pub func: ItemRef<'a, kw::func>,
@@ -296,6 +304,7 @@ impl Default for CanonLower<'_> {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 /// Canonical ABI options.
 pub enum CanonOpt<'a> {
     /// Encode strings as UTF-8.
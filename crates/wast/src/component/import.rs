@@ -6,6 +6,7 @@ use crate::token::{Id, NameAnnotation, Span};
 
 /// An `import` statement and entry in a WebAssembly component.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentImport<'a> {
     /// Where this `import` was defined
     pub span: Span,
@@ -34,6 +35,7 @@ impl<'a> Parse<'a> for ComponentImport<'a> {
 
 /// An item signature for imported items.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ItemSig<'a> {
     /// Where this item is defined in the source.
     pub span: Span,
@@ -55,6 +57,7 @@ impl<'a> Parse<'a> for ItemSig<'a> {
 
 /// An item signature for imported items.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ItemSigNoName<'a>(pub ItemSig<'a>);
 
 impl<'a> Parse<'a> for ItemSigNoName<'a> {
@@ -99,6 +102,7 @@ fn parse_item_sig<'a>(parser: Parser<'a>, name: bool) -> Result<ItemSig<'a>> {
 
 /// The kind of signatures for imported items.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ItemSigKind<'a> {
     /// The item signature is for a core module.
     CoreModule(CoreTypeUse<'a, ModuleType<'a>>),
@@ -116,6 +120,7 @@ pub enum ItemSigKind<'a> {
 
 /// Represents the bounds applied to types being imported.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TypeBounds<'a> {
     /// The equality type bounds.
     Eq(Index<'a>),
@@ -134,6 +139,7 @@ impl<'a> Parse<'a> for TypeBounds<'a> {
 /// This is the same as `core::InlineImport` except only one string import is
 /// required.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct InlineImport<'a> {
     /// The name of the item being imported.
     pub name: &'a str,
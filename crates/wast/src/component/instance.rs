@@ -6,6 +6,7 @@ use crate::token::{Id, LParen, NameAnnotation, Span};
 
 /// A core instance defined by instantiation or exporting core items.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CoreInstance<'a> {
     /// Where this `core instance` was defined.
     pub span: Span,
@@ -37,6 +38,7 @@ impl<'a> Parse<'a> for CoreInstance<'a> {
 
 /// The kinds of core instances in the text format.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CoreInstanceKind<'a> {
     /// Instantiate a core module.
     Instantiate {
@@ -73,6 +75,7 @@ impl Default for kw::module {
 
 /// An argument to instantiate a core module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CoreInstantiationArg<'a> {
     /// The name of the instantiation argument.
     pub name: &'a str,
@@ -102,6 +105,7 @@ impl<'a> Parse<'a> for Vec<CoreInstantiationArg<'a>> {
 
 /// The kind of core instantiation argument.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CoreInstantiationArgKind<'a> {
     /// The argument is a reference to an instance.
     Instance(CoreItemRef<'a, kw::instance>),
@@ -127,6 +131,7 @@ impl<'a> Parse<'a> for CoreInstantiationArgKind<'a> {
 
 /// An exported item as part of a core instance.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CoreInstanceExport<'a> {
     /// Where this export was defined.
     pub span: Span,
@@ -158,6 +163,7 @@ impl<'a> Parse<'a> for Vec<CoreInstanceExport<'a>> {
 
 /// A component instance defined by instantiation or exporting items.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Instance<'a> {
     /// Where this `instance` was defined.
     pub span: Span,
@@ -193,6 +199,7 @@ impl<'a> Parse<'a> for Instance<'a> {
 
 /// The kinds of instances in the text format.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum InstanceKind<'a> {
     /// The `(instance (import "x"))` sugar syntax
     Import {
@@ -243,6 +250,7 @@ impl Default for kw::component {
 
 /// An argument to instantiate a component.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct InstantiationArg<'a> {
     /// The name of the instantiation argument.
     pub name: &'a str,
@@ -272,6 +280,7 @@ impl<'a> Parse<'a> for Vec<InstantiationArg<'a>> {
 
 /// The kind of instantiation argument.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum InstantiationArgKind<'a> {
     /// The argument is a reference to a component item.
     Item(ComponentExportKind<'a>),
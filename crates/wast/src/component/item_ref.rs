@@ -39,8 +39,10 @@ fn peek<K: Peek>(cursor: Cursor) -> bool {
 
 /// Parses core item references.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CoreItemRef<'a, K> {
     /// The item kind being parsed.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub kind: K,
     /// The item or instance reference.
     pub idx: Index<'a>,
@@ -76,8 +78,10 @@ impl<'a, K: Peek> Peek for CoreItemRef<'a, K> {
 
 /// Parses component item references.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ItemRef<'a, K> {
     /// The item kind being parsed.
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub kind: K,
     /// The item or instance reference.
     pub idx: Index<'a>,
@@ -113,6 +117,7 @@ impl<'a, K: Peek> Peek for ItemRef<'a, K> {
 
 /// Convenience structure to parse `$f` or `(item $f)`.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct IndexOrRef<'a, K>(pub ItemRef<'a, K>);
 
 impl<'a, K> Parse<'a> for IndexOrRef<'a, K>
@@ -134,6 +139,7 @@ where
 
 /// Convenience structure to parse `$f` or `(item $f)`.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct IndexOrCoreRef<'a, K>(pub CoreItemRef<'a, K>);
 
 impl<'a, K> Parse<'a> for IndexOrCoreRef<'a, K>
@@ -8,6 +8,7 @@ use crate::token::{Id, NameAnnotation, Span};
 ///
 /// This is a member of the core module section.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CoreModule<'a> {
     /// Where this `core module` was defined.
     pub span: Span,
@@ -25,6 +26,7 @@ pub struct CoreModule<'a> {
 
 /// Possible ways to define a core module in the text format.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CoreModuleKind<'a> {
     /// A core module which is actually defined as an import
     Import {
@@ -57,6 +59,8 @@ impl<'a> Parse<'a> for CoreModule<'a> {
                 ty: parser.parse()?,
             }
         } else {
+            let _r = parser.register_annotation("target_features");
+            let _r = parser.register_annotation("dylink.0");
             let mut fields = Vec::new();
             while !parser.is_empty() {
                 fields.push(parser.parens(|p| p.parse())?);
@@ -0,0 +1,815 @@
+use crate::component::*;
+use crate::core;
+use crate::core::print::{Output, Print};
+
+// Printing of components, which uses the `Printer` and the printing of core
+// items in `core/print.rs`.
+
+impl Print for Component<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("component");
+        self.id.print(out);
+        self.name.print(out);
+        match &self.kind {
+            ComponentKind::Text(fields) => component_fields(out, fields),
+            ComponentKind::Binary(blobs) => {
+                out.word("binary");
+                out.depth += 1;
+                for blob in blobs {
+                    out.newline();
+                    out.string(blob);
+                }
+                out.depth -= 1;
+            }
+        }
+        out.close();
+    }
+}
+
+/// Prints the fields of a component one per line.
+fn component_fields(out: &mut Output<'_>, fields: &[ComponentField<'_>]) {
+    out.depth += 1;
+    for field in fields {
+        out.newline();
+        field.print(out);
+    }
+    out.depth -= 1;
+}
+
+impl Print for ComponentField<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            ComponentField::CoreModule(m) => m.print(out),
+            ComponentField::CoreInstance(i) => i.print(out),
+            ComponentField::CoreType(t) => t.print(out),
+            ComponentField::Component(c) => c.print(out),
+            ComponentField::Instance(i) => i.print(out),
+            ComponentField::Alias(a) => a.print(out),
+            ComponentField::Type(t) => t.print(out),
+            ComponentField::CanonicalFunc(f) => f.print(out),
+            ComponentField::CoreFunc(f) => f.print(out),
+            ComponentField::Func(f) => f.print(out),
+            ComponentField::Start(s) => s.print(out),
+            ComponentField::Import(i) => i.print(out),
+            ComponentField::Export(e) => e.print(out),
+            ComponentField::Custom(c) => c.print(out),
+            ComponentField::Producers(p) => p.print(out),
+        }
+    }
+}
+
+/// Prints `(keyword idx "name"...)`, a reference to an item or to an export
+/// of an instance.
+fn item_ref<K>(out: &mut Output<'_>, keyword: &str, item: &ItemRef<'_, K>) {
+    out.open(keyword);
+    item.idx.print(out);
+    for name in item.export_names.iter() {
+        out.string(name.as_bytes());
+    }
+    out.close();
+}
+
+/// Prints a reference which may be abbreviated to a bare index.
+fn index_or_ref<K>(out: &mut Output<'_>, keyword: &str, item: &ItemRef<'_, K>) {
+    if item.export_names.is_empty() {
+        item.idx.print(out);
+    } else {
+        item_ref(out, keyword, item);
+    }
+}
+
+/// Prints `(keyword idx "name"?)`, a reference to a core item or to an
+/// export of a core instance.
+fn core_item_ref<K>(out: &mut Output<'_>, keyword: &str, item: &CoreItemRef<'_, K>) {
+    out.open(keyword);
+    item.idx.print(out);
+    if let Some(name) = item.export_name {
+        out.string(name.as_bytes());
+    }
+    out.close();
+}
+
+/// Prints a core reference which may be abbreviated to a bare index.
+fn index_or_core_ref<K>(out: &mut Output<'_>, keyword: &str, item: &CoreItemRef<'_, K>) {
+    if item.export_name.is_none() {
+        item.idx.print(out);
+    } else {
+        core_item_ref(out, keyword, item);
+    }
+}
+
+fn core_export_kind(kind: core::ExportKind) -> &'static str {
+    match kind {
+        core::ExportKind::Func => "func",
+        core::ExportKind::Table => "table",
+        core::ExportKind::Memory => "memory",
+        core::ExportKind::Global => "global",
+        core::ExportKind::Tag => "tag",
+    }
+}
+
+impl Print for InlineExport<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        for (name, url) in self.names.iter() {
+            out.open("export");
+            out.string(name.as_bytes());
+            if let Some(url) = url {
+                out.string(url.as_bytes());
+            }
+            out.close();
+        }
+    }
+}
+
+impl Print for InlineImport<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("import");
+        out.string(self.name.as_bytes());
+        if let Some(url) = self.url {
+            out.string(url.as_bytes());
+        }
+        out.close();
+    }
+}
+
+impl<T: Print> Print for CoreTypeUse<'_, T> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            CoreTypeUse::Ref(r) => core_item_ref(out, "type", r),
+            CoreTypeUse::Inline(ty) => ty.print(out),
+        }
+    }
+}
+
+impl<T: Print> Print for ComponentTypeUse<'_, T> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            ComponentTypeUse::Ref(r) => item_ref(out, "type", r),
+            ComponentTypeUse::Inline(ty) => ty.print(out),
+        }
+    }
+}
+
+impl Print for CoreModule<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("core module");
+        self.id.print(out);
+        self.name.print(out);
+        self.exports.print(out);
+        match &self.kind {
+            CoreModuleKind::Import { import, ty } => {
+                import.print(out);
+                ty.print(out);
+            }
+            CoreModuleKind::Inline { fields } => {
+                out.depth += 1;
+                for field in fields {
+                    out.newline();
+                    field.print(out);
+                }
+                out.depth -= 1;
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for CoreInstance<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("core instance");
+        self.id.print(out);
+        self.name.print(out);
+        match &self.kind {
+            CoreInstanceKind::Instantiate { module, args } => {
+                out.open("instantiate");
+                index_or_ref(out, "module", module);
+                for arg in args {
+                    out.open("with");
+                    out.string(arg.name.as_bytes());
+                    match &arg.kind {
+                        CoreInstantiationArgKind::Instance(i) => core_item_ref(out, "instance", i),
+                        CoreInstantiationArgKind::BundleOfExports(_, exports) => {
+                            out.open("instance");
+                            exports.print(out);
+                            out.close();
+                        }
+                    }
+                    out.close();
+                }
+                out.close();
+            }
+            CoreInstanceKind::BundleOfExports(exports) => exports.print(out),
+        }
+        out.close();
+    }
+}
+
+impl Print for CoreInstanceExport<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("export");
+        out.string(self.name.as_bytes());
+        core_item_ref(out, core_export_kind(self.item.kind), &self.item);
+        out.close();
+    }
+}
+
+impl Print for CoreType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("core type");
+        self.id.print(out);
+        self.name.print(out);
+        match &self.def {
+            CoreTypeDef::Def(def) => def.print(out),
+            CoreTypeDef::Module(ty) => {
+                out.open("module");
+                ty.print(out);
+                out.close();
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for ModuleType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.depth += 1;
+        for decl in self.decls.iter() {
+            out.newline();
+            match decl {
+                ModuleTypeDecl::Type(ty) => ty.print(out),
+                // Only outer aliases of core types can appear here, and they
+                // are written without the `core` prefix.
+                ModuleTypeDecl::Alias(alias) => match &alias.target {
+                    AliasTarget::Outer { outer, index, .. } => {
+                        out.open("alias outer");
+                        outer.print(out);
+                        index.print(out);
+                        out.open("type");
+                        alias.id.print(out);
+                        alias.name.print(out);
+                        out.close();
+                        out.close();
+                    }
+                    _ => alias.print(out),
+                },
+                ModuleTypeDecl::Import(import) => import.print(out),
+                ModuleTypeDecl::Export(name, sig) => {
+                    out.open("export");
+                    out.string(name.as_bytes());
+                    sig.print(out);
+                    out.close();
+                }
+            }
+        }
+        out.depth -= 1;
+    }
+}
+
+impl Print for NestedComponent<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("component");
+        self.id.print(out);
+        self.name.print(out);
+        self.exports.print(out);
+        match &self.kind {
+            NestedComponentKind::Import { import, ty } => {
+                import.print(out);
+                ty.print(out);
+            }
+            NestedComponentKind::Inline(fields) => component_fields(out, fields),
+        }
+        out.close();
+    }
+}
+
+impl Print for Instance<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("instance");
+        self.id.print(out);
+        self.name.print(out);
+        self.exports.print(out);
+        match &self.kind {
+            InstanceKind::Import { import, ty } => {
+                import.print(out);
+                ty.print(out);
+            }
+            InstanceKind::Instantiate { component, args } => {
+                out.open("instantiate");
+                index_or_ref(out, "component", component);
+                for arg in args {
+                    out.open("with");
+                    out.string(arg.name.as_bytes());
+                    match &arg.kind {
+                        InstantiationArgKind::Item(item) => item.print(out),
+                        InstantiationArgKind::BundleOfExports(_, exports) => {
+                            out.open("instance");
+                            exports.print(out);
+                            out.close();
+                        }
+                    }
+                    out.close();
+                }
+                out.close();
+            }
+            InstanceKind::BundleOfExports(exports) => exports.print(out),
+        }
+        out.close();
+    }
+}
+
+impl Print for Alias<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("alias");
+        let kind = match &self.target {
+            AliasTarget::Export {
+                instance,
+                name,
+                kind,
+            } => {
+                out.word("export");
+                instance.print(out);
+                out.string(name.as_bytes());
+                match kind {
+                    ComponentExportAliasKind::CoreModule => "core module",
+                    ComponentExportAliasKind::Func => "func",
+                    ComponentExportAliasKind::Value => "value",
+                    ComponentExportAliasKind::Type => "type",
+                    ComponentExportAliasKind::Component => "component",
+                    ComponentExportAliasKind::Instance => "instance",
+                }
+            }
+            AliasTarget::CoreExport {
+                instance,
+                name,
+                kind,
+            } => {
+                out.word("core export");
+                instance.print(out);
+                out.string(name.as_bytes());
+                match kind {
+                    core::ExportKind::Func => "core func",
+                    core::ExportKind::Table => "core table",
+                    core::ExportKind::Memory => "core memory",
+                    core::ExportKind::Global => "core global",
+                    core::ExportKind::Tag => "core tag",
+                }
+            }
+            AliasTarget::Outer { outer, index, kind } => {
+                out.word("outer");
+                outer.print(out);
+                index.print(out);
+                match kind {
+                    ComponentOuterAliasKind::CoreModule => "core module",
+                    ComponentOuterAliasKind::CoreType => "core type",
+                    ComponentOuterAliasKind::Type => "type",
+                    ComponentOuterAliasKind::Component => "component",
+                }
+            }
+        };
+        out.open(kind);
+        self.id.print(out);
+        self.name.print(out);
+        out.close();
+        out.close();
+    }
+}
+
+impl Print for Type<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("type");
+        self.id.print(out);
+        self.name.print(out);
+        self.exports.print(out);
+        self.def.print(out);
+        out.close();
+    }
+}
+
+impl Print for TypeDef<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            TypeDef::Defined(ty) => ty.print(out),
+            TypeDef::Func(ty) => {
+                out.open("func");
+                ty.print(out);
+                out.close();
+            }
+            TypeDef::Component(ty) => {
+                out.open("component");
+                ty.print(out);
+                out.close();
+            }
+            TypeDef::Instance(ty) => {
+                out.open("instance");
+                ty.print(out);
+                out.close();
+            }
+        }
+    }
+}
+
+impl Print for PrimitiveValType {
+    fn print(&self, out: &mut Output<'_>) {
+        out.word(match self {
+            PrimitiveValType::Bool => "bool",
+            PrimitiveValType::S8 => "s8",
+            PrimitiveValType::U8 => "u8",
+            PrimitiveValType::S16 => "s16",
+            PrimitiveValType::U16 => "u16",
+            PrimitiveValType::S32 => "s32",
+            PrimitiveValType::U32 => "u32",
+            PrimitiveValType::S64 => "s64",
+            PrimitiveValType::U64 => "u64",
+            PrimitiveValType::Float32 => "float32",
+            PrimitiveValType::Float64 => "float64",
+            PrimitiveValType::Char => "char",
+            PrimitiveValType::String => "string",
+        });
+    }
+}
+
+impl Print for ComponentValType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            ComponentValType::Inline(ty) => ty.print(out),
+            ComponentValType::Ref(idx) => idx.print(out),
+        }
+    }
+}
+
+/// Prints a list of names, such as the cases of an enum.
+fn names(out: &mut Output<'_>, names: &[&str]) {
+    for name in names {
+        out.string(name.as_bytes());
+    }
+}
+
+impl Print for ComponentDefinedType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            ComponentDefinedType::Primitive(ty) => return ty.print(out),
+            ComponentDefinedType::Record(r) => {
+                out.open("record");
+                for field in r.fields.iter() {
+                    out.open("field");
+                    out.string(field.name.as_bytes());
+                    field.ty.print(out);
+                    out.close();
+                }
+            }
+            ComponentDefinedType::Variant(v) => {
+                out.open("variant");
+                for case in v.cases.iter() {
+                    out.open("case");
+                    case.id.print(out);
+                    out.string(case.name.as_bytes());
+                    case.ty.print(out);
+                    if let Some(refines) = &case.refines {
+                        out.open("refines");
+                        match refines {
+                            Refinement::Index(_, idx) => idx.print(out),
+                            Refinement::Resolved(idx) => out.display(idx),
+                        }
+                        out.close();
+                    }
+                    out.close();
+                }
+            }
+            ComponentDefinedType::List(l) => {
+                out.open("list");
+                l.element.print(out);
+            }
+            ComponentDefinedType::Tuple(t) => {
+                out.open("tuple");
+                t.fields.print(out);
+            }
+            ComponentDefinedType::Flags(f) => {
+                out.open("flags");
+                names(out, &f.names);
+            }
+            ComponentDefinedType::Enum(e) => {
+                out.open("enum");
+                names(out, &e.names);
+            }
+            ComponentDefinedType::Union(u) => {
+                out.open("union");
+                u.types.print(out);
+            }
+            ComponentDefinedType::Option(o) => {
+                out.open("option");
+                o.element.print(out);
+            }
+            ComponentDefinedType::Result(r) => {
+                out.open("result");
+                if let Some(ok) = &r.ok {
+                    ok.print(out);
+                }
+                if let Some(err) = &r.err {
+                    out.open("error");
+                    err.print(out);
+                    out.close();
+                }
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for ComponentFunctionType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        for param in self.params.iter() {
+            out.open("param");
+            out.string(param.name.as_bytes());
+            param.ty.print(out);
+            out.close();
+        }
+        for result in self.results.iter() {
+            out.open("result");
+            if let Some(name) = result.name {
+                out.string(name.as_bytes());
+            }
+            result.ty.print(out);
+            out.close();
+        }
+    }
+}
+
+impl Print for ComponentType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.depth += 1;
+        for decl in self.decls.iter() {
+            out.newline();
+            match decl {
+                ComponentTypeDecl::CoreType(t) => t.print(out),
+                ComponentTypeDecl::Type(t) => t.print(out),
+                ComponentTypeDecl::Alias(a) => a.print(out),
+                ComponentTypeDecl::Import(i) => i.print(out),
+                ComponentTypeDecl::Export(e) => e.print(out),
+            }
+        }
+        out.depth -= 1;
+    }
+}
+
+impl Print for InstanceType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.depth += 1;
+        for decl in self.decls.iter() {
+            out.newline();
+            match decl {
+                InstanceTypeDecl::CoreType(t) => t.print(out),
+                InstanceTypeDecl::Type(t) => t.print(out),
+                InstanceTypeDecl::Alias(a) => a.print(out),
+                InstanceTypeDecl::Export(e) => e.print(out),
+            }
+        }
+        out.depth -= 1;
+    }
+}
+
+impl Print for ComponentExportType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("export");
+        out.string(self.name.as_bytes());
+        if let Some(url) = self.url {
+            out.string(url.as_bytes());
+        }
+        self.item.print(out);
+        out.close();
+    }
+}
+
+// The text format only has the inline forms of canonical functions, so they
+// are printed as a `func` or `core func`.
+impl Print for CanonicalFunc<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match &self.kind {
+            CanonicalFuncKind::Lift { ty, info } => {
+                out.open("func");
+                self.id.print(out);
+                self.name.print(out);
+                ty.print(out);
+                out.open("canon");
+                info.print(out);
+                out.close();
+            }
+            CanonicalFuncKind::Lower(info) => {
+                out.open("core func");
+                self.id.print(out);
+                self.name.print(out);
+                out.open("canon");
+                info.print(out);
+                out.close();
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for CanonLift<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.word("lift");
+        core_item_ref(out, "core func", &self.func);
+        self.opts.print(out);
+    }
+}
+
+impl Print for CanonLower<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.word("lower");
+        item_ref(out, "func", &self.func);
+        self.opts.print(out);
+    }
+}
+
+impl Print for CanonOpt<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            CanonOpt::StringUtf8 => out.word("string-encoding=utf8"),
+            CanonOpt::StringUtf16 => out.word("string-encoding=utf16"),
+            CanonOpt::StringLatin1Utf16 => out.word("string-encoding=latin1+utf16"),
+            CanonOpt::Memory(m) => {
+                out.open("memory");
+                m.idx.print(out);
+                if let Some(name) = m.export_name {
+                    out.string(name.as_bytes());
+                }
+                out.close();
+            }
+            CanonOpt::Realloc(f) => {
+                out.open("realloc");
+                index_or_core_ref(out, "func", f);
+                out.close();
+            }
+            CanonOpt::PostReturn(f) => {
+                out.open("post-return");
+                index_or_core_ref(out, "func", f);
+                out.close();
+            }
+        }
+    }
+}
+
+impl Print for CoreFunc<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("core func");
+        self.id.print(out);
+        self.name.print(out);
+        match &self.kind {
+            CoreFuncKind::Lower(info) => {
+                out.open("canon");
+                info.print(out);
+                out.close();
+            }
+            CoreFuncKind::Alias(alias) => {
+                out.open("alias core export");
+                alias.instance.print(out);
+                out.string(alias.name.as_bytes());
+                out.close();
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for Func<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("func");
+        self.id.print(out);
+        self.name.print(out);
+        self.exports.print(out);
+        match &self.kind {
+            FuncKind::Import { import, ty } => {
+                import.print(out);
+                ty.print(out);
+            }
+            FuncKind::Lift { ty, info } => {
+                ty.print(out);
+                out.open("canon");
+                info.print(out);
+                out.close();
+            }
+            FuncKind::Alias(alias) => {
+                out.open("alias export");
+                alias.instance.print(out);
+                out.string(alias.name.as_bytes());
+                out.close();
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for Start<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("start");
+        self.func.print(out);
+        for arg in self.args.iter() {
+            item_ref(out, "value", arg);
+        }
+        for result in self.results.iter() {
+            out.open("result");
+            out.open("value");
+            result.print(out);
+            out.close();
+            out.close();
+        }
+        out.close();
+    }
+}
+
+impl Print for ComponentImport<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("import");
+        out.string(self.name.as_bytes());
+        if let Some(url) = self.url {
+            out.string(url.as_bytes());
+        }
+        self.item.print(out);
+        out.close();
+    }
+}
+
+impl Print for ItemSig<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        let (keyword, ty): (_, &dyn Print) = match &self.kind {
+            ItemSigKind::CoreModule(ty) => ("core module", ty),
+            ItemSigKind::Func(ty) => ("func", ty),
+            ItemSigKind::Component(ty) => ("component", ty),
+            ItemSigKind::Instance(ty) => ("instance", ty),
+            ItemSigKind::Value(ty) => ("value", ty),
+            ItemSigKind::Type(ty) => ("type", ty),
+        };
+        out.open(keyword);
+        self.id.print(out);
+        self.name.print(out);
+        ty.print(out);
+        out.close();
+    }
+}
+
+impl Print for ComponentValTypeUse<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match &self.0 {
+            ComponentValType::Ref(idx) => {
+                out.open("type");
+                idx.print(out);
+                out.close();
+            }
+            ComponentValType::Inline(ty) => ty.print(out),
+        }
+    }
+}
+
+impl Print for TypeBounds<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            TypeBounds::Eq(idx) => {
+                out.open("eq");
+                idx.print(out);
+                out.close();
+            }
+        }
+    }
+}
+
+impl Print for ComponentExport<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("export");
+        self.id.print(out);
+        self.debug_name.print(out);
+        out.string(self.name.as_bytes());
+        if let Some(url) = self.url {
+            out.string(url.as_bytes());
+        }
+        self.kind.print(out);
+        if let Some(ty) = &self.ty {
+            ty.0.print(out);
+        }
+        out.close();
+    }
+}
+
+impl Print for ComponentExportKind<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            ComponentExportKind::CoreModule(r) => item_ref(out, "core module", r),
+            ComponentExportKind::Func(r) => item_ref(out, "func", r),
+            ComponentExportKind::Value(r) => item_ref(out, "value", r),
+            ComponentExportKind::Type(r) => item_ref(out, "type", r),
+            ComponentExportKind::Component(r) => item_ref(out, "component", r),
+            ComponentExportKind::Instance(r) => item_ref(out, "instance", r),
+        }
+    }
+}
+
+impl Print for Custom<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("@custom");
+        out.string(self.name.as_bytes());
+        for data in self.data.iter() {
+            out.string(data);
+        }
+        out.close();
+    }
+}
@@ -5,16 +5,73 @@ use crate::names::Namespace;
 use crate::token::Span;
 use crate::token::{Id, Index};
 use crate::Error;
+use std::collections::HashMap;
 
 /// Resolve the fields of a component and everything nested within it, changing
 /// `Index::Id` to `Index::Num` and expanding alias syntax sugar.
-pub fn resolve(component: &mut Component<'_>) -> Result<(), Error> {
+pub fn resolve<'a>(component: &mut Component<'a>) -> Result<ComponentNames<'a>, Error> {
     let fields = match &mut component.kind {
         ComponentKind::Text(fields) => fields,
-        ComponentKind::Binary(_) => return Ok(()),
+        ComponentKind::Binary(_) => return Ok(Default::default()),
     };
     let mut resolver = Resolver::default();
-    resolver.fields(component.id, fields)
+    let state = resolver.fields(component.id, fields)?;
+    Ok(ComponentNames { state })
+}
+
+/// Representation of the results of name resolution for a component.
+///
+/// This structure is returned from the
+/// [`Component::resolve`](crate::component::Component::resolve) function and
+/// can be used to look up the indices of the names defined directly within
+/// the component. Names within nested components and core modules aren't
+/// included.
+#[derive(Default)]
+pub struct ComponentNames<'a> {
+    state: ComponentState<'a>,
+}
+
+impl<'a> ComponentNames<'a> {
+    /// Resolves `idx` within the namespace of items of `kind`, returning the
+    /// index it refers to.
+    ///
+    /// If `idx` is a `Num`, it is left as is, but if it's an `Id` then it will
+    /// be looked up in the namespace and converted to a `Num`. If the `Id` is
+    /// not defined then an error will be returned.
+    pub fn resolve(&self, idx: &mut Index<'a>, kind: ComponentNameKind) -> Result<u32, Error> {
+        let (ns, desc) = self.state.namespace(kind.into());
+        ns.resolve(idx, desc)
+    }
+
+    /// Returns the index of the item of `kind` named `$name`, or `None` if
+    /// there's no such item.
+    ///
+    /// The `name` is given without its leading `$`.
+    pub fn index_of(&self, name: &str, kind: ComponentNameKind) -> Option<u32> {
+        self.state.namespace(kind.into()).0.get(name)
+    }
+}
+
+/// The kinds of items in a component which can be named, each of which has
+/// its own index space.
+///
+/// This is used to look up names with [`ComponentNames`].
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[allow(missing_docs)]
+pub enum ComponentNameKind {
+    CoreFunc,
+    CoreGlobal,
+    CoreTable,
+    CoreMemory,
+    CoreType,
+    CoreTag,
+    CoreInstance,
+    CoreModule,
+    Func,
+    Type,
+    Instance,
+    Component,
+    Value,
 }
 
 impl<'a> From<Alias<'a>> for ComponentField<'a> {
@@ -74,6 +131,18 @@ struct ComponentState<'a> {
     instances: Namespace<'a>,
     components: Namespace<'a>,
     values: Namespace<'a>,
+
+    // Values must be used at most once, so this records where each value
+    // index has been used so far. Exported values are recorded as used by
+    // their export since the new index can't be used again either.
+    value_uses: HashMap<u32, Span>,
+
+    // Where each value index of this component was defined, used to report
+    // values which are never used once the component has been resolved.
+    value_defs: Vec<Span>,
+
+    // Where the start function of this component is, if one has been seen.
+    start: Option<Span>,
 }
 
 impl<'a> ComponentState<'a> {
@@ -103,15 +172,32 @@ impl<'a> Resolver<'a> {
             .expect("should have at least one component state")
     }
 
+    /// Resolves the fields of a component, returning the state of its
+    /// namespaces afterwards.
     fn fields(
         &mut self,
         id: Option<Id<'a>>,
         fields: &mut Vec<ComponentField<'a>>,
-    ) -> Result<(), Error> {
+    ) -> Result<ComponentState<'a>, Error> {
         self.stack.push(ComponentState::new(id));
         self.resolve_prepending_aliases(fields, Resolver::field, ComponentState::register)?;
-        self.stack.pop();
-        Ok(())
+        let state = self.stack.pop().unwrap();
+
+        // Values must also be used at least once, which can only be checked
+        // now that all of the component's fields have been seen.
+        for (index, span) in state.value_defs.iter().enumerate() {
+            let index = index as u32;
+            if !state.value_uses.contains_key(&index) {
+                return Err(Error::new(
+                    *span,
+                    format!(
+                        "value index {index} was not used as part of an \
+                         instantiation, start function, or export"
+                    ),
+                ));
+            }
+        }
+        Ok(state)
     }
 
     fn resolve_prepending_aliases<T>(
@@ -170,7 +256,7 @@ impl<'a> Resolver<'a> {
                 }
                 self.export(&mut e.kind)
             }
-            ComponentField::Custom(_) => Ok(()),
+            ComponentField::Custom(_) | ComponentField::Producers(_) => Ok(()),
         }
     }
 
@@ -191,7 +277,10 @@ impl<'a> Resolver<'a> {
     fn component(&mut self, component: &mut NestedComponent<'a>) -> Result<(), Error> {
         match &mut component.kind {
             NestedComponentKind::Import { .. } => unreachable!("should be expanded already"),
-            NestedComponentKind::Inline(fields) => self.fields(component.id, fields),
+            NestedComponentKind::Inline(fields) => {
+                self.fields(component.id, fields)?;
+                Ok(())
+            }
         }
     }
 
@@ -266,7 +355,10 @@ impl<'a> Resolver<'a> {
             // Here we do *not* have to be explicit as the item ref is to a core module
             ComponentExportKind::CoreModule(r) => self.component_item_ref(r),
             ComponentExportKind::Func(r) => self.component_item_ref(r),
-            ComponentExportKind::Value(r) => self.component_item_ref(r),
+            ComponentExportKind::Value(r) => {
+                self.component_item_ref(r)?;
+                self.use_value(&r.idx)
+            }
             ComponentExportKind::Type(r) => self.component_item_ref(r),
             ComponentExportKind::Component(r) => self.component_item_ref(r),
             ComponentExportKind::Instance(r) => self.component_item_ref(r),
@@ -274,13 +366,39 @@ impl<'a> Resolver<'a> {
     }
 
     fn start(&mut self, start: &mut Start<'a>) -> Result<(), Error> {
+        let span = start.func.span();
+        if let Some(prev) = self.current().start.replace(span) {
+            return Err(Error::new(
+                span,
+                "component cannot have more than one start function".to_string(),
+            )
+            .with_note(prev, "previous start function here".to_string()));
+        }
         self.resolve_ns(&mut start.func, Ns::Func)?;
         for arg in start.args.iter_mut() {
             self.component_item_ref(arg)?;
+            self.use_value(&arg.idx)?;
         }
         Ok(())
     }
 
+    /// Records a use of the already-resolved value index `idx`, returning an
+    /// error if the value has been used before.
+    fn use_value(&mut self, idx: &Index<'a>) -> Result<(), Error> {
+        let (n, span) = match *idx {
+            Index::Num(n, span) => (n, span),
+            Index::Id(_) => unreachable!("value index should be resolved"),
+        };
+        match self.current().value_uses.insert(n, span) {
+            Some(prev) => Err(Error::new(
+                span,
+                format!("value {} cannot be used more than once", n),
+            )
+            .with_note(prev, format!("value {} previously used here", n))),
+            None => Ok(()),
+        }
+    }
+
     fn outer_alias<T: Into<Ns>>(
         &mut self,
         outer: &mut Index<'a>,
@@ -757,20 +875,26 @@ impl<'a> Resolver<'a> {
 
 impl<'a> ComponentState<'a> {
     fn resolve(&mut self, ns: Ns, idx: &mut Index<'a>) -> Result<u32, Error> {
+        let (names, desc) = self.namespace(ns);
+        names.resolve(idx, desc)
+    }
+
+    /// Returns the namespace `ns` and its description.
+    fn namespace(&self, ns: Ns) -> (&Namespace<'a>, &'static str) {
         match ns {
-            Ns::CoreFunc => self.core_funcs.resolve(idx, "core func"),
-            Ns::CoreGlobal => self.core_globals.resolve(idx, "core global"),
-            Ns::CoreTable => self.core_tables.resolve(idx, "core table"),
-            Ns::CoreMemory => self.core_memories.resolve(idx, "core memory"),
-            Ns::CoreType => self.core_types.resolve(idx, "core type"),
-            Ns::CoreTag => self.core_tags.resolve(idx, "core tag"),
-            Ns::CoreInstance => self.core_instances.resolve(idx, "core instance"),
-            Ns::CoreModule => self.core_modules.resolve(idx, "core module"),
-            Ns::Func => self.funcs.resolve(idx, "func"),
-            Ns::Type => self.types.resolve(idx, "type"),
-            Ns::Instance => self.instances.resolve(idx, "instance"),
-            Ns::Component => self.components.resolve(idx, "component"),
-            Ns::Value => self.values.resolve(idx, "value"),
+            Ns::CoreFunc => (&self.core_funcs, "core func"),
+            Ns::CoreGlobal => (&self.core_globals, "core global"),
+            Ns::CoreTable => (&self.core_tables, "core table"),
+            Ns::CoreMemory => (&self.core_memories, "core memory"),
+            Ns::CoreType => (&self.core_types, "core type"),
+            Ns::CoreTag => (&self.core_tags, "core tag"),
+            Ns::CoreInstance => (&self.core_instances, "core instance"),
+            Ns::CoreModule => (&self.core_modules, "core module"),
+            Ns::Func => (&self.funcs, "func"),
+            Ns::Type => (&self.types, "type"),
+            Ns::Instance => (&self.instances, "instance"),
+            Ns::Component => (&self.components, "component"),
+            Ns::Value => (&self.values, "value"),
         }
     }
 
@@ -784,7 +908,16 @@ impl<'a> ComponentState<'a> {
             ComponentField::CoreType(t) => self.core_types.register(t.id, "core type")?,
             ComponentField::Component(c) => self.components.register(c.id, "component")?,
             ComponentField::Instance(i) => self.instances.register(i.id, "instance")?,
-            ComponentField::Alias(a) => self.register_alias(a)?,
+            ComponentField::Alias(a) => {
+                if let AliasTarget::Export {
+                    kind: ComponentExportAliasKind::Value,
+                    ..
+                } = a.target
+                {
+                    self.value_defs.push(a.span);
+                }
+                self.register_alias(a)?
+            }
             ComponentField::Type(t) => self.types.register(t.id, "type")?,
             ComponentField::CanonicalFunc(f) => match &f.kind {
                 CanonicalFuncKind::Lift { .. } => self.funcs.register(f.id, "func")?,
@@ -796,21 +929,32 @@ impl<'a> ComponentState<'a> {
             ComponentField::Start(s) => {
                 for r in &s.results {
                     self.values.register(*r, "value")?;
+                    self.value_defs.push(s.func.span());
                 }
                 return Ok(());
             }
-            ComponentField::Import(i) => self.register_item_sig(&i.item)?,
+            ComponentField::Import(i) => {
+                if let ItemSigKind::Value(_) = i.item.kind {
+                    self.value_defs.push(i.span);
+                }
+                self.register_item_sig(&i.item)?
+            }
             ComponentField::Export(e) => match &e.kind {
                 ComponentExportKind::CoreModule(_) => {
                     self.core_modules.register(e.id, "core module")?
                 }
                 ComponentExportKind::Func(_) => self.funcs.register(e.id, "func")?,
                 ComponentExportKind::Instance(_) => self.instances.register(e.id, "instance")?,
-                ComponentExportKind::Value(_) => self.values.register(e.id, "value")?,
+                ComponentExportKind::Value(_) => {
+                    let index = self.values.register(e.id, "value")?;
+                    self.value_uses.insert(index, e.span);
+                    self.value_defs.push(e.span);
+                    index
+                }
                 ComponentExportKind::Component(_) => self.components.register(e.id, "component")?,
                 ComponentExportKind::Type(_) => self.types.register(e.id, "type")?,
             },
-            ComponentField::Custom(_) => return Ok(()),
+            ComponentField::Custom(_) | ComponentField::Producers(_) => return Ok(()),
         };
 
         Ok(())
@@ -910,6 +1054,26 @@ core_item!(kw::memory, CoreMemory);
 core_item!(kw::r#type, CoreType);
 core_item!(kw::r#instance, CoreInstance);
 
+impl From<ComponentNameKind> for Ns {
+    fn from(kind: ComponentNameKind) -> Self {
+        match kind {
+            ComponentNameKind::CoreFunc => Self::CoreFunc,
+            ComponentNameKind::CoreGlobal => Self::CoreGlobal,
+            ComponentNameKind::CoreTable => Self::CoreTable,
+            ComponentNameKind::CoreMemory => Self::CoreMemory,
+            ComponentNameKind::CoreType => Self::CoreType,
+            ComponentNameKind::CoreTag => Self::CoreTag,
+            ComponentNameKind::CoreInstance => Self::CoreInstance,
+            ComponentNameKind::CoreModule => Self::CoreModule,
+            ComponentNameKind::Func => Self::Func,
+            ComponentNameKind::Type => Self::Type,
+            ComponentNameKind::Instance => Self::Instance,
+            ComponentNameKind::Component => Self::Component,
+            ComponentNameKind::Value => Self::Value,
+        }
+    }
+}
+
 impl From<Ns> for ComponentExportAliasKind {
     fn from(ns: Ns) -> Self {
         match ns {
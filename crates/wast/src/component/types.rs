@@ -10,6 +10,7 @@ use crate::token::{Id, NameAnnotation, Span};
 
 /// A core type declaration.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CoreType<'a> {
     /// Where this type was defined.
     pub span: Span,
@@ -44,6 +45,7 @@ impl<'a> Parse<'a> for CoreType<'a> {
 /// In the future this may be removed when module types are a part of
 /// a core module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CoreTypeDef<'a> {
     /// The type definition is one of the core types.
     Def(core::TypeDef<'a>),
@@ -64,6 +66,7 @@ impl<'a> Parse<'a> for CoreTypeDef<'a> {
 
 /// A type definition for a core module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ModuleType<'a> {
     /// The declarations of the module type.
     pub decls: Vec<ModuleTypeDecl<'a>>,
@@ -80,6 +83,7 @@ impl<'a> Parse<'a> for ModuleType<'a> {
 
 /// The declarations of a [`ModuleType`].
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ModuleTypeDecl<'a> {
     /// A core type.
     Type(core::Type<'a>),
@@ -123,6 +127,7 @@ impl<'a> Parse<'a> for Vec<ModuleTypeDecl<'a>> {
 
 /// A type declaration in a component.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Type<'a> {
     /// Where this type was defined.
     pub span: Span,
@@ -158,6 +163,7 @@ impl<'a> Parse<'a> for Type<'a> {
 
 /// A definition of a component type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TypeDef<'a> {
     /// A defined value type.
     Defined(ComponentDefinedType<'a>),
@@ -200,7 +206,8 @@ impl<'a> Parse<'a> for TypeDef<'a> {
 
 /// A primitive value type.
 #[allow(missing_docs)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum PrimitiveValType {
     Bool,
     S8,
@@ -293,6 +300,7 @@ impl Peek for PrimitiveValType {
 /// A component value type.
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentValType<'a> {
     /// The value type is an inline defined type.
     Inline(ComponentDefinedType<'a>),
@@ -325,6 +333,7 @@ impl Peek for ComponentValType<'_> {
 /// This variation does not parse type indexes.
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct InlineComponentValType<'a>(ComponentDefinedType<'a>);
 
 impl<'a> Parse<'a> for InlineComponentValType<'a> {
@@ -345,6 +354,7 @@ impl<'a> Parse<'a> for InlineComponentValType<'a> {
 // A component defined type.
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentDefinedType<'a> {
     Primitive(PrimitiveValType),
     Record(Record<'a>),
@@ -421,6 +431,7 @@ impl Peek for ComponentDefinedType<'_> {
 
 /// A record defined type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Record<'a> {
     /// The fields of the record.
     pub fields: Vec<RecordField<'a>>,
@@ -439,6 +450,7 @@ impl<'a> Parse<'a> for Record<'a> {
 
 /// A record type field.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RecordField<'a> {
     /// The name of the field.
     pub name: &'a str,
@@ -458,6 +470,7 @@ impl<'a> Parse<'a> for RecordField<'a> {
 
 /// A variant defined type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Variant<'a> {
     /// The cases of the variant type.
     pub cases: Vec<VariantCase<'a>>,
@@ -476,6 +489,7 @@ impl<'a> Parse<'a> for Variant<'a> {
 
 /// A case of a variant type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct VariantCase<'a> {
     /// Where this `case` was defined
     pub span: Span,
@@ -513,6 +527,7 @@ impl<'a> Parse<'a> for VariantCase<'a> {
 
 /// A refinement for a variant case.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Refinement<'a> {
     /// The refinement is referenced by index.
     Index(Span, Index<'a>),
@@ -533,6 +548,7 @@ impl<'a> Parse<'a> for Refinement<'a> {
 
 /// A list type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct List<'a> {
     /// The element type of the array.
     pub element: Box<ComponentValType<'a>>,
@@ -549,6 +565,7 @@ impl<'a> Parse<'a> for List<'a> {
 
 /// A tuple type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Tuple<'a> {
     /// The types of the fields of the tuple.
     pub fields: Vec<ComponentValType<'a>>,
@@ -567,6 +584,7 @@ impl<'a> Parse<'a> for Tuple<'a> {
 
 /// A flags type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Flags<'a> {
     /// The names of the individual flags.
     pub names: Vec<&'a str>,
@@ -585,6 +603,7 @@ impl<'a> Parse<'a> for Flags<'a> {
 
 /// An enum type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Enum<'a> {
     /// The tag names of the enum.
     pub names: Vec<&'a str>,
@@ -603,6 +622,7 @@ impl<'a> Parse<'a> for Enum<'a> {
 
 /// A union type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Union<'a> {
     /// The types of the union.
     pub types: Vec<ComponentValType<'a>>,
@@ -621,6 +641,7 @@ impl<'a> Parse<'a> for Union<'a> {
 
 /// An optional type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct OptionType<'a> {
     /// The type of the value, when a value is present.
     pub element: Box<ComponentValType<'a>>,
@@ -637,6 +658,7 @@ impl<'a> Parse<'a> for OptionType<'a> {
 
 /// A result type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ResultType<'a> {
     /// The type on success.
     pub ok: Option<Box<ComponentValType<'a>>>,
@@ -667,6 +689,7 @@ impl<'a> Parse<'a> for ResultType<'a> {
 
 /// A component function type with parameters and result.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentFunctionType<'a> {
     /// The parameters of a function, optionally each having an identifier for
     /// name resolution and a name for the custom `name` section.
@@ -697,6 +720,7 @@ impl<'a> Parse<'a> for ComponentFunctionType<'a> {
 
 /// A parameter of a [`ComponentFunctionType`].
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentFunctionParam<'a> {
     /// The name of the parameter
     pub name: &'a str,
@@ -716,6 +740,7 @@ impl<'a> Parse<'a> for ComponentFunctionParam<'a> {
 
 /// A result of a [`ComponentFunctionType`].
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentFunctionResult<'a> {
     /// An optionally-specified name of this result
     pub name: Option<&'a str>,
@@ -735,6 +760,7 @@ impl<'a> Parse<'a> for ComponentFunctionResult<'a> {
 
 /// The type of an exported item from an component or instance type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentExportType<'a> {
     /// Where this export was defined.
     pub span: Span,
@@ -749,14 +775,27 @@ pub struct ComponentExportType<'a> {
 impl<'a> Parse<'a> for ComponentExportType<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let span = parser.parse::<kw::export>()?.0;
-        let id = parser.parse()?;
-        let debug_name = parser.parse()?;
+        let id: Option<Id> = parser.parse()?;
+        let debug_name: Option<NameAnnotation> = parser.parse()?;
         let name = parser.parse()?;
         let url = parser.parse()?;
+        // The identifier of the exported item may be written either before
+        // the export's name or within the item's signature, as in
+        // `(export "t" (type $t (eq 0)))`, but not both.
         let item = parser.parens(|p| {
-            let mut item = p.parse::<ItemSigNoName<'_>>()?.0;
-            item.id = id;
-            item.name = debug_name;
+            let mut item = p.parse::<ItemSig<'_>>()?;
+            if id.is_some() {
+                if item.id.is_some() {
+                    return Err(p.error_at(item.span, "export has more than one identifier"));
+                }
+                item.id = id;
+            }
+            if debug_name.is_some() {
+                if item.name.is_some() {
+                    return Err(p.error_at(item.span, "export has more than one name annotation"));
+                }
+                item.name = debug_name;
+            }
             Ok(item)
         })?;
         Ok(Self {
@@ -770,6 +809,7 @@ impl<'a> Parse<'a> for ComponentExportType<'a> {
 
 /// A type definition for a component type.
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentType<'a> {
     /// The declarations of the component type.
     pub decls: Vec<ComponentTypeDecl<'a>>,
@@ -786,6 +826,7 @@ impl<'a> Parse<'a> for ComponentType<'a> {
 
 /// A declaration of a component type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentTypeDecl<'a> {
     /// A core type definition local to the component type.
     CoreType(CoreType<'a>),
@@ -830,6 +871,7 @@ impl<'a> Parse<'a> for Vec<ComponentTypeDecl<'a>> {
 
 /// A type definition for an instance type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct InstanceType<'a> {
     /// The declarations of the instance type.
     pub decls: Vec<InstanceTypeDecl<'a>>,
@@ -846,6 +888,7 @@ impl<'a> Parse<'a> for InstanceType<'a> {
 
 /// A declaration of an instance type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum InstanceTypeDecl<'a> {
     /// A core type definition local to the component type.
     CoreType(CoreType<'a>),
@@ -886,6 +929,7 @@ impl<'a> Parse<'a> for Vec<InstanceTypeDecl<'a>> {
 
 /// A value type declaration used for values in import signatures.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ComponentValTypeUse<'a>(pub ComponentValType<'a>);
 
 impl<'a> Parse<'a> for ComponentValTypeUse<'a> {
@@ -902,6 +946,7 @@ impl<'a> Parse<'a> for ComponentValTypeUse<'a> {
 /// This is the same as `TypeUse`, but accepts `$T` as shorthand for
 /// `(type $T)`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CoreTypeUse<'a, T> {
     /// The type that we're referencing.
     Ref(CoreItemRef<'a, kw::r#type>),
@@ -936,6 +981,7 @@ impl<T> Default for CoreTypeUse<'_, T> {
 /// This is the same as `TypeUse`, but accepts `$T` as shorthand for
 /// `(type $T)`.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ComponentTypeUse<'a, T> {
     /// The type that we're referencing.
     Ref(ItemRef<'a, kw::r#type>),
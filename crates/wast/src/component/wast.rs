@@ -5,6 +5,7 @@ use crate::token::{Float32, Float64};
 /// Expression that can be used inside of `invoke` expressions for core wasm
 /// functions.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum WastVal<'a> {
     Bool(bool),
@@ -8,10 +8,13 @@ mod global;
 mod import;
 mod memory;
 mod module;
+mod source_map;
 mod table;
 mod tag;
 mod types;
+mod visit;
 mod wast;
+pub use self::binary::Offsets;
 pub use self::custom::*;
 pub use self::export::*;
 pub use self::expr::*;
@@ -20,10 +23,15 @@ pub use self::global::*;
 pub use self::import::*;
 pub use self::memory::*;
 pub use self::module::*;
+pub use self::print::Printer;
 pub use self::table::*;
 pub use self::tag::*;
 pub use self::types::*;
+pub use self::visit::*;
 pub use self::wast::*;
 
+pub(crate) use self::source_map::json_string;
+
 pub(crate) mod binary;
+pub(crate) mod print;
 pub(crate) mod resolve;
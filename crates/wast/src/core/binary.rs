@@ -1,12 +1,129 @@
 use crate::core::*;
-use crate::encode::Encode;
+use crate::encode::{check_size, Encode};
 use crate::token::*;
+use crate::Error;
+use std::io::{self, Write};
 
 pub fn encode(
+    module_span: Span,
     module_id: &Option<Id<'_>>,
     module_name: &Option<NameAnnotation<'_>>,
     fields: &[ModuleField<'_>],
-) -> Vec<u8> {
+) -> Result<Vec<u8>, Error> {
+    let mut wasm = Vec::new();
+    encode_into(module_span, module_id, module_name, fields, &mut wasm, None)?;
+    Ok(wasm)
+}
+
+/// Like `encode`, but appends the module to `wasm`.
+pub fn encode_into(
+    module_span: Span,
+    module_id: &Option<Id<'_>>,
+    module_name: &Option<NameAnnotation<'_>>,
+    fields: &[ModuleField<'_>],
+    wasm: &mut Vec<u8>,
+    offsets: Option<&mut Offsets>,
+) -> Result<(), Error> {
+    let base = wasm.len();
+    let output = Output::Vec { wasm, base };
+    match encode_fields(module_span, module_id, module_name, fields, output, offsets) {
+        Ok(()) => Ok(()),
+        Err(EncodeError::Wast(e)) => Err(e),
+        Err(EncodeError::Io(_)) => unreachable!("writing to a `Vec` cannot fail"),
+    }
+}
+
+/// An error from `encode_to`: either the module can't be encoded, or
+/// writing it out failed.
+pub enum EncodeError {
+    Wast(Error),
+    Io(io::Error),
+}
+
+impl From<Error> for EncodeError {
+    fn from(e: Error) -> EncodeError {
+        EncodeError::Wast(e)
+    }
+}
+
+impl From<io::Error> for EncodeError {
+    fn from(e: io::Error) -> EncodeError {
+        EncodeError::Io(e)
+    }
+}
+
+/// The offsets of items within the binary encoding of a module, returned by
+/// [`Module::encode_with_offsets`].
+///
+/// Each item is listed with the span of its text, and all offsets are
+/// relative to the start of the module's binary. This allows tools such as
+/// debuggers and source map generators to correlate code offsets with
+/// positions in the text.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Offsets {
+    /// The offset of the body of each function defined in the module, after
+    /// the body's size and starting with its locals, in the order of the
+    /// code section.
+    pub funcs: Vec<(Span, usize)>,
+    /// The offset of each instruction of the functions' bodies, in the order
+    /// they're encoded.
+    ///
+    /// This is only filled in for function bodies parsed with
+    /// [`ParseBuffer::track_instr_spans`](crate::parser::ParseBuffer::track_instr_spans)
+    /// enabled, and doesn't include the implicit `end` of each body.
+    pub instrs: Vec<(Span, usize)>,
+    /// The offset of each data segment, at its leading flags, in the order of
+    /// the data section.
+    pub data: Vec<(Span, usize)>,
+}
+
+/// Writes the module to `wasm`, filling in `offsets` if given.
+///
+/// The size of each section is computed before it's written by encoding its
+/// items one at a time, and then the items are encoded again as they're
+/// written out. Only the largest item of a section, such as a function body
+/// or data segment, is held in memory at once rather than a whole section.
+pub fn encode_to(
+    module_span: Span,
+    module_id: &Option<Id<'_>>,
+    module_name: &Option<NameAnnotation<'_>>,
+    fields: &[ModuleField<'_>],
+    wasm: &mut dyn Write,
+    offsets: Option<&mut Offsets>,
+) -> Result<(), EncodeError> {
+    let output = Output::Write {
+        wasm,
+        pos: 0,
+        scratch: Vec::new(),
+    };
+    encode_fields(module_span, module_id, module_name, fields, output, offsets)
+}
+
+/// Returns the id and contents of each section of the module, without the
+/// module's header or the sections' sizes.
+pub fn encode_sections(
+    module_span: Span,
+    module_id: &Option<Id<'_>>,
+    module_name: &Option<NameAnnotation<'_>>,
+    fields: &[ModuleField<'_>],
+) -> Result<Vec<(u8, Vec<u8>)>, Error> {
+    let mut sections = Vec::new();
+    let output = Output::Sections(&mut sections);
+    match encode_fields(module_span, module_id, module_name, fields, output, None) {
+        Ok(()) => Ok(sections),
+        Err(EncodeError::Wast(e)) => Err(e),
+        Err(EncodeError::Io(_)) => unreachable!("writing to a `Vec` cannot fail"),
+    }
+}
+
+fn encode_fields(
+    module_span: Span,
+    module_id: &Option<Id<'_>>,
+    module_name: &Option<NameAnnotation<'_>>,
+    fields: &[ModuleField<'_>],
+    output: Output<'_>,
+    offsets: Option<&mut Offsets>,
+) -> Result<(), EncodeError> {
     use CustomPlace::*;
     use CustomPlaceAnchor::*;
 
@@ -40,86 +157,363 @@ pub fn encode(
         }
     }
 
+    // A module has only one producers section, so all `@producers`
+    // annotations are merged into the first of them.
+    let merged;
+    let mut producers = customs.iter().enumerate().filter_map(|(i, c)| match c {
+        Custom::Producers(p) => Some((i, p)),
+        _ => None,
+    });
+    if let Some((first, p)) = producers.next() {
+        let mut all = p.clone();
+        for (_, p) in producers {
+            all.merge(p);
+        }
+        merged = Custom::Producers(all);
+        customs[first] = &merged;
+        let mut i = 0;
+        customs.retain(|c| {
+            i += 1;
+            i - 1 == first || !matches!(c, Custom::Producers(_))
+        });
+    }
+
     let mut e = Encoder {
-        wasm: Vec::new(),
-        tmp: Vec::new(),
+        output,
         customs: &customs,
+        span: module_span,
+        offsets,
     };
-    e.wasm.extend(b"\0asm");
-    e.wasm.extend(b"\x01\0\0\0");
+    e.write(b"\0asm")?;
+    e.write(b"\x01\0\0\0")?;
 
-    e.custom_sections(BeforeFirst);
+    e.custom_sections(BeforeFirst)?;
 
-    e.section_list(1, Type, &types);
-    e.section_list(2, Import, &imports);
+    e.section_list(1, Type, &types)?;
+    e.section_list(2, Import, &imports)?;
 
     let functys = funcs.iter().map(|f| &f.ty).collect::<Vec<_>>();
-    e.section_list(3, Func, &functys);
-    e.section_list(4, Table, &tables);
-    e.section_list(5, Memory, &memories);
-    e.section_list(13, Tag, &tags);
-    e.section_list(6, Global, &globals);
-    e.section_list(7, Export, &exports);
-    e.custom_sections(Before(Start));
+    e.section_list(3, Func, &functys)?;
+    e.section_list(4, Table, &tables)?;
+    e.section_list(5, Memory, &memories)?;
+    e.section_list(13, Tag, &tags)?;
+    e.section_list(6, Global, &globals)?;
+    e.section_list(7, Export, &exports)?;
+    e.custom_sections(Before(Start))?;
     if let Some(start) = start.get(0) {
-        e.section(8, start);
+        e.section(8, start)?;
+    }
+    e.custom_sections(After(Start))?;
+    e.section_list(9, Elem, &elem)?;
+    if needs_data_count(fields) {
+        e.section(12, &data.len())?;
     }
-    e.custom_sections(After(Start));
-    e.section_list(9, Elem, &elem);
-    if needs_data_count(&funcs) {
-        e.section(12, &data.len());
+    let num_import_funcs = imports
+        .iter()
+        .filter(|i| matches!(i.item.kind, ItemKind::Func(..)))
+        .count();
+    let branch_hints = BranchHints::new(num_import_funcs, &funcs);
+    if !branch_hints.0.is_empty() {
+        e.section(0, &("metadata.code.branch_hint", branch_hints))?;
     }
-    e.section_list(10, Code, &funcs);
-    e.section_list(11, Data, &data);
+    e.code_section(&funcs)?;
+    for data in data.iter() {
+        let len = data.data.iter().map(|l| l.len()).sum();
+        check_size(data.span, || "data segment".to_string(), len)?;
+    }
+    e.data_section(&data)?;
 
     let names = find_names(module_id, module_name, fields);
     if !names.is_empty() {
-        e.section(0, &("name", names));
+        e.section(0, &("name", names))?;
     }
-    e.custom_sections(AfterLast);
+    e.custom_sections(AfterLast)?;
 
-    return e.wasm;
+    return Ok(());
 
-    fn needs_data_count(funcs: &[&crate::core::Func<'_>]) -> bool {
-        funcs
+    /// Returns whether any expression in the module refers to a data
+    /// segment, in which case the data count section is required.
+    fn needs_data_count(fields: &[ModuleField<'_>]) -> bool {
+        let mut exprs = Vec::new();
+        for field in fields {
+            match field {
+                ModuleField::Func(f) => {
+                    if let FuncKind::Inline { expression, .. } = &f.kind {
+                        exprs.push(expression);
+                    }
+                }
+                ModuleField::Global(g) => {
+                    if let GlobalKind::Inline(expr) = &g.kind {
+                        exprs.push(expr);
+                    }
+                }
+                ModuleField::Table(t) => {
+                    if let TableKind::Normal {
+                        init_expr: Some(expr),
+                        ..
+                    } = &t.kind
+                    {
+                        exprs.push(expr);
+                    }
+                }
+                ModuleField::Elem(e) => {
+                    if let ElemKind::Active { offset, .. } = &e.kind {
+                        exprs.push(offset);
+                    }
+                    if let ElemPayload::Exprs { exprs: list, .. } = &e.payload {
+                        exprs.extend(list);
+                    }
+                }
+                ModuleField::Data(d) => {
+                    if let DataKind::Active { offset, .. } = &d.kind {
+                        exprs.push(offset);
+                    }
+                }
+                _ => {}
+            }
+        }
+        exprs
             .iter()
-            .filter_map(|f| match &f.kind {
-                FuncKind::Inline { expression, .. } => Some(expression),
-                _ => None,
-            })
             .flat_map(|e| e.instrs.iter())
             .any(|i| i.needs_data_count())
     }
 }
 
-struct Encoder<'a> {
-    wasm: Vec<u8>,
-    tmp: Vec<u8>,
+/// Where an `Encoder` writes the module to.
+enum Output<'a> {
+    /// Sections are encoded directly onto the end of `wasm`, which had `base`
+    /// bytes in it before the module, and the size of each section is
+    /// inserted in front of its contents once they've been encoded.
+    Vec { wasm: &'a mut Vec<u8>, base: usize },
+    /// Each section is encoded twice, one item at a time, first to compute
+    /// its size and then to write it out after its id and size. `pos` is the
+    /// number of bytes written to `wasm` so far.
+    Write {
+        wasm: &'a mut dyn Write,
+        pos: usize,
+        scratch: Vec<u8>,
+    },
+    /// Each section's contents are encoded into a vector of their own, and
+    /// pushed along with the section's id. The module's header isn't written.
+    Sections(&'a mut Vec<(u8, Vec<u8>)>),
+}
+
+struct Encoder<'a, 'o> {
+    output: Output<'o>,
     customs: &'a [&'a Custom<'a>],
+    span: Span,
+    offsets: Option<&'a mut Offsets>,
 }
 
-impl Encoder<'_> {
-    fn section(&mut self, id: u8, section: &dyn Encode) {
-        self.tmp.truncate(0);
-        section.encode(&mut self.tmp);
-        self.wasm.push(id);
-        self.tmp.encode(&mut self.wasm);
+impl Encoder<'_, '_> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), EncodeError> {
+        match &mut self.output {
+            Output::Vec { wasm, .. } => wasm.extend_from_slice(bytes),
+            Output::Write { wasm, pos, .. } => {
+                wasm.write_all(bytes)?;
+                *pos += bytes.len();
+            }
+            Output::Sections(_) => {}
+        }
+        Ok(())
+    }
+
+    fn section(&mut self, id: u8, section: &dyn Encode) -> Result<(), EncodeError> {
+        self.section_items(id, 1, false, |_, e, _| {
+            section.encode(e);
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Writes section `id`, whose contents are `count` items, preceded by
+    /// their count if `prefix_count` is set.
+    ///
+    /// The `i`th item is encoded onto the end of `e` with `item(i, e,
+    /// offset)`, where `offset` is where the item starts within the section's
+    /// contents. The offset is only given when the item is encoded for the
+    /// last time, as an item may be encoded more than once. Returns the offset
+    /// of the section's contents within the module.
+    fn section_items(
+        &mut self,
+        id: u8,
+        count: usize,
+        prefix_count: bool,
+        mut item: impl FnMut(usize, &mut Vec<u8>, Option<usize>) -> Result<(), Error>,
+    ) -> Result<usize, EncodeError> {
+        let span = self.span;
+        let check = |size| check_size(span, || format!("{} section", section_name(id)), size);
+        match &mut self.output {
+            Output::Vec { wasm, base } => {
+                wasm.push(id);
+                let start = wasm.len();
+                if prefix_count {
+                    count.encode(wasm);
+                }
+                for i in 0..count {
+                    let offset = wasm.len() - start;
+                    item(i, wasm, Some(offset))?;
+                }
+                let size = wasm.len() - start;
+                check(size)?;
+                let mut header = Vec::new();
+                size.encode(&mut header);
+                wasm.splice(start..start, header.iter().copied());
+                Ok(start + header.len() - *base)
+            }
+            Output::Write { wasm, pos, scratch } => {
+                scratch.truncate(0);
+                if prefix_count {
+                    count.encode(scratch);
+                }
+                let mut size = scratch.len();
+                for i in 0..count {
+                    scratch.truncate(0);
+                    item(i, scratch, None)?;
+                    size += scratch.len();
+                }
+                check(size)?;
+
+                scratch.truncate(0);
+                scratch.push(id);
+                size.encode(scratch);
+                let start = *pos + scratch.len();
+                if prefix_count {
+                    count.encode(scratch);
+                }
+                wasm.write_all(scratch)?;
+                *pos += scratch.len();
+                for i in 0..count {
+                    scratch.truncate(0);
+                    item(i, scratch, Some(*pos - start))?;
+                    wasm.write_all(scratch)?;
+                    *pos += scratch.len();
+                }
+                Ok(start)
+            }
+            Output::Sections(sections) => {
+                let mut contents = Vec::new();
+                if prefix_count {
+                    count.encode(&mut contents);
+                }
+                for i in 0..count {
+                    let offset = contents.len();
+                    item(i, &mut contents, Some(offset))?;
+                }
+                check(contents.len())?;
+                sections.push((id, contents));
+                // Sections aren't part of a module here, and offsets within
+                // a module are never requested for them.
+                Ok(0)
+            }
+        }
     }
 
-    fn custom_sections(&mut self, place: CustomPlace) {
+    fn custom_sections(&mut self, place: CustomPlace) -> Result<(), EncodeError> {
         for entry in self.customs.iter() {
-            if entry.place == place {
-                self.section(0, &(entry.name, entry));
+            if entry.place() == place {
+                self.section(0, &(entry.name(), entry))?;
             }
         }
+        Ok(())
     }
 
-    fn section_list(&mut self, id: u8, anchor: CustomPlaceAnchor, list: &[impl Encode]) {
-        self.custom_sections(CustomPlace::Before(anchor));
+    fn section_list(
+        &mut self,
+        id: u8,
+        anchor: CustomPlaceAnchor,
+        list: &[impl Encode],
+    ) -> Result<(), EncodeError> {
+        self.custom_sections(CustomPlace::Before(anchor))?;
         if !list.is_empty() {
-            self.section(id, &list)
+            self.section_items(id, list.len(), true, |i, e, _| {
+                list[i].encode(e);
+                Ok(())
+            })?;
+        }
+        self.custom_sections(CustomPlace::After(anchor))
+    }
+
+    /// Like `section_list` for the code section, but checking that each
+    /// function body isn't too large to encode.
+    fn code_section(&mut self, funcs: &[&Func<'_>]) -> Result<(), EncodeError> {
+        self.custom_sections(CustomPlace::Before(CustomPlaceAnchor::Code))?;
+        if !funcs.is_empty() {
+            let mut body = Vec::new();
+            // Offsets within the section, adjusted once the section's
+            // position is known.
+            let mut func_offsets = Vec::new();
+            let mut instr_offsets = Vec::new();
+            let track = self.offsets.is_some();
+            let start = self.section_items(10, funcs.len(), true, |i, e, offset| {
+                let func = funcs[i];
+                body.truncate(0);
+                let instrs_start = instr_offsets.len();
+                let instrs = (track && offset.is_some()).then_some(&mut instr_offsets);
+                func.encode_body(&mut body, instrs);
+                check_size(func.span, || "function body".to_string(), body.len())?;
+                let before = e.len();
+                body.encode(e);
+                if let Some(offset) = offset {
+                    let start = offset + (e.len() - before) - body.len();
+                    func_offsets.push((func.span, start));
+                    for (_, offset) in instr_offsets[instrs_start..].iter_mut() {
+                        *offset += start;
+                    }
+                }
+                Ok(())
+            })?;
+            if let Some(offsets) = &mut self.offsets {
+                let relocate = |(span, offset): (Span, usize)| (span, offset + start);
+                offsets.funcs.extend(func_offsets.into_iter().map(relocate));
+                offsets.instrs.extend(instr_offsets.into_iter().map(relocate));
+            }
+        }
+        self.custom_sections(CustomPlace::After(CustomPlaceAnchor::Code))
+    }
+
+    /// Like `section_list` for the data section, but recording the offset of
+    /// each segment if requested.
+    fn data_section(&mut self, data: &[&Data<'_>]) -> Result<(), EncodeError> {
+        self.custom_sections(CustomPlace::Before(CustomPlaceAnchor::Data))?;
+        if !data.is_empty() {
+            let mut data_offsets = Vec::new();
+            let start = self.section_items(11, data.len(), true, |i, e, offset| {
+                if let Some(offset) = offset {
+                    data_offsets.push((data[i].span, offset));
+                }
+                data[i].encode(e);
+                Ok(())
+            })?;
+            if let Some(offsets) = &mut self.offsets {
+                offsets.data.extend(
+                    data_offsets
+                        .into_iter()
+                        .map(|(span, offset)| (span, offset + start)),
+                );
+            }
         }
-        self.custom_sections(CustomPlace::After(anchor));
+        self.custom_sections(CustomPlace::After(CustomPlaceAnchor::Data))
+    }
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        0 => "custom",
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
     }
 }
 
@@ -173,10 +567,19 @@ impl Encode for RecOrType<'_> {
 
 impl Encode for Type<'_> {
     fn encode(&self, e: &mut Vec<u8>) {
-        if let Some(parent) = &self.parent {
-            e.push(0x50);
-            (1 as usize).encode(e);
-            parent.encode(e);
+        match (&self.parent, self.final_type) {
+            // A final type without a parent is encoded without `sub`.
+            (None, None | Some(true)) => {}
+            (parent, final_type) => {
+                e.push(if final_type == Some(true) { 0x4f } else { 0x50 });
+                match parent {
+                    Some(parent) => {
+                        1usize.encode(e);
+                        parent.encode(e);
+                    }
+                    None => 0usize.encode(e),
+                }
+            }
         }
         match &self.def {
             TypeDef::Func(func) => {
@@ -202,7 +605,7 @@ impl Encode for Rec<'_> {
             return;
         }
 
-        e.push(0x4f);
+        e.push(0x4e);
         self.types.len().encode(e);
         for ty in &self.types {
             ty.encode(e);
@@ -238,9 +641,12 @@ impl<'a> Encode for HeapType<'a> {
             HeapType::Extern => e.push(0x6f),
             HeapType::Any => e.push(0x6e),
             HeapType::Eq => e.push(0x6d),
-            HeapType::Struct => e.push(0x67),
-            HeapType::Array => e.push(0x66),
-            HeapType::I31 => e.push(0x6a),
+            HeapType::Struct => e.push(0x6b),
+            HeapType::Array => e.push(0x6a),
+            HeapType::I31 => e.push(0x6c),
+            HeapType::None => e.push(0x71),
+            HeapType::NoFunc => e.push(0x73),
+            HeapType::NoExtern => e.push(0x72),
             // Note that this is encoded as a signed leb128 so be sure to cast
             // to an i64 first
             HeapType::Index(Index::Num(n, _)) => i64::from(*n).encode(e),
@@ -273,19 +679,34 @@ impl<'a> Encode for RefType<'a> {
             RefType {
                 nullable: true,
                 heap: HeapType::Struct,
-            } => e.push(0x67),
+            } => e.push(0x6b),
             // The 'i31ref' binary abbreviation
             RefType {
                 nullable: true,
                 heap: HeapType::I31,
-            } => e.push(0x6a),
+            } => e.push(0x6c),
+            // The 'nullref' binary abbreviation
+            RefType {
+                nullable: true,
+                heap: HeapType::None,
+            } => e.push(0x71),
+            // The 'nullfuncref' binary abbreviation
+            RefType {
+                nullable: true,
+                heap: HeapType::NoFunc,
+            } => e.push(0x73),
+            // The 'nullexternref' binary abbreviation
+            RefType {
+                nullable: true,
+                heap: HeapType::NoExtern,
+            } => e.push(0x72),
 
             // Generic 'ref opt <heaptype>' encoding
             RefType {
                 nullable: true,
                 heap,
             } => {
-                e.push(0x6c);
+                e.push(0x63);
                 heap.encode(e);
             }
             // Generic 'ref <heaptype>' encoding
@@ -293,7 +714,7 @@ impl<'a> Encode for RefType<'a> {
                 nullable: false,
                 heap,
             } => {
-                e.push(0x6b);
+                e.push(0x64);
                 heap.encode(e);
             }
         }
@@ -391,25 +812,41 @@ impl Encode for Limits {
 impl Encode for MemoryType {
     fn encode(&self, e: &mut Vec<u8>) {
         match self {
-            MemoryType::B32 { limits, shared } => {
+            MemoryType::B32 {
+                limits,
+                shared,
+                page_size_log2,
+            } => {
                 let flag_max = limits.max.is_some() as u8;
                 let flag_shared = *shared as u8;
-                let flags = flag_max | (flag_shared << 1);
+                let flag_page_size = page_size_log2.is_some() as u8;
+                let flags = flag_max | (flag_shared << 1) | (flag_page_size << 3);
                 e.push(flags);
                 limits.min.encode(e);
                 if let Some(max) = limits.max {
                     max.encode(e);
                 }
+                if let Some(p) = page_size_log2 {
+                    p.encode(e);
+                }
             }
-            MemoryType::B64 { limits, shared } => {
+            MemoryType::B64 {
+                limits,
+                shared,
+                page_size_log2,
+            } => {
                 let flag_max = limits.max.is_some() as u8;
                 let flag_shared = *shared as u8;
-                let flags = flag_max | (flag_shared << 1) | 0x04;
+                let flag_page_size = page_size_log2.is_some() as u8;
+                let flags = flag_max | (flag_shared << 1) | 0x04 | (flag_page_size << 3);
                 e.push(flags);
                 limits.min.encode(e);
                 if let Some(max) = limits.max {
                     max.encode(e);
                 }
+                if let Some(p) = page_size_log2 {
+                    p.encode(e);
+                }
             }
         }
     }
@@ -591,20 +1028,78 @@ impl Encode for Data<'_> {
     }
 }
 
-impl Encode for Func<'_> {
-    fn encode(&self, e: &mut Vec<u8>) {
+impl Func<'_> {
+    /// Encodes the body of this function, without its size, returning the
+    /// offset within the body of each instruction with a branch hint along
+    /// with the value of the hint.
+    ///
+    /// If `instrs` is given then the span and offset within the body of each
+    /// instruction with a known span is appended to it.
+    fn encode_body(
+        &self,
+        e: &mut Vec<u8>,
+        mut instrs: Option<&mut Vec<(Span, usize)>>,
+    ) -> Vec<(usize, u32)> {
         assert!(self.exports.names.is_empty());
-        let mut tmp = Vec::new();
         let (expr, locals) = match &self.kind {
             FuncKind::Inline { expression, locals } => (expression, locals),
             _ => panic!("should only have inline functions in emission"),
         };
 
-        locals.encode(&mut tmp);
-        expr.encode(&mut tmp);
+        let start = e.len();
+        locals.encode(e);
+        let instrs_start = instrs.as_ref().map_or(0, |i| i.len());
+        let mut hints = expr.encode_with_offsets(e, instrs.as_deref_mut());
+        for (offset, _) in hints.iter_mut() {
+            *offset -= start;
+        }
+        if let Some(instrs) = instrs {
+            for (_, offset) in instrs[instrs_start..].iter_mut() {
+                *offset -= start;
+            }
+        }
+        hints
+    }
+}
+
+/// The contents of the `metadata.code.branch_hint` custom section: for each
+/// function with hints its index, and then the offset within its body and
+/// value of each hint.
+struct BranchHints(Vec<(u32, Vec<(usize, u32)>)>);
 
-        tmp.len().encode(e);
-        e.extend_from_slice(&tmp);
+impl BranchHints {
+    fn new(num_import_funcs: usize, funcs: &[&Func<'_>]) -> BranchHints {
+        let mut ret = Vec::new();
+        let mut tmp = Vec::new();
+        for (i, func) in funcs.iter().enumerate() {
+            let has_hints = match &func.kind {
+                FuncKind::Inline { expression, .. } => !expression.branch_hints.is_empty(),
+                _ => false,
+            };
+            if !has_hints {
+                continue;
+            }
+            tmp.truncate(0);
+            let hints = func.encode_body(&mut tmp, None);
+            ret.push(((num_import_funcs + i) as u32, hints));
+        }
+        BranchHints(ret)
+    }
+}
+
+impl Encode for BranchHints {
+    fn encode(&self, e: &mut Vec<u8>) {
+        self.0.len().encode(e);
+        for (func, hints) in self.0.iter() {
+            func.encode(e);
+            hints.len().encode(e);
+            for (offset, value) in hints {
+                offset.encode(e);
+                // The size of the hint's value, which is always one byte.
+                1u32.encode(e);
+                value.encode(e);
+            }
+        }
     }
 }
 
@@ -624,12 +1119,41 @@ impl Encode for Vec<Local<'_>> {
     }
 }
 
-impl Encode for Expression<'_> {
-    fn encode(&self, e: &mut Vec<u8>) {
-        for instr in self.instrs.iter() {
+impl Expression<'_> {
+    /// Encodes this expression, returning the offset within `e` of each
+    /// instruction with a branch hint along with the value of the hint.
+    ///
+    /// If `instrs` is given and the spans of this expression's instructions
+    /// were recorded then the span and offset within `e` of each instruction
+    /// is appended to it.
+    fn encode_with_offsets(
+        &self,
+        e: &mut Vec<u8>,
+        mut instrs: Option<&mut Vec<(Span, usize)>>,
+    ) -> Vec<(usize, u32)> {
+        let spans = match (&self.instr_spans, &instrs) {
+            (Some(spans), Some(_)) => &spans[..],
+            _ => &[],
+        };
+        let mut hints = self.branch_hints.iter().peekable();
+        let mut ret = Vec::new();
+        for (i, instr) in self.instrs.iter().enumerate() {
+            if let Some(hint) = hints.next_if(|h| h.instr_index == i) {
+                ret.push((e.len(), hint.value));
+            }
+            if let (Some(span), Some(instrs)) = (spans.get(i), instrs.as_deref_mut()) {
+                instrs.push((*span, e.len()));
+            }
             instr.encode(e);
         }
         e.push(0x0b);
+        ret
+    }
+}
+
+impl Encode for Expression<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        self.encode_with_offsets(e, None);
     }
 }
 
@@ -935,8 +1459,7 @@ impl Names<'_> {
             && self.types.is_empty()
             && self.data.is_empty()
             && self.elems.is_empty()
-        // NB: specifically don't check tags/modules/instances since they're
-        // not encoded for now.
+            && self.tags.is_empty()
     }
 }
 
@@ -990,6 +1513,10 @@ impl Encode for Names<'_> {
             self.data.encode(&mut tmp);
             subsec(9, &mut tmp);
         }
+        if !self.tags.is_empty() {
+            self.tags.encode(&mut tmp);
+            subsec(11, &mut tmp);
+        }
     }
 }
 
@@ -1025,6 +1552,18 @@ impl<'a> Encode for SelectTypes<'a> {
 }
 
 impl Encode for Custom<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        match self {
+            Custom::Raw(r) => r.encode(e),
+            Custom::Producers(p) => p.encode(e),
+            Custom::TargetFeatures(p) => p.encode(e),
+            Custom::Dylink0(p) => p.encode(e),
+            Custom::Registered(r) => e.extend_from_slice(&r.data),
+        }
+    }
+}
+
+impl Encode for RawCustomSection<'_> {
     fn encode(&self, e: &mut Vec<u8>) {
         for list in self.data.iter() {
             e.extend_from_slice(list);
@@ -1032,6 +1571,60 @@ impl Encode for Custom<'_> {
     }
 }
 
+impl Encode for Producers<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        self.fields.encode(e);
+    }
+}
+
+impl Encode for TargetFeatures<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        self.features.encode(e);
+    }
+}
+
+impl Encode for Dylink0<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        for section in self.subsections.iter() {
+            e.push(section.id());
+            let mut tmp = Vec::new();
+            section.encode(&mut tmp);
+            tmp.encode(e);
+        }
+    }
+}
+
+impl Encode for Dylink0Subsection<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        match self {
+            Dylink0Subsection::MemInfo {
+                memory_size,
+                memory_align,
+                table_size,
+                table_align,
+            } => {
+                memory_size.encode(e);
+                memory_align.encode(e);
+                table_size.encode(e);
+                table_align.encode(e);
+            }
+            Dylink0Subsection::Needed(libs) => libs.encode(e),
+            Dylink0Subsection::ExportInfo(list) => list.encode(e),
+            Dylink0Subsection::ImportInfo(list) => list.encode(e),
+        }
+    }
+}
+
+impl Encode for FeaturePolicy {
+    fn encode(&self, e: &mut Vec<u8>) {
+        e.push(match self {
+            FeaturePolicy::Used => b'+',
+            FeaturePolicy::Disallowed => b'-',
+            FeaturePolicy::Required => b'=',
+        });
+    }
+}
+
 impl Encode for Tag<'_> {
     fn encode(&self, e: &mut Vec<u8>) {
         self.ty.encode(e);
@@ -1088,9 +1681,49 @@ impl Encode for ArrayNewElem<'_> {
     }
 }
 
+impl Encode for RefTest<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        e.push(0xfb);
+        if self.r#type.nullable {
+            e.push(0x15);
+        } else {
+            e.push(0x14);
+        }
+        self.r#type.heap.encode(e);
+    }
+}
+
+impl Encode for RefCast<'_> {
+    fn encode(&self, e: &mut Vec<u8>) {
+        e.push(0xfb);
+        if self.r#type.nullable {
+            e.push(0x17);
+        } else {
+            e.push(0x16);
+        }
+        self.r#type.heap.encode(e);
+    }
+}
+
+fn br_on_cast_flags(from_nullable: bool, to_nullable: bool) -> u8 {
+    let mut flag = 0;
+    if from_nullable {
+        flag |= 1 << 0;
+    }
+    if to_nullable {
+        flag |= 1 << 1;
+    }
+    flag
+}
+
 impl Encode for BrOnCast<'_> {
     fn encode(&self, e: &mut Vec<u8>) {
+        e.push(br_on_cast_flags(
+            self.from_type.nullable,
+            self.to_type.nullable,
+        ));
         self.label.encode(e);
-        self.r#type.encode(e);
+        self.from_type.heap.encode(e);
+        self.to_type.heap.encode(e);
     }
 }
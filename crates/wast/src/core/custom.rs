@@ -2,9 +2,70 @@ use crate::parser::{Parse, Parser, Result};
 use crate::token::{self, Span};
 use crate::{annotation, kw};
 
-/// A wasm custom section within a module.
+/// A custom section within a module.
 #[derive(Debug)]
-pub struct Custom<'a> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Custom<'a> {
+    /// A raw custom section with the payload specified as a list of strings,
+    /// written with `@custom`.
+    Raw(RawCustomSection<'a>),
+    /// A `producers` custom section, written with `@producers`.
+    Producers(Producers<'a>),
+    /// A `target_features` custom section, written with `@target_features`.
+    TargetFeatures(TargetFeatures<'a>),
+    /// A `dylink.0` custom section, written with `@dylink.0`.
+    Dylink0(Dylink0<'a>),
+    /// A custom section produced by a handler registered with
+    /// [`ParseBuffer::register_custom_annotation`](crate::parser::ParseBuffer::register_custom_annotation).
+    Registered(RegisteredCustomSection<'a>),
+}
+
+impl Custom<'_> {
+    /// Where this custom section is placed within the module.
+    pub fn place(&self) -> CustomPlace {
+        match self {
+            Custom::Raw(s) => s.place,
+            // Dynamic linking metadata must be the first section of a module.
+            Custom::Dylink0(_) => CustomPlace::BeforeFirst,
+            Custom::Producers(_) | Custom::TargetFeatures(_) | Custom::Registered(_) => {
+                CustomPlace::AfterLast
+            }
+        }
+    }
+
+    /// The name of this custom section.
+    pub fn name(&self) -> &str {
+        match self {
+            Custom::Raw(s) => s.name,
+            Custom::Producers(_) => "producers",
+            Custom::TargetFeatures(_) => "target_features",
+            Custom::Dylink0(_) => "dylink.0",
+            Custom::Registered(s) => s.name,
+        }
+    }
+}
+
+impl<'a> Parse<'a> for Custom<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        if parser.peek::<annotation::producers>() {
+            Ok(Custom::Producers(parser.parse()?))
+        } else if parser.peek::<annotation::target_features>() {
+            Ok(Custom::TargetFeatures(parser.parse()?))
+        } else if parser.peek::<annotation::dylink_0>() {
+            Ok(Custom::Dylink0(parser.parse()?))
+        } else if parser.peek_custom_annotation() {
+            Ok(Custom::Registered(parser.parse()?))
+        } else {
+            Ok(Custom::Raw(parser.parse()?))
+        }
+    }
+}
+
+/// A wasm custom section within a module, with its payload written out
+/// explicitly.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RawCustomSection<'a> {
     /// Where this `@custom` was defined.
     pub span: Span,
 
@@ -18,8 +79,365 @@ pub struct Custom<'a> {
     pub data: Vec<&'a [u8]>,
 }
 
+impl<'a> Parse<'a> for RawCustomSection<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let span = parser.parse::<annotation::custom>()?.0;
+        let name = parser.parse()?;
+        let place = if parser.peek::<token::LParen>() {
+            parser.parens(|p| p.parse())?
+        } else {
+            CustomPlace::AfterLast
+        };
+        let mut data = Vec::new();
+        while !parser.is_empty() {
+            data.push(parser.parse()?);
+        }
+        Ok(RawCustomSection {
+            span,
+            name,
+            place,
+            data,
+        })
+    }
+}
+
+/// A custom section written with an annotation whose handler was registered
+/// with [`ParseBuffer::register_custom_annotation`].
+///
+/// [`ParseBuffer::register_custom_annotation`]: crate::parser::ParseBuffer::register_custom_annotation
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RegisteredCustomSection<'a> {
+    /// Where this annotation was defined.
+    pub span: Span,
+
+    /// Name of the annotation, which is also the name of the custom section.
+    pub name: &'a str,
+
+    /// Payload of this custom section, as produced by the handler.
+    pub data: Vec<u8>,
+}
+
+impl<'a> Parse<'a> for RegisteredCustomSection<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let (span, name, data) = parser.parse_custom_annotation()?;
+        Ok(RegisteredCustomSection { span, name, data })
+    }
+}
+
+/// A `producers` custom section, which records the tools and languages that
+/// produced a module.
+///
+/// In the text format this is written as a list of `(field "name"
+/// "version")` entries, such as:
+///
+/// ```text
+/// (@producers
+///   (language "C" "11")
+///   (processed-by "clang" "16.0.0")
+/// )
+/// ```
+///
+/// A module has at most one producers section, so all of its `@producers`
+/// annotations are merged into the section of the first one, as if by
+/// [`Producers::merge`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Producers<'a> {
+    /// Where this `@producers` was defined.
+    pub span: Span,
+
+    /// The fields of this section, in order, each with its list of names
+    /// and versions.
+    pub fields: Vec<(&'a str, Vec<(&'a str, &'a str)>)>,
+}
+
+impl<'a> Parse<'a> for Producers<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let span = parser.parse::<annotation::producers>()?.0;
+        let mut producers = Producers {
+            span,
+            fields: Vec::new(),
+        };
+        while !parser.is_empty() {
+            parser.parens(|p| {
+                let field = p.step(|c| match c.keyword() {
+                    Some(pair) => Ok(pair),
+                    None => Err(c.error("expected a producers field name")),
+                })?;
+                producers.add(field, p.parse()?, p.parse()?);
+                Ok(())
+            })?;
+        }
+        Ok(producers)
+    }
+}
+
+impl<'a> Producers<'a> {
+    /// Adds `name` at `version` to `field`, replacing the version of `name`
+    /// if it's already listed in that field.
+    pub fn add(&mut self, field: &'a str, name: &'a str, version: &'a str) {
+        let values = match self.fields.iter().position(|(f, _)| *f == field) {
+            Some(i) => &mut self.fields[i].1,
+            None => {
+                self.fields.push((field, Vec::new()));
+                &mut self.fields.last_mut().unwrap().1
+            }
+        };
+        match values.iter_mut().find(|(n, _)| *n == name) {
+            Some(value) => value.1 = version,
+            None => values.push((name, version)),
+        }
+    }
+
+    /// Adds all of the fields of `other` to this section with
+    /// [`Producers::add`], so that versions in `other` take precedence.
+    pub fn merge(&mut self, other: &Producers<'a>) {
+        for (field, values) in other.fields.iter() {
+            for (name, version) in values {
+                self.add(field, name, version);
+            }
+        }
+    }
+}
+
+/// A `target_features` custom section, which records the WebAssembly
+/// features that a module was compiled with.
+///
+/// In the text format each feature is written with how it's used:
+///
+/// ```text
+/// (@target_features
+///   (used "simd128")
+///   (disallowed "atomics")
+/// )
+/// ```
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TargetFeatures<'a> {
+    /// Where this `@target_features` was defined.
+    pub span: Span,
+
+    /// The features listed in this section, in order.
+    pub features: Vec<(FeaturePolicy, &'a str)>,
+}
+
+/// How a feature listed in a `target_features` section is used.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum FeaturePolicy {
+    /// The feature is used by the module, written as `used` and encoded as
+    /// a `+` prefix.
+    Used,
+    /// The feature must not be used by modules linked with this one,
+    /// written as `disallowed` and encoded as a `-` prefix.
+    Disallowed,
+    /// The feature must be used by modules linked with this one, written as
+    /// `required` and encoded as a `=` prefix.
+    Required,
+}
+
+impl<'a> Parse<'a> for TargetFeatures<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let span = parser.parse::<annotation::target_features>()?.0;
+        let mut features = Vec::new();
+        while !parser.is_empty() {
+            features.push(parser.parens(|p| Ok((p.parse()?, p.parse()?)))?);
+        }
+        Ok(TargetFeatures { span, features })
+    }
+}
+
+impl<'a> Parse<'a> for FeaturePolicy {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let mut l = parser.lookahead1();
+        if l.peek::<kw::used>() {
+            parser.parse::<kw::used>()?;
+            Ok(FeaturePolicy::Used)
+        } else if l.peek::<kw::disallowed>() {
+            parser.parse::<kw::disallowed>()?;
+            Ok(FeaturePolicy::Disallowed)
+        } else if l.peek::<kw::required>() {
+            parser.parse::<kw::required>()?;
+            Ok(FeaturePolicy::Required)
+        } else {
+            Err(l.error())
+        }
+    }
+}
+
+/// A `dylink.0` custom section, which describes the requirements of a
+/// dynamic library as defined by the [dynamic linking conventions].
+///
+/// In the text format each subsection is written as a list:
+///
+/// ```text
+/// (@dylink.0
+///   (mem-info (memory 16 2) (table 1 0))
+///   (needed "libc.so")
+///   (export-info "foo" binding-weak)
+///   (import-info "env" "bar" undefined visibility-hidden)
+/// )
+/// ```
+///
+/// The sizes in `mem-info` are followed by alignments as powers of two, and
+/// either of `memory` or `table` may be omitted if they're zero. Symbol flags
+/// are a list of names, such as `binding-weak` or `tls`, and integers, which
+/// are combined together.
+///
+/// [dynamic linking conventions]: https://github.com/WebAssembly/tool-conventions/blob/main/DynamicLinking.md
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Dylink0<'a> {
+    /// Where this `@dylink.0` was defined.
+    pub span: Span,
+
+    /// The subsections of this section, in order. Adjacent `export-info` or
+    /// `import-info` entries are grouped into one subsection.
+    pub subsections: Vec<Dylink0Subsection<'a>>,
+}
+
+/// A subsection of a [`Dylink0`] section.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Dylink0Subsection<'a> {
+    /// The memory and table requirements of the library, written as
+    /// `mem-info`.
+    MemInfo {
+        /// The size of the library's static data, in bytes.
+        memory_size: u32,
+        /// The alignment of the static data, as a power of two.
+        memory_align: u32,
+        /// The number of table slots the library uses.
+        table_size: u32,
+        /// The alignment of the table slots, as a power of two.
+        table_align: u32,
+    },
+    /// The libraries this library depends on, written as `needed`.
+    Needed(Vec<&'a str>),
+    /// Symbol flags for exports, each written as an `export-info`.
+    ExportInfo(Vec<(&'a str, u32)>),
+    /// Symbol flags for imports, each written as an `import-info`.
+    ImportInfo(Vec<(&'a str, &'a str, u32)>),
+}
+
+impl Dylink0Subsection<'_> {
+    /// The id of this subsection in the binary format.
+    pub fn id(&self) -> u8 {
+        match self {
+            Dylink0Subsection::MemInfo { .. } => 1,
+            Dylink0Subsection::Needed(_) => 2,
+            Dylink0Subsection::ExportInfo(_) => 3,
+            Dylink0Subsection::ImportInfo(_) => 4,
+        }
+    }
+}
+
+// The names of the symbol flags in `export-info` and `import-info`, with the
+// bit of each.
+pub(crate) const DYLINK_0_SYMBOL_FLAGS: &[(&str, u32)] = &[
+    ("binding-weak", 0x1),
+    ("binding-local", 0x2),
+    ("visibility-hidden", 0x4),
+    ("undefined", 0x10),
+    ("exported", 0x20),
+    ("explicit-name", 0x40),
+    ("no-strip", 0x80),
+    ("tls", 0x100),
+    ("absolute", 0x200),
+];
+
+impl<'a> Parse<'a> for Dylink0<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        let span = parser.parse::<annotation::dylink_0>()?.0;
+        let mut subsections = Vec::new();
+        while !parser.is_empty() {
+            parser.parens(|p| parse_dylink0_subsection(p, &mut subsections))?;
+        }
+        Ok(Dylink0 { span, subsections })
+    }
+}
+
+fn parse_dylink0_subsection<'a>(
+    parser: Parser<'a>,
+    subsections: &mut Vec<Dylink0Subsection<'a>>,
+) -> Result<()> {
+    let mut l = parser.lookahead1();
+    if l.peek::<kw::mem_info>() {
+        parser.parse::<kw::mem_info>()?;
+        let mut sizes = [0; 4];
+        if parser.peek2::<kw::memory>() {
+            parser.parens(|p| {
+                p.parse::<kw::memory>()?;
+                sizes[0] = p.parse()?;
+                sizes[1] = p.parse()?;
+                Ok(())
+            })?;
+        }
+        if parser.peek2::<kw::table>() {
+            parser.parens(|p| {
+                p.parse::<kw::table>()?;
+                sizes[2] = p.parse()?;
+                sizes[3] = p.parse()?;
+                Ok(())
+            })?;
+        }
+        let [memory_size, memory_align, table_size, table_align] = sizes;
+        subsections.push(Dylink0Subsection::MemInfo {
+            memory_size,
+            memory_align,
+            table_size,
+            table_align,
+        });
+    } else if l.peek::<kw::needed>() {
+        parser.parse::<kw::needed>()?;
+        let mut names = Vec::new();
+        while !parser.is_empty() {
+            names.push(parser.parse()?);
+        }
+        subsections.push(Dylink0Subsection::Needed(names));
+    } else if l.peek::<kw::export_info>() {
+        parser.parse::<kw::export_info>()?;
+        let info = (parser.parse()?, parse_symbol_flags(parser)?);
+        match subsections.last_mut() {
+            Some(Dylink0Subsection::ExportInfo(list)) => list.push(info),
+            _ => subsections.push(Dylink0Subsection::ExportInfo(vec![info])),
+        }
+    } else if l.peek::<kw::import_info>() {
+        parser.parse::<kw::import_info>()?;
+        let info = (parser.parse()?, parser.parse()?, parse_symbol_flags(parser)?);
+        match subsections.last_mut() {
+            Some(Dylink0Subsection::ImportInfo(list)) => list.push(info),
+            _ => subsections.push(Dylink0Subsection::ImportInfo(vec![info])),
+        }
+    } else {
+        return Err(l.error());
+    }
+    Ok(())
+}
+
+fn parse_symbol_flags(parser: Parser<'_>) -> Result<u32> {
+    let mut flags = 0;
+    while !parser.is_empty() {
+        if parser.peek::<u32>() {
+            flags |= parser.parse::<u32>()?;
+            continue;
+        }
+        flags |= parser.step(|c| {
+            if let Some((name, rest)) = c.keyword() {
+                if let Some((_, bit)) = DYLINK_0_SYMBOL_FLAGS.iter().find(|(n, _)| *n == name) {
+                    return Ok((*bit, rest));
+                }
+            }
+            Err(c.error("expected a symbol flag"))
+        })?;
+    }
+    Ok(flags)
+}
+
 /// Possible locations to place a custom section within a module.
 #[derive(Debug, PartialEq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum CustomPlace {
     /// This custom section will appear before the first section in the module.
     BeforeFirst,
@@ -33,6 +451,7 @@ pub enum CustomPlace {
 
 /// Known sections that custom sections can be placed relative to.
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum CustomPlaceAnchor {
     Type,
@@ -49,28 +468,6 @@ pub enum CustomPlaceAnchor {
     Tag,
 }
 
-impl<'a> Parse<'a> for Custom<'a> {
-    fn parse(parser: Parser<'a>) -> Result<Self> {
-        let span = parser.parse::<annotation::custom>()?.0;
-        let name = parser.parse()?;
-        let place = if parser.peek::<token::LParen>() {
-            parser.parens(|p| p.parse())?
-        } else {
-            CustomPlace::AfterLast
-        };
-        let mut data = Vec::new();
-        while !parser.is_empty() {
-            data.push(parser.parse()?);
-        }
-        Ok(Custom {
-            span,
-            name,
-            place,
-            data,
-        })
-    }
-}
-
 impl<'a> Parse<'a> for CustomPlace {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let mut l = parser.lookahead1();
@@ -4,6 +4,7 @@ use crate::token::{Index, Span};
 
 /// A entry in a WebAssembly module's export section.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Export<'a> {
     /// Where this export was defined.
     pub span: Span,
@@ -18,6 +19,7 @@ pub struct Export<'a> {
 /// Different kinds of elements that can be exported from a WebAssembly module,
 /// contained in an [`Export`].
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum ExportKind {
     Func,
@@ -27,6 +29,18 @@ pub enum ExportKind {
     Tag,
 }
 
+impl<'a> Export<'a> {
+    /// Creates an export of the `kind` item at `item` under `name`.
+    pub fn new(name: &'a str, kind: ExportKind, item: Index<'a>) -> Export<'a> {
+        Export {
+            span: Span::from_offset(0),
+            name,
+            kind,
+            item,
+        }
+    }
+}
+
 impl<'a> Parse<'a> for Export<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let span = parser.parse::<kw::export>()?.0;
@@ -105,6 +119,7 @@ kw_conversions! {
 /// A listing of inline `(export "foo")` statements on a WebAssembly item in
 /// its textual format.
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct InlineExport<'a> {
     /// The extra names to export an item as, if any.
     pub names: Vec<&'a str>,
@@ -1,6 +1,9 @@
+use crate::annotation;
+use crate::core::print::{Output, Print};
 use crate::core::*;
 use crate::encode::Encode;
 use crate::kw;
+use crate::lexer::{Lexer, Token};
 use crate::parser::{Cursor, Parse, Parser, Result};
 use crate::token::*;
 use std::mem;
@@ -11,18 +14,74 @@ use std::mem;
 /// list of instructions for emission later on. The implicit `end` instruction
 /// at the end of an expression is not included in the `instrs` field.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub struct Expression<'a> {
     pub instrs: Box<[Instruction<'a>]>,
+    pub branch_hints: Box<[BranchHint]>,
+    /// The span of each instruction in `instrs`, only recorded if enabled
+    /// with [`ParseBuffer::track_instr_spans`](crate::parser::ParseBuffer::track_instr_spans).
+    ///
+    /// Instructions without a keyword of their own, such as the `end` of a
+    /// folded `block`, have the span of the token which implies them.
+    pub instr_spans: Option<Box<[Span]>>,
+}
+
+/// A `@metadata.code.branch_hint` annotation from the branch hinting
+/// proposal, which precedes an `if` or `br_if` instruction.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct BranchHint {
+    /// The index, within [`Expression::instrs`], of the instruction this
+    /// hint applies to.
+    pub instr_index: usize,
+    /// The value of the hint: 1 if the branch is likely to be taken and 0 if
+    /// it isn't.
+    pub value: u32,
 }
 
 impl<'a> Parse<'a> for Expression<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let mut exprs = ExpressionParser::default();
         exprs.parse(parser)?;
-        Ok(Expression {
-            instrs: exprs.instrs.into(),
-        })
+        Ok(exprs.finish(parser))
+    }
+}
+
+impl<'a> Expression<'a> {
+    /// Creates an expression of `instrs`, without any branch hints or spans.
+    pub fn new(instrs: Vec<Instruction<'a>>) -> Expression<'a> {
+        Expression {
+            instrs: instrs.into(),
+            branch_hints: Box::new([]),
+            instr_spans: None,
+        }
+    }
+
+    /// Parses the contents of a folded instruction, the instruction name
+    /// followed by its folded operands, such as `i32.add (i32.const 0)
+    /// (global.get 0)`.
+    ///
+    /// This is used for the abbreviated form of offsets in data and element
+    /// segments, `(data (i32.const 0) ...)`, where the parentheses around the
+    /// offset are those of the instruction itself. With the extended-const
+    /// proposal the offset may have nested operands, and they're all
+    /// evaluated before the outermost instruction.
+    ///
+    /// For compatibility a flat sequence of instructions, `(i32.const 0
+    /// i32.const 1 i32.add)`, is also accepted and kept in order.
+    pub(crate) fn parse_folded_instruction(parser: Parser<'a>) -> Result<Self> {
+        let span = parser.cur_span();
+        let insn = parser.parse()?;
+        let mut exprs = ExpressionParser::default();
+        if parser.peek::<LParen>() {
+            exprs.parse(parser)?;
+            exprs.push_instr(insn, span, None);
+        } else {
+            exprs.push_instr(insn, span, None);
+            exprs.parse(parser)?;
+        }
+        Ok(exprs.finish(parser))
     }
 }
 
@@ -41,6 +100,16 @@ struct ExpressionParser<'a> {
     /// Descriptor of all our nested s-expr blocks. This only happens when
     /// instructions themselves are nested.
     stack: Vec<Level<'a>>,
+
+    /// The branch hints of `instrs` parsed so far.
+    branch_hints: Vec<BranchHint>,
+
+    /// The span of each of `instrs`.
+    spans: Vec<Span>,
+
+    /// The value of a branch hint annotation which applies to the next
+    /// instruction parsed.
+    pending_hint: Option<u32>,
 }
 
 enum Paren {
@@ -52,8 +121,9 @@ enum Paren {
 /// A "kind" of nested block that we can be parsing inside of.
 enum Level<'a> {
     /// This is a normal `block` or `loop` or similar, where the instruction
-    /// payload here is pushed when the block is exited.
-    EndWith(Instruction<'a>),
+    /// payload here, along with its branch hint if any, is pushed when the
+    /// block is exited. Its span is that of the closing `)` if `None`.
+    EndWith(Instruction<'a>, Option<Span>, Option<u32>),
 
     /// This is a pretty special variant which means that we're parsing an `if`
     /// statement, and the state of the `if` parsing is tracked internally in
@@ -71,15 +141,19 @@ enum Level<'a> {
 
     /// Similar to `IfArm` but for `(do ...)` and `(catch ...)` blocks.
     TryArm,
+
+    /// This is a `(@metadata.code.branch_hint ...)` annotation which has
+    /// already been parsed.
+    BranchHint,
 }
 
 /// Possible states of "what should be parsed next?" in an `if` expression.
 enum If<'a> {
     /// Only the `if` has been parsed, next thing to parse is the clause, if
     /// any, of the `if` instruction.
-    Clause(Instruction<'a>),
+    Clause(Instruction<'a>, Span, Option<u32>),
     /// Next thing to parse is the `then` block
-    Then(Instruction<'a>),
+    Then(Instruction<'a>, Span, Option<u32>),
     /// Next thing to parse is the `else` block
     Else,
     /// This `if` statement has finished parsing and if anything remains it's a
@@ -90,7 +164,7 @@ enum If<'a> {
 /// Possible state of "what should be parsed next?" in a `try` expression.
 enum Try<'a> {
     /// Next thing to parse is the `do` block.
-    Do(Instruction<'a>),
+    Do(Instruction<'a>, Span),
     /// Next thing to parse is `catch`/`catch_all`, or `delegate`.
     CatchOrDelegate,
     /// Next thing to parse is a `catch` block or `catch_all`.
@@ -103,7 +177,59 @@ enum Try<'a> {
 }
 
 impl<'a> ExpressionParser<'a> {
+    fn finish(self, parser: Parser<'a>) -> Expression<'a> {
+        Expression {
+            instrs: self.instrs.into(),
+            branch_hints: self.branch_hints.into(),
+            instr_spans: if parser.track_instr_spans() {
+                Some(self.spans.into())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Pushes `instr`, found at `span`, onto the list of instructions,
+    /// recording `hint` as its branch hint.
+    fn push_instr(&mut self, instr: Instruction<'a>, span: Span, hint: Option<u32>) {
+        if let Some(value) = hint {
+            self.branch_hints.push(BranchHint {
+                instr_index: self.instrs.len(),
+                value,
+            });
+        }
+        self.instrs.push(instr);
+        self.spans.push(span);
+    }
+
+    /// Takes the pending branch hint, if any, for the instruction `instr`
+    /// which was just parsed.
+    fn take_hint(&mut self, parser: Parser<'a>, instr: &Instruction<'a>) -> Result<Option<u32>> {
+        match (self.pending_hint.take(), instr) {
+            (None, _) => Ok(None),
+            (Some(hint), Instruction::If(_) | Instruction::BrIf(_)) => Ok(Some(hint)),
+            (Some(_), _) => Err(parser.error("branch hints are only allowed on `if` and `br_if`")),
+        }
+    }
+
+    /// Parses the `(@metadata.code.branch_hint "\01")` annotation, after its
+    /// opening parenthesis, which applies to the next instruction.
+    fn parse_branch_hint(&mut self, parser: Parser<'a>) -> Result<()> {
+        parser.parse::<annotation::metadata_code_branch_hint>()?;
+        let value = match parser.parse::<&[u8]>()? {
+            [0] => 0,
+            [1] => 1,
+            _ => return Err(parser.error("invalid value for branch hint")),
+        };
+        if self.pending_hint.replace(value).is_some() {
+            return Err(parser.error("multiple branch hints for one instruction"));
+        }
+        Ok(())
+    }
+
     fn parse(&mut self, parser: Parser<'a>) -> Result<()> {
+        let _r = parser.register_annotation("metadata.code.branch_hint");
+
         // Here we parse instructions in a loop, and we do not recursively
         // invoke this parse function to avoid blowing the stack on
         // deeply-recursive parses.
@@ -125,7 +251,12 @@ impl<'a> ExpressionParser<'a> {
             match self.paren(parser)? {
                 // No parenthesis seen? Then we just parse the next instruction
                 // and move on.
-                Paren::None => self.instrs.push(parser.parse()?),
+                Paren::None => {
+                    let span = parser.cur_span();
+                    let instr = parser.parse()?;
+                    let hint = self.take_hint(parser, &instr)?;
+                    self.push_instr(instr, span, hint);
+                }
 
                 // If we see a left-parenthesis then things are a little
                 // special. We handle block-like instructions specially
@@ -136,6 +267,14 @@ impl<'a> ExpressionParser<'a> {
                 // In all cases here we push something onto the `stack` to get
                 // popped when the `)` character is seen.
                 Paren::Left => {
+                    // Branch hint annotations are parsed entirely here and
+                    // apply to whichever instruction is parsed next.
+                    if parser.peek::<annotation::metadata_code_branch_hint>() {
+                        self.parse_branch_hint(parser)?;
+                        self.stack.push(Level::BranchHint);
+                        continue;
+                    }
+
                     // First up is handling `if` parsing, which is funky in a
                     // whole bunch of ways. See the method internally for more
                     // information.
@@ -147,34 +286,38 @@ impl<'a> ExpressionParser<'a> {
                     if self.handle_try_lparen(parser)? {
                         continue;
                     }
-                    match parser.parse()? {
+                    let span = parser.cur_span();
+                    let instr = parser.parse()?;
+                    let hint = self.take_hint(parser, &instr)?;
+                    match instr {
                         // If block/loop show up then we just need to be sure to
                         // push an `end` instruction whenever the `)` token is
                         // seen
                         i @ Instruction::Block(_)
                         | i @ Instruction::Loop(_)
                         | i @ Instruction::Let(_) => {
-                            self.instrs.push(i);
-                            self.stack.push(Level::EndWith(Instruction::End(None)));
+                            self.push_instr(i, span, None);
+                            self.stack
+                                .push(Level::EndWith(Instruction::End(None), None, None));
                         }
 
                         // Parsing an `if` instruction is super tricky, so we
                         // push an `If` scope and we let all our scope-based
                         // parsing handle the remaining items.
                         i @ Instruction::If(_) => {
-                            self.stack.push(Level::If(If::Clause(i)));
+                            self.stack.push(Level::If(If::Clause(i, span, hint)));
                         }
 
                         // Parsing a `try` is easier than `if` but we also push
                         // a `Try` scope to handle the required nested blocks.
                         i @ Instruction::Try(_) => {
-                            self.stack.push(Level::Try(Try::Do(i)));
+                            self.stack.push(Level::Try(Try::Do(i, span)));
                         }
 
                         // Anything else means that we're parsing a nested form
                         // such as `(i32.add ...)` which means that the
                         // instruction we parsed will be coming at the end.
-                        other => self.stack.push(Level::EndWith(other)),
+                        other => self.stack.push(Level::EndWith(other, Some(span), hint)),
                     }
                 }
 
@@ -182,38 +325,45 @@ impl<'a> ExpressionParser<'a> {
                 // guaranteed there's an item in the `stack` stack for us to
                 // pop. We peel that off and take a look at what it says to do.
                 Paren::Right => match self.stack.pop().unwrap() {
-                    Level::EndWith(i) => self.instrs.push(i),
+                    Level::EndWith(i, span, hint) => {
+                        let span = span.unwrap_or_else(|| parser.prev_span());
+                        self.push_instr(i, span, hint);
+                    }
                     Level::IfArm => {}
                     Level::TryArm => {}
+                    Level::BranchHint => {}
 
                     // If an `if` statement hasn't parsed the clause or `then`
                     // block, then that's an error because there weren't enough
                     // items in the `if` statement. Otherwise we're just careful
                     // to terminate with an `end` instruction.
-                    Level::If(If::Clause(_)) => {
+                    Level::If(If::Clause(..)) => {
                         return Err(parser.error("previous `if` had no clause"));
                     }
-                    Level::If(If::Then(_)) => {
+                    Level::If(If::Then(..)) => {
                         return Err(parser.error("previous `if` had no `then`"));
                     }
                     Level::If(_) => {
-                        self.instrs.push(Instruction::End(None));
+                        self.push_instr(Instruction::End(None), parser.prev_span(), None);
                     }
 
                     // The `do` clause is required in a `try` statement, so
                     // we will signal that error here. Otherwise, terminate with
                     // an `end` or `delegate` instruction.
-                    Level::Try(Try::Do(_)) => {
+                    Level::Try(Try::Do(..)) => {
                         return Err(parser.error("previous `try` had no `do`"));
                     }
                     Level::Try(Try::Delegate) => {}
                     Level::Try(_) => {
-                        self.instrs.push(Instruction::End(None));
+                        self.push_instr(Instruction::End(None), parser.prev_span(), None);
                     }
                 },
             }
         }
 
+        if self.pending_hint.is_some() {
+            return Err(parser.error("branch hint must be followed by an instruction"));
+        }
         Ok(())
     }
 
@@ -262,9 +412,9 @@ impl<'a> ExpressionParser<'a> {
         // The first thing parsed in an `if` statement is the clause. If the
         // clause starts with `then`, however, then we know to skip the clause
         // and fall through to below.
-        if let If::Clause(if_instr) = i {
+        if let If::Clause(if_instr, span, hint) = i {
             let instr = mem::replace(if_instr, Instruction::End(None));
-            *i = If::Then(instr);
+            *i = If::Then(instr, *span, *hint);
             if !parser.peek::<kw::then>() {
                 return Ok(false);
             }
@@ -277,9 +427,18 @@ impl<'a> ExpressionParser<'a> {
         //
         // Note that when we see the `then`, that's when we actually add the
         // original `if` instruction to the stream.
-        if let If::Then(if_instr) = i {
+        if let If::Then(if_instr, span, hint) = i {
             let instr = mem::replace(if_instr, Instruction::End(None));
+            // Note that `self.push_instr` can't be used here since `i`
+            // borrows the stack.
+            if let Some(value) = *hint {
+                self.branch_hints.push(BranchHint {
+                    instr_index: self.instrs.len(),
+                    value,
+                });
+            }
             self.instrs.push(instr);
+            self.spans.push(*span);
             *i = If::Else;
             if parser.parse::<Option<kw::then>>()?.is_some() {
                 self.stack.push(Level::IfArm);
@@ -291,9 +450,11 @@ impl<'a> ExpressionParser<'a> {
         // effectively the same as the `then` parsing above
         if let If::Else = i {
             self.instrs.push(Instruction::Else(None));
+            self.spans.push(parser.cur_span());
             if parser.parse::<Option<kw::r#else>>()?.is_some() {
                 if parser.is_empty() {
                     self.instrs.pop();
+                    self.spans.pop();
                 }
                 self.stack.push(Level::IfArm);
                 return Ok(true);
@@ -329,9 +490,10 @@ impl<'a> ExpressionParser<'a> {
         };
 
         // Try statements must start with a `do` block.
-        if let Try::Do(try_instr) = i {
+        if let Try::Do(try_instr, span) = i {
             let instr = mem::replace(try_instr, Instruction::End(None));
             self.instrs.push(instr);
+            self.spans.push(*span);
             if parser.parse::<Option<kw::r#do>>()?.is_some() {
                 // The state is advanced here only if the parse succeeds in
                 // order to strictly require the keyword.
@@ -348,9 +510,11 @@ impl<'a> ExpressionParser<'a> {
         // After a try's `do`, there are several possible kinds of handlers.
         if let Try::CatchOrDelegate = i {
             // `catch` may be followed by more `catch`s or `catch_all`.
+            let span = parser.cur_span();
             if parser.parse::<Option<kw::catch>>()?.is_some() {
                 let evt = parser.parse::<Index<'a>>()?;
                 self.instrs.push(Instruction::Catch(evt));
+                self.spans.push(span);
                 *i = Try::Catch;
                 self.stack.push(Level::TryArm);
                 return Ok(true);
@@ -358,6 +522,7 @@ impl<'a> ExpressionParser<'a> {
             // `catch_all` can only come at the end and has no argument.
             if parser.parse::<Option<kw::catch_all>>()?.is_some() {
                 self.instrs.push(Instruction::CatchAll);
+                self.spans.push(span);
                 *i = Try::End;
                 self.stack.push(Level::TryArm);
                 return Ok(true);
@@ -366,6 +531,7 @@ impl<'a> ExpressionParser<'a> {
             if parser.parse::<Option<kw::delegate>>()?.is_some() {
                 let depth = parser.parse::<Index<'a>>()?;
                 self.instrs.push(Instruction::Delegate(depth));
+                self.spans.push(span);
                 *i = Try::Delegate;
                 match self.paren(parser)? {
                     Paren::Left | Paren::None => return Ok(false),
@@ -376,15 +542,18 @@ impl<'a> ExpressionParser<'a> {
         }
 
         if let Try::Catch = i {
+            let span = parser.cur_span();
             if parser.parse::<Option<kw::catch>>()?.is_some() {
                 let evt = parser.parse::<Index<'a>>()?;
                 self.instrs.push(Instruction::Catch(evt));
+                self.spans.push(span);
                 *i = Try::Catch;
                 self.stack.push(Level::TryArm);
                 return Ok(true);
             }
             if parser.parse::<Option<kw::catch_all>>()?.is_some() {
                 self.instrs.push(Instruction::CatchAll);
+                self.spans.push(span);
                 *i = Try::End;
                 self.stack.push(Level::TryArm);
                 return Ok(true);
@@ -407,6 +576,7 @@ macro_rules! instructions {
         /// A listing of all WebAssembly instructions that can be in a module
         /// that this crate currently parses.
         #[derive(Debug)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize))]
         #[allow(missing_docs)]
         pub enum Instruction<'a> {
             $(
@@ -456,7 +626,34 @@ macro_rules! instructions {
             }
         }
 
+        impl Print for Instruction<'_> {
+            #[allow(non_snake_case)]
+            fn print(&self, out: &mut Output<'_>) {
+                match self {
+                    $(
+                        Instruction::$name $((instructions!(@first x $($arg)*)))? => {
+                            out.word($instr);
+                            $(instructions!(@print out x $($arg)*);)?
+                        }
+                    )*
+                }
+            }
+        }
+
         impl<'a> Instruction<'a> {
+            /// Returns the associated [`MemArg`] if one is available for this
+            /// instruction.
+            #[allow(unused_variables, non_snake_case)]
+            pub fn memarg(&self) -> Option<&MemArg<'a>> {
+                match self {
+                    $(
+                        Instruction::$name $((instructions!(@memarg_binding a $($arg)*)))? => {
+                            instructions!(@get_memarg_ref a $($($arg)*)?)
+                        }
+                    )*
+                }
+            }
+
             /// Returns the associated [`MemArg`] if one is available for this
             /// instruction.
             #[allow(unused_variables, non_snake_case)]
@@ -484,6 +681,10 @@ macro_rules! instructions {
     (@parse $parser:ident LoadOrStoreLane) => (compile_error!("must specify `LoadOrStoreLane` default"));
     (@parse $parser:ident $other:ty) => ($parser.parse::<$other>());
 
+    (@print $out:ident $x:ident MemArg<$amt:tt>) => ($x.print_with_align($out, $amt));
+    (@print $out:ident $x:ident LoadOrStoreLane<$amt:tt>) => ($x.print_with_align($out, $amt));
+    (@print $out:ident $x:ident $other:ty) => (Print::print($x, $out));
+
     // simd opcodes prefixed with `0xfd` get a varuint32 encoding for their payload
     (@encode $dst:ident 0xfd, $simd:tt) => ({
         $dst.push(0xfd);
@@ -495,6 +696,10 @@ macro_rules! instructions {
     (@get_memarg $name:ident LoadOrStoreLane<$amt:tt>) => (Some(&mut $name.memarg));
     (@get_memarg $($other:tt)*) => (None);
 
+    (@get_memarg_ref $name:ident MemArg<$amt:tt>) => (Some($name));
+    (@get_memarg_ref $name:ident LoadOrStoreLane<$amt:tt>) => (Some(&$name.memarg));
+    (@get_memarg_ref $($other:tt)*) => (None);
+
     (@memarg_binding $name:ident MemArg<$amt:tt>) => ($name);
     (@memarg_binding $name:ident LoadOrStoreLane<$amt:tt>) => ($name);
     (@memarg_binding $name:ident $other:ty) => (_);
@@ -582,44 +787,47 @@ instructions! {
         RefFunc(Index<'a>) : [0xd2] : "ref.func",
 
         // function-references proposal
-        RefAsNonNull : [0xd3] : "ref.as_non_null",
-        BrOnNull(Index<'a>) : [0xd4] : "br_on_null",
+        RefAsNonNull : [0xd4] : "ref.as_non_null",
+        BrOnNull(Index<'a>) : [0xd5] : "br_on_null",
         BrOnNonNull(Index<'a>) : [0xd6] : "br_on_non_null",
 
         // gc proposal: eqref
-        RefEq : [0xd5] : "ref.eq",
+        RefEq : [0xd3] : "ref.eq",
 
         // gc proposal: struct
-        StructNew(Index<'a>) : [0xfb, 0x07] : "struct.new",
-        StructNewDefault(Index<'a>) : [0xfb, 0x08] : "struct.new_default",
-        StructGet(StructAccess<'a>) : [0xfb, 0x03] : "struct.get",
-        StructGetS(StructAccess<'a>) : [0xfb, 0x04] : "struct.get_s",
-        StructGetU(StructAccess<'a>) : [0xfb, 0x05] : "struct.get_u",
-        StructSet(StructAccess<'a>) : [0xfb, 0x06] : "struct.set",
+        StructNew(Index<'a>) : [0xfb, 0x00] : "struct.new",
+        StructNewDefault(Index<'a>) : [0xfb, 0x01] : "struct.new_default",
+        StructGet(StructAccess<'a>) : [0xfb, 0x02] : "struct.get",
+        StructGetS(StructAccess<'a>) : [0xfb, 0x03] : "struct.get_s",
+        StructGetU(StructAccess<'a>) : [0xfb, 0x04] : "struct.get_u",
+        StructSet(StructAccess<'a>) : [0xfb, 0x05] : "struct.set",
 
         // gc proposal: array
-        ArrayNew(Index<'a>) : [0xfb, 0x1b] : "array.new",
-        ArrayNewDefault(Index<'a>) : [0xfb, 0x1c] : "array.new_default",
-        ArrayNewFixed(ArrayNewFixed<'a>) : [0xfb, 0x1a] : "array.new_fixed",
-        ArrayNewData(ArrayNewData<'a>) : [0xfb, 0x1d] : "array.new_data",
-        ArrayNewElem(ArrayNewElem<'a>) : [0xfb, 0x10] : "array.new_elem",
-        ArrayGet(Index<'a>) : [0xfb, 0x13] : "array.get",
-        ArrayGetS(Index<'a>) : [0xfb, 0x14] : "array.get_s",
-        ArrayGetU(Index<'a>) : [0xfb, 0x15] : "array.get_u",
-        ArraySet(Index<'a>) : [0xfb, 0x16] : "array.set",
-        ArrayCopy(ArrayCopy<'a>) : [0xfb, 0x18] : "array.copy",
-        ArrayLen : [0xfb, 0x19] : "array.len",
+        ArrayNew(Index<'a>) : [0xfb, 0x06] : "array.new",
+        ArrayNewDefault(Index<'a>) : [0xfb, 0x07] : "array.new_default",
+        ArrayNewFixed(ArrayNewFixed<'a>) : [0xfb, 0x08] : "array.new_fixed",
+        ArrayNewData(ArrayNewData<'a>) : [0xfb, 0x09] : "array.new_data",
+        ArrayNewElem(ArrayNewElem<'a>) : [0xfb, 0x0a] : "array.new_elem",
+        ArrayGet(Index<'a>) : [0xfb, 0x0b] : "array.get",
+        ArrayGetS(Index<'a>) : [0xfb, 0x0c] : "array.get_s",
+        ArrayGetU(Index<'a>) : [0xfb, 0x0d] : "array.get_u",
+        ArraySet(Index<'a>) : [0xfb, 0x0e] : "array.set",
+        ArrayLen : [0xfb, 0x0f] : "array.len",
+        ArrayFill(Index<'a>) : [0xfb, 0x10] : "array.fill",
+        ArrayCopy(ArrayCopy<'a>) : [0xfb, 0x11] : "array.copy",
+        ArrayInitData(ArrayNewData<'a>) : [0xfb, 0x12] : "array.init_data",
+        ArrayInitElem(ArrayNewElem<'a>) : [0xfb, 0x13] : "array.init_elem",
 
         // gc proposal, i31
-        I31New : [0xfb, 0x20] : "i31.new",
-        I31GetS : [0xfb, 0x21] : "i31.get_s",
-        I31GetU : [0xfb, 0x22] : "i31.get_u",
+        RefI31 : [0xfb, 0x1c] : "ref.i31" | "i31.new",
+        I31GetS : [0xfb, 0x1d] : "i31.get_s",
+        I31GetU : [0xfb, 0x1e] : "i31.get_u",
 
         // gc proposal, concrete casting
-        RefTest(Index<'a>) : [0xfb, 0x44] : "ref.test",
-        RefCast(Index<'a>) : [0xfb, 0x45] : "ref.cast",
-        BrOnCast(BrOnCast<'a>) : [0xfb, 0x46] : "br_on_cast",
-        BrOnCastFail(BrOnCast<'a>) : [0xfb, 0x47] : "br_on_cast_fail",
+        RefTest(RefTest<'a>) : [] : "ref.test",
+        RefCast(RefCast<'a>) : [] : "ref.cast",
+        BrOnCast(BrOnCast<'a>) : [0xfb, 0x18] : "br_on_cast",
+        BrOnCastFail(BrOnCast<'a>) : [0xfb, 0x19] : "br_on_cast_fail",
 
         // gc proposal, heap casting
         RefIsFunc : [0xfb, 0x50] : "ref.is_func",
@@ -643,8 +851,8 @@ instructions! {
         BrOnNonArray(Index<'a>) : [0xfb, 0x67] : "br_on_non_array",
 
         // gc proposal extern/any coercion operations
-        ExternInternalize : [0xfb, 0x70] : "extern.internalize",
-        ExternExternalize : [0xfb, 0x71] : "extern.externalize",
+        AnyConvertExtern : [0xfb, 0x1a] : "any.convert_extern" | "extern.internalize",
+        ExternConvertAny : [0xfb, 0x1b] : "extern.convert_any" | "extern.externalize",
 
         I32Const(i32) : [0x41] : "i32.const",
         I64Const(i64) : [0x42] : "i64.const",
@@ -1172,7 +1380,8 @@ impl<'a> Instruction<'a> {
         match self {
             Instruction::MemoryInit(_)
             | Instruction::DataDrop(_)
-            | Instruction::ArrayNewData(_) => true,
+            | Instruction::ArrayNewData(_)
+            | Instruction::ArrayInitData(_) => true,
             _ => false,
         }
     }
@@ -1183,6 +1392,7 @@ impl<'a> Instruction<'a> {
 /// This is used to label blocks and also annotate what types are expected for
 /// the block.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub struct BlockType<'a> {
     pub label: Option<Id<'a>>,
@@ -1204,6 +1414,7 @@ impl<'a> Parse<'a> for BlockType<'a> {
 
 /// Extra information associated with the func.bind instruction.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub struct FuncBindType<'a> {
     pub ty: TypeUse<'a, FunctionType<'a>>,
@@ -1221,6 +1432,7 @@ impl<'a> Parse<'a> for FuncBindType<'a> {
 
 /// Extra information associated with the let instruction.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub struct LetType<'a> {
     pub block: BlockType<'a>,
@@ -1239,6 +1451,7 @@ impl<'a> Parse<'a> for LetType<'a> {
 /// Extra information associated with the `br_table` instruction.
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BrTableIndices<'a> {
     pub labels: Vec<Index<'a>>,
     pub default: Index<'a>,
@@ -1257,6 +1470,7 @@ impl<'a> Parse<'a> for BrTableIndices<'a> {
 
 /// Payload for lane-related instructions. Unsigned with no + prefix.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LaneArg {
     /// The lane argument.
     pub lane: u8,
@@ -1285,6 +1499,7 @@ impl<'a> Parse<'a> for LaneArg {
 /// Payload for memory-related instructions indicating offset/alignment of
 /// memory accesses.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MemArg<'a> {
     /// The alignment of this access.
     ///
@@ -1316,11 +1531,16 @@ impl<'a> MemArg<'a> {
                 if !kw.starts_with('=') {
                     return Ok((None, c));
                 }
+                // Lex the number the same way as any other integer so that
+                // underscores between digits are accepted too.
                 let num = &kw[1..];
-                let num = if let Some(stripped) = num.strip_prefix("0x") {
-                    f(c, stripped, 16)?
-                } else {
-                    f(c, num, 10)?
+                let mut lexer = Lexer::new(num);
+                let num = match (lexer.next(), lexer.next()) {
+                    (Some(Ok(Token::Integer(i))), None) if i.sign().is_none() => {
+                        let (val, radix) = i.val();
+                        f(c, val, radix)?
+                    }
+                    _ => f(c, num, 10)?,
                 };
 
                 Ok((Some(num), rest))
@@ -1360,6 +1580,7 @@ impl<'a> MemArg<'a> {
 
 /// Extra data associated with the `loadN_lane` and `storeN_lane` instructions.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LoadOrStoreLane<'a> {
     /// The memory argument for this instruction.
     pub memarg: MemArg<'a>,
@@ -1414,6 +1635,7 @@ impl<'a> LoadOrStoreLane<'a> {
 
 /// Extra data associated with the `call_indirect` instruction.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CallIndirect<'a> {
     /// The table that this call is going to be indexing.
     pub table: Index<'a>,
@@ -1435,6 +1657,7 @@ impl<'a> Parse<'a> for CallIndirect<'a> {
 
 /// Extra data associated with the `table.init` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TableInit<'a> {
     /// The index of the table we're copying into.
     pub table: Index<'a>,
@@ -1457,6 +1680,7 @@ impl<'a> Parse<'a> for TableInit<'a> {
 
 /// Extra data associated with the `table.copy` instruction.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TableCopy<'a> {
     /// The index of the destination table to copy into.
     pub dst: Index<'a>,
@@ -1479,6 +1703,7 @@ impl<'a> Parse<'a> for TableCopy<'a> {
 
 /// Extra data associated with unary table instructions.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TableArg<'a> {
     /// The index of the table argument.
     pub dst: Index<'a>,
@@ -1497,6 +1722,7 @@ impl<'a> Parse<'a> for TableArg<'a> {
 
 /// Extra data associated with unary memory instructions.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MemoryArg<'a> {
     /// The index of the memory space.
     pub mem: Index<'a>,
@@ -1515,6 +1741,7 @@ impl<'a> Parse<'a> for MemoryArg<'a> {
 
 /// Extra data associated with the `memory.init` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MemoryInit<'a> {
     /// The index of the data segment we're copying into memory.
     pub data: Index<'a>,
@@ -1537,6 +1764,7 @@ impl<'a> Parse<'a> for MemoryInit<'a> {
 
 /// Extra data associated with the `memory.copy` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MemoryCopy<'a> {
     /// The index of the memory we're copying from.
     pub src: Index<'a>,
@@ -1559,6 +1787,7 @@ impl<'a> Parse<'a> for MemoryCopy<'a> {
 
 /// Extra data associated with the `struct.get/set` instructions
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct StructAccess<'a> {
     /// The index of the struct type we're accessing.
     pub r#struct: Index<'a>,
@@ -1577,6 +1806,7 @@ impl<'a> Parse<'a> for StructAccess<'a> {
 
 /// Extra data associated with the `array.copy` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArrayCopy<'a> {
     /// The index of the array type we're copying to.
     pub dest_array: Index<'a>,
@@ -1595,6 +1825,7 @@ impl<'a> Parse<'a> for ArrayCopy<'a> {
 
 /// Extra data associated with the `array.new_fixed` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArrayNewFixed<'a> {
     /// The index of the array type we're accessing.
     pub array: Index<'a>,
@@ -1611,8 +1842,10 @@ impl<'a> Parse<'a> for ArrayNewFixed<'a> {
     }
 }
 
-/// Extra data associated with the `array.new_data` instruction
+/// Extra data associated with the `array.new_data` and `array.init_data`
+/// instructions
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArrayNewData<'a> {
     /// The index of the array type we're accessing.
     pub array: Index<'a>,
@@ -1629,8 +1862,10 @@ impl<'a> Parse<'a> for ArrayNewData<'a> {
     }
 }
 
-/// Extra data associated with the `array.new_elem` instruction
+/// Extra data associated with the `array.new_elem` and `array.init_elem`
+/// instructions
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArrayNewElem<'a> {
     /// The index of the array type we're accessing.
     pub array: Index<'a>,
@@ -1647,26 +1882,64 @@ impl<'a> Parse<'a> for ArrayNewElem<'a> {
     }
 }
 
-/// Extra data associated with the `br_on_cast` instruction
+/// Extra data associated with the `ref.test` instruction
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RefTest<'a> {
+    /// The type to test for.
+    pub r#type: RefType<'a>,
+}
+
+impl<'a> Parse<'a> for RefTest<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        Ok(RefTest {
+            r#type: parser.parse()?,
+        })
+    }
+}
+
+/// Extra data associated with the `ref.cast` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RefCast<'a> {
+    /// The type to cast to.
+    pub r#type: RefType<'a>,
+}
+
+impl<'a> Parse<'a> for RefCast<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        Ok(RefCast {
+            r#type: parser.parse()?,
+        })
+    }
+}
+
+/// Extra data associated with the `br_on_cast` and `br_on_cast_fail`
+/// instructions
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BrOnCast<'a> {
     /// The label to branch to.
     pub label: Index<'a>,
-    /// The index of the type we're casting.
-    pub r#type: Index<'a>,
+    /// The type we're casting from.
+    pub from_type: RefType<'a>,
+    /// The type we're casting to.
+    pub to_type: RefType<'a>,
 }
 
 impl<'a> Parse<'a> for BrOnCast<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         Ok(BrOnCast {
             label: parser.parse()?,
-            r#type: parser.parse()?,
+            from_type: parser.parse()?,
+            to_type: parser.parse()?,
         })
     }
 }
 
 /// Different ways to specify a `v128.const` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum V128Const {
     I8x16([i8; 16]),
@@ -1833,6 +2106,7 @@ impl<'a> Parse<'a> for V128Const {
 
 /// Lanes being shuffled in the `i8x16.shuffle` instruction
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct I8x16Shuffle {
     #[allow(missing_docs)]
     pub lanes: [u8; 16],
@@ -1865,6 +2139,7 @@ impl<'a> Parse<'a> for I8x16Shuffle {
 
 /// Payload of the `select` instructions
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SelectTypes<'a> {
     #[allow(missing_docs)]
     pub tys: Option<Vec<ValType<'a>>>,
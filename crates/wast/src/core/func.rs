@@ -7,6 +7,7 @@ use crate::token::{Id, NameAnnotation, Span};
 ///
 /// This is a member of both the function and code sections.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Func<'a> {
     /// Where this `func` was defined.
     pub span: Span,
@@ -25,8 +26,34 @@ pub struct Func<'a> {
     pub ty: TypeUse<'a, FunctionType<'a>>,
 }
 
+impl<'a> Func<'a> {
+    /// Creates a function of type `ty` which is defined inline with `locals`
+    /// and the instructions of `body`.
+    ///
+    /// The function has no identifier, name or inline exports, which can be
+    /// set afterwards.
+    pub fn new(
+        ty: TypeUse<'a, FunctionType<'a>>,
+        locals: Vec<Local<'a>>,
+        body: Vec<Instruction<'a>>,
+    ) -> Func<'a> {
+        Func {
+            span: Span::from_offset(0),
+            id: None,
+            name: None,
+            exports: InlineExport::default(),
+            kind: FuncKind::Inline {
+                locals,
+                expression: Expression::new(body),
+            },
+            ty,
+        }
+    }
+}
+
 /// Possible ways to define a function in the text format.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum FuncKind<'a> {
     /// A function which is actually defined as an import, such as:
     ///
@@ -82,6 +109,7 @@ impl<'a> Parse<'a> for Func<'a> {
 /// Each local has an optional identifier for name resolution, an optional name
 /// for the custom `name` section, and a value type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Local<'a> {
     /// An identifier that this local is resolved with (optionally) for name
     /// resolution.
@@ -93,6 +121,15 @@ pub struct Local<'a> {
 }
 
 impl<'a> Local<'a> {
+    /// Creates a local of type `ty` without an identifier or name.
+    pub fn new(ty: ValType<'a>) -> Local<'a> {
+        Local {
+            id: None,
+            name: None,
+            ty,
+        }
+    }
+
     pub(crate) fn parse_remainder(parser: Parser<'a>) -> Result<Vec<Local<'a>>> {
         let mut locals = Vec::new();
         while parser.peek2::<kw::local>() {
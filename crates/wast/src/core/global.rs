@@ -5,6 +5,7 @@ use crate::token::{Id, NameAnnotation, Span};
 
 /// A WebAssembly global in a module
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Global<'a> {
     /// Where this `global` was defined.
     pub span: Span,
@@ -23,6 +24,7 @@ pub struct Global<'a> {
 
 /// Different kinds of globals that can be defined in a module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum GlobalKind<'a> {
     /// A global which is actually defined as an import, such as:
     ///
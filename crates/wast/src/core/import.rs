@@ -5,6 +5,7 @@ use crate::token::{Id, NameAnnotation, Span};
 
 /// An `import` statement and entry in a WebAssembly module.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Import<'a> {
     /// Where this `import` was defined
     pub span: Span,
@@ -20,6 +21,11 @@ impl<'a> Parse<'a> for Import<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let span = parser.parse::<kw::import>()?.0;
         let module = parser.parse()?;
+        // Single-level imports, with only a module name, were part of the
+        // module linking proposal and are no longer valid.
+        if !parser.peek::<&str>() {
+            return Err(parser.error("expected the field name of the import after its module name"));
+        }
         let field = parser.parse()?;
         let item = parser.parens(|p| p.parse())?;
         Ok(Import {
@@ -32,6 +38,7 @@ impl<'a> Parse<'a> for Import<'a> {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub struct ItemSig<'a> {
     /// Where this item is defined in the source.
@@ -47,6 +54,7 @@ pub struct ItemSig<'a> {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum ItemKind<'a> {
     Func(TypeUse<'a, FunctionType<'a>>),
@@ -112,6 +120,7 @@ impl<'a> Parse<'a> for ItemSig<'a> {
 /// so it's so far been a bit nicer to have the optionality handled through
 /// `Peek` rather than `Option<T>`.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub struct InlineImport<'a> {
     pub module: &'a str,
@@ -5,6 +5,7 @@ use crate::token::*;
 
 /// A defined WebAssembly memory instance inside of a module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Memory<'a> {
     /// Where this `memory` was defined
     pub span: Span,
@@ -21,6 +22,7 @@ pub struct Memory<'a> {
 
 /// Different syntactical ways a memory can be defined in a module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum MemoryKind<'a> {
     /// This memory is actually an inlined import definition.
     #[allow(missing_docs)]
@@ -59,7 +61,9 @@ impl<'a> Parse<'a> for Memory<'a> {
                 import,
                 ty: parser.parse()?,
             }
-        } else if l.peek::<LParen>() || parser.peek2::<LParen>() {
+        } else if l.peek::<LParen>()
+            || ((parser.peek::<kw::i32>() || parser.peek::<kw::i64>()) && parser.peek2::<LParen>())
+        {
             let is_32 = if parser.parse::<Option<kw::i32>>()?.is_some() {
                 true
             } else {
@@ -91,6 +95,7 @@ impl<'a> Parse<'a> for Memory<'a> {
 
 /// A `data` directive in a WebAssembly module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Data<'a> {
     /// Where this `data` was defined
     pub span: Span,
@@ -111,6 +116,7 @@ pub struct Data<'a> {
 
 /// Different kinds of data segments, either passive or active.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum DataKind<'a> {
     /// A passive data segment which isn't associated with a memory and is
     /// referenced from various instructions.
@@ -158,33 +164,7 @@ impl<'a> Parse<'a> for Data<'a> {
                     parser.parse::<kw::offset>()?;
                     parser.parse()
                 } else {
-                    // This is all that the spec allows, which is that if
-                    // `offset` isn't present then this is "sugar" for a
-                    // single-instruction expression.
-                    let insn = parser.parse()?;
-                    if parser.is_empty() {
-                        return Ok(Expression {
-                            instrs: [insn].into(),
-                        });
-                    }
-
-                    // This is support for what is currently invalid syntax
-                    // according to the strict specification but is otherwise
-                    // present in the spec test suite:
-                    //
-                    //    (data (i32.add (i32.const 0) (i32.const 0)))
-                    //
-                    // Technically the spec says this should be:
-                    //
-                    //    (data (offset ...))
-                    //
-                    // but alas
-                    let expr: Expression = parser.parse()?;
-                    let mut instrs = Vec::from(expr.instrs);
-                    instrs.push(insn);
-                    Ok(Expression {
-                        instrs: instrs.into(),
-                    })
+                    Expression::parse_folded_instruction(parser)
                 }
             })?;
             DataKind::Active { memory, offset }
@@ -206,6 +186,7 @@ impl<'a> Parse<'a> for Data<'a> {
 
 /// Differnet ways the value of a data segment can be defined.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum DataVal<'a> {
     String(&'a [u8]),
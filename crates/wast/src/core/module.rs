@@ -3,10 +3,11 @@ use crate::parser::{Parse, Parser, Result};
 use crate::token::{Id, Index, NameAnnotation, Span};
 use crate::{annotation, kw};
 
-pub use crate::core::resolve::Names;
+pub use crate::core::resolve::{NameKind, Names};
 
 /// A parsed WebAssembly core module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Module<'a> {
     /// Where this `module` was defined
     pub span: Span,
@@ -20,6 +21,7 @@ pub struct Module<'a> {
 
 /// The different kinds of ways to define a module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ModuleKind<'a> {
     /// A module defined in the textual s-expression format.
     Text(Vec<ModuleField<'a>>),
@@ -29,6 +31,21 @@ pub enum ModuleKind<'a> {
 }
 
 impl<'a> Module<'a> {
+    /// Creates a module in the text format from `fields`, for building a
+    /// module in code rather than parsing it.
+    ///
+    /// The module has no identifier or name, and its span is the start of
+    /// the source, which is also where errors are reported for any items
+    /// without a real span.
+    pub fn new(fields: Vec<ModuleField<'a>>) -> Module<'a> {
+        Module {
+            span: Span::from_offset(0),
+            id: None,
+            name: None,
+            kind: ModuleKind::Text(fields),
+        }
+    }
+
     /// Performs a name resolution pass on this [`Module`], resolving all
     /// symbolic names to indices.
     ///
@@ -60,6 +77,30 @@ impl<'a> Module<'a> {
         Ok(names)
     }
 
+    /// Returns the names of the items defined in this [`Module`] without
+    /// resolving it.
+    ///
+    /// Unlike [`Module::resolve`] the AST is left untouched, so any
+    /// [`Index::Id`](crate::token::Index::Id) references and inline
+    /// shorthands are kept as they were written. This is intended for tools
+    /// which analyze the text as written rather than encode it: the returned
+    /// [`Names`] can then resolve a reference on demand with
+    /// [`Names::index`] while traversing the AST.
+    ///
+    /// Items are numbered the same way as by [`Module::resolve`]. Note that
+    /// the names of locals and labels are scoped to their function, so they
+    /// aren't included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if two items of the same kind have the same name.
+    pub fn names(&self) -> std::result::Result<Names<'a>, crate::Error> {
+        match &self.kind {
+            ModuleKind::Text(fields) => crate::core::resolve::names(fields),
+            ModuleKind::Binary(_blobs) => Ok(Default::default()),
+        }
+    }
+
     /// Encodes this [`Module`] to its binary form.
     ///
     /// This function will take the textual representation in [`Module`] and
@@ -83,15 +124,183 @@ impl<'a> Module<'a> {
     /// # Errors
     ///
     /// This function can return an error for name resolution errors and other
-    /// expansion-related errors.
+    /// expansion-related errors, or if a section, function body, or data
+    /// segment is too large to be encoded.
     pub fn encode(&mut self) -> std::result::Result<Vec<u8>, crate::Error> {
         self.resolve()?;
         Ok(match &self.kind {
-            ModuleKind::Text(fields) => crate::core::binary::encode(&self.id, &self.name, fields),
+            ModuleKind::Text(fields) => {
+                crate::core::binary::encode(self.span, &self.id, &self.name, fields)?
+            }
             ModuleKind::Binary(blobs) => blobs.iter().flat_map(|b| b.iter().cloned()).collect(),
         })
     }
 
+    /// Same as [`Module::encode`], but appends the binary form of this module
+    /// to `wasm` instead of returning a new `Vec<u8>`.
+    ///
+    /// This allows reusing one buffer when encoding many modules. If an error
+    /// is returned then `wasm` may have had part of the module appended.
+    pub fn encode_into(&mut self, wasm: &mut Vec<u8>) -> std::result::Result<(), crate::Error> {
+        self.resolve()?;
+        match &self.kind {
+            ModuleKind::Text(fields) => {
+                crate::core::binary::encode_into(self.span, &self.id, &self.name, fields, wasm, None)
+            }
+            ModuleKind::Binary(blobs) => {
+                for blob in blobs {
+                    wasm.extend_from_slice(blob);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Same as [`Module::encode`], but writes the binary form of this module
+    /// to `writer`.
+    ///
+    /// The size of each section is computed before it's written, and then its
+    /// items, such as function bodies or data segments, are written out one at
+    /// a time. Only the largest of those items is held in memory at once
+    /// rather than the whole module, which is useful for large modules written
+    /// to a file or socket.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `writer`, or an error of kind
+    /// [`std::io::ErrorKind::InvalidInput`] wrapping a [`crate::Error`] if
+    /// this module can't be encoded, in which case part of the module may
+    /// have been written already.
+    pub fn encode_to(&mut self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        use crate::core::binary::EncodeError;
+
+        let invalid = |e: crate::Error| std::io::Error::new(std::io::ErrorKind::InvalidInput, e);
+        self.resolve().map_err(invalid)?;
+        match &self.kind {
+            ModuleKind::Text(fields) => {
+                match crate::core::binary::encode_to(
+                    self.span,
+                    &self.id,
+                    &self.name,
+                    fields,
+                    &mut writer,
+                    None,
+                ) {
+                    Ok(()) => Ok(()),
+                    Err(EncodeError::Wast(e)) => Err(invalid(e)),
+                    Err(EncodeError::Io(e)) => Err(e),
+                }
+            }
+            ModuleKind::Binary(blobs) => {
+                for blob in blobs {
+                    writer.write_all(blob)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Same as [`Module::encode`], but also returns the offsets within the
+    /// binary of this module's functions, instructions and data segments.
+    ///
+    /// Offsets of instructions are only reported if the module was parsed
+    /// with [`ParseBuffer::track_instr_spans`](crate::parser::ParseBuffer::track_instr_spans)
+    /// enabled. No offsets are reported for modules written as `(module
+    /// binary ...)`.
+    pub fn encode_with_offsets(
+        &mut self,
+    ) -> std::result::Result<(Vec<u8>, Offsets), crate::Error> {
+        self.resolve()?;
+        let mut offsets = Offsets::default();
+        let wasm = match &self.kind {
+            ModuleKind::Text(fields) => {
+                let mut wasm = Vec::new();
+                crate::core::binary::encode_into(
+                    self.span,
+                    &self.id,
+                    &self.name,
+                    fields,
+                    &mut wasm,
+                    Some(&mut offsets),
+                )?;
+                wasm
+            }
+            ModuleKind::Binary(blobs) => blobs.iter().flat_map(|b| b.iter().cloned()).collect(),
+        };
+        Ok((wasm, offsets))
+    }
+
+    /// Same as [`Module::encode`], but returns the sections of the binary
+    /// separately instead of a whole module.
+    ///
+    /// Each section is returned with its id, such as 10 for the code section
+    /// or 0 for a custom section, along with its contents. The contents don't
+    /// include the section's id and size, so they're suitable for splicing
+    /// into another binary, for example with `wasm_encoder::RawSection`.
+    /// Sections are returned in the order they're encoded, and only the
+    /// sections present in the binary are returned.
+    ///
+    /// Note that the contents of a section refer to items by the indices that
+    /// they have in this module, so splicing them into another binary is only
+    /// meaningful if the items they refer to have the same indices there.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Module::encode`], and an error for
+    /// modules written as `(module binary ...)` since they aren't encoded by
+    /// this crate.
+    pub fn encode_sections(&mut self) -> std::result::Result<Vec<(u8, Vec<u8>)>, crate::Error> {
+        self.resolve()?;
+        match &self.kind {
+            ModuleKind::Text(fields) => {
+                crate::core::binary::encode_sections(self.span, &self.id, &self.name, fields)
+            }
+            ModuleKind::Binary(_) => Err(crate::Error::new(
+                self.span,
+                "sections can't be encoded individually for a binary module".to_string(),
+            )),
+        }
+    }
+
+    /// Same as [`Module::encode`], but also validates the module with the
+    /// given set of `features` enabled.
+    ///
+    /// Validation errors are reported at the location in the text of the
+    /// function, instruction or data segment which failed to validate, and
+    /// otherwise at the start of the module. Errors are only attributed to
+    /// individual instructions if the module was parsed with
+    /// [`ParseBuffer::track_instr_spans`](crate::parser::ParseBuffer::track_instr_spans)
+    /// enabled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the module fails to encode, as with
+    /// [`Module::encode`], or if the encoded module is invalid.
+    #[cfg(feature = "validate")]
+    pub fn encode_validated(
+        &mut self,
+        features: wasmparser::WasmFeatures,
+    ) -> std::result::Result<Vec<u8>, crate::Error> {
+        let (wasm, offsets) = self.encode_with_offsets()?;
+        let mut validator = wasmparser::Validator::new_with_features(features);
+        if let Err(e) = validator.validate_all(&wasm) {
+            // Blame the last item which starts at or before the error, which
+            // works since the code and data sections are the last ones
+            // which are validated.
+            let span = offsets
+                .funcs
+                .iter()
+                .chain(&offsets.instrs)
+                .chain(&offsets.data)
+                .filter(|(_, offset)| *offset <= e.offset())
+                .max_by_key(|(_, offset)| *offset)
+                .map(|(span, _)| *span)
+                .unwrap_or(self.span);
+            return Err(crate::Error::new(span, e.message().to_string()));
+        }
+        Ok(wasm)
+    }
+
     pub(crate) fn validate(&self, parser: Parser<'_>) -> Result<()> {
         let mut starts = 0;
         if let ModuleKind::Text(fields) = &self.kind {
@@ -111,6 +320,10 @@ impl<'a> Module<'a> {
 impl<'a> Parse<'a> for Module<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
         let _r = parser.register_annotation("custom");
+        let _r = parser.register_annotation("producers");
+        let _r = parser.register_annotation("target_features");
+        let _r = parser.register_annotation("dylink.0");
+        let _r = parser.register_custom_annotations();
         let span = parser.parse::<kw::module>()?.0;
         let id = parser.parse()?;
         let name = parser.parse()?;
@@ -137,6 +350,7 @@ impl<'a> Parse<'a> for Module<'a> {
 /// A listing of all possible fields that can make up a WebAssembly module.
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ModuleField<'a> {
     Type(Type<'a>),
     Rec(Rec<'a>),
@@ -202,7 +416,12 @@ impl<'a> Parse<'a> for ModuleField<'a> {
         if parser.peek::<kw::tag>() {
             return Ok(ModuleField::Tag(parser.parse()?));
         }
-        if parser.peek::<annotation::custom>() {
+        if parser.peek::<annotation::custom>()
+            || parser.peek::<annotation::producers>()
+            || parser.peek::<annotation::target_features>()
+            || parser.peek::<annotation::dylink_0>()
+            || parser.peek_custom_annotation()
+        {
             return Ok(ModuleField::Custom(parser.parse()?));
         }
         Err(parser.error("expected valid module field"))
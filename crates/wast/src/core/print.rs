@@ -0,0 +1,1330 @@
+use crate::component::Component;
+use crate::core::*;
+use crate::token::{Float32, Float64, Id, Index, NameAnnotation};
+use std::fmt::Write;
+
+/// Prints a parsed [`Module`] or [`Component`] back to the WebAssembly text
+/// format.
+///
+/// Unlike printing the binary encoding of a module with `wasmprinter`, this
+/// works directly on the AST, so identifiers such as `$f` and the
+/// abbreviations used in the source, such as inline exports, are preserved.
+/// The output is canonically formatted: each module or component field
+/// starts on a new line and instructions are printed one per line, indented
+/// by their nesting depth. Comments aren't part of the AST, so they aren't
+/// printed, but see
+/// [`ParseBuffer::trivia`](crate::parser::ParseBuffer::trivia) for recovering
+/// them.
+///
+/// Modules and components should be printed before they're encoded, since
+/// encoding them expands abbreviations and resolves identifiers in place.
+///
+/// # Examples
+///
+/// ```
+/// use wast::core::Printer;
+/// use wast::parser::{self, ParseBuffer};
+/// use wast::Wat;
+///
+/// # fn foo() -> Result<(), wast::Error> {
+/// let buf = ParseBuffer::new(
+///     "(module (func $f (export \"f\") (result i32) block (result i32) i32.const 1 end))",
+/// )?;
+/// let module = match parser::parse::<Wat>(&buf)? {
+///     Wat::Module(module) => module,
+///     Wat::Component(_) => unreachable!(),
+/// };
+///
+/// let mut printer = Printer::new();
+/// assert_eq!(
+///     printer.print_module(&module),
+///     "(module
+///   (func $f (export \"f\") (result i32)
+///     block (result i32)
+///       i32.const 1
+///     end))",
+/// );
+///
+/// printer.fold_instructions(true);
+/// assert_eq!(
+///     printer.print_module(&module),
+///     "(module
+///   (func $f (export \"f\") (result i32)
+///     (block (result i32)
+///       i32.const 1)))",
+/// );
+/// # Ok(())
+/// # }
+/// # foo().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Printer {
+    fold_instructions: bool,
+    indent_width: usize,
+}
+
+impl Printer {
+    /// Creates a new printer, which prints flat instructions indented by two
+    /// spaces per level.
+    pub fn new() -> Printer {
+        Printer {
+            fold_instructions: false,
+            indent_width: 2,
+        }
+    }
+
+    /// Configures whether `block`, `loop` and `if` instructions are printed
+    /// in their folded s-expression form, such as `(if (then ...) (else
+    /// ...))`, rather than being terminated with `end`.
+    ///
+    /// Other instructions are always printed flat since folding their
+    /// operands requires type information which isn't available in the AST.
+    pub fn fold_instructions(&mut self, fold: bool) -> &mut Self {
+        self.fold_instructions = fold;
+        self
+    }
+
+    /// Configures the number of spaces printed per level of indentation.
+    pub fn indent_width(&mut self, width: usize) -> &mut Self {
+        self.indent_width = width;
+        self
+    }
+
+    /// Prints `module` to a string.
+    pub fn print_module(&self, module: &Module<'_>) -> String {
+        self.print_to_string(module)
+    }
+
+    /// Prints `component` to a string.
+    pub fn print_component(&self, component: &Component<'_>) -> String {
+        self.print_to_string(component)
+    }
+
+    fn print_to_string(&self, item: &dyn Print) -> String {
+        let mut out = Output {
+            config: self,
+            result: String::new(),
+            depth: 0,
+        };
+        item.print(&mut out);
+        out.result
+    }
+}
+
+impl Default for Printer {
+    fn default() -> Printer {
+        Printer::new()
+    }
+}
+
+/// The in-progress text printed by a [`Printer`].
+pub(crate) struct Output<'c> {
+    config: &'c Printer,
+    result: String,
+    pub(crate) depth: usize,
+}
+
+impl Output<'_> {
+    pub(crate) fn newline(&mut self) {
+        self.result.push('\n');
+        for _ in 0..self.depth * self.config.indent_width {
+            self.result.push(' ');
+        }
+    }
+
+    /// Prints `word`, separated from whatever precedes it by a space unless
+    /// it follows an opening parenthesis or the start of a line.
+    pub(crate) fn word(&mut self, word: &str) {
+        match self.result.chars().last() {
+            None | Some('(' | ' ' | '\n') => {}
+            Some(_) => self.result.push(' '),
+        }
+        self.result.push_str(word);
+    }
+
+    pub(crate) fn open(&mut self, keyword: &str) {
+        self.word("(");
+        self.result.push_str(keyword);
+    }
+
+    pub(crate) fn close(&mut self) {
+        self.result.push(')');
+    }
+
+    pub(crate) fn string(&mut self, bytes: &[u8]) {
+        let mut s = String::from("\"");
+        for byte in bytes {
+            match byte {
+                b'"' => s.push_str("\\\""),
+                b'\\' => s.push_str("\\\\"),
+                0x20..=0x7e => s.push(*byte as char),
+                _ => write!(s, "\\{:02x}", byte).unwrap(),
+            }
+        }
+        s.push('"');
+        self.word(&s);
+    }
+
+    pub(crate) fn display(&mut self, value: impl std::fmt::Display) {
+        self.word(&value.to_string());
+    }
+
+    /// Prints the names of the `dylink.0` symbol flags set in `flags`,
+    /// followed by any unknown bits as an integer.
+    fn symbol_flags(&mut self, mut flags: u32) {
+        for (name, bit) in DYLINK_0_SYMBOL_FLAGS {
+            if flags & bit != 0 {
+                self.word(name);
+                flags &= !bit;
+            }
+        }
+        if flags != 0 {
+            self.word(&format!("{:#x}", flags));
+        }
+    }
+
+    fn inline_exports(&mut self, exports: &InlineExport<'_>) {
+        for name in exports.names.iter() {
+            self.open("export");
+            self.string(name.as_bytes());
+            self.close();
+        }
+    }
+
+    fn inline_import(&mut self, import: &InlineImport<'_>) {
+        self.open("import");
+        self.string(import.module.as_bytes());
+        self.string(import.field.as_bytes());
+        self.close();
+    }
+
+    /// Prints a list of parameters or locals, grouping unnamed ones
+    /// together, either on the current line or one group per line.
+    fn typed_list<'b, 'a: 'b>(
+        &mut self,
+        keyword: &str,
+        items: impl IntoIterator<
+            Item = (
+                &'b Option<Id<'a>>,
+                &'b Option<NameAnnotation<'a>>,
+                &'b ValType<'a>,
+            ),
+        >,
+        one_per_line: bool,
+    ) {
+        let mut open = false;
+        for (id, name, ty) in items {
+            let named = id.is_some() || name.is_some();
+            if open && named {
+                self.close();
+                open = false;
+            }
+            if !open {
+                if one_per_line {
+                    self.newline();
+                }
+                self.open(keyword);
+                id.print(self);
+                name.print(self);
+                open = true;
+            }
+            ty.print(self);
+            if named {
+                self.close();
+                open = false;
+            }
+        }
+        if open {
+            self.close();
+        }
+    }
+
+    /// Prints a constant expression, or another short expression, on the
+    /// current line.
+    fn inline_expr(&mut self, expr: &Expression<'_>) {
+        for instr in expr.instrs.iter() {
+            instr.print(self);
+        }
+    }
+
+    /// Prints the instructions of a function body one per line.
+    fn body(&mut self, expr: &Expression<'_>) {
+        enum Frame {
+            Flat,
+            Folded,
+            FoldedIf,
+        }
+        // Only blocks which are terminated with an `end` can be folded.
+        let mut closed = vec![false; expr.instrs.len()];
+        let mut starts = Vec::new();
+        for (i, instr) in expr.instrs.iter().enumerate() {
+            match instr {
+                Instruction::Block(_)
+                | Instruction::Loop(_)
+                | Instruction::If(_)
+                | Instruction::Try(_)
+                | Instruction::Let(_) => starts.push(i),
+                Instruction::End(_) | Instruction::Delegate(_) => {
+                    if let Some(start) = starts.pop() {
+                        closed[start] = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let base = self.depth;
+        let mut frames = Vec::new();
+        let mut hints = expr.branch_hints.iter().peekable();
+        for (i, instr) in expr.instrs.iter().enumerate() {
+            match (instr, frames.last()) {
+                (Instruction::End(_), Some(Frame::Folded)) => {
+                    frames.pop();
+                    self.close();
+                    self.depth -= 1;
+                    continue;
+                }
+                (Instruction::End(_), Some(Frame::FoldedIf)) => {
+                    frames.pop();
+                    self.close();
+                    self.close();
+                    self.depth -= 2;
+                    continue;
+                }
+                (Instruction::Else(_), Some(Frame::FoldedIf)) => {
+                    self.close();
+                    self.depth -= 1;
+                    self.newline();
+                    self.open("else");
+                    self.depth += 1;
+                    continue;
+                }
+                (Instruction::End(_) | Instruction::Delegate(_), _) => {
+                    frames.pop();
+                    self.depth = base.max(self.depth - 1);
+                }
+                (Instruction::Else(_) | Instruction::Catch(_) | Instruction::CatchAll, _) => {
+                    self.depth = base.max(self.depth - 1);
+                }
+                _ => {}
+            }
+
+            self.newline();
+            while let Some(hint) = hints.next_if(|h| h.instr_index <= i) {
+                if hint.instr_index == i {
+                    self.open("@metadata.code.branch_hint");
+                    self.string(&[hint.value as u8]);
+                    self.close();
+                }
+            }
+
+            match instr {
+                Instruction::Block(ty) | Instruction::Loop(ty) if self.config.fold_instructions && closed[i] => {
+                    let keyword = match instr {
+                        Instruction::Block(_) => "block",
+                        _ => "loop",
+                    };
+                    self.open(keyword);
+                    ty.print(self);
+                    frames.push(Frame::Folded);
+                    self.depth += 1;
+                }
+                Instruction::If(ty) if self.config.fold_instructions && closed[i] => {
+                    self.open("if");
+                    ty.print(self);
+                    self.depth += 1;
+                    self.newline();
+                    self.open("then");
+                    self.depth += 1;
+                    frames.push(Frame::FoldedIf);
+                }
+                Instruction::Block(_)
+                | Instruction::Loop(_)
+                | Instruction::If(_)
+                | Instruction::Try(_)
+                | Instruction::Let(_) => {
+                    instr.print(self);
+                    frames.push(Frame::Flat);
+                    self.depth += 1;
+                }
+                Instruction::Else(_) | Instruction::Catch(_) | Instruction::CatchAll => {
+                    instr.print(self);
+                    self.depth += 1;
+                }
+                _ => instr.print(self),
+            }
+        }
+        self.depth = base;
+    }
+}
+
+/// An item of the AST which can be printed in the text format.
+pub(crate) trait Print {
+    fn print(&self, out: &mut Output<'_>);
+}
+
+impl<T: Print> Print for Option<T> {
+    fn print(&self, out: &mut Output<'_>) {
+        if let Some(item) = self {
+            item.print(out);
+        }
+    }
+}
+
+impl<T: Print> Print for [T] {
+    fn print(&self, out: &mut Output<'_>) {
+        for item in self {
+            item.print(out);
+        }
+    }
+}
+
+impl Print for Id<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.word("$");
+        out.result.push_str(self.name());
+    }
+}
+
+impl Print for Index<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            Index::Num(n, _) => out.display(n),
+            Index::Id(id) => id.print(out),
+        }
+    }
+}
+
+impl Print for NameAnnotation<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("@name");
+        out.string(self.name.as_bytes());
+        out.close();
+    }
+}
+
+impl Print for u8 {
+    fn print(&self, out: &mut Output<'_>) {
+        out.display(self);
+    }
+}
+
+impl Print for i8 {
+    fn print(&self, out: &mut Output<'_>) {
+        out.display(self);
+    }
+}
+
+impl Print for i16 {
+    fn print(&self, out: &mut Output<'_>) {
+        out.display(self);
+    }
+}
+
+impl Print for i32 {
+    fn print(&self, out: &mut Output<'_>) {
+        out.display(self);
+    }
+}
+
+impl Print for i64 {
+    fn print(&self, out: &mut Output<'_>) {
+        out.display(self);
+    }
+}
+
+macro_rules! floats {
+    ($($ty:ident($float:ident, $int:ident, $mantissa_bits:tt))*) => ($(
+        impl Print for $ty {
+            fn print(&self, out: &mut Output<'_>) {
+                let value = $float::from_bits(self.bits);
+                let sign = if self.bits >> ($int::BITS - 1) != 0 { "-" } else { "" };
+                if value.is_nan() {
+                    let payload = self.bits & ((1 << $mantissa_bits) - 1);
+                    if payload == 1 << ($mantissa_bits - 1) {
+                        out.word(&format!("{}nan", sign));
+                    } else {
+                        out.word(&format!("{}nan:{:#x}", sign, payload));
+                    }
+                } else if value.is_infinite() {
+                    out.word(&format!("{}inf", sign));
+                } else {
+                    // The `Debug` representation is the shortest one which
+                    // parses back to the same value.
+                    out.word(&format!("{:?}", value));
+                }
+            }
+        }
+    )*)
+}
+
+floats! {
+    Float32(f32, u32, 23)
+    Float64(f64, u64, 52)
+}
+
+impl Print for Module<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("module");
+        self.id.print(out);
+        self.name.print(out);
+        out.depth += 1;
+        match &self.kind {
+            ModuleKind::Text(fields) => {
+                for field in fields {
+                    out.newline();
+                    field.print(out);
+                }
+            }
+            ModuleKind::Binary(blobs) => {
+                out.word("binary");
+                for blob in blobs {
+                    out.newline();
+                    out.string(blob);
+                }
+            }
+        }
+        out.depth -= 1;
+        out.close();
+    }
+}
+
+impl Print for ModuleField<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            ModuleField::Type(t) => t.print(out),
+            ModuleField::Rec(r) => r.print(out),
+            ModuleField::Import(i) => i.print(out),
+            ModuleField::Func(f) => f.print(out),
+            ModuleField::Table(t) => t.print(out),
+            ModuleField::Memory(m) => m.print(out),
+            ModuleField::Global(g) => g.print(out),
+            ModuleField::Export(e) => e.print(out),
+            ModuleField::Start(s) => {
+                out.open("start");
+                s.print(out);
+                out.close();
+            }
+            ModuleField::Elem(e) => e.print(out),
+            ModuleField::Data(d) => d.print(out),
+            ModuleField::Tag(t) => t.print(out),
+            ModuleField::Custom(c) => c.print(out),
+        }
+    }
+}
+
+impl Print for ValType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            ValType::I32 => out.word("i32"),
+            ValType::I64 => out.word("i64"),
+            ValType::F32 => out.word("f32"),
+            ValType::F64 => out.word("f64"),
+            ValType::V128 => out.word("v128"),
+            ValType::Ref(r) => r.print(out),
+        }
+    }
+}
+
+impl Print for HeapType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            HeapType::Func => out.word("func"),
+            HeapType::Extern => out.word("extern"),
+            HeapType::Any => out.word("any"),
+            HeapType::Eq => out.word("eq"),
+            HeapType::Struct => out.word("struct"),
+            HeapType::Array => out.word("array"),
+            HeapType::I31 => out.word("i31"),
+            HeapType::None => out.word("none"),
+            HeapType::NoFunc => out.word("nofunc"),
+            HeapType::NoExtern => out.word("noextern"),
+            HeapType::Index(i) => i.print(out),
+        }
+    }
+}
+
+impl Print for RefType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        let abbreviation = match (self.nullable, &self.heap) {
+            (true, HeapType::Func) => Some("funcref"),
+            (true, HeapType::Extern) => Some("externref"),
+            (true, HeapType::Any) => Some("anyref"),
+            (true, HeapType::Eq) => Some("eqref"),
+            (true, HeapType::Struct) => Some("structref"),
+            (true, HeapType::Array) => Some("arrayref"),
+            (true, HeapType::I31) => Some("i31ref"),
+            (true, HeapType::None) => Some("nullref"),
+            (true, HeapType::NoFunc) => Some("nullfuncref"),
+            (true, HeapType::NoExtern) => Some("nullexternref"),
+            _ => None,
+        };
+        match abbreviation {
+            Some(name) => out.word(name),
+            None => {
+                out.open("ref");
+                if self.nullable {
+                    out.word("null");
+                }
+                self.heap.print(out);
+                out.close();
+            }
+        }
+    }
+}
+
+impl Print for StorageType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            StorageType::I8 => out.word("i8"),
+            StorageType::I16 => out.word("i16"),
+            StorageType::Val(ty) => ty.print(out),
+        }
+    }
+}
+
+/// Prints a field type which may be mutable, such as a global's type.
+fn mutable(out: &mut Output<'_>, mutable: bool, ty: &dyn Print) {
+    if mutable {
+        out.open("mut");
+        ty.print(out);
+        out.close();
+    } else {
+        ty.print(out);
+    }
+}
+
+impl Print for GlobalType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        mutable(out, self.mutable, &self.ty);
+    }
+}
+
+impl Print for Limits {
+    fn print(&self, out: &mut Output<'_>) {
+        out.display(self.min);
+        if let Some(max) = self.max {
+            out.display(max);
+        }
+    }
+}
+
+impl Print for TableType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.limits.print(out);
+        self.elem.print(out);
+    }
+}
+
+impl Print for MemoryType {
+    fn print(&self, out: &mut Output<'_>) {
+        let (shared, page_size_log2) = match self {
+            MemoryType::B32 {
+                limits,
+                shared,
+                page_size_log2,
+            } => {
+                limits.print(out);
+                (shared, page_size_log2)
+            }
+            MemoryType::B64 {
+                limits,
+                shared,
+                page_size_log2,
+            } => {
+                out.word("i64");
+                out.display(limits.min);
+                if let Some(max) = limits.max {
+                    out.display(max);
+                }
+                (shared, page_size_log2)
+            }
+        };
+        if *shared {
+            out.word("shared");
+        }
+        if let Some(p) = page_size_log2 {
+            out.open("pagesize");
+            out.display(1u64 << p);
+            out.close();
+        }
+    }
+}
+
+impl Print for FunctionType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.typed_list(
+            "param",
+            self.params.iter().map(|(id, name, ty)| (id, name, ty)),
+            false,
+        );
+        if !self.results.is_empty() {
+            out.open("result");
+            self.results.print(out);
+            out.close();
+        }
+    }
+}
+
+impl<T: Print> Print for TypeUse<'_, T> {
+    fn print(&self, out: &mut Output<'_>) {
+        if let Some(index) = &self.index {
+            out.open("type");
+            index.print(out);
+            out.close();
+        }
+        self.inline.print(out);
+    }
+}
+
+impl Print for TypeDef<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            TypeDef::Func(f) => {
+                out.open("func");
+                f.print(out);
+            }
+            TypeDef::Struct(s) => {
+                out.open("struct");
+                for field in s.fields.iter() {
+                    out.open("field");
+                    field.id.print(out);
+                    mutable(out, field.mutable, &field.ty);
+                    out.close();
+                }
+            }
+            TypeDef::Array(a) => {
+                out.open("array");
+                mutable(out, a.mutable, &a.ty);
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for Type<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("type");
+        self.id.print(out);
+        self.name.print(out);
+        match self.final_type {
+            Some(final_type) => {
+                out.open("sub");
+                if final_type {
+                    out.word("final");
+                }
+                self.parent.print(out);
+                self.def.print(out);
+                out.close();
+            }
+            None => self.def.print(out),
+        }
+        out.close();
+    }
+}
+
+impl Print for Rec<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("rec");
+        out.depth += 1;
+        for ty in self.types.iter() {
+            out.newline();
+            ty.print(out);
+        }
+        out.depth -= 1;
+        out.close();
+    }
+}
+
+impl Print for Import<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("import");
+        out.string(self.module.as_bytes());
+        out.string(self.field.as_bytes());
+        self.item.print(out);
+        out.close();
+    }
+}
+
+impl Print for ItemSig<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        let (keyword, ty): (_, &dyn Print) = match &self.kind {
+            ItemKind::Func(ty) => ("func", ty),
+            ItemKind::Table(ty) => ("table", ty),
+            ItemKind::Memory(ty) => ("memory", ty),
+            ItemKind::Global(ty) => ("global", ty),
+            ItemKind::Tag(ty) => ("tag", ty),
+        };
+        out.open(keyword);
+        self.id.print(out);
+        self.name.print(out);
+        ty.print(out);
+        out.close();
+    }
+}
+
+impl Print for TagType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            TagType::Exception(ty) => ty.print(out),
+        }
+    }
+}
+
+impl Print for Func<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("func");
+        self.id.print(out);
+        self.name.print(out);
+        out.inline_exports(&self.exports);
+        if let FuncKind::Import(import) = &self.kind {
+            out.inline_import(import);
+        }
+        self.ty.print(out);
+        if let FuncKind::Inline { locals, expression } = &self.kind {
+            out.depth += 1;
+            out.typed_list(
+                "local",
+                locals.iter().map(|l| (&l.id, &l.name, &l.ty)),
+                true,
+            );
+            out.body(expression);
+            out.depth -= 1;
+        }
+        out.close();
+    }
+}
+
+impl Print for Table<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("table");
+        self.id.print(out);
+        self.name.print(out);
+        out.inline_exports(&self.exports);
+        match &self.kind {
+            TableKind::Import { import, ty } => {
+                out.inline_import(import);
+                ty.print(out);
+            }
+            TableKind::Normal { ty, init_expr } => {
+                ty.print(out);
+                if let Some(init) = init_expr {
+                    out.inline_expr(init);
+                }
+            }
+            TableKind::Inline { elem, payload } => {
+                elem.print(out);
+                out.open("elem");
+                match payload {
+                    ElemPayload::Indices(indices) => indices.print(out),
+                    ElemPayload::Exprs { exprs, .. } => items(out, exprs),
+                }
+                out.close();
+            }
+        }
+        out.close();
+    }
+}
+
+/// Prints the expressions of an element segment as `(item ...)` forms.
+fn items(out: &mut Output<'_>, exprs: &[Expression<'_>]) {
+    for expr in exprs {
+        out.open("item");
+        out.inline_expr(expr);
+        out.close();
+    }
+}
+
+impl Print for Memory<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("memory");
+        self.id.print(out);
+        self.name.print(out);
+        out.inline_exports(&self.exports);
+        match &self.kind {
+            MemoryKind::Import { import, ty } => {
+                out.inline_import(import);
+                ty.print(out);
+            }
+            MemoryKind::Normal(ty) => ty.print(out),
+            MemoryKind::Inline { is_32, data } => {
+                if !is_32 {
+                    out.word("i64");
+                }
+                out.open("data");
+                data.print(out);
+                out.close();
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for DataVal<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            DataVal::String(s) => out.string(s),
+            DataVal::Integral(bytes) => out.string(bytes),
+        }
+    }
+}
+
+impl Print for Global<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("global");
+        self.id.print(out);
+        self.name.print(out);
+        out.inline_exports(&self.exports);
+        match &self.kind {
+            GlobalKind::Import(import) => {
+                out.inline_import(import);
+                self.ty.print(out);
+            }
+            GlobalKind::Inline(init) => {
+                self.ty.print(out);
+                out.inline_expr(init);
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for Export<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("export");
+        out.string(self.name.as_bytes());
+        out.open(match self.kind {
+            ExportKind::Func => "func",
+            ExportKind::Table => "table",
+            ExportKind::Memory => "memory",
+            ExportKind::Global => "global",
+            ExportKind::Tag => "tag",
+        });
+        self.item.print(out);
+        out.close();
+        out.close();
+    }
+}
+
+/// Prints the index of a table or memory, which is omitted if it's the
+/// default of zero.
+fn non_default(out: &mut Output<'_>, index: &Index<'_>, keyword: Option<&str>) {
+    if let Index::Num(0, _) = index {
+        return;
+    }
+    match keyword {
+        Some(keyword) => {
+            out.open(keyword);
+            index.print(out);
+            out.close();
+        }
+        None => index.print(out),
+    }
+}
+
+/// Prints the offset of an active data or element segment.
+fn offset(out: &mut Output<'_>, offset: &Expression<'_>) {
+    out.open("offset");
+    out.inline_expr(offset);
+    out.close();
+}
+
+impl Print for Elem<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("elem");
+        self.id.print(out);
+        self.name.print(out);
+        match &self.kind {
+            ElemKind::Passive => {}
+            ElemKind::Declared => out.word("declare"),
+            ElemKind::Active { table, offset: o } => {
+                non_default(out, table, Some("table"));
+                offset(out, o);
+            }
+        }
+        match &self.payload {
+            ElemPayload::Indices(indices) => {
+                out.word("func");
+                indices.print(out);
+            }
+            ElemPayload::Exprs { ty, exprs } => {
+                ty.print(out);
+                items(out, exprs);
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for Data<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("data");
+        self.id.print(out);
+        self.name.print(out);
+        if let DataKind::Active { memory, offset: o } = &self.kind {
+            non_default(out, memory, Some("memory"));
+            offset(out, o);
+        }
+        self.data.print(out);
+        out.close();
+    }
+}
+
+impl Print for Tag<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("tag");
+        self.id.print(out);
+        self.name.print(out);
+        out.inline_exports(&self.exports);
+        if let TagKind::Import(import) = &self.kind {
+            out.inline_import(import);
+        }
+        self.ty.print(out);
+        out.close();
+    }
+}
+
+impl Print for Custom<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            Custom::Raw(raw) => {
+                out.open("@custom");
+                out.string(raw.name.as_bytes());
+                if raw.place != CustomPlace::AfterLast {
+                    raw.place.print(out);
+                }
+                for data in raw.data.iter() {
+                    out.string(data);
+                }
+            }
+            Custom::Producers(producers) => return producers.print(out),
+            Custom::TargetFeatures(features) => {
+                out.open("@target_features");
+                for (policy, feature) in features.features.iter() {
+                    out.open(match policy {
+                        FeaturePolicy::Used => "used",
+                        FeaturePolicy::Disallowed => "disallowed",
+                        FeaturePolicy::Required => "required",
+                    });
+                    out.string(feature.as_bytes());
+                    out.close();
+                }
+            }
+            Custom::Dylink0(dylink) => {
+                out.open("@dylink.0");
+                for section in dylink.subsections.iter() {
+                    match section {
+                        Dylink0Subsection::MemInfo {
+                            memory_size,
+                            memory_align,
+                            table_size,
+                            table_align,
+                        } => {
+                            out.open("mem-info");
+                            out.open("memory");
+                            out.display(memory_size);
+                            out.display(memory_align);
+                            out.close();
+                            out.open("table");
+                            out.display(table_size);
+                            out.display(table_align);
+                            out.close();
+                            out.close();
+                        }
+                        Dylink0Subsection::Needed(libs) => {
+                            out.open("needed");
+                            for lib in libs {
+                                out.string(lib.as_bytes());
+                            }
+                            out.close();
+                        }
+                        Dylink0Subsection::ExportInfo(list) => {
+                            for (name, flags) in list {
+                                out.open("export-info");
+                                out.string(name.as_bytes());
+                                out.symbol_flags(*flags);
+                                out.close();
+                            }
+                        }
+                        Dylink0Subsection::ImportInfo(list) => {
+                            for (module, name, flags) in list {
+                                out.open("import-info");
+                                out.string(module.as_bytes());
+                                out.string(name.as_bytes());
+                                out.symbol_flags(*flags);
+                                out.close();
+                            }
+                        }
+                    }
+                }
+            }
+            // The original annotation can only be parsed by its handler, so
+            // the section it produced is printed instead.
+            Custom::Registered(registered) => {
+                out.open("@custom");
+                out.string(registered.name.as_bytes());
+                out.string(&registered.data);
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for Producers<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        out.open("@producers");
+        for (field, values) in self.fields.iter() {
+            for (name, version) in values {
+                out.open(field);
+                out.string(name.as_bytes());
+                out.string(version.as_bytes());
+                out.close();
+            }
+        }
+        out.close();
+    }
+}
+
+impl Print for CustomPlace {
+    fn print(&self, out: &mut Output<'_>) {
+        let (place, anchor) = match self {
+            CustomPlace::BeforeFirst => ("before", None),
+            CustomPlace::Before(anchor) => ("before", Some(anchor)),
+            CustomPlace::After(anchor) => ("after", Some(anchor)),
+            CustomPlace::AfterLast => ("after", None),
+        };
+        out.open(place);
+        out.word(match anchor {
+            None if place == "before" => "first",
+            None => "last",
+            Some(CustomPlaceAnchor::Type) => "type",
+            Some(CustomPlaceAnchor::Import) => "import",
+            Some(CustomPlaceAnchor::Func) => "func",
+            Some(CustomPlaceAnchor::Table) => "table",
+            Some(CustomPlaceAnchor::Memory) => "memory",
+            Some(CustomPlaceAnchor::Global) => "global",
+            Some(CustomPlaceAnchor::Export) => "export",
+            Some(CustomPlaceAnchor::Start) => "start",
+            Some(CustomPlaceAnchor::Elem) => "elem",
+            Some(CustomPlaceAnchor::Code) => "code",
+            Some(CustomPlaceAnchor::Data) => "data",
+            Some(CustomPlaceAnchor::Tag) => "tag",
+        });
+        out.close();
+    }
+}
+
+// Immediates of instructions, which are printed by the `Print`
+// implementation for `Instruction` generated in `expr.rs`.
+
+impl Print for BlockType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.label.print(out);
+        self.label_name.print(out);
+        self.ty.print(out);
+    }
+}
+
+impl Print for FuncBindType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.ty.print(out);
+    }
+}
+
+impl Print for LetType<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.block.print(out);
+        out.typed_list(
+            "local",
+            self.locals.iter().map(|l| (&l.id, &l.name, &l.ty)),
+            false,
+        );
+    }
+}
+
+impl Print for BrTableIndices<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.labels.print(out);
+        self.default.print(out);
+    }
+}
+
+impl Print for LaneArg {
+    fn print(&self, out: &mut Output<'_>) {
+        out.display(self.lane);
+    }
+}
+
+impl MemArg<'_> {
+    pub(crate) fn print_with_align(&self, out: &mut Output<'_>, default_align: u32) {
+        non_default(out, &self.memory, None);
+        if self.offset != 0 {
+            out.word(&format!("offset={}", self.offset));
+        }
+        if self.align != default_align {
+            out.word(&format!("align={}", self.align));
+        }
+    }
+}
+
+impl LoadOrStoreLane<'_> {
+    pub(crate) fn print_with_align(&self, out: &mut Output<'_>, default_align: u32) {
+        self.memarg.print_with_align(out, default_align);
+        self.lane.print(out);
+    }
+}
+
+impl Print for CallIndirect<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        non_default(out, &self.table, None);
+        self.ty.print(out);
+    }
+}
+
+impl Print for TableInit<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        non_default(out, &self.table, None);
+        self.elem.print(out);
+    }
+}
+
+impl Print for TableCopy<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        if let (Index::Num(0, _), Index::Num(0, _)) = (&self.dst, &self.src) {
+            return;
+        }
+        self.dst.print(out);
+        self.src.print(out);
+    }
+}
+
+impl Print for TableArg<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        non_default(out, &self.dst, None);
+    }
+}
+
+impl Print for MemoryArg<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        non_default(out, &self.mem, None);
+    }
+}
+
+impl Print for MemoryInit<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        non_default(out, &self.mem, None);
+        self.data.print(out);
+    }
+}
+
+impl Print for MemoryCopy<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        if let (Index::Num(0, _), Index::Num(0, _)) = (&self.dst, &self.src) {
+            return;
+        }
+        self.dst.print(out);
+        self.src.print(out);
+    }
+}
+
+impl Print for StructAccess<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.r#struct.print(out);
+        self.field.print(out);
+    }
+}
+
+impl Print for ArrayCopy<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.dest_array.print(out);
+        self.src_array.print(out);
+    }
+}
+
+impl Print for ArrayNewFixed<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.array.print(out);
+        out.display(self.length);
+    }
+}
+
+impl Print for ArrayNewData<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.array.print(out);
+        self.data_idx.print(out);
+    }
+}
+
+impl Print for ArrayNewElem<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.array.print(out);
+        self.elem_idx.print(out);
+    }
+}
+
+impl Print for RefTest<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.r#type.print(out);
+    }
+}
+
+impl Print for RefCast<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.r#type.print(out);
+    }
+}
+
+impl Print for BrOnCast<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        self.label.print(out);
+        self.from_type.print(out);
+        self.to_type.print(out);
+    }
+}
+
+impl Print for V128Const {
+    fn print(&self, out: &mut Output<'_>) {
+        match self {
+            V128Const::I8x16(lanes) => {
+                out.word("i8x16");
+                lanes.print(out);
+            }
+            V128Const::I16x8(lanes) => {
+                out.word("i16x8");
+                lanes.print(out);
+            }
+            V128Const::I32x4(lanes) => {
+                out.word("i32x4");
+                lanes.print(out);
+            }
+            V128Const::I64x2(lanes) => {
+                out.word("i64x2");
+                lanes.print(out);
+            }
+            V128Const::F32x4(lanes) => {
+                out.word("f32x4");
+                lanes.print(out);
+            }
+            V128Const::F64x2(lanes) => {
+                out.word("f64x2");
+                lanes.print(out);
+            }
+        }
+    }
+}
+
+impl Print for I8x16Shuffle {
+    fn print(&self, out: &mut Output<'_>) {
+        self.lanes.print(out);
+    }
+}
+
+impl Print for SelectTypes<'_> {
+    fn print(&self, out: &mut Output<'_>) {
+        if let Some(tys) = &self.tys {
+            out.open("result");
+            tys.print(out);
+            out.close();
+        }
+    }
+}
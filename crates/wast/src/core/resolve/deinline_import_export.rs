@@ -49,23 +49,28 @@ pub fn run(fields: &mut Vec<ModuleField>) {
                     // If data is defined inline insert an explicit `data` module
                     // field here instead, switching this to a `Normal` memory.
                     MemoryKind::Inline { is_32, ref data } => {
-                        let len = data.iter().map(|l| l.len()).sum::<usize>() as u32;
+                        let len = data.iter().map(|l| l.len() as u64).sum::<u64>();
                         let pages = (len + page_size() - 1) / page_size();
                         let kind = MemoryKind::Normal(if is_32 {
+                            // Saturate rather than truncate so an oversized
+                            // memory is still reported as invalid.
+                            let pages = u32::try_from(pages).unwrap_or(u32::MAX);
                             MemoryType::B32 {
                                 limits: Limits {
                                     min: pages,
                                     max: Some(pages),
                                 },
                                 shared: false,
+                                page_size_log2: None,
                             }
                         } else {
                             MemoryType::B64 {
                                 limits: Limits64 {
-                                    min: u64::from(pages),
-                                    max: Some(u64::from(pages)),
+                                    min: pages,
+                                    max: Some(pages),
                                 },
                                 shared: false,
+                                page_size_log2: None,
                             }
                         });
                         let data = match mem::replace(&mut m.kind, kind) {
@@ -85,6 +90,8 @@ pub fn run(fields: &mut Vec<ModuleField>) {
                                     } else {
                                         Instruction::I64Const(0)
                                     }]),
+                                    branch_hints: Box::new([]),
+                                    instr_spans: None,
                                 },
                             },
                             data,
@@ -143,6 +150,8 @@ pub fn run(fields: &mut Vec<ModuleField>) {
                                 table: Index::Id(id),
                                 offset: Expression {
                                     instrs: Box::new([Instruction::I32Const(0)]),
+                                    branch_hints: Box::new([]),
+                                    instr_spans: None,
                                 },
                             },
                             payload,
@@ -210,7 +219,7 @@ pub fn run(fields: &mut Vec<ModuleField>) {
         fields.push(item);
     }
 
-    fn page_size() -> u32 {
+    fn page_size() -> u64 {
         1 << 16
     }
 }
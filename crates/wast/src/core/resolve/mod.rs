@@ -48,22 +48,40 @@ pub fn resolve<'a>(fields: &mut Vec<ModuleField<'a>>) -> Result<Names<'a>, Error
 
     // Expand all `TypeUse` annotations so all necessary `type` nodes are
     // present in the AST.
-    types::expand(fields);
+    let added = types::expand(fields);
 
     // Perform name resolution over all `Index` items to resolve them all to
     // indices instead of symbolic names.
     let resolver = names::resolve(fields)?;
-    Ok(Names { resolver })
+    let implicit_types = added
+        .into_iter()
+        .map(|id| resolver.resolve(&mut Index::Id(id), Ns::Type))
+        .collect::<Result<_, _>>()?;
+    Ok(Names {
+        resolver,
+        implicit_types,
+    })
+}
+
+/// Returns the names defined by `fields` without modifying them.
+pub fn names<'a>(fields: &[ModuleField<'a>]) -> Result<Names<'a>, Error> {
+    let resolver = names::register(fields)?;
+    Ok(Names {
+        resolver,
+        implicit_types: Vec::new(),
+    })
 }
 
 /// Representation of the results of name resolution for a module.
 ///
 /// This structure is returned from the
-/// [`Module::resolve`](crate::core::Module::resolve) function and can be used
+/// [`Module::resolve`](crate::core::Module::resolve) and
+/// [`Module::names`](crate::core::Module::names) functions and can be used
 /// to resolve your own name arguments if you have any.
 #[derive(Default)]
 pub struct Names<'a> {
     resolver: names::Resolver<'a>,
+    implicit_types: Vec<u32>,
 }
 
 impl<'a> Names<'a> {
@@ -106,4 +124,90 @@ impl<'a> Names<'a> {
         self.resolver.resolve(idx, Ns::Global)?;
         Ok(())
     }
+
+    /// Resolves `idx` within the namespace of items of `kind`, returning the
+    /// index it refers to.
+    ///
+    /// If `idx` is a `Num`, it is left as is, but if it's an `Id` then it will
+    /// be looked up in the namespace and converted to a `Num`. If the `Id` is
+    /// not defined then an error will be returned.
+    pub fn resolve(&self, idx: &mut Index<'a>, kind: NameKind) -> Result<u32, Error> {
+        let (ns, desc) = self.resolver.namespace(kind);
+        ns.resolve(idx, desc)
+    }
+
+    /// Returns the index that `idx` refers to within the namespace of items
+    /// of `kind`, leaving `idx` itself as is.
+    ///
+    /// This is useful to look up the references of a module that hasn't been
+    /// resolved, for example one whose names were collected with
+    /// [`Module::names`](crate::core::Module::names).
+    pub fn index(&self, idx: &Index<'a>, kind: NameKind) -> Result<u32, Error> {
+        let mut idx = *idx;
+        self.resolve(&mut idx, kind)
+    }
+
+    /// Returns the index of the item of `kind` named `$name`, or `None` if
+    /// there's no such item.
+    ///
+    /// The `name` is given without its leading `$`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wast::core::NameKind;
+    /// use wast::parser::{self, ParseBuffer};
+    /// use wast::Wat;
+    ///
+    /// # fn foo() -> Result<(), wast::Error> {
+    /// let buf = ParseBuffer::new("(module (table 1 funcref) (table $t 1 funcref))")?;
+    /// let mut module = match parser::parse::<Wat>(&buf)? {
+    ///     Wat::Module(m) => m,
+    ///     Wat::Component(_) => unreachable!(),
+    /// };
+    /// let names = module.resolve()?;
+    /// assert_eq!(names.index_of("t", NameKind::Table), Some(1));
+    /// assert_eq!(names.index_of("t", NameKind::Memory), None);
+    /// # Ok(())
+    /// # }
+    /// # foo().unwrap();
+    /// ```
+    pub fn index_of(&self, name: &str, kind: NameKind) -> Option<u32> {
+        self.resolver.namespace(kind).0.get(name)
+    }
+
+    /// Returns the indices of the function types which were added to the
+    /// module by [`Module::resolve`](crate::core::Module::resolve) for inline
+    /// type annotations, in ascending order.
+    ///
+    /// An inline type annotation such as `(param i32) (result i32)` on a
+    /// function, or `(result i32 i32)` on a block, which doesn't have the same
+    /// signature as an earlier annotation or an explicitly defined function
+    /// type, gets a new type at the end of the type section. These types are
+    /// added in the order that the annotations appear in the text, so their
+    /// indices are the same every time the module is resolved.
+    ///
+    /// This is empty for names returned by
+    /// [`Module::names`](crate::core::Module::names), which doesn't add any
+    /// types.
+    pub fn implicit_types(&self) -> &[u32] {
+        &self.implicit_types
+    }
+}
+
+/// The kinds of items in a core module which can be named, each of which has
+/// its own index space.
+///
+/// This is used to look up names with [`Names`].
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[allow(missing_docs)]
+pub enum NameKind {
+    Func,
+    Table,
+    Memory,
+    Global,
+    Tag,
+    Type,
+    Data,
+    Elem,
 }
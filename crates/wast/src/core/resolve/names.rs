@@ -1,7 +1,7 @@
 use crate::core::resolve::Ns;
 use crate::core::*;
 use crate::names::{resolve_error, Namespace};
-use crate::token::{Id, Index};
+use crate::token::{Id, Index, Span};
 use crate::Error;
 
 pub fn resolve<'a>(fields: &mut Vec<ModuleField<'a>>) -> Result<Resolver<'a>, Error> {
@@ -10,6 +10,15 @@ pub fn resolve<'a>(fields: &mut Vec<ModuleField<'a>>) -> Result<Resolver<'a>, Er
     Ok(resolver)
 }
 
+/// Numbers the items of `fields` without resolving any of their references.
+pub fn register<'a>(fields: &[ModuleField<'a>]) -> Result<Resolver<'a>, Error> {
+    let mut resolver = Resolver::default();
+    for field in fields {
+        resolver.register(field)?;
+    }
+    Ok(resolver)
+}
+
 /// Context structure used to perform name resolution.
 #[derive(Default)]
 pub struct Resolver<'a> {
@@ -70,7 +79,11 @@ impl<'a> Resolver<'a> {
             TypeDef::Func(f) => {
                 let params = f.params.iter().map(|p| p.2).collect();
                 let results = f.results.clone();
-                self.type_info.push(TypeInfo::Func { params, results });
+                self.type_info.push(TypeInfo::Func {
+                    span: ty.span,
+                    params,
+                    results,
+                });
             }
             _ => self.type_info.push(TypeInfo::Other),
         }
@@ -89,9 +102,22 @@ impl<'a> Resolver<'a> {
                 ItemKind::Tag(_) => self.tags.register(i.item.id, "tag")?,
             },
             ModuleField::Global(i) => self.globals.register(i.id, "global")?,
-            ModuleField::Memory(i) => self.memories.register(i.id, "memory")?,
+            ModuleField::Memory(i) => {
+                // Inline data is only turned into its own segment when
+                // resolving, so account for that segment if we're only
+                // numbering items.
+                if let MemoryKind::Inline { .. } = i.kind {
+                    self.datas.alloc();
+                }
+                self.memories.register(i.id, "memory")?
+            }
             ModuleField::Func(i) => self.funcs.register(i.id, "func")?,
-            ModuleField::Table(i) => self.tables.register(i.id, "table")?,
+            ModuleField::Table(i) => {
+                if let TableKind::Inline { .. } = i.kind {
+                    self.elems.alloc();
+                }
+                self.tables.register(i.id, "table")?
+            }
 
             ModuleField::Type(i) => {
                 return self.register_type(i);
@@ -352,6 +378,20 @@ impl<'a> Resolver<'a> {
             Ns::Type => self.types.resolve(idx, "type"),
         }
     }
+
+    /// Returns the namespace of items of `kind` and its description.
+    pub fn namespace(&self, kind: NameKind) -> (&Namespace<'a>, &'static str) {
+        match kind {
+            NameKind::Func => (&self.funcs, "func"),
+            NameKind::Table => (&self.tables, "table"),
+            NameKind::Memory => (&self.memories, "memory"),
+            NameKind::Global => (&self.globals, "global"),
+            NameKind::Tag => (&self.tags, "tag"),
+            NameKind::Type => (&self.types, "type"),
+            NameKind::Data => (&self.datas, "data"),
+            NameKind::Elem => (&self.elems, "elem"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -573,9 +613,13 @@ impl<'a, 'b> ExprResolver<'a, 'b> {
 
             BrOnCast(i) | BrOnCastFail(i) => {
                 self.resolve_label(&mut i.label)?;
-                self.resolver.resolve(&mut i.r#type, Ns::Type)?;
+                self.resolver.resolve_heaptype(&mut i.from_type.heap)?;
+                self.resolver.resolve_heaptype(&mut i.to_type.heap)?;
             }
 
+            RefTest(i) => self.resolver.resolve_heaptype(&mut i.r#type.heap)?,
+            RefCast(i) => self.resolver.resolve_heaptype(&mut i.r#type.heap)?,
+
             BrOnFunc(l) | BrOnData(l) | BrOnI31(l) | BrOnArray(l) | BrOnNonFunc(l)
             | BrOnNonData(l) | BrOnNonI31(l) | BrOnNonArray(l) => {
                 self.resolve_label(l)?;
@@ -589,8 +633,8 @@ impl<'a, 'b> ExprResolver<'a, 'b> {
                 }
             }
 
-            RefTest(i) | RefCast(i) | StructNew(i) | StructNewDefault(i) | ArrayNew(i)
-            | ArrayNewDefault(i) | ArrayGet(i) | ArrayGetS(i) | ArrayGetU(i) | ArraySet(i) => {
+            StructNew(i) | StructNewDefault(i) | ArrayNew(i) | ArrayNewDefault(i) | ArrayGet(i)
+            | ArrayGetS(i) | ArrayGetU(i) | ArraySet(i) | ArrayFill(i) => {
                 self.resolver.resolve(i, Ns::Type)?;
             }
 
@@ -602,11 +646,11 @@ impl<'a, 'b> ExprResolver<'a, 'b> {
             ArrayNewFixed(a) => {
                 self.resolver.resolve(&mut a.array, Ns::Type)?;
             }
-            ArrayNewData(a) => {
+            ArrayNewData(a) | ArrayInitData(a) => {
                 self.resolver.resolve(&mut a.array, Ns::Type)?;
                 self.resolver.datas.resolve(&mut a.data_idx, "data")?;
             }
-            ArrayNewElem(a) => {
+            ArrayNewElem(a) | ArrayInitElem(a) => {
                 self.resolver.resolve(&mut a.array, Ns::Type)?;
                 self.resolver.elems.resolve(&mut a.elem_idx, "elem")?;
             }
@@ -646,6 +690,7 @@ impl<'a, 'b> ExprResolver<'a, 'b> {
 
 enum TypeInfo<'a> {
     Func {
+        span: Span,
         params: Box<[ValType<'a>]>,
         results: Box<[ValType<'a>]>,
     },
@@ -663,8 +708,12 @@ impl<'a> TypeReference<'a> for FunctionType<'a> {
             Index::Num(n, _) => *n,
             Index::Id(_) => panic!("expected `Num`"),
         };
-        let (params, results) = match cx.type_info.get(n as usize) {
-            Some(TypeInfo::Func { params, results }) => (params, results),
+        let (span, params, results) = match cx.type_info.get(n as usize) {
+            Some(TypeInfo::Func {
+                span,
+                params,
+                results,
+            }) => (*span, params, results),
             _ => return Ok(()),
         };
 
@@ -696,7 +745,8 @@ impl<'a> TypeReference<'a> for FunctionType<'a> {
             return Err(Error::new(
                 idx.span(),
                 format!("inline function type doesn't match type reference"),
-            ));
+            )
+            .with_note(span, "referenced type defined here".to_string()));
         }
 
         Ok(())
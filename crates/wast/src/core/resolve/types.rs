@@ -1,11 +1,20 @@
 use crate::core::*;
 use crate::gensym;
-use crate::token::{Index, Span};
+use crate::token::{Id, Index, Span};
 use std::collections::HashMap;
 
-pub fn expand<'a>(fields: &mut Vec<ModuleField<'a>>) {
+/// Expands the inline type annotations of `fields`, returning the ids of the
+/// types which were added for them in the order they were added.
+///
+/// Each inline type annotation refers to the first type definition with the
+/// same signature, if any. Otherwise a new type is appended to `fields` for
+/// the first annotation with that signature. Fields are visited in order, as
+/// are the instructions of each expression, so the types that are added only
+/// depend on the text of the module.
+pub fn expand<'a>(fields: &mut Vec<ModuleField<'a>>) -> Vec<Id<'a>> {
     let mut expander = Expander::default();
     expander.process(fields);
+    expander.added
 }
 
 #[derive(Default)]
@@ -19,6 +28,9 @@ pub(crate) struct Expander<'a> {
     /// currently-being-processed field. This should always be empty after
     /// processing is complete.
     to_prepend: Vec<ModuleField<'a>>,
+
+    /// The ids of the types added for inline type annotations.
+    added: Vec<Id<'a>>,
 }
 
 impl<'a> Expander<'a> {
@@ -51,11 +63,14 @@ impl<'a> Expander<'a> {
         match item {
             ModuleField::Type(ty) => {
                 let id = gensym::fill(ty.span, &mut ty.id);
+                // Only final types without a parent are equivalent to the
+                // types implied by inline type annotations.
+                let is_plain = ty.parent.is_none() && ty.final_type != Some(false);
                 match &mut ty.def {
-                    TypeDef::Func(f) => {
+                    TypeDef::Func(f) if is_plain => {
                         f.key().insert(self, Index::Id(id));
                     }
-                    TypeDef::Array(_) | TypeDef::Struct(_) => {}
+                    TypeDef::Func(_) | TypeDef::Array(_) | TypeDef::Struct(_) => {}
                 }
             }
             _ => {}
@@ -218,7 +233,9 @@ impl<'a> Expander<'a> {
             name: None,
             def: key.to_def(span),
             parent: None,
+            final_type: None,
         }));
+        self.added.push(id);
         let idx = Index::Id(id);
         key.insert(self, idx);
         idx
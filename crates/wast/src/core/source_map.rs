@@ -0,0 +1,98 @@
+use crate::core::Offsets;
+use std::fmt::Write;
+
+impl Offsets {
+    /// Returns a [source map], in version 3 of the format, which maps the
+    /// code offsets of these functions and instructions back to `text`.
+    ///
+    /// `text` must be the source that the module was parsed from, and
+    /// `source` is the name it's given in the map, typically the path of the
+    /// `*.wat` file relative to the map. The text is also embedded in the map
+    /// so that it's usable on its own.
+    ///
+    /// As is conventional for WebAssembly, the map describes a single line
+    /// whose columns are offsets within the module's binary. A browser can
+    /// find the map through a `sourceMappingURL` custom section holding its
+    /// URL, which can be added with `(@custom "sourceMappingURL" "...")` or
+    /// appended to the binary after encoding since it doesn't affect any
+    /// other offsets.
+    ///
+    /// [source map]: https://sourcemaps.info/spec.html
+    pub fn source_map(&self, text: &str, source: &str) -> String {
+        let mut entries = self.funcs.iter().chain(&self.instrs).collect::<Vec<_>>();
+        entries.sort_by_key(|(_, offset)| *offset);
+        entries.dedup_by_key(|(_, offset)| *offset);
+
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect::<Vec<_>>();
+
+        let mut mappings = String::new();
+        let mut prev = (0, 0, 0);
+        for (span, offset) in entries {
+            let line = match line_starts.binary_search(&span.offset()) {
+                Ok(i) => i,
+                Err(i) => i - 1,
+            };
+            // Columns are counted in UTF-16 code units.
+            let col = text[line_starts[line]..span.offset()]
+                .encode_utf16()
+                .count();
+            if !mappings.is_empty() {
+                mappings.push(',');
+            }
+            vlq(&mut mappings, *offset as i64 - prev.0 as i64);
+            vlq(&mut mappings, 0);
+            vlq(&mut mappings, line as i64 - prev.1 as i64);
+            vlq(&mut mappings, col as i64 - prev.2 as i64);
+            prev = (*offset, line, col);
+        }
+
+        let mut map = String::new();
+        map.push_str("{\"version\":3,\"sources\":[");
+        json_string(&mut map, source);
+        map.push_str("],\"sourcesContent\":[");
+        json_string(&mut map, text);
+        map.push_str("],\"names\":[],\"mappings\":\"");
+        map.push_str(&mappings);
+        map.push_str("\"}");
+        map
+    }
+}
+
+/// Appends `value` encoded as a base64 VLQ.
+fn vlq(dst: &mut String, value: i64) {
+    const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    // The sign is stored in the lowest bit, and then 5 bits are emitted at a
+    // time with the sixth bit set if more follow.
+    let mut value = (value.unsigned_abs() << 1) | u64::from(value < 0);
+    loop {
+        let mut digit = value & 0x1f;
+        value >>= 5;
+        if value != 0 {
+            digit |= 0x20;
+        }
+        dst.push(BASE64[digit as usize] as char);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// Appends `s` as a quoted JSON string.
+pub(crate) fn json_string(dst: &mut String, s: &str) {
+    dst.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => dst.push_str("\\\""),
+            '\\' => dst.push_str("\\\\"),
+            '\n' => dst.push_str("\\n"),
+            '\r' => dst.push_str("\\r"),
+            '\t' => dst.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(dst, "\\u{:04x}", c as u32).unwrap(),
+            c => dst.push(c),
+        }
+    }
+    dst.push('"');
+}
@@ -5,6 +5,7 @@ use crate::token::{Id, Index, LParen, NameAnnotation, Span};
 
 /// A WebAssembly `table` directive in a module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Table<'a> {
     /// Where this table was defined.
     pub span: Span,
@@ -21,6 +22,7 @@ pub struct Table<'a> {
 
 /// Different ways to textually define a table.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TableKind<'a> {
     /// This table is actually an inlined import definition.
     #[allow(missing_docs)]
@@ -101,6 +103,7 @@ impl<'a> Parse<'a> for Table<'a> {
 
 /// An `elem` segment in a WebAssembly module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Elem<'a> {
     /// Where this `elem` was defined.
     pub span: Span,
@@ -116,6 +119,7 @@ pub struct Elem<'a> {
 
 /// Different ways to define an element segment in an mdoule.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ElemKind<'a> {
     /// A passive segment that isn't associated with a table and can be used in
     /// various bulk-memory instructions.
@@ -136,6 +140,7 @@ pub enum ElemKind<'a> {
 
 /// Different ways to define the element segment payload in a module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ElemPayload<'a> {
     /// This element segment has a contiguous list of function indices
     Indices(Vec<Index<'a>>),
@@ -177,8 +182,10 @@ impl<'a> Parse<'a> for Elem<'a> {
             let offset = parser.parens(|parser| {
                 if parser.peek::<kw::offset>() {
                     parser.parse::<kw::offset>()?;
+                    parser.parse()
+                } else {
+                    Expression::parse_folded_instruction(parser)
                 }
-                parser.parse()
             })?;
             ElemKind::Active { table, offset }
         } else {
@@ -228,9 +235,16 @@ impl<'a> ElemPayload<'a> {
                 } else {
                     // Without `item` this is "sugar" for a single-instruction
                     // expression.
+                    let span = parser.cur_span();
                     let insn = parser.parse()?;
                     Ok(Expression {
                         instrs: [insn].into(),
+                        branch_hints: Box::new([]),
+                        instr_spans: if parser.track_instr_spans() {
+                            Some([span].into())
+                        } else {
+                            None
+                        },
                     })
                 }
             })?;
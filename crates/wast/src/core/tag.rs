@@ -5,6 +5,7 @@ use crate::token::{Id, NameAnnotation, Span};
 
 /// A WebAssembly tag directive, part of the exception handling proposal.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Tag<'a> {
     /// Where this tag was defined
     pub span: Span,
@@ -22,6 +23,7 @@ pub struct Tag<'a> {
 
 /// Listing of various types of tags that can be defined in a wasm module.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TagType<'a> {
     /// An exception tag, where the payload is the type signature of the tag
     /// (constructor parameters, etc).
@@ -30,6 +32,7 @@ pub enum TagType<'a> {
 
 /// Different kinds of tags that can be defined in a module.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TagKind<'a> {
     /// An tag which is actually defined as an import, such as:
     ///
@@ -7,6 +7,7 @@ use std::mem;
 /// The value types for a wasm module.
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ValType<'a> {
     I32,
     I64,
@@ -59,6 +60,7 @@ impl<'a> Peek for ValType<'a> {
 /// A heap type for a reference type
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum HeapType<'a> {
     /// An untyped function reference: funcref. This is part of the reference
     /// types proposal.
@@ -79,6 +81,15 @@ pub enum HeapType<'a> {
     /// An unboxed 31-bit integer: i31ref. This may be going away if there is no common
     /// supertype of all reference types. Part of the GC proposal.
     I31,
+    /// The bottom type of the `any` hierarchy, with no values other than
+    /// null: nullref. Part of the GC proposal.
+    None,
+    /// The bottom type of the `func` hierarchy: nullfuncref. Part of the GC
+    /// proposal.
+    NoFunc,
+    /// The bottom type of the `extern` hierarchy: nullexternref. Part of the
+    /// GC proposal.
+    NoExtern,
     /// A reference to a function, struct, or array: ref T. This is part of the
     /// GC proposal.
     Index(Index<'a>),
@@ -108,6 +119,15 @@ impl<'a> Parse<'a> for HeapType<'a> {
         } else if l.peek::<kw::i31>() {
             parser.parse::<kw::i31>()?;
             Ok(HeapType::I31)
+        } else if l.peek::<kw::none>() {
+            parser.parse::<kw::none>()?;
+            Ok(HeapType::None)
+        } else if l.peek::<kw::nofunc>() {
+            parser.parse::<kw::nofunc>()?;
+            Ok(HeapType::NoFunc)
+        } else if l.peek::<kw::noextern>() {
+            parser.parse::<kw::noextern>()?;
+            Ok(HeapType::NoExtern)
         } else if l.peek::<Index>() {
             Ok(HeapType::Index(parser.parse()?))
         } else {
@@ -125,6 +145,9 @@ impl<'a> Peek for HeapType<'a> {
             || kw::r#struct::peek(cursor)
             || kw::array::peek(cursor)
             || kw::i31::peek(cursor)
+            || kw::none::peek(cursor)
+            || kw::nofunc::peek(cursor)
+            || kw::noextern::peek(cursor)
             || (LParen::peek(cursor) && kw::r#type::peek2(cursor))
     }
     fn display() -> &'static str {
@@ -135,6 +158,7 @@ impl<'a> Peek for HeapType<'a> {
 /// A reference type in a wasm module.
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct RefType<'a> {
     pub nullable: bool,
     pub heap: HeapType<'a>,
@@ -196,6 +220,30 @@ impl<'a> RefType<'a> {
             heap: HeapType::I31,
         }
     }
+
+    /// A `nullref` as an abbreviation for `(ref null none)`.
+    pub fn nullref() -> Self {
+        RefType {
+            nullable: true,
+            heap: HeapType::None,
+        }
+    }
+
+    /// A `nullfuncref` as an abbreviation for `(ref null nofunc)`.
+    pub fn nullfuncref() -> Self {
+        RefType {
+            nullable: true,
+            heap: HeapType::NoFunc,
+        }
+    }
+
+    /// A `nullexternref` as an abbreviation for `(ref null noextern)`.
+    pub fn nullexternref() -> Self {
+        RefType {
+            nullable: true,
+            heap: HeapType::NoExtern,
+        }
+    }
 }
 
 impl<'a> Parse<'a> for RefType<'a> {
@@ -225,6 +273,15 @@ impl<'a> Parse<'a> for RefType<'a> {
         } else if l.peek::<kw::i31ref>() {
             parser.parse::<kw::i31ref>()?;
             Ok(RefType::i31())
+        } else if l.peek::<kw::nullref>() {
+            parser.parse::<kw::nullref>()?;
+            Ok(RefType::nullref())
+        } else if l.peek::<kw::nullfuncref>() {
+            parser.parse::<kw::nullfuncref>()?;
+            Ok(RefType::nullfuncref())
+        } else if l.peek::<kw::nullexternref>() {
+            parser.parse::<kw::nullexternref>()?;
+            Ok(RefType::nullexternref())
         } else if l.peek::<LParen>() {
             parser.parens(|p| {
                 let mut l = parser.lookahead1();
@@ -261,6 +318,9 @@ impl<'a> Peek for RefType<'a> {
             || kw::structref::peek(cursor)
             || kw::arrayref::peek(cursor)
             || kw::i31ref::peek(cursor)
+            || kw::nullref::peek(cursor)
+            || kw::nullfuncref::peek(cursor)
+            || kw::nullexternref::peek(cursor)
             || (LParen::peek(cursor) && kw::r#ref::peek2(cursor))
     }
     fn display() -> &'static str {
@@ -271,6 +331,7 @@ impl<'a> Peek for RefType<'a> {
 /// The types of values that may be used in a struct or array.
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum StorageType<'a> {
     I8,
     I16,
@@ -296,6 +357,7 @@ impl<'a> Parse<'a> for StorageType<'a> {
 
 /// Type for a `global` in a wasm module
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GlobalType<'a> {
     /// The element type of this `global`
     pub ty: ValType<'a>,
@@ -324,6 +386,7 @@ impl<'a> Parse<'a> for GlobalType<'a> {
 
 /// Min/max limits used for tables/memories.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Limits {
     /// The minimum number of units for this type.
     pub min: u32,
@@ -345,6 +408,7 @@ impl<'a> Parse<'a> for Limits {
 
 /// Min/max limits used for 64-bit memories
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Limits64 {
     /// The minimum number of units for this type.
     pub min: u64,
@@ -366,6 +430,7 @@ impl<'a> Parse<'a> for Limits64 {
 
 /// Configuration for a table of a wasm mdoule
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TableType<'a> {
     /// Limits on the element sizes of this table
     pub limits: Limits,
@@ -384,6 +449,7 @@ impl<'a> Parse<'a> for TableType<'a> {
 
 /// Configuration for a memory of a wasm module
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum MemoryType {
     /// A 32-bit memory
     B32 {
@@ -391,6 +457,9 @@ pub enum MemoryType {
         limits: Limits,
         /// Whether or not this is a shared (atomic) memory type
         shared: bool,
+        /// The log2 of this memory's custom page size, from the
+        /// custom-page-sizes proposal, if it doesn't use the default size.
+        page_size_log2: Option<u32>,
     },
     /// A 64-bit memory
     B64 {
@@ -398,6 +467,9 @@ pub enum MemoryType {
         limits: Limits64,
         /// Whether or not this is a shared (atomic) memory type
         shared: bool,
+        /// The log2 of this memory's custom page size, from the
+        /// custom-page-sizes proposal, if it doesn't use the default size.
+        page_size_log2: Option<u32>,
     },
 }
 
@@ -407,18 +479,45 @@ impl<'a> Parse<'a> for MemoryType {
             parser.parse::<kw::i64>()?;
             let limits = parser.parse()?;
             let shared = parser.parse::<Option<kw::shared>>()?.is_some();
-            Ok(MemoryType::B64 { limits, shared })
+            let page_size_log2 = page_size_log2(parser)?;
+            Ok(MemoryType::B64 {
+                limits,
+                shared,
+                page_size_log2,
+            })
         } else {
             parser.parse::<Option<kw::i32>>()?;
             let limits = parser.parse()?;
             let shared = parser.parse::<Option<kw::shared>>()?.is_some();
-            Ok(MemoryType::B32 { limits, shared })
+            let page_size_log2 = page_size_log2(parser)?;
+            Ok(MemoryType::B32 {
+                limits,
+                shared,
+                page_size_log2,
+            })
         }
     }
 }
 
+/// Parses an optional `(pagesize N)` clause, returning the log2 of `N`.
+fn page_size_log2(parser: Parser<'_>) -> Result<Option<u32>> {
+    if !parser.peek2::<kw::pagesize>() {
+        return Ok(None);
+    }
+    parser.parens(|p| {
+        p.parse::<kw::pagesize>()?;
+        let span = p.cur_span();
+        let size = p.parse::<u64>()?;
+        if !size.is_power_of_two() {
+            return Err(p.error_at(span, "invalid custom page size"));
+        }
+        Ok(Some(size.trailing_zeros()))
+    })
+}
+
 /// A function type with parameters and results.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FunctionType<'a> {
     /// The parameters of a function, optionally each having an identifier for
     /// name resolution and a name for the custom `name` section.
@@ -428,6 +527,14 @@ pub struct FunctionType<'a> {
 }
 
 impl<'a> FunctionType<'a> {
+    /// Creates a function type with unnamed `params` and `results`.
+    pub fn new(params: Vec<ValType<'a>>, results: Vec<ValType<'a>>) -> FunctionType<'a> {
+        FunctionType {
+            params: params.into_iter().map(|ty| (None, None, ty)).collect(),
+            results: results.into(),
+        }
+    }
+
     fn finish_parse(&mut self, allow_names: bool, parser: Parser<'a>) -> Result<()> {
         let mut params = Vec::from(mem::take(&mut self.params));
         let mut results = Vec::from(mem::take(&mut self.results));
@@ -503,6 +610,7 @@ impl<'a> Peek for FunctionType<'a> {
 
 /// A function type with parameters and results.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FunctionTypeNoNames<'a>(pub FunctionType<'a>);
 
 impl<'a> Parse<'a> for FunctionTypeNoNames<'a> {
@@ -534,6 +642,7 @@ impl<'a> From<FunctionTypeNoNames<'a>> for FunctionType<'a> {
 
 /// A struct type with fields.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct StructType<'a> {
     /// The fields of the struct
     pub fields: Vec<StructField<'a>>,
@@ -559,6 +668,7 @@ impl<'a> Parse<'a> for StructType<'a> {
 
 /// A field of a struct type.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct StructField<'a> {
     /// An optional identifier for name resolution.
     pub id: Option<Id<'a>>,
@@ -586,6 +696,7 @@ impl<'a> StructField<'a> {
 
 /// An array type with fields.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ArrayType<'a> {
     /// Whether this field may be mutated or not.
     pub mutable: bool,
@@ -610,6 +721,7 @@ impl<'a> Parse<'a> for ArrayType<'a> {
 
 /// The type of an exported item from a module or instance.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ExportType<'a> {
     /// Where this export was defined.
     pub span: Span,
@@ -630,6 +742,7 @@ impl<'a> Parse<'a> for ExportType<'a> {
 
 /// A definition of a type.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum TypeDef<'a> {
     /// A function type definition.
     Func(FunctionType<'a>),
@@ -659,6 +772,7 @@ impl<'a> Parse<'a> for TypeDef<'a> {
 
 /// A type declaration in a module
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Type<'a> {
     /// Where this type was defined.
     pub span: Span,
@@ -671,20 +785,47 @@ pub struct Type<'a> {
     pub def: TypeDef<'a>,
     /// The declared parent type of this definition.
     pub parent: Option<Index<'a>>,
+    /// Whether this type was declared with `sub`, and if so whether it was
+    /// declared `final`.
+    ///
+    /// Types declared without `sub` are final and have no parent, so `None`
+    /// and `Some(true)` only differ in how they were written.
+    pub final_type: Option<bool>,
 }
 
 impl<'a> Type<'a> {
-    fn parse_inner(parser: Parser<'a>, parent: Option<Index<'a>>) -> Result<Self> {
+    fn parse_inner(parser: Parser<'a>, legacy_parent: Option<Option<Index<'a>>>) -> Result<Self> {
         let span = parser.parse::<kw::r#type>()?.0;
         let id = parser.parse()?;
         let name = parser.parse()?;
-        let def = parser.parens(|parser| parser.parse())?;
+        let (def, parent, final_type) = parser.parens(|parser| {
+            if !parser.peek::<kw::sub>() {
+                let def = parser.parse()?;
+                return Ok(match legacy_parent {
+                    Some(parent) => (def, parent, Some(false)),
+                    None => (def, None, None),
+                });
+            }
+            if legacy_parent.is_some() {
+                return Err(parser.error("type is already declared with `sub`"));
+            }
+            parser.parse::<kw::sub>()?;
+            let final_type = parser.parse::<Option<kw::r#final>>()?.is_some();
+            let parent = if parser.peek::<Index<'a>>() {
+                Some(parser.parse()?)
+            } else {
+                None
+            };
+            let def = parser.parens(|parser| parser.parse())?;
+            Ok((def, parent, Some(final_type)))
+        })?;
         Ok(Type {
             span,
             id,
             name,
             def,
             parent,
+            final_type,
         })
     }
 }
@@ -700,6 +841,9 @@ impl<'a> Peek for Type<'a> {
 
 impl<'a> Parse<'a> for Type<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
+        // An older form of subtyping which wraps the whole type declaration,
+        // `(sub $parent (type ...))`, is still accepted and is equivalent to
+        // `(type (sub $parent ...))`.
         if parser.peek::<kw::sub>() {
             parser.parse::<kw::sub>()?;
             let parent = if parser.peek::<Index<'a>>() {
@@ -707,7 +851,7 @@ impl<'a> Parse<'a> for Type<'a> {
             } else {
                 None
             };
-            return parser.parens(|parser| Type::parse_inner(parser, parent));
+            return parser.parens(|parser| Type::parse_inner(parser, Some(parent)));
         }
         Type::parse_inner(parser, None)
     }
@@ -715,6 +859,7 @@ impl<'a> Parse<'a> for Type<'a> {
 
 /// A recursion group declaration in a module
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Rec<'a> {
     /// Where this recursion group was defined.
     pub span: Span,
@@ -735,6 +880,7 @@ impl<'a> Parse<'a> for Rec<'a> {
 
 /// A reference to a type defined in this module.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TypeUse<'a, T> {
     /// The type that we're referencing, if it was present.
     pub index: Option<Index<'a>>,
@@ -751,6 +897,15 @@ impl<'a, T> TypeUse<'a, T> {
             inline: None,
         }
     }
+
+    /// Constructs a new instance of `TypeUse` with an inline definition but
+    /// without an index, so a type is found or added for it.
+    pub fn new_with_inline(ty: T) -> TypeUse<'a, T> {
+        TypeUse {
+            index: None,
+            inline: Some(ty),
+        }
+    }
 }
 
 impl<'a, T: Peek + Parse<'a>> Parse<'a> for TypeUse<'a, T> {
@@ -0,0 +1,323 @@
+use crate::core::*;
+use crate::token::Index;
+
+/// A visitor for the instructions of an [`Expression`], used with
+/// [`Expression::visit`] or [`Instruction::visit`].
+///
+/// Each method is called with the instruction being visited and has a
+/// default implementation which does nothing, so an analysis only needs to
+/// implement the methods it's interested in. For every instruction
+/// [`VisitInstruction::visit_instruction`] is called first, followed by a
+/// method for each index that the instruction refers to, in the order they're
+/// written.
+///
+/// Note that indices are passed as they are in the AST, so they are only
+/// numeric if the module has been [resolved](crate::core::Module::resolve).
+/// Otherwise they can be looked up with [`Names::index`].
+///
+/// # Examples
+///
+/// Finding the memories that a function accesses:
+///
+/// ```
+/// use wast::core::{Instruction, VisitInstruction};
+/// use wast::token::Index;
+///
+/// #[derive(Default)]
+/// struct Memories(Vec<u32>);
+///
+/// impl<'a> VisitInstruction<'a> for Memories {
+///     fn visit_memory(&mut self, _instr: &Instruction<'a>, memory: &Index<'a>) {
+///         if let Index::Num(n, _) = memory {
+///             self.0.push(*n);
+///         }
+///     }
+/// }
+/// ```
+#[allow(unused_variables)]
+pub trait VisitInstruction<'a> {
+    /// Called for every instruction before any of the other methods.
+    fn visit_instruction(&mut self, instr: &Instruction<'a>) {}
+
+    /// Called for each function referred to, for example by a `call` or
+    /// `ref.func`.
+    fn visit_func(&mut self, instr: &Instruction<'a>, func: &Index<'a>) {}
+
+    /// Called for each memory accessed, including by loads and stores.
+    fn visit_memory(&mut self, instr: &Instruction<'a>, memory: &Index<'a>) {}
+
+    /// Called for each table accessed, including by `call_indirect`.
+    fn visit_table(&mut self, instr: &Instruction<'a>, table: &Index<'a>) {}
+
+    /// Called for each global accessed.
+    fn visit_global(&mut self, instr: &Instruction<'a>, global: &Index<'a>) {}
+
+    /// Called for each local accessed.
+    fn visit_local(&mut self, instr: &Instruction<'a>, local: &Index<'a>) {}
+
+    /// Called for each label branched to.
+    fn visit_label(&mut self, instr: &Instruction<'a>, label: &Index<'a>) {}
+
+    /// Called for each tag thrown or caught.
+    fn visit_tag(&mut self, instr: &Instruction<'a>, tag: &Index<'a>) {}
+
+    /// Called for each type index written as an operand of the instruction,
+    /// such as the type of a `call_indirect` or `struct.new`, including those
+    /// within value types such as `(ref $t)` in a block type or a typed
+    /// `select`.
+    fn visit_type(&mut self, instr: &Instruction<'a>, ty: &Index<'a>) {}
+
+    /// Called for each data segment referred to.
+    fn visit_data(&mut self, instr: &Instruction<'a>, data: &Index<'a>) {}
+
+    /// Called for each element segment referred to.
+    fn visit_elem(&mut self, instr: &Instruction<'a>, elem: &Index<'a>) {}
+}
+
+impl<'a> Expression<'a> {
+    /// Visits each of the instructions of this expression in order with
+    /// `visitor`.
+    pub fn visit(&self, visitor: &mut impl VisitInstruction<'a>) {
+        for instr in self.instrs.iter() {
+            instr.visit(visitor);
+        }
+    }
+
+    /// Returns the instructions of this expression with the instructions of
+    /// each block nested within the instruction that starts it.
+    ///
+    /// This reconstructs the structure of the control flow of the expression,
+    /// as written with folded instructions, regardless of whether the text
+    /// used them.
+    pub fn fold(&self) -> Vec<FoldedInstruction<'_, 'a>> {
+        let mut instrs = self.instrs.iter();
+        let mut ret = Vec::new();
+        loop {
+            let (mut seq, end) = fold_seq(&mut instrs);
+            ret.append(&mut seq);
+            match end {
+                // An unbalanced `end` or `else` is kept in place, and will
+                // fail to validate later on.
+                Some(end) => ret.push(FoldedInstruction::new(end)),
+                None => break ret,
+            }
+        }
+    }
+}
+
+impl<'a> Instruction<'a> {
+    /// Calls the methods of `visitor` for this instruction and the indices
+    /// that it refers to.
+    pub fn visit(&self, visitor: &mut impl VisitInstruction<'a>) {
+        use Instruction::*;
+
+        visitor.visit_instruction(self);
+        if let Some(memarg) = self.memarg() {
+            visitor.visit_memory(self, &memarg.memory);
+        }
+        match self {
+            MemorySize(i) | MemoryGrow(i) | MemoryFill(i) | MemoryDiscard(i) => {
+                visitor.visit_memory(self, &i.mem);
+            }
+            MemoryInit(i) => {
+                visitor.visit_data(self, &i.data);
+                visitor.visit_memory(self, &i.mem);
+            }
+            MemoryCopy(i) => {
+                visitor.visit_memory(self, &i.dst);
+                visitor.visit_memory(self, &i.src);
+            }
+            DataDrop(i) => visitor.visit_data(self, i),
+
+            TableInit(i) => {
+                visitor.visit_table(self, &i.table);
+                visitor.visit_elem(self, &i.elem);
+            }
+            TableCopy(i) => {
+                visitor.visit_table(self, &i.dst);
+                visitor.visit_table(self, &i.src);
+            }
+            TableFill(i) | TableSet(i) | TableGet(i) | TableSize(i) | TableGrow(i) => {
+                visitor.visit_table(self, &i.dst);
+            }
+            ElemDrop(i) => visitor.visit_elem(self, i),
+
+            GlobalSet(i) | GlobalGet(i) => visitor.visit_global(self, i),
+            LocalSet(i) | LocalGet(i) | LocalTee(i) => visitor.visit_local(self, i),
+
+            Call(i) | RefFunc(i) | ReturnCall(i) => visitor.visit_func(self, i),
+            CallIndirect(c) | ReturnCallIndirect(c) => {
+                visitor.visit_table(self, &c.table);
+                visit_type_use(visitor, self, &c.ty);
+            }
+            FuncBind(b) => visit_type_use(visitor, self, &b.ty),
+            Let(t) => {
+                visit_type_use(visitor, self, &t.block.ty);
+                for local in t.locals.iter() {
+                    visit_val_type(visitor, self, &local.ty);
+                }
+            }
+            Block(bt) | If(bt) | Loop(bt) | Try(bt) => visit_type_use(visitor, self, &bt.ty),
+            Select(s) => {
+                for ty in s.tys.iter().flat_map(|tys| tys.iter()) {
+                    visit_val_type(visitor, self, ty);
+                }
+            }
+
+            Br(i) | BrIf(i) | BrOnNull(i) | BrOnNonNull(i) | Rethrow(i) | Delegate(i) => {
+                visitor.visit_label(self, i);
+            }
+            BrTable(i) => {
+                for label in i.labels.iter() {
+                    visitor.visit_label(self, label);
+                }
+                visitor.visit_label(self, &i.default);
+            }
+            BrOnFunc(l) | BrOnData(l) | BrOnI31(l) | BrOnArray(l) | BrOnNonFunc(l)
+            | BrOnNonData(l) | BrOnNonI31(l) | BrOnNonArray(l) => {
+                visitor.visit_label(self, l);
+            }
+            BrOnCast(i) | BrOnCastFail(i) => {
+                visitor.visit_label(self, &i.label);
+                visit_heap_type(visitor, self, &i.from_type.heap);
+                visit_heap_type(visitor, self, &i.to_type.heap);
+            }
+
+            Throw(i) | Catch(i) => visitor.visit_tag(self, i),
+
+            RefNull(ty) | CallRef(ty) | ReturnCallRef(ty) => visit_heap_type(visitor, self, ty),
+            RefTest(i) => visit_heap_type(visitor, self, &i.r#type.heap),
+            RefCast(i) => visit_heap_type(visitor, self, &i.r#type.heap),
+
+            StructNew(i) | StructNewDefault(i) | ArrayNew(i) | ArrayNewDefault(i) | ArrayGet(i)
+            | ArrayGetS(i) | ArrayGetU(i) | ArraySet(i) | ArrayFill(i) => {
+                visitor.visit_type(self, i);
+            }
+            StructSet(s) | StructGet(s) | StructGetS(s) | StructGetU(s) => {
+                visitor.visit_type(self, &s.r#struct);
+            }
+            ArrayNewFixed(a) => visitor.visit_type(self, &a.array),
+            ArrayNewData(a) | ArrayInitData(a) => {
+                visitor.visit_type(self, &a.array);
+                visitor.visit_data(self, &a.data_idx);
+            }
+            ArrayNewElem(a) | ArrayInitElem(a) => {
+                visitor.visit_type(self, &a.array);
+                visitor.visit_elem(self, &a.elem_idx);
+            }
+            ArrayCopy(a) => {
+                visitor.visit_type(self, &a.dest_array);
+                visitor.visit_type(self, &a.src_array);
+            }
+
+            // The remaining instructions don't refer to anything by index.
+            _ => {}
+        }
+    }
+}
+
+fn visit_heap_type<'a>(
+    visitor: &mut impl VisitInstruction<'a>,
+    instr: &Instruction<'a>,
+    ty: &HeapType<'a>,
+) {
+    if let HeapType::Index(i) = ty {
+        visitor.visit_type(instr, i);
+    }
+}
+
+fn visit_val_type<'a>(
+    visitor: &mut impl VisitInstruction<'a>,
+    instr: &Instruction<'a>,
+    ty: &ValType<'a>,
+) {
+    if let ValType::Ref(r) = ty {
+        visit_heap_type(visitor, instr, &r.heap);
+    }
+}
+
+fn visit_type_use<'a>(
+    visitor: &mut impl VisitInstruction<'a>,
+    instr: &Instruction<'a>,
+    ty: &TypeUse<'a, FunctionType<'a>>,
+) {
+    if let Some(i) = &ty.index {
+        visitor.visit_type(instr, i);
+    }
+    if let Some(func) = &ty.inline {
+        for (_, _, ty) in func.params.iter() {
+            visit_val_type(visitor, instr, ty);
+        }
+        for ty in func.results.iter() {
+            visit_val_type(visitor, instr, ty);
+        }
+    }
+}
+
+/// An instruction of an [`Expression`] along with the instructions of the
+/// block that it starts, if any, as returned by [`Expression::fold`].
+#[derive(Debug)]
+pub struct FoldedInstruction<'e, 'a> {
+    /// The instruction itself.
+    pub instr: &'e Instruction<'a>,
+    /// The instructions of the block started by `instr`, which for an `if`
+    /// is its `then` arm. This is empty for other instructions.
+    pub body: Vec<FoldedInstruction<'e, 'a>>,
+    /// The `else`, `catch` and `catch_all` arms of the block which follow
+    /// `body`, each along with the instruction that starts it.
+    pub arms: Vec<(&'e Instruction<'a>, Vec<FoldedInstruction<'e, 'a>>)>,
+    /// The `end` or `delegate` instruction which closes the block, which is
+    /// `None` for other instructions or if the block is never closed.
+    pub end: Option<&'e Instruction<'a>>,
+}
+
+impl<'e, 'a> FoldedInstruction<'e, 'a> {
+    fn new(instr: &'e Instruction<'a>) -> FoldedInstruction<'e, 'a> {
+        FoldedInstruction {
+            instr,
+            body: Vec::new(),
+            arms: Vec::new(),
+            end: None,
+        }
+    }
+}
+
+/// Folds instructions until one which ends a sequence of them, such as an
+/// `end` or `else`, returning that instruction as well.
+fn fold_seq<'e, 'a>(
+    instrs: &mut std::slice::Iter<'e, Instruction<'a>>,
+) -> (Vec<FoldedInstruction<'e, 'a>>, Option<&'e Instruction<'a>>) {
+    let mut ret = Vec::new();
+    while let Some(instr) = instrs.next() {
+        let mut folded = FoldedInstruction::new(instr);
+        match instr {
+            Instruction::Block(_)
+            | Instruction::Loop(_)
+            | Instruction::If(_)
+            | Instruction::Try(_)
+            | Instruction::Let(_) => {
+                let (body, mut end) = fold_seq(instrs);
+                folded.body = body;
+                while let Some(arm) = end {
+                    match arm {
+                        Instruction::Else(_) | Instruction::Catch(_) | Instruction::CatchAll => {
+                            let (instrs, next) = fold_seq(instrs);
+                            folded.arms.push((arm, instrs));
+                            end = next;
+                        }
+                        _ => break,
+                    }
+                }
+                folded.end = end;
+            }
+            Instruction::Else(_)
+            | Instruction::Catch(_)
+            | Instruction::CatchAll
+            | Instruction::End(_)
+            | Instruction::Delegate(_) => return (ret, Some(instr)),
+            _ => {}
+        }
+        ret.push(folded);
+    }
+    (ret, None)
+}
@@ -6,6 +6,7 @@ use crate::token::{Float32, Float64, Index};
 /// Expression that can be used inside of `invoke` expressions for core wasm
 /// functions.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum WastArgCore<'a> {
     I32(i32),
@@ -61,6 +62,7 @@ impl Peek for WastArgCore<'_> {
 /// Expressions that can be used inside of `assert_return` to validate the
 /// return value of a core wasm function.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum WastRetCore<'a> {
     I32(i32),
@@ -132,6 +134,7 @@ impl Peek for WastRetCore<'_> {
 
 /// Either a NaN pattern (`nan:canonical`, `nan:arithmetic`) or a value of type `T`.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum NanPattern<T> {
     CanonicalNan,
@@ -162,6 +165,7 @@ where
 /// This implementation is necessary because only float types can include NaN patterns; otherwise
 /// it is largely similar to the implementation of `V128Const`.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum V128Pattern {
     I8x16([i8; 16]),
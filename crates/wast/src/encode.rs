@@ -1,7 +1,31 @@
+use crate::token::Span;
+use crate::Error;
+
 pub(crate) trait Encode {
     fn encode(&self, e: &mut Vec<u8>);
 }
 
+/// Returns an error at `span` if an item of `size` bytes, described by
+/// `item`, is too large to have its size encoded as a `u32`.
+pub(crate) fn check_size(
+    span: Span,
+    item: impl FnOnce() -> String,
+    size: usize,
+) -> Result<(), Error> {
+    if size > u32::MAX as usize {
+        return Err(Error::new(
+            span,
+            format!(
+                "{} is too large to encode: its size of {} exceeds the limit of {}",
+                item(),
+                size,
+                u32::MAX
+            ),
+        ));
+    }
+    Ok(())
+}
+
 impl<T: Encode + ?Sized> Encode for &'_ T {
     fn encode(&self, e: &mut Vec<u8>) {
         T::encode(self, e)
@@ -73,3 +97,11 @@ impl<T: Encode, U: Encode> Encode for (T, U) {
         self.1.encode(e);
     }
 }
+
+impl<T: Encode, U: Encode, V: Encode> Encode for (T, U, V) {
+    fn encode(&self, e: &mut Vec<u8>) {
+        self.0.encode(e);
+        self.1.encode(e);
+        self.2.encode(e);
+    }
+}
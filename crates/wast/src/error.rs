@@ -23,7 +23,18 @@ struct ErrorInner {
     text: Option<Text>,
     file: Option<PathBuf>,
     span: Span,
-    kind: ErrorKind,
+    message: Message,
+    expected: Vec<&'static str>,
+    notes: Vec<Note>,
+}
+
+/// A secondary location attached to an error, such as the previous
+/// definition or use of an item that conflicts with the one at `span`.
+#[derive(Debug)]
+struct Note {
+    span: Span,
+    message: String,
+    text: Option<Text>,
 }
 
 #[derive(Debug)]
@@ -34,11 +45,26 @@ struct Text {
 }
 
 #[derive(Debug)]
-enum ErrorKind {
+enum Message {
     Lex(LexError),
+    Parse(String),
     Custom(String),
 }
 
+/// The broad category of an [`Error`], as returned by [`Error::kind`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The text failed to lex, and [`Error::lex_error`] describes why.
+    Lex,
+    /// The tokens failed to parse, for example because an unexpected token
+    /// was found. [`Error::expected`] may list the tokens that would have
+    /// been accepted instead.
+    Parse,
+    /// Any other error, such as a name which failed to resolve.
+    Other,
+}
+
 impl Error {
     pub(crate) fn lex(span: Span, content: &str, kind: LexError) -> Error {
         let mut ret = Error {
@@ -46,7 +72,9 @@ impl Error {
                 text: None,
                 file: None,
                 span,
-                kind: ErrorKind::Lex(kind),
+                message: Message::Lex(kind),
+                expected: Vec::new(),
+                notes: Vec::new(),
             }),
         };
         ret.set_text(content);
@@ -59,7 +87,9 @@ impl Error {
                 text: None,
                 file: None,
                 span,
-                kind: ErrorKind::Custom(message),
+                message: Message::Parse(message),
+                expected: Vec::new(),
+                notes: Vec::new(),
             }),
         };
         ret.set_text(content);
@@ -77,16 +107,65 @@ impl Error {
                 text: None,
                 file: None,
                 span,
-                kind: ErrorKind::Custom(message),
+                message: Message::Custom(message),
+                expected: Vec::new(),
+                notes: Vec::new(),
             }),
         }
     }
 
+    /// Attaches a note with the given `message` targeted at `span`, which is
+    /// rendered after the primary location of this error.
+    pub(crate) fn with_note(mut self, span: Span, message: String) -> Error {
+        self.inner.notes.push(Note {
+            span,
+            message,
+            text: None,
+        });
+        self
+    }
+
+    /// Records the tokens which would have been accepted where this error
+    /// happened.
+    pub(crate) fn with_expected(mut self, expected: Vec<&'static str>) -> Error {
+        self.inner.expected = expected;
+        self
+    }
+
     /// Return the `Span` for this error.
     pub fn span(&self) -> Span {
         self.inner.span
     }
 
+    /// Returns the broad category of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match &self.inner.message {
+            Message::Lex(_) => ErrorKind::Lex,
+            Message::Parse(_) => ErrorKind::Parse,
+            Message::Custom(_) => ErrorKind::Other,
+        }
+    }
+
+    /// Returns the descriptions of the tokens which would have been accepted
+    /// at the location of this error, such as `` `(` `` or `an identifier`.
+    ///
+    /// This is only known for some parse errors, such as those produced by
+    /// [`Lookahead1::error`](crate::parser::Lookahead1::error), and is empty
+    /// otherwise.
+    pub fn expected(&self) -> &[&'static str] {
+        &self.inner.expected
+    }
+
+    /// Returns the secondary locations attached to this error along with a
+    /// message for each, such as where a conflicting item was previously
+    /// defined.
+    pub fn notes(&self) -> impl ExactSizeIterator<Item = (Span, &str)> + '_ {
+        self.inner
+            .notes
+            .iter()
+            .map(|note| (note.span, note.message.as_str()))
+    }
+
     /// To provide a more useful error this function can be used to extract
     /// relevant textual information about this error into the error itself.
     ///
@@ -98,6 +177,9 @@ impl Error {
             return;
         }
         self.inner.text = Some(Text::new(contents, self.inner.span));
+        for note in self.inner.notes.iter_mut() {
+            note.text = Some(Text::new(contents, note.span));
+        }
     }
 
     /// To provide a more useful error this function can be used to set
@@ -114,31 +196,35 @@ impl Error {
 
     /// Returns the underlying `LexError`, if any, that describes this error.
     pub fn lex_error(&self) -> Option<&LexError> {
-        match &self.inner.kind {
-            ErrorKind::Lex(e) => Some(e),
+        match &self.inner.message {
+            Message::Lex(e) => Some(e),
             _ => None,
         }
     }
 
     /// Returns the underlying message, if any, that describes this error.
     pub fn message(&self) -> String {
-        match &self.inner.kind {
-            ErrorKind::Lex(e) => e.to_string(),
-            ErrorKind::Custom(e) => e.clone(),
+        match &self.inner.message {
+            Message::Lex(e) => e.to_string(),
+            Message::Parse(e) | Message::Custom(e) => e.clone(),
         }
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let err = match &self.inner.kind {
-            ErrorKind::Lex(e) => e as &dyn fmt::Display,
-            ErrorKind::Custom(e) => e as &dyn fmt::Display,
+        let err = match &self.inner.message {
+            Message::Lex(e) => e as &dyn fmt::Display,
+            Message::Parse(e) | Message::Custom(e) => e as &dyn fmt::Display,
         };
         let text = match &self.inner.text {
             Some(text) => text,
             None => {
-                return write!(f, "{} at byte offset {}", err, self.inner.span.offset);
+                write!(f, "{} at byte offset {}", err, self.inner.span.offset)?;
+                for note in self.inner.notes.iter() {
+                    write!(f, " ({} at byte offset {})", note.message, note.span.offset)?;
+                }
+                return Ok(());
             }
         };
         let file = self
@@ -162,7 +248,30 @@ impl fmt::Display for Error {
             err = err,
             text = text.snippet,
             marker = "^",
-        )
+        )?;
+        for note in self.inner.notes.iter() {
+            let text = match &note.text {
+                Some(text) => text,
+                None => continue,
+            };
+            write!(
+                f,
+                "
+      = note: {message}
+     --> {file}:{line}:{col}
+      |
+ {line:4} | {text}
+      | {marker:>0$}",
+                text.col + 1,
+                message = note.message,
+                file = file,
+                line = text.line + 1,
+                col = text.col + 1,
+                text = text.snippet,
+                marker = "^",
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -350,8 +350,10 @@ pub mod token;
 mod encode;
 mod error;
 mod gensym;
+mod location;
 mod names;
 pub use self::error::*;
+pub use self::location::*;
 
 macro_rules! id {
     ($($t:tt)*) => ($($t)*)
@@ -360,8 +362,10 @@ macro_rules! id {
 #[cfg(feature = "wasm-module")]
 id! {
     mod wast;
+    mod wast2json;
     mod wat;
     pub use self::wast::*;
+    pub use self::wast2json::*;
     pub use self::wat::*;
 
     // Support for core wasm parsing
@@ -412,6 +416,7 @@ pub mod kw {
     custom_keyword!(f64);
     custom_keyword!(f64x2);
     custom_keyword!(field);
+    custom_keyword!(r#final = "final");
     custom_keyword!(first);
     custom_keyword!(func);
     custom_keyword!(funcref);
@@ -439,10 +444,16 @@ pub mod kw {
     custom_keyword!(modulecode);
     custom_keyword!(nan_arithmetic = "nan:arithmetic");
     custom_keyword!(nan_canonical = "nan:canonical");
+    custom_keyword!(noextern);
+    custom_keyword!(nofunc);
+    custom_keyword!(none);
     custom_keyword!(null);
+    custom_keyword!(nullexternref);
+    custom_keyword!(nullfuncref);
     custom_keyword!(nullref);
     custom_keyword!(offset);
     custom_keyword!(outer);
+    custom_keyword!(pagesize);
     custom_keyword!(param);
     custom_keyword!(parent);
     custom_keyword!(passive);
@@ -463,9 +474,11 @@ pub mod kw {
     custom_keyword!(sub);
     custom_keyword!(table);
     custom_keyword!(then);
+    custom_keyword!(thread);
     custom_keyword!(r#try = "try");
     custom_keyword!(v128);
     custom_keyword!(value);
+    custom_keyword!(wait);
     custom_keyword!(s8);
     custom_keyword!(s16);
     custom_keyword!(s32);
@@ -503,6 +516,13 @@ pub mod kw {
     custom_keyword!(with);
     custom_keyword!(core);
     custom_keyword!(true_ = "true");
+    custom_keyword!(used);
+    custom_keyword!(disallowed);
+    custom_keyword!(required);
+    custom_keyword!(mem_info = "mem-info");
+    custom_keyword!(needed);
+    custom_keyword!(export_info = "export-info");
+    custom_keyword!(import_info = "import-info");
     custom_keyword!(false_ = "false");
 }
 
@@ -510,4 +530,8 @@ pub mod kw {
 pub mod annotation {
     annotation!(custom);
     annotation!(name);
+    annotation!(metadata_code_branch_hint = "metadata.code.branch_hint");
+    annotation!(producers);
+    annotation!(target_features);
+    annotation!(dylink_0 = "dylink.0");
 }
@@ -0,0 +1,114 @@
+use crate::lexer::{Lexer, Token};
+use crate::token::Span;
+use crate::Error;
+
+/// A location recorded in a location comment of the text format.
+///
+/// Location comments are block comments whose contents start with `@`, and
+/// they describe where the text that follows them came from. Two forms are
+/// recognized:
+///
+/// * `(;@1a2b;)` - a hexadecimal offset into an original binary, which is
+///   what `wasmprinter` emits when printing offsets.
+/// * `(;@file.wat:12;)` - a line number within some other source file.
+///
+/// Whitespace around the location within the comment is ignored. Location
+/// comments are otherwise ordinary comments, so text containing them parses
+/// the same as if they weren't present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location<'a> {
+    /// A byte offset into the original binary.
+    Offset(usize),
+    /// A 1-based line number within a source file.
+    Line {
+        /// The name of the source file.
+        file: &'a str,
+        /// The line number within `file`.
+        line: u32,
+    },
+}
+
+impl<'a> Location<'a> {
+    /// Parses the location out of the source text of a block comment, such as
+    /// `(;@1a2b;)`, returning `None` if it isn't a location comment.
+    pub fn from_comment(comment: &'a str) -> Option<Location<'a>> {
+        let body = comment.strip_prefix("(;@")?.strip_suffix(";)")?.trim();
+        if let Some((file, line)) = body.rsplit_once(':') {
+            let file = file.trim_end();
+            if file.is_empty() {
+                return None;
+            }
+            let line = line.trim_start().parse().ok()?;
+            return Some(Location::Line { file, line });
+        }
+        if body.is_empty() || !body.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        usize::from_str_radix(body, 16).ok().map(Location::Offset)
+    }
+}
+
+/// All of the location comments found within some text, used to correlate
+/// spans of parsed items with the locations they came from.
+///
+/// This makes it possible to print a binary with offsets, edit the text, and
+/// parse it again while still knowing which original bytes each part of the
+/// edited text corresponds to.
+///
+/// # Examples
+///
+/// ```
+/// use wast::{Location, LocationComments};
+/// use wast::token::Span;
+///
+/// let wat = "(module\n(;@b ;)  (type (func))\n(;@11;)  (func (type 0)))";
+/// let comments = LocationComments::new(wat)?;
+/// let func = wat.find("func (type").unwrap();
+/// assert_eq!(
+///     comments.get(Span::from_offset(func)),
+///     Some(Location::Offset(0x11)),
+/// );
+/// assert_eq!(comments.get(Span::from_offset(0)), None);
+/// # Ok::<(), wast::Error>(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct LocationComments<'a> {
+    /// The byte offset of each location comment within the text and the
+    /// location that it describes, sorted by offset.
+    comments: Vec<(usize, Location<'a>)>,
+}
+
+impl<'a> LocationComments<'a> {
+    /// Lexes `wat` to find all of its location comments.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `wat` can't be lexed.
+    pub fn new(wat: &'a str) -> Result<LocationComments<'a>, Error> {
+        let mut comments = Vec::new();
+        for token in Lexer::new(wat) {
+            if let Token::BlockComment(comment) = token? {
+                if let Some(location) = Location::from_comment(comment) {
+                    let offset = comment.as_ptr() as usize - wat.as_ptr() as usize;
+                    comments.push((offset, location));
+                }
+            }
+        }
+        Ok(LocationComments { comments })
+    }
+
+    /// Returns the location of the text at `span`, which is described by the
+    /// closest location comment preceding it.
+    pub fn get(&self, span: Span) -> Option<Location<'a>> {
+        let i = self
+            .comments
+            .partition_point(|(offset, _)| *offset <= span.offset());
+        Some(self.comments.get(i.checked_sub(1)?)?.1)
+    }
+
+    /// Returns an iterator over all location comments, as the byte offset of
+    /// the comment within the text and the location it describes.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Location<'a>)> + '_ {
+        self.comments.iter().copied()
+    }
+}
@@ -12,7 +12,9 @@ impl<'a> Namespace<'a> {
     pub fn register(&mut self, name: Option<Id<'a>>, desc: &str) -> Result<u32, Error> {
         let index = self.alloc();
         if let Some(name) = name {
-            if let Some(_prev) = self.names.insert(name, index) {
+            let prev = self.names.get_key_value(&name).map(|(id, _)| id.span());
+            self.names.insert(name, index);
+            if let Some(prev) = prev {
                 // FIXME: temporarily allow duplicately-named data and element
                 // segments. This is a sort of dumb hack to get the spec test
                 // suite working (ironically).
@@ -34,10 +36,10 @@ impl<'a> Namespace<'a> {
                 // spec tests get updated enough we can remove this condition
                 // and return errors for them.
                 if desc != "elem" && desc != "data" {
-                    return Err(Error::new(
-                        name.span(),
-                        format!("duplicate {} identifier", desc),
-                    ));
+                    return Err(
+                        Error::new(name.span(), format!("duplicate {} identifier", desc))
+                            .with_note(prev, "previous definition here".to_string()),
+                    );
                 }
             }
         }
@@ -51,15 +53,24 @@ impl<'a> Namespace<'a> {
     }
 
     pub fn register_specific(&mut self, name: Id<'a>, index: u32, desc: &str) -> Result<(), Error> {
-        if let Some(_prev) = self.names.insert(name, index) {
-            return Err(Error::new(
-                name.span(),
-                format!("duplicate identifier for {}", desc),
-            ));
+        if let Some((prev, _)) = self.names.get_key_value(&name) {
+            return Err(
+                Error::new(name.span(), format!("duplicate identifier for {}", desc))
+                    .with_note(prev.span(), "previous definition here".to_string()),
+            );
         }
+        self.names.insert(name, index);
         Ok(())
     }
 
+    /// Returns the index that `$name` refers to in this namespace, if any.
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.names
+            .iter()
+            .find(|(id, _)| !id.is_gensym() && id.name() == name)
+            .map(|(_, index)| *index)
+    }
+
     pub fn resolve(&self, idx: &mut Index<'a>, desc: &str) -> Result<u32, Error> {
         let id = match idx {
             Index::Num(n, _) => return Ok(*n),
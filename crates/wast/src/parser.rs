@@ -65,10 +65,10 @@
 //! likely also draw inspiration from the excellent examples in the `syn` crate.
 
 use crate::lexer::{Float, Integer, Lexer, Token};
-use crate::token::Span;
+use crate::token::{Interner, LParen, Span, Symbol};
 use crate::Error;
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::usize;
 
@@ -298,9 +298,46 @@ pub struct ParseBuffer<'a> {
     input: &'a str,
     cur: Cell<usize>,
     known_annotations: RefCell<HashMap<String, usize>>,
+    custom_annotations: HashMap<String, CustomAnnotationHandler<'a>>,
+    track_trivia: bool,
+    // indices of the `(` tokens of annotations which were parsed, rather than
+    // skipped, only recorded if `track_trivia` is set.
+    parsed_annotations: RefCell<HashSet<usize>>,
+    track_instr_spans: bool,
+    interned: Interner,
     depth: Cell<usize>,
 }
 
+/// A callback registered with [`ParseBuffer::register_custom_annotation`].
+type CustomAnnotationHandler<'a> = Box<dyn Fn(Parser<'_>) -> Result<Vec<u8>> + 'a>;
+
+/// A piece of source text which isn't part of any parsed item, returned by
+/// [`ParseBuffer::trivia`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Trivia<'a> {
+    /// What kind of trivia this is.
+    pub kind: TriviaKind,
+    /// The span of the start of this trivia.
+    pub span: Span,
+    /// The source text of this trivia, including delimiters such as `;;` or
+    /// the parentheses around an annotation.
+    pub text: &'a str,
+}
+
+/// The different kinds of [`Trivia`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TriviaKind {
+    /// A run of whitespace.
+    Whitespace,
+    /// A `;;` comment, not including the newline that ends it.
+    LineComment,
+    /// A `(; ... ;)` comment.
+    BlockComment,
+    /// An annotation such as `(@foo ...)` which was skipped rather than
+    /// parsed.
+    Annotation,
+}
+
 #[derive(Copy, Clone, Debug)]
 enum NextTokenAt {
     /// Haven't computed where the next token is yet.
@@ -371,6 +408,11 @@ impl ParseBuffer<'_> {
             depth: Cell::new(0),
             input,
             known_annotations: Default::default(),
+            custom_annotations: Default::default(),
+            track_trivia: false,
+            parsed_annotations: Default::default(),
+            track_instr_spans: false,
+            interned: Default::default(),
         };
         ret.validate_annotations()?;
         Ok(ret)
@@ -437,6 +479,157 @@ impl ParseBuffer<'_> {
     }
 }
 
+impl<'a> ParseBuffer<'a> {
+    /// Registers a handler for the module-level annotation `(@name ...)`,
+    /// which is then encoded as a custom section called `name`.
+    ///
+    /// Annotations are otherwise ignored unless they're known to this crate,
+    /// such as `@custom` or `@name`. This allows toolchains to embed their own
+    /// metadata in text files: when a module field of the form `(@name ...)`
+    /// is parsed, `handler` is called with a [`Parser`] positioned just after
+    /// the annotation's name. It must consume all of the annotation's tokens,
+    /// for example with [`Parser::step`], and returns the payload of the
+    /// custom section which is emitted at the end of the binary module.
+    ///
+    /// Registering the name of an annotation this crate already knows about,
+    /// such as `custom`, has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wast::parser::{self, ParseBuffer};
+    /// use wast::Wat;
+    ///
+    /// # fn foo() -> wast::parser::Result<()> {
+    /// let mut buf = ParseBuffer::new(r#"(module (@version "1.2.3"))"#)?;
+    /// buf.register_custom_annotation("version", |parser| {
+    ///     let version = parser.parse::<&[u8]>()?;
+    ///     Ok(version.to_vec())
+    /// });
+    /// let mut wat = parser::parse::<Wat>(&buf)?;
+    /// let wasm = wat.encode()?;
+    /// assert!(wasm.ends_with(b"\x07version1.2.3"));
+    /// # Ok(())
+    /// # }
+    /// # foo().unwrap();
+    /// ```
+    pub fn register_custom_annotation(
+        &mut self,
+        name: &str,
+        handler: impl Fn(Parser<'_>) -> Result<Vec<u8>> + 'a,
+    ) {
+        self.custom_annotations
+            .insert(name.to_string(), Box::new(handler));
+    }
+
+    /// Configures whether this buffer tracks which annotations are parsed,
+    /// which is required for [`ParseBuffer::trivia`] to report the skipped
+    /// ones.
+    ///
+    /// This is disabled by default, and must be enabled before parsing.
+    pub fn track_trivia(&mut self, track: bool) {
+        self.track_trivia = track;
+    }
+
+    /// Configures whether the span of each instruction is recorded in
+    /// [`Expression::instr_spans`](crate::core::Expression::instr_spans).
+    ///
+    /// This is disabled by default, and must be enabled before parsing.
+    pub fn track_instr_spans(&mut self, track: bool) {
+        self.track_instr_spans = track;
+    }
+
+    /// Returns all the source text which isn't part of a parsed item, in
+    /// source order.
+    ///
+    /// This is intended for tools such as formatters which reprint a text
+    /// file: together with the spans of parsed items it accounts for the
+    /// whitespace, comments and unknown annotations between them, which
+    /// would otherwise be lost. Whitespace and comments within a skipped
+    /// annotation are part of that annotation's text rather than being
+    /// reported separately.
+    ///
+    /// This must be called after parsing, and returns an empty list unless
+    /// [`ParseBuffer::track_trivia`] was enabled beforehand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wast::parser::{self, ParseBuffer, TriviaKind};
+    /// use wast::Wat;
+    ///
+    /// # fn foo() -> Result<(), wast::Error> {
+    /// let wat = "(module (@tool 1) ;; comment\n)";
+    /// let mut buf = ParseBuffer::new(wat)?;
+    /// buf.track_trivia(true);
+    /// parser::parse::<Wat>(&buf)?;
+    /// let trivia = buf.trivia();
+    /// let kinds = trivia.iter().map(|t| t.kind).collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     kinds,
+    ///     [
+    ///         TriviaKind::Whitespace,
+    ///         TriviaKind::Annotation,
+    ///         TriviaKind::Whitespace,
+    ///         TriviaKind::LineComment,
+    ///         TriviaKind::Whitespace,
+    ///     ],
+    /// );
+    /// assert_eq!(trivia[1].text, "(@tool 1)");
+    /// # Ok(())
+    /// # }
+    /// # foo().unwrap();
+    /// ```
+    pub fn trivia(&self) -> Vec<Trivia<'a>> {
+        let mut ret = Vec::new();
+        if !self.track_trivia {
+            return ret;
+        }
+        let parsed = self.parsed_annotations.borrow();
+        let mut i = 0;
+        while let Some((token, _)) = self.tokens.get(i) {
+            let kind = match token {
+                Token::Whitespace(_) => TriviaKind::Whitespace,
+                Token::LineComment(_) => TriviaKind::LineComment,
+                Token::BlockComment(_) => TriviaKind::BlockComment,
+                _ => {
+                    let cursor = Cursor {
+                        parser: self.parser(),
+                        cur: i,
+                    };
+                    if cursor.annotation_start().is_none() || parsed.contains(&i) {
+                        i += 1;
+                        continue;
+                    }
+                    // Annotations are validated to be balanced when lexing,
+                    // so this always finds the closing `)`.
+                    let end = cursor.find_next().unwrap();
+                    let start = self.input_pos(token.src());
+                    let last = self.tokens[end - 1].0.src();
+                    let text = &self.input[start..self.input_pos(last) + last.len()];
+                    ret.push(Trivia {
+                        kind: TriviaKind::Annotation,
+                        span: Span { offset: start },
+                        text,
+                    });
+                    i = end;
+                    continue;
+                }
+            };
+            let text = token.src();
+            ret.push(Trivia {
+                kind,
+                span: Span {
+                    offset: self.input_pos(text),
+                },
+                text,
+            });
+            i += 1;
+        }
+        ret
+    }
+}
+
 impl<'a> Parser<'a> {
     /// Returns whether there are no more `Token` tokens to parse from this
     /// [`Parser`].
@@ -700,14 +893,20 @@ impl<'a> Parser<'a> {
         let res = self.step(|cursor| {
             let mut cursor = match cursor.lparen() {
                 Some(rest) => rest,
-                None => return Err(cursor.error("expected `(`")),
+                None => {
+                    return Err(cursor
+                        .error("expected `(`")
+                        .with_expected(vec![LParen::display()]))
+                }
             };
             cursor.parser.buf.cur.set(cursor.cur);
             let result = f(cursor.parser)?;
             cursor.cur = cursor.parser.buf.cur.get();
             match cursor.rparen() {
                 Some(rest) => Ok((result, rest)),
-                None => Err(cursor.error("expected `)`")),
+                None => Err(cursor
+                    .error("expected `)`")
+                    .with_expected(vec!["right paren"])),
             }
         });
         self.buf.depth.set(self.buf.depth.get() - 1);
@@ -777,6 +976,11 @@ impl<'a> Parser<'a> {
         self.cursor().cur_span()
     }
 
+    /// Returns whether [`ParseBuffer::track_instr_spans`] is enabled.
+    pub(crate) fn track_instr_spans(&self) -> bool {
+        self.buf.track_instr_spans
+    }
+
     /// Returns the span of the previous token
     pub fn prev_span(&self) -> Span {
         self.cursor()
@@ -936,6 +1140,48 @@ impl<'a> Parser<'a> {
             }
         }
     }
+
+    /// Registers all annotations which have a handler registered with
+    /// [`ParseBuffer::register_custom_annotation`], for as long as the
+    /// returned value is alive.
+    pub(crate) fn register_custom_annotations(self) -> Vec<impl Drop + 'a> {
+        self.buf
+            .custom_annotations
+            .keys()
+            .map(|name| self.register_annotation(name))
+            .collect()
+    }
+
+    /// Returns whether the next token is an annotation with a handler
+    /// registered with [`ParseBuffer::register_custom_annotation`].
+    pub(crate) fn peek_custom_annotation(self) -> bool {
+        match self.cursor().annotation() {
+            Some((name, _)) => self.buf.custom_annotations.contains_key(name),
+            None => false,
+        }
+    }
+
+    /// Parses an annotation with a registered handler, returning its name and
+    /// the payload produced by the handler.
+    pub(crate) fn parse_custom_annotation(self) -> Result<(Span, &'a str, Vec<u8>)> {
+        let (span, name) = self.step(|c| match c.annotation() {
+            Some((name, rest)) => Ok(((c.cur_span(), name), rest)),
+            None => Err(c.error("expected a custom annotation")),
+        })?;
+        let data = self.buf.custom_annotations[name](self)?;
+        Ok((span, name, data))
+    }
+
+    /// Interns `name` in the [`ParseBuffer`] that this parser is parsing,
+    /// returning its [`Symbol`].
+    ///
+    /// Interning the same string multiple times within one buffer always
+    /// returns the same symbol, so symbols can be used to compare strings
+    /// such as identifiers without looking at their contents. This is how
+    /// [`Id`](crate::token::Id)s are compared during name resolution.
+    pub fn intern(self, name: &str) -> Symbol {
+        self.buf.interned.intern(name)
+    }
 }
 
 impl<'a> Cursor<'a> {
@@ -1159,7 +1405,15 @@ impl<'a> Cursor<'a> {
                 Token::Whitespace(_) | Token::LineComment(_) | Token::BlockComment(_) => {}
                 _ => match self.annotation_start() {
                     Some(n) if !is_known_annotation(n) => {}
-                    _ => {
+                    Some(_) => {
+                        if self.parser.buf.track_trivia {
+                            let mut parsed = self.parser.buf.parsed_annotations.borrow_mut();
+                            parsed.insert(self.cur);
+                        }
+                        self.cur += 1;
+                        return Some(token);
+                    }
+                    None => {
                         self.cur += 1;
                         return Some(token);
                     }
@@ -1276,7 +1530,7 @@ impl Lookahead1<'_> {
     /// Before calling this method you should call [`Lookahead1::peek`] for all
     /// possible tokens you'd like to parse.
     pub fn error(self) -> Error {
-        match self.attempts.len() {
+        let err = match self.attempts.len() {
             0 => {
                 if self.parser.is_empty() {
                     self.parser.error("unexpected end of input")
@@ -1300,7 +1554,8 @@ impl Lookahead1<'_> {
                 let message = format!("unexpected token, expected one of: {}", join);
                 self.parser.error(&message)
             }
-        }
+        };
+        err.with_expected(self.attempts)
     }
 }
 
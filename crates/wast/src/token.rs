@@ -3,14 +3,19 @@
 //! contexts too perhaps).
 
 use crate::annotation;
-use crate::lexer::FloatVal;
+use crate::lexer::{FloatVal, Lexer, Token};
 use crate::parser::{Cursor, Parse, Parser, Peek, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::ops::Range;
 use std::str;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// A position in the original source stream, used to render errors.
 #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Span {
     pub(crate) offset: usize,
 }
@@ -43,6 +48,58 @@ impl Span {
     pub fn offset(&self) -> usize {
         self.offset
     }
+
+    /// Returns the byte range within `text` of the token which starts at this
+    /// span.
+    ///
+    /// If no token can be lexed at this span, for example because it points
+    /// at invalid input, then an empty range at this span's offset is
+    /// returned.
+    pub fn byte_range_in(&self, text: &str) -> Range<usize> {
+        let len = text
+            .get(self.offset..)
+            .and_then(|rest| Lexer::new(rest).parse().ok().flatten())
+            .map(|token| token.src().len())
+            .unwrap_or(0);
+        self.offset..self.offset + len
+    }
+
+    /// Returns the byte range within `text` of the whole item which starts at
+    /// this span.
+    ///
+    /// The spans of items, such as the [`Func`](crate::core::Func) parsed from
+    /// `(func $f (param i32) ...)`, point at the keyword just after the opening
+    /// parenthesis. For such spans this returns the range of the entire
+    /// s-expression, from the `(` through its matching `)`, which is useful to
+    /// highlight all of an item rather than just its start. Otherwise this is
+    /// the same as [`Span::byte_range_in`].
+    pub fn item_range_in(&self, text: &str) -> Range<usize> {
+        let token = self.byte_range_in(text);
+        let start = match text.get(..self.offset) {
+            Some(before) => before.trim_end(),
+            None => return token,
+        };
+        if !start.ends_with('(') {
+            return token;
+        }
+        let start = start.len() - 1;
+        let mut depth = 0;
+        let mut lexer = Lexer::new(&text[start..]);
+        while let Ok(Some(t)) = lexer.parse() {
+            match t {
+                Token::LParen(_) => depth += 1,
+                Token::RParen(s) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        let end = s.as_ptr() as usize - text.as_ptr() as usize + s.len();
+                        return start..end;
+                    }
+                }
+                _ => {}
+            }
+        }
+        token
+    }
 }
 
 /// An identifier in a WebAssembly module, prefixed by `$` in the textual
@@ -50,21 +107,34 @@ impl Span {
 ///
 /// An identifier is used to symbolically refer to items in a a wasm module,
 /// typically via the [`Index`] type.
+///
+/// Identifiers are compared and hashed by their name, so identifiers with
+/// the same name are equal even if they were parsed from different
+/// [`ParseBuffer`](crate::parser::ParseBuffer)s. Identifiers from the same
+/// buffer are first compared by their interned [`Symbol`], which is cheaper.
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Id<'a> {
     name: &'a str,
+    sym: Symbol,
     gen: u32,
     span: Span,
 }
 
 impl<'a> Id<'a> {
-    fn new(name: &'a str, span: Span) -> Id<'a> {
-        Id { name, gen: 0, span }
+    fn new(name: &'a str, sym: Symbol, span: Span) -> Id<'a> {
+        Id {
+            name,
+            sym,
+            gen: 0,
+            span,
+        }
     }
 
     pub(crate) fn gensym(span: Span, gen: u32) -> Id<'a> {
         Id {
             name: "gensym",
+            sym: Symbol::GENSYM,
             gen,
             span,
         }
@@ -77,6 +147,11 @@ impl<'a> Id<'a> {
         self.name
     }
 
+    /// Returns the interned symbol of this identifier's name.
+    pub fn symbol(&self) -> Symbol {
+        self.sym
+    }
+
     /// Returns span of this identifier in the original source
     pub fn span(&self) -> Span {
         self.span
@@ -96,7 +171,7 @@ impl<'a> Hash for Id<'a> {
 
 impl<'a> PartialEq for Id<'a> {
     fn eq(&self, other: &Id<'a>) -> bool {
-        self.name == other.name && self.gen == other.gen
+        self.gen == other.gen && (self.sym == other.sym || self.name == other.name)
     }
 }
 
@@ -104,12 +179,13 @@ impl<'a> Eq for Id<'a> {}
 
 impl<'a> Parse<'a> for Id<'a> {
     fn parse(parser: Parser<'a>) -> Result<Self> {
-        parser.step(|c| {
+        let (name, span) = parser.step(|c| {
             if let Some((name, rest)) = c.id() {
-                return Ok((Id::new(name, c.cur_span()), rest));
+                return Ok(((name, c.cur_span()), rest));
             }
             Err(c.error("expected an identifier"))
-        })
+        })?;
+        Ok(Id::new(name, parser.intern(name), span))
     }
 }
 
@@ -133,6 +209,80 @@ impl Peek for Id<'_> {
     }
 }
 
+/// A string interned within a [`ParseBuffer`](crate::parser::ParseBuffer).
+///
+/// Symbols are created with [`Parser::intern`] and two symbols are equal only
+/// if they were interned from equal strings in the same buffer, which makes
+/// them cheaper to compare and hash than the strings themselves.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Symbol(u64);
+
+impl Symbol {
+    /// The symbol shared by all generated identifiers, which are instead
+    /// distinguished by their generation number.
+    const GENSYM: Symbol = Symbol(u64::MAX);
+
+    /// Returns a symbol which is distinct from all others in this process,
+    /// so symbols from different interners never collide.
+    fn fresh() -> Symbol {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        Symbol(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Creates the identifiers of an AST which is built in code rather than
+/// parsed from text.
+///
+/// Identifiers borrow their names from the caller, so an AST can be built
+/// from strings owned by a code generator, and are given symbols by this
+/// interner. As with a [`ParseBuffer`](crate::parser::ParseBuffer), only
+/// identifiers created by the same interner should be used together.
+///
+/// ```
+/// use wast::token::Interner;
+///
+/// let interner = Interner::new();
+/// let name = String::from("f");
+/// assert_eq!(interner.id(&name), interner.id("f"));
+/// assert_ne!(interner.id("f"), interner.id("g"));
+///
+/// // Identifiers are equal by name, but their symbols are only equal if
+/// // they come from the same interner.
+/// let other = Interner::new();
+/// assert_eq!(interner.id("f"), other.id("f"));
+/// assert_ne!(interner.id("f").symbol(), other.id("f").symbol());
+/// ```
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Interner {
+    symbols: RefCell<HashMap<Box<str>, Symbol>>,
+}
+
+impl Interner {
+    /// Creates an interner which hasn't interned any names yet.
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    /// Returns the symbol for `name`, which is equal to the symbols of all
+    /// other equal names interned by this interner.
+    pub fn intern(&self, name: &str) -> Symbol {
+        let mut symbols = self.symbols.borrow_mut();
+        if let Some(sym) = symbols.get(name) {
+            return *sym;
+        }
+        let sym = Symbol::fresh();
+        symbols.insert(name.into(), sym);
+        sym
+    }
+
+    /// Returns the identifier `$name`, without any location in a source.
+    pub fn id<'a>(&self, name: &'a str) -> Id<'a> {
+        Id::new(name, self.intern(name), Span::from_offset(0))
+    }
+}
+
 /// A reference to another item in a wasm module.
 ///
 /// This type is used for items referring to other items (such as `call $foo`
@@ -142,6 +292,7 @@ impl Peek for Id<'_> {
 /// The emission phase of a module will ensure that `Index::Id` is never used
 /// and switch them all to `Index::Num`.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Index<'a> {
     /// A numerical index that this references. The index space this is
     /// referencing is implicit based on where this [`Index`] is stored.
@@ -224,6 +375,7 @@ impl Hash for Index<'_> {
 
 /// Parses `(func $foo)`
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub struct ItemRef<'a, K> {
     pub kind: K,
@@ -255,6 +407,7 @@ impl<'a, K: Peek> Peek for ItemRef<'a, K> {
 
 /// An `@name` annotation in source, currently of the form `@name "foo"`
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NameAnnotation<'a> {
     /// The name specified for the item
     pub name: &'a str,
@@ -380,6 +533,7 @@ macro_rules! float {
     })*) => ($(
         /// A parsed floating-point type
         #[derive(Debug, Copy, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize))]
         pub struct $name {
             /// The raw bits that this floating point number represents.
             pub bits: $int,
@@ -656,6 +810,37 @@ impl Peek for LParen {
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn ranges() {
+        use super::Span;
+
+        let text = "(module\n  (func $f (param i32)\n    (; ) ;) nop)\n  ( type (func)))";
+        let func = text.find("func").unwrap();
+        assert_eq!(&text[Span::from_offset(func).byte_range_in(text)], "func");
+        assert_eq!(
+            &text[Span::from_offset(func).item_range_in(text)],
+            "(func $f (param i32)\n    (; ) ;) nop)"
+        );
+        let id = text.find('$').unwrap();
+        assert_eq!(&text[Span::from_offset(id).item_range_in(text)], "$f");
+        let ty = text.find("type").unwrap();
+        assert_eq!(
+            &text[Span::from_offset(ty).item_range_in(text)],
+            "( type (func))"
+        );
+        assert_eq!(Span::from_offset(0).item_range_in(text), 0..1);
+        assert_eq!(Span::from_offset(1).item_range_in(text), 0..text.len());
+        assert_eq!(
+            Span::from_offset(text.len()).byte_range_in(text),
+            text.len()..text.len()
+        );
+        assert_eq!(Span::from_offset(1000).item_range_in(text), 1000..1000);
+
+        // Unbalanced or unlexable input falls back to the token itself.
+        assert_eq!(Span::from_offset(1).item_range_in("(module"), 1..7);
+        assert_eq!(Span::from_offset(1).byte_range_in("(\"abc"), 1..1);
+    }
+
     #[test]
     fn hex_strtof() {
         macro_rules! f {
@@ -692,4 +877,29 @@ mod tests {
             Some(0x26800000)
         );
     }
+
+    #[test]
+    fn ids_are_interned() {
+        use crate::parser::{self, Parse, ParseBuffer, Parser, Result};
+        use crate::token::Id;
+
+        struct Ids<'a>(Vec<Id<'a>>);
+
+        impl<'a> Parse<'a> for Ids<'a> {
+            fn parse(parser: Parser<'a>) -> Result<Self> {
+                let mut ids = Vec::new();
+                while !parser.is_empty() {
+                    ids.push(parser.parse()?);
+                }
+                Ok(Ids(ids))
+            }
+        }
+
+        let buf = ParseBuffer::new("$a $b $a").unwrap();
+        let Ids(ids) = parser::parse::<Ids>(&buf).unwrap();
+        assert_eq!(ids[0].symbol(), ids[2].symbol());
+        assert_ne!(ids[0].symbol(), ids[1].symbol());
+        assert_eq!(ids[0], ids[2]);
+        assert_eq!(ids[2].name(), "a");
+    }
 }
@@ -4,6 +4,7 @@ use crate::kw;
 use crate::parser::{self, Cursor, Parse, ParseBuffer, Parser, Peek, Result};
 use crate::token::{Id, Span};
 use crate::{Error, Wat};
+use std::collections::HashMap;
 
 /// A parsed representation of a `*.wast` file.
 ///
@@ -11,6 +12,7 @@ use crate::{Error, Wat};
 /// suite to write official spec tests for wasm. This type represents a parsed
 /// `*.wast` file which parses a list of directives in a file.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Wast<'a> {
     #[allow(missing_docs)]
     pub directives: Vec<WastDirective<'a>>,
@@ -47,6 +49,8 @@ impl Peek for WastDirectiveToken {
             || kw == "component"
             || kw == "register"
             || kw == "invoke"
+            || kw == "thread"
+            || kw == "wait"
     }
 
     fn display() -> &'static str {
@@ -60,6 +64,7 @@ impl Peek for WastDirectiveToken {
 /// really interesting to test harnesses mostly.
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum WastDirective<'a> {
     Wat(QuoteWat<'a>),
     AssertMalformed {
@@ -95,23 +100,25 @@ pub enum WastDirective<'a> {
     },
     AssertUnlinkable {
         span: Span,
-        module: Wat<'a>,
+        module: QuoteWat<'a>,
         message: &'a str,
     },
     AssertException {
         span: Span,
         exec: WastExecute<'a>,
     },
+    Thread(WastThread<'a>),
+    Wait {
+        span: Span,
+        thread: Id<'a>,
+    },
 }
 
 impl WastDirective<'_> {
     /// Returns the location in the source that this directive was defined at
     pub fn span(&self) -> Span {
         match self {
-            WastDirective::Wat(QuoteWat::Wat(Wat::Module(m))) => m.span,
-            WastDirective::Wat(QuoteWat::Wat(Wat::Component(c))) => c.span,
-            WastDirective::Wat(QuoteWat::QuoteModule(span, _)) => *span,
-            WastDirective::Wat(QuoteWat::QuoteComponent(span, _)) => *span,
+            WastDirective::Wat(module) => module.span(),
             WastDirective::AssertMalformed { span, .. }
             | WastDirective::Register { span, .. }
             | WastDirective::AssertTrap { span, .. }
@@ -119,8 +126,300 @@ impl WastDirective<'_> {
             | WastDirective::AssertExhaustion { span, .. }
             | WastDirective::AssertUnlinkable { span, .. }
             | WastDirective::AssertInvalid { span, .. }
-            | WastDirective::AssertException { span, .. } => *span,
+            | WastDirective::AssertException { span, .. }
+            | WastDirective::Wait { span, .. } => *span,
             WastDirective::Invoke(i) => i.span,
+            WastDirective::Thread(t) => t.span,
+        }
+    }
+}
+
+/// Tracks the modules defined by the directives of a [`Wast`] script, to
+/// work out which module each directive refers to.
+///
+/// Modules are numbered in the order they're defined, and a test harness
+/// can keep its instances in a list indexed by these numbers. Each directive
+/// is passed to [`WastModules::directive`] in order, which resolves optional
+/// module identifiers the same way as the reference interpreter: a missing
+/// identifier refers to the most recently defined module.
+///
+/// ```
+/// use wast::parser::{self, ParseBuffer};
+/// use wast::{Wast, WastModules};
+///
+/// let buf = ParseBuffer::new(r#"
+///     (module $a (func (export "f")))
+///     (module $b (func (export "f")))
+///     (register "a" $a)
+///     (invoke $a "f")
+///     (invoke "f")
+/// "#)?;
+/// let wast = parser::parse::<Wast>(&buf)?;
+/// let mut modules = WastModules::new();
+/// let refs = wast
+///     .directives
+///     .iter()
+///     .map(|d| modules.directive(d))
+///     .collect::<Result<Vec<_>, _>>()?;
+/// assert_eq!(refs, [Some(0), Some(1), Some(0), Some(0), Some(1)]);
+/// assert_eq!(modules.registered("a"), Some(0));
+/// # Ok::<(), wast::Error>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct WastModules<'a> {
+    len: usize,
+    current: Option<usize>,
+    ids: HashMap<Id<'a>, usize>,
+    registered: HashMap<&'a str, usize>,
+}
+
+impl<'a> WastModules<'a> {
+    /// Creates a tracker for a script which hasn't defined any modules yet.
+    pub fn new() -> WastModules<'a> {
+        WastModules::default()
+    }
+
+    /// Records the effect of `directive` and returns the module it defines
+    /// or refers to, if any.
+    ///
+    /// A module or component definition returns its new index and becomes
+    /// the current module. An `invoke`, a `get`, or an assertion about one
+    /// returns the index of the module it's executed in, and a `register`
+    /// returns the module which was registered. Modules that are only
+    /// instantiated by an assertion can't be referred to, and aren't
+    /// numbered.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directive names a module which isn't defined,
+    /// or omits the module when none has been defined yet.
+    pub fn directive(&mut self, directive: &WastDirective<'a>) -> Result<Option<usize>> {
+        let exec = match directive {
+            WastDirective::Wat(wat) => return Ok(Some(self.define(wat.id()))),
+            WastDirective::Register { span, name, module } => {
+                let index = self.resolve(*span, *module)?;
+                self.registered.insert(name, index);
+                return Ok(Some(index));
+            }
+            WastDirective::Invoke(invoke)
+            | WastDirective::AssertExhaustion { call: invoke, .. } => {
+                return self.resolve(invoke.span, invoke.module).map(Some)
+            }
+            WastDirective::AssertTrap { span, exec, .. }
+            | WastDirective::AssertReturn { span, exec, .. }
+            | WastDirective::AssertException { span, exec } => (*span, exec),
+            WastDirective::AssertMalformed { .. }
+            | WastDirective::AssertInvalid { .. }
+            | WastDirective::AssertUnlinkable { .. }
+            | WastDirective::Thread(_)
+            | WastDirective::Wait { .. } => return Ok(None),
+        };
+        match exec.1 {
+            WastExecute::Invoke(invoke) => self.resolve(invoke.span, invoke.module).map(Some),
+            WastExecute::Get { module, .. } => self.resolve(exec.0, *module).map(Some),
+            WastExecute::Wat(_) => Ok(None),
+        }
+    }
+
+    /// Records a module with the optional identifier `id`, which becomes the
+    /// current module, and returns its index.
+    pub fn define(&mut self, id: Option<Id<'a>>) -> usize {
+        let index = self.len;
+        self.len += 1;
+        self.current = Some(index);
+        if let Some(id) = id {
+            self.ids.insert(id, index);
+        }
+        index
+    }
+
+    /// Returns the index of the module named `id`, or of the current module
+    /// if `id` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error at `span` if there's no current module, or at `id`
+    /// if no module has that identifier.
+    pub fn resolve(&self, span: Span, id: Option<Id<'a>>) -> Result<usize> {
+        match id {
+            Some(id) => match self.ids.get(&id) {
+                Some(index) => Ok(*index),
+                None => Err(Error::new(
+                    id.span(),
+                    format!("unknown module `${}`", id.name()),
+                )),
+            },
+            None => self
+                .current
+                .ok_or_else(|| Error::new(span, "no module has been defined".to_string())),
+        }
+    }
+
+    /// Returns the module most recently registered as `name`, which modules
+    /// defined afterwards can import from.
+    pub fn registered(&self, name: &str) -> Option<usize> {
+        self.registered.get(name).copied()
+    }
+
+    /// Returns a tracker for the directives of `thread`.
+    ///
+    /// A thread only sees the module it shares, so its modules are numbered
+    /// separately: the shared module, which is
+    /// `self.resolve(thread.span, thread.shared_module)`, is index 0 and is
+    /// the thread's current module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shared module isn't defined.
+    pub fn thread(&self, thread: &WastThread<'a>) -> Result<WastModules<'a>> {
+        let mut modules = WastModules::new();
+        if let Some(id) = thread.shared_module {
+            self.resolve(thread.span, Some(id))?;
+            modules.define(Some(id));
+        }
+        Ok(modules)
+    }
+}
+
+/// A normalized category of the messages expected by `assert_trap` and
+/// `assert_exhaustion` directives.
+///
+/// Engines word their trap messages differently from the reference
+/// interpreter, so rather than comparing strings exactly a test harness can
+/// compare the categories that [`TrapKind::classify`] assigns to the expected
+/// and actual messages, for example with [`TrapKind::messages_match`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum TrapKind {
+    /// A load or store was out of bounds of its memory.
+    OutOfBoundsMemory,
+    /// A table access was out of bounds of its table.
+    OutOfBoundsTable,
+    /// An `unreachable` instruction was executed.
+    Unreachable,
+    /// The call stack was exhausted.
+    StackOverflow,
+    /// The signature of an indirectly-called function did not match.
+    IndirectCallTypeMismatch,
+    /// An indirect call referred to an element outside of its table.
+    UndefinedElement,
+    /// An indirect call referred to a null table element.
+    UninitializedElement,
+    /// An integer was divided by zero.
+    IntegerDivideByZero,
+    /// An integer operation overflowed.
+    IntegerOverflow,
+    /// A float could not be converted to an integer.
+    InvalidConversionToInteger,
+    /// A null reference was dereferenced.
+    NullReference,
+    /// An atomic memory access was not naturally aligned.
+    UnalignedAtomic,
+    /// An atomic wait was performed on a memory that is not shared.
+    ExpectedSharedMemory,
+}
+
+impl TrapKind {
+    /// Substrings, in lowercase, identifying each category. Earlier entries
+    /// take priority, so more specific patterns come first.
+    const PATTERNS: &'static [(TrapKind, &'static [&'static str])] = &[
+        (
+            TrapKind::OutOfBoundsTable,
+            &[
+                "out of bounds table access",
+                "table out of bounds",
+                "table index out of bounds",
+            ],
+        ),
+        (
+            TrapKind::OutOfBoundsMemory,
+            &[
+                "out of bounds memory access",
+                "memory access out of bounds",
+                "heap out of bounds",
+                "memory out of bounds",
+            ],
+        ),
+        (
+            TrapKind::IndirectCallTypeMismatch,
+            &[
+                "indirect call type mismatch",
+                "indirect call signature mismatch",
+                "bad signature",
+            ],
+        ),
+        (TrapKind::UndefinedElement, &["undefined element"]),
+        (
+            TrapKind::UninitializedElement,
+            &["uninitialized element", "indirect call to null"],
+        ),
+        (
+            TrapKind::StackOverflow,
+            &["call stack exhausted", "stack overflow", "stack exhausted"],
+        ),
+        (
+            TrapKind::IntegerDivideByZero,
+            &["integer divide by zero", "division by zero"],
+        ),
+        (TrapKind::IntegerOverflow, &["integer overflow"]),
+        (
+            TrapKind::InvalidConversionToInteger,
+            &["invalid conversion to integer", "bad conversion to integer"],
+        ),
+        (
+            TrapKind::UnalignedAtomic,
+            &["unaligned atomic", "misaligned memory access"],
+        ),
+        (
+            TrapKind::ExpectedSharedMemory,
+            &["expected shared memory", "atomic wait on non-shared memory"],
+        ),
+        (
+            TrapKind::NullReference,
+            &["null reference", "null function reference", "null structure reference"],
+        ),
+        (TrapKind::Unreachable, &["unreachable"]),
+    ];
+
+    /// Classifies a trap or exhaustion message into its category, returning
+    /// `None` if the message isn't recognized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wast::TrapKind;
+    ///
+    /// assert_eq!(
+    ///     TrapKind::classify("out of bounds memory access"),
+    ///     Some(TrapKind::OutOfBoundsMemory),
+    /// );
+    /// assert_eq!(
+    ///     TrapKind::classify("wasm trap: wasm `unreachable` instruction executed"),
+    ///     Some(TrapKind::Unreachable),
+    /// );
+    /// assert_eq!(TrapKind::classify("something else"), None);
+    /// ```
+    pub fn classify(message: &str) -> Option<TrapKind> {
+        let message = message.to_lowercase();
+        TrapKind::PATTERNS
+            .iter()
+            .find(|(_, patterns)| patterns.iter().any(|p| message.contains(p)))
+            .map(|(kind, _)| *kind)
+    }
+
+    /// Returns whether the `actual` message produced by an engine matches the
+    /// `expected` message of a directive.
+    ///
+    /// This is true if `actual` contains `expected` verbatim, or if both
+    /// messages are classified into the same [`TrapKind`].
+    pub fn messages_match(expected: &str, actual: &str) -> bool {
+        if actual.contains(expected) {
+            return true;
+        }
+        match TrapKind::classify(expected) {
+            Some(kind) => TrapKind::classify(actual) == Some(kind),
+            None => false,
         }
     }
 }
@@ -183,7 +482,7 @@ impl<'a> Parse<'a> for WastDirective<'a> {
             let span = parser.parse::<kw::assert_unlinkable>()?.0;
             Ok(WastDirective::AssertUnlinkable {
                 span,
-                module: parser.parens(parse_wat)?,
+                module: parser.parens(|p| p.parse())?,
                 message: parser.parse()?,
             })
         } else if l.peek::<kw::assert_exception>() {
@@ -192,6 +491,14 @@ impl<'a> Parse<'a> for WastDirective<'a> {
                 span,
                 exec: parser.parens(|p| p.parse())?,
             })
+        } else if l.peek::<kw::thread>() {
+            Ok(WastDirective::Thread(parser.parse()?))
+        } else if l.peek::<kw::wait>() {
+            let span = parser.parse::<kw::wait>()?.0;
+            Ok(WastDirective::Wait {
+                span,
+                thread: parser.parse()?,
+            })
         } else {
             Err(l.error())
         }
@@ -200,9 +507,10 @@ impl<'a> Parse<'a> for WastDirective<'a> {
 
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum WastExecute<'a> {
     Invoke(WastInvoke<'a>),
-    Wat(Wat<'a>),
+    Wat(QuoteWat<'a>),
     Get {
         module: Option<Id<'a>>,
         global: &'a str,
@@ -215,7 +523,7 @@ impl<'a> Parse<'a> for WastExecute<'a> {
         if l.peek::<kw::invoke>() {
             Ok(WastExecute::Invoke(parser.parse()?))
         } else if l.peek::<kw::module>() || l.peek::<kw::component>() {
-            Ok(WastExecute::Wat(parse_wat(parser)?))
+            Ok(WastExecute::Wat(parser.parse()?))
         } else if l.peek::<kw::get>() {
             parser.parse::<kw::get>()?;
             Ok(WastExecute::Get {
@@ -244,6 +552,7 @@ fn parse_wat(parser: Parser) -> Result<Wat> {
 
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct WastInvoke<'a> {
     pub span: Span,
     pub module: Option<Id<'a>>,
@@ -269,15 +578,115 @@ impl<'a> Parse<'a> for WastInvoke<'a> {
     }
 }
 
+/// A `thread` directive from the threads proposal's test suite.
+///
+/// The directives of a thread run concurrently with the rest of the script
+/// until a `wait` directive naming the thread. A thread can only see the
+/// modules listed in its `shared` clause, for example:
+///
+/// ```wast
+/// (thread $T1 (shared (module $Mem))
+///   (register "mem" $Mem)
+///   (module (memory (import "mem" "shared") 1 1 shared) ...)
+///   (invoke "run"))
+/// (wait $T1)
+/// ```
 #[allow(missing_docs)]
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WastThread<'a> {
+    pub span: Span,
+    pub name: Id<'a>,
+    pub shared_module: Option<Id<'a>>,
+    pub directives: Vec<WastDirective<'a>>,
+}
+
+impl<'a> Parse<'a> for WastThread<'a> {
+    fn parse(parser: Parser<'a>) -> Result<Self> {
+        parser.depth_check()?;
+        let span = parser.parse::<kw::thread>()?.0;
+        let name = parser.parse()?;
+
+        let shared_module = if parser.peek2::<kw::shared>() {
+            let name = parser.parens(|p| {
+                p.parse::<kw::shared>()?;
+                p.parens(|p| {
+                    p.parse::<kw::module>()?;
+                    p.parse()
+                })
+            })?;
+            Some(name)
+        } else {
+            None
+        };
+        let mut directives = Vec::new();
+        while !parser.is_empty() {
+            directives.push(parser.parens(|p| p.parse())?);
+        }
+        Ok(WastThread {
+            span,
+            name,
+            shared_module,
+            directives,
+        })
+    }
+}
+
+/// A module or component within a `*.wast` script.
+///
+/// This is accepted wherever a script defines a module, including in all
+/// assertions. It's either written in the text format, which includes the
+/// `(module binary ...)` and `(component binary ...)` forms, or is `quote`d
+/// text which is only parsed by [`QuoteWat::encode`]. [`QuoteWat::kind`]
+/// tells these apart.
+#[allow(missing_docs)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum QuoteWat<'a> {
     Wat(Wat<'a>),
     QuoteModule(Span, Vec<(Span, &'a [u8])>),
     QuoteComponent(Span, Vec<(Span, &'a [u8])>),
 }
 
-impl QuoteWat<'_> {
+/// Whether a [`QuoteWat`] defines a core module or a component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum WatKind {
+    /// A core module, written as `(module ...)`.
+    Module,
+    /// A component, written as `(component ...)`.
+    Component,
+}
+
+impl<'a> QuoteWat<'a> {
+    /// Returns whether this is a module or a component, regardless of
+    /// whether it was written as text, as a `quote` or as `binary` bytes.
+    pub fn kind(&self) -> WatKind {
+        match self {
+            QuoteWat::Wat(Wat::Module(_)) | QuoteWat::QuoteModule(..) => WatKind::Module,
+            QuoteWat::Wat(Wat::Component(_)) | QuoteWat::QuoteComponent(..) => WatKind::Component,
+        }
+    }
+
+    /// Returns the identifier of this module or component, which a quoted
+    /// one doesn't have.
+    pub fn id(&self) -> Option<Id<'a>> {
+        match self {
+            QuoteWat::Wat(Wat::Module(m)) => m.id,
+            QuoteWat::Wat(Wat::Component(c)) => c.id,
+            QuoteWat::QuoteModule(..) | QuoteWat::QuoteComponent(..) => None,
+        }
+    }
+
+    /// Returns the location of this module or component in the source.
+    pub fn span(&self) -> Span {
+        match self {
+            QuoteWat::Wat(Wat::Module(m)) => m.span,
+            QuoteWat::Wat(Wat::Component(c)) => c.span,
+            QuoteWat::QuoteModule(span, _) | QuoteWat::QuoteComponent(span, _) => *span,
+        }
+    }
+
     /// Encodes this module to bytes, either by encoding the module directly or
     /// parsing the contents and then encoding it.
     pub fn encode(&mut self) -> Result<Vec<u8>, Error> {
@@ -331,6 +740,7 @@ impl<'a> Parse<'a> for QuoteWat<'a> {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum WastArg<'a> {
     Core(WastArgCore<'a>),
@@ -348,6 +758,7 @@ impl<'a> Parse<'a> for WastArg<'a> {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum WastRet<'a> {
     Core(WastRetCore<'a>),
@@ -0,0 +1,381 @@
+use crate::core::{json_string, HeapType, NanPattern, V128Const, V128Pattern};
+use crate::core::{WastArgCore, WastRetCore};
+use crate::token::{Index, Span};
+use crate::{Error, QuoteWat, Wast, WastArg, WastDirective, WastExecute, WastInvoke, WastRet};
+use std::path::Path;
+
+/// The output of [`Wast::to_json`], in the layout of WABT's `wast2json` tool.
+///
+/// The `json` describes each directive of the script as a command, and the
+/// modules that the commands refer to are listed in `files` by the name the
+/// JSON uses for them. Writing `json` to a `*.json` file with each of the
+/// `files` next to it produces the same layout as `wast2json`.
+#[derive(Debug)]
+pub struct WastJson {
+    /// The JSON description of the script.
+    pub json: String,
+    /// The file name and contents of each module referred to by `json`,
+    /// either a `*.wasm` binary or, for `quote` modules in assertions, the
+    /// `*.wat` text.
+    pub files: Vec<(String, Vec<u8>)>,
+}
+
+impl Wast<'_> {
+    /// Converts this script to the JSON and module files that WABT's
+    /// `wast2json` tool produces, so that test harnesses consuming that
+    /// format can run scripts parsed by this crate.
+    ///
+    /// The `text` must be the source that this script was parsed from, which
+    /// is used to compute the line number of each command, and
+    /// `source_filename` is the path of the script recorded in the JSON.
+    /// Module files are named after the file stem of `source_filename`, for
+    /// example `foo.0.wasm` for `foo.wast`.
+    ///
+    /// Modules are encoded to the binary format, except for `quote` modules
+    /// in assertions, which are written out as text since they often don't
+    /// parse. The `expected` list of `action` commands is always empty, since
+    /// the types of their results aren't known without validating the module.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a module fails to encode or if the script uses a
+    /// feature that the JSON format can't describe, such as component model
+    /// values or `thread` directives.
+    pub fn to_json(&mut self, text: &str, source_filename: &str) -> Result<WastJson, Error> {
+        let stem = Path::new(source_filename)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut json = Json {
+            text,
+            stem,
+            files: Vec::new(),
+        };
+        let mut commands = Vec::new();
+        for directive in self.directives.iter_mut() {
+            commands.push(json.directive(directive)?);
+        }
+
+        let mut ret = String::new();
+        ret.push_str("{\"source_filename\": ");
+        json_string(&mut ret, source_filename);
+        ret.push_str(",\n \"commands\": [\n");
+        for (i, command) in commands.iter().enumerate() {
+            if i > 0 {
+                ret.push_str(", \n");
+            }
+            ret.push_str("  ");
+            ret.push_str(command);
+        }
+        ret.push_str("]}\n");
+        Ok(WastJson {
+            json: ret,
+            files: json.files,
+        })
+    }
+}
+
+struct Json<'a> {
+    text: &'a str,
+    stem: String,
+    files: Vec<(String, Vec<u8>)>,
+}
+
+/// The fields of a JSON object, with each value already encoded as JSON.
+type Fields = Vec<(&'static str, String)>;
+
+impl Json<'_> {
+    fn directive(&mut self, directive: &mut WastDirective<'_>) -> Result<String, Error> {
+        let span = directive.span();
+        let line = span.linecol_in(self.text).0 + 1;
+        let (kind, mut fields) = match directive {
+            WastDirective::Wat(module) => {
+                let mut fields = name("name", module.id().map(|id| id.name()));
+                let file = self.binary_file(module)?;
+                fields.push(("filename", string(&file)));
+                ("module", fields)
+            }
+            WastDirective::AssertMalformed {
+                module, message, ..
+            } => ("assert_malformed", self.assert_module(module, message)?),
+            WastDirective::AssertInvalid {
+                module, message, ..
+            } => ("assert_invalid", self.assert_module(module, message)?),
+            WastDirective::AssertUnlinkable {
+                module, message, ..
+            } => ("assert_unlinkable", self.assert_module(module, message)?),
+            WastDirective::Register {
+                name: registered,
+                module,
+                ..
+            } => {
+                let mut fields = name("name", module.map(|id| id.name()));
+                fields.push(("as", string(registered)));
+                ("register", fields)
+            }
+            WastDirective::Invoke(invoke) => {
+                let action = invoke_action(span, invoke)?;
+                let fields = vec![("action", action), ("expected", "[]".to_string())];
+                ("action", fields)
+            }
+            WastDirective::AssertReturn { exec, results, .. } => {
+                let action = execute_action(span, exec)?;
+                let results = results
+                    .iter()
+                    .map(|ret| match ret {
+                        WastRet::Core(ret) => ret_core(span, ret),
+                        WastRet::Component(_) => Err(component_value(span)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let fields = vec![("action", action), ("expected", array(&results))];
+                ("assert_return", fields)
+            }
+            WastDirective::AssertTrap {
+                exec: WastExecute::Wat(module),
+                message,
+                ..
+            } => ("assert_uninstantiable", self.assert_module(module, message)?),
+            WastDirective::AssertTrap { exec, message, .. } => {
+                let action = execute_action(span, exec)?;
+                let fields = vec![
+                    ("action", action),
+                    ("text", string(message)),
+                    ("expected", "[]".to_string()),
+                ];
+                ("assert_trap", fields)
+            }
+            WastDirective::AssertExhaustion { call, message, .. } => {
+                let action = invoke_action(span, call)?;
+                let fields = vec![
+                    ("action", action),
+                    ("text", string(message)),
+                    ("expected", "[]".to_string()),
+                ];
+                ("assert_exhaustion", fields)
+            }
+            WastDirective::AssertException { exec, .. } => {
+                let action = execute_action(span, exec)?;
+                let fields = vec![("action", action), ("expected", "[]".to_string())];
+                ("assert_exception", fields)
+            }
+            WastDirective::Thread(_) | WastDirective::Wait { .. } => {
+                return Err(unsupported(span, "`thread` and `wait` directives"));
+            }
+        };
+        fields.insert(0, ("type", string(kind)));
+        fields.insert(1, ("line", line.to_string()));
+        Ok(object(&fields))
+    }
+
+    /// Returns the fields describing the module of an assertion, which is
+    /// written out as text if it's quoted.
+    fn assert_module(&mut self, module: &mut QuoteWat<'_>, message: &str) -> Result<Fields, Error> {
+        let (file, module_type) = match module {
+            QuoteWat::QuoteModule(_, source) => {
+                let mut text = Vec::new();
+                for (_, src) in source.iter() {
+                    text.extend_from_slice(src);
+                    text.push(b' ');
+                }
+                (self.file("wat", text), "text")
+            }
+            _ => (self.binary_file(module)?, "binary"),
+        };
+        Ok(vec![
+            ("filename", string(&file)),
+            ("text", string(message)),
+            ("module_type", string(module_type)),
+        ])
+    }
+
+    fn binary_file(&mut self, module: &mut QuoteWat<'_>) -> Result<String, Error> {
+        let wasm = module.encode()?;
+        Ok(self.file("wasm", wasm))
+    }
+
+    fn file(&mut self, extension: &str, contents: Vec<u8>) -> String {
+        let name = format!("{}.{}.{}", self.stem, self.files.len(), extension);
+        self.files.push((name.clone(), contents));
+        name
+    }
+}
+
+fn execute_action(span: Span, exec: &WastExecute<'_>) -> Result<String, Error> {
+    match exec {
+        WastExecute::Invoke(invoke) => invoke_action(span, invoke),
+        WastExecute::Get { module, global } => {
+            let mut fields = vec![("type", string("get"))];
+            fields.extend(name("module", module.map(|id| id.name())));
+            fields.push(("field", string(global)));
+            Ok(object(&fields))
+        }
+        WastExecute::Wat(_) => Err(unsupported(span, "modules as actions")),
+    }
+}
+
+fn invoke_action(span: Span, invoke: &WastInvoke<'_>) -> Result<String, Error> {
+    let args = invoke
+        .args
+        .iter()
+        .map(|arg| match arg {
+            WastArg::Core(arg) => arg_core(span, arg),
+            WastArg::Component(_) => Err(component_value(span)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut fields = vec![("type", string("invoke"))];
+    fields.extend(name("module", invoke.module.map(|id| id.name())));
+    fields.push(("field", string(invoke.name)));
+    fields.push(("args", array(&args)));
+    Ok(object(&fields))
+}
+
+fn arg_core(span: Span, arg: &WastArgCore<'_>) -> Result<String, Error> {
+    Ok(match arg {
+        WastArgCore::I32(i) => value("i32", (*i as u32).to_string()),
+        WastArgCore::I64(i) => value("i64", (*i as u64).to_string()),
+        WastArgCore::F32(f) => value("f32", f.bits.to_string()),
+        WastArgCore::F64(f) => value("f64", f.bits.to_string()),
+        WastArgCore::V128(v) => {
+            let (lane_type, lanes) = match v {
+                V128Const::I8x16(l) => ("i8", l.iter().map(|i| (*i as u8).to_string()).collect()),
+                V128Const::I16x8(l) => ("i16", l.iter().map(|i| (*i as u16).to_string()).collect()),
+                V128Const::I32x4(l) => ("i32", l.iter().map(|i| (*i as u32).to_string()).collect()),
+                V128Const::I64x2(l) => ("i64", l.iter().map(|i| (*i as u64).to_string()).collect()),
+                V128Const::F32x4(l) => ("f32", l.iter().map(|f| f.bits.to_string()).collect()),
+                V128Const::F64x2(l) => ("f64", l.iter().map(|f| f.bits.to_string()).collect()),
+            };
+            v128(lane_type, lanes)
+        }
+        WastArgCore::RefNull(ty) => value(ref_type(span, ty)?, "null".to_string()),
+        WastArgCore::RefExtern(n) => value("externref", n.to_string()),
+    })
+}
+
+fn ret_core(span: Span, ret: &WastRetCore<'_>) -> Result<String, Error> {
+    Ok(match ret {
+        WastRetCore::I32(i) => value("i32", (*i as u32).to_string()),
+        WastRetCore::I64(i) => value("i64", (*i as u64).to_string()),
+        WastRetCore::F32(f) => value("f32", nan_pattern(f, |f| f.bits.to_string())),
+        WastRetCore::F64(f) => value("f64", nan_pattern(f, |f| f.bits.to_string())),
+        WastRetCore::V128(v) => {
+            let (lane_type, lanes) = match v {
+                V128Pattern::I8x16(l) => ("i8", l.iter().map(|i| (*i as u8).to_string()).collect()),
+                V128Pattern::I16x8(l) => {
+                    ("i16", l.iter().map(|i| (*i as u16).to_string()).collect())
+                }
+                V128Pattern::I32x4(l) => {
+                    ("i32", l.iter().map(|i| (*i as u32).to_string()).collect())
+                }
+                V128Pattern::I64x2(l) => {
+                    ("i64", l.iter().map(|i| (*i as u64).to_string()).collect())
+                }
+                V128Pattern::F32x4(l) => (
+                    "f32",
+                    l.iter()
+                        .map(|f| nan_pattern(f, |f| f.bits.to_string()))
+                        .collect(),
+                ),
+                V128Pattern::F64x2(l) => (
+                    "f64",
+                    l.iter()
+                        .map(|f| nan_pattern(f, |f| f.bits.to_string()))
+                        .collect(),
+                ),
+            };
+            v128(lane_type, lanes)
+        }
+        WastRetCore::RefNull(Some(ty)) => value(ref_type(span, ty)?, "null".to_string()),
+        WastRetCore::RefNull(None) => return Err(unsupported(span, "untyped `ref.null`")),
+        WastRetCore::RefExtern(n) => value("externref", n.to_string()),
+        WastRetCore::RefFunc(None) => object(&[("type", string("funcref"))]),
+        WastRetCore::RefFunc(Some(Index::Num(n, _))) => value("funcref", n.to_string()),
+        WastRetCore::RefFunc(Some(Index::Id(_))) => {
+            return Err(unsupported(span, "`ref.func` with an identifier"))
+        }
+        WastRetCore::Either(rets) => {
+            let rets = rets
+                .iter()
+                .map(|ret| ret_core(span, ret))
+                .collect::<Result<Vec<_>, _>>()?;
+            object(&[("type", string("either")), ("values", array(&rets))])
+        }
+    })
+}
+
+fn nan_pattern<T>(pattern: &NanPattern<T>, bits: impl Fn(&T) -> String) -> String {
+    match pattern {
+        NanPattern::CanonicalNan => "nan:canonical".to_string(),
+        NanPattern::ArithmeticNan => "nan:arithmetic".to_string(),
+        NanPattern::Value(f) => bits(f),
+    }
+}
+
+fn ref_type(span: Span, ty: &HeapType<'_>) -> Result<&'static str, Error> {
+    Ok(match ty {
+        HeapType::Func => "funcref",
+        HeapType::Extern => "externref",
+        HeapType::Any => "anyref",
+        HeapType::Eq => "eqref",
+        HeapType::Struct => "structref",
+        HeapType::Array => "arrayref",
+        HeapType::I31 => "i31ref",
+        HeapType::None => "nullref",
+        HeapType::NoFunc => "nullfuncref",
+        HeapType::NoExtern => "nullexternref",
+        HeapType::Index(_) => return Err(unsupported(span, "concrete reference types")),
+    })
+}
+
+/// Returns a `{"type": ..., "value": ...}` object for a value.
+fn value(ty: &str, value: String) -> String {
+    object(&[("type", string(ty)), ("value", string(&value))])
+}
+
+fn v128(lane_type: &str, lanes: Vec<String>) -> String {
+    let lanes = lanes.iter().map(|l| string(l)).collect::<Vec<_>>();
+    object(&[
+        ("type", string("v128")),
+        ("lane_type", string(lane_type)),
+        ("value", array(&lanes)),
+    ])
+}
+
+/// Returns a field for the `$`-prefixed name of a module, if it has one.
+fn name(key: &'static str, name: Option<&str>) -> Fields {
+    match name {
+        Some(name) => vec![(key, string(&format!("${}", name)))],
+        None => Vec::new(),
+    }
+}
+
+fn object(fields: &[(&str, String)]) -> String {
+    let mut ret = String::from("{");
+    for (i, (key, value)) in fields.iter().enumerate() {
+        if i > 0 {
+            ret.push_str(", ");
+        }
+        json_string(&mut ret, key);
+        ret.push_str(": ");
+        ret.push_str(value);
+    }
+    ret.push('}');
+    ret
+}
+
+fn array(values: &[String]) -> String {
+    format!("[{}]", values.join(", "))
+}
+
+fn string(s: &str) -> String {
+    let mut ret = String::new();
+    json_string(&mut ret, s);
+    ret
+}
+
+fn component_value(span: Span) -> Error {
+    unsupported(span, "component model values")
+}
+
+fn unsupported(span: Span, what: &str) -> Error {
+    Error::new(span, format!("the wast2json format doesn't support {}", what))
+}
@@ -10,6 +10,7 @@ use crate::token::Span;
 /// this crate. A `*.wat` file is either one `module` s-expression or a sequence
 /// of s-expressions that are module fields.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[allow(missing_docs)]
 pub enum Wat<'a> {
     Module(Module<'a>),
@@ -20,7 +21,7 @@ impl Wat<'_> {
     fn validate(&self, parser: Parser<'_>) -> Result<()> {
         match self {
             Wat::Module(m) => m.validate(parser),
-            Wat::Component(c) => c.validate(parser),
+            Wat::Component(_) => Ok(()),
         }
     }
 
@@ -32,6 +33,33 @@ impl Wat<'_> {
             Wat::Component(c) => c.encode(),
         }
     }
+
+    /// Appends the binary form of this `Wat` to `wasm`. This calls either
+    /// [`Module::encode_into`] or [`Component::encode`].
+    pub fn encode_into(&mut self, wasm: &mut Vec<u8>) -> std::result::Result<(), crate::Error> {
+        match self {
+            Wat::Module(m) => m.encode_into(wasm),
+            Wat::Component(c) => {
+                wasm.extend_from_slice(&c.encode()?);
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the binary form of this `Wat` to `writer`. This calls either
+    /// [`Module::encode_to`] or [`Component::encode`], with the same errors as
+    /// [`Module::encode_to`].
+    pub fn encode_to(&mut self, mut writer: impl std::io::Write) -> std::io::Result<()> {
+        match self {
+            Wat::Module(m) => m.encode_to(writer),
+            Wat::Component(c) => {
+                let wasm = c
+                    .encode()
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+                writer.write_all(&wasm)
+            }
+        }
+    }
 }
 
 impl<'a> Parse<'a> for Wat<'a> {
@@ -41,6 +69,10 @@ impl<'a> Parse<'a> for Wat<'a> {
         }
 
         let _r = parser.register_annotation("custom");
+        let _r = parser.register_annotation("producers");
+        let _r = parser.register_annotation("target_features");
+        let _r = parser.register_annotation("dylink.0");
+        let _r = parser.register_custom_annotations();
         let wat = if parser.peek2::<kw::module>() {
             Wat::Module(parser.parens(|parser| parser.parse())?)
         } else if parser.peek2::<kw::component>() {
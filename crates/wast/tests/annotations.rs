@@ -198,3 +198,67 @@ fn custom_section_order() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn registered_custom_annotations() -> anyhow::Result<()> {
+    let mut buf = wast::parser::ParseBuffer::new(
+        r#"
+            (module
+              (@flags a b c)
+              (func)
+              (@unknown a b c)
+              (@flags)
+            )
+        "#,
+    )?;
+    buf.register_custom_annotation("flags", |parser| {
+        let mut data = Vec::new();
+        while !parser.is_empty() {
+            let flag = parser.step(|c| match c.keyword() {
+                Some(pair) => Ok(pair),
+                None => Err(c.error("expected a flag")),
+            })?;
+            data.extend_from_slice(flag.as_bytes());
+        }
+        Ok(data)
+    });
+    let mut wat = wast::parser::parse::<wast::Wat>(&buf)?;
+    let bytes = wat.encode()?;
+
+    let mut customs = Vec::new();
+    for payload in Parser::new(0).parse_all(&bytes) {
+        if let Payload::CustomSection(c) = payload? {
+            customs.push((c.name().to_string(), c.data().to_vec()));
+        }
+    }
+    assert_eq!(
+        customs,
+        [
+            ("flags".to_string(), b"abc".to_vec()),
+            ("flags".to_string(), Vec::new()),
+        ]
+    );
+
+    let mut buf = wast::parser::ParseBuffer::new("(module (@flags 1))")?;
+    buf.register_custom_annotation("flags", |parser| Err(parser.error("expected a flag")));
+    let err = wast::parser::parse::<wast::Wat>(&buf).unwrap_err();
+    assert!(err.to_string().contains("expected a flag"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn registered_custom_annotations_without_module() -> anyhow::Result<()> {
+    let mut buf = wast::parser::ParseBuffer::new(r#"(@hello "x") (func)"#)?;
+    buf.register_custom_annotation("hello", |parser| Ok(parser.parse::<&[u8]>()?.to_vec()));
+    let mut wat = wast::parser::parse::<wast::Wat>(&buf)?;
+    let bytes = wat.encode()?;
+
+    let mut customs = Vec::new();
+    for payload in Parser::new(0).parse_all(&bytes) {
+        if let Payload::CustomSection(c) = payload? {
+            customs.push((c.name().to_string(), c.data().to_vec()));
+        }
+    }
+    assert_eq!(customs, [("hello".to_string(), b"x".to_vec())]);
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use wast::core::{
+    Export, ExportKind, Func, FunctionType, Instruction, Local, Module, ModuleField, TypeUse,
+    ValType,
+};
+use wast::token::{Index, Interner, Span};
+
+#[test]
+fn build_and_encode() -> anyhow::Result<()> {
+    // Names owned by the generator, which the AST borrows.
+    let names = [String::from("add_one"), String::from("twice")];
+    let interner = Interner::new();
+    let add_one = interner.id(&names[0]);
+
+    let mut add = Func::new(
+        TypeUse::new_with_inline(FunctionType::new(vec![ValType::I32], vec![ValType::I32])),
+        Vec::new(),
+        vec![
+            Instruction::LocalGet(Index::Num(0, Span::from_offset(0))),
+            Instruction::I32Const(1),
+            Instruction::I32Add,
+        ],
+    );
+    add.id = Some(add_one);
+
+    let twice = Func::new(
+        TypeUse::new_with_inline(FunctionType::new(vec![ValType::I32], vec![ValType::I32])),
+        vec![Local::new(ValType::I64)],
+        vec![
+            Instruction::LocalGet(Index::Num(0, Span::from_offset(0))),
+            Instruction::Call(Index::Id(interner.id("add_one"))),
+            Instruction::Call(Index::Id(add_one)),
+        ],
+    );
+
+    let mut module = Module::new(vec![
+        ModuleField::Func(add),
+        ModuleField::Func(twice),
+        ModuleField::Export(Export::new(
+            &names[1],
+            ExportKind::Func,
+            Index::Num(1, Span::from_offset(0)),
+        )),
+    ]);
+    let wasm = module.encode()?;
+
+    let expected = wat::parse_str(
+        r#"
+        (module
+            (func $add_one (param i32) (result i32)
+                local.get 0
+                i32.const 1
+                i32.add)
+            (func (param i32) (result i32) (local i64)
+                local.get 0
+                call $add_one
+                call $add_one)
+            (export "twice" (func 1)))
+        "#,
+    )?;
+    assert_eq!(wasm, expected);
+
+    // Unknown names are reported like they are for text.
+    let mut module = Module::new(vec![ModuleField::Func(Func::new(
+        TypeUse::new_with_inline(FunctionType::new(Vec::new(), Vec::new())),
+        Vec::new(),
+        vec![Instruction::Call(Index::Id(interner.id("missing")))],
+    ))]);
+    let err = module.encode().unwrap_err();
+    assert!(err.message().contains("unknown func"), "{}", err);
+    Ok(())
+}
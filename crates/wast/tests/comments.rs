@@ -1,4 +1,4 @@
-use wast::parser::{self, Parse, ParseBuffer, Parser, Result};
+use wast::parser::{self, Parse, ParseBuffer, Parser, Result, TriviaKind};
 
 pub struct Comments<'a> {
     comments: Vec<&'a str>,
@@ -74,3 +74,42 @@ multiple;)
     drop(d.item);
     Ok(())
 }
+
+#[test]
+fn trivia() -> anyhow::Result<()> {
+    let wat = r#"
+;; leading
+(module (@tool "x" ;; inside
+  )
+  (func (@name "f") (; block ;) nop) ;; trailing
+)
+"#;
+    let mut buf = ParseBuffer::new(wat)?;
+    buf.track_trivia(true);
+    parser::parse::<wast::Wat>(&buf)?;
+
+    let trivia = buf.trivia();
+    let items = trivia
+        .iter()
+        .filter(|t| t.kind != TriviaKind::Whitespace)
+        .map(|t| (t.kind, t.text))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        items,
+        [
+            (TriviaKind::LineComment, ";; leading"),
+            (TriviaKind::Annotation, "(@tool \"x\" ;; inside\n  )"),
+            (TriviaKind::BlockComment, "(; block ;)"),
+            (TriviaKind::LineComment, ";; trailing"),
+        ]
+    );
+    for t in trivia.iter() {
+        assert_eq!(&wat[t.span.offset()..][..t.text.len()], t.text);
+    }
+
+    // Without tracking nothing is reported.
+    let buf = ParseBuffer::new(wat)?;
+    parser::parse::<wast::Wat>(&buf)?;
+    assert!(buf.trivia().is_empty());
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use wast::parser::{self, ParseBuffer};
+use wast::{ErrorKind, Wat};
+
+fn parse_error(wat: &str) -> wast::Error {
+    let buf = match ParseBuffer::new(wat) {
+        Ok(buf) => buf,
+        Err(e) => return e,
+    };
+    let mut wat = match parser::parse::<Wat>(&buf) {
+        Ok(wat) => wat,
+        Err(e) => return e,
+    };
+    wat.encode().unwrap_err()
+}
+
+#[test]
+fn lex_errors() {
+    let err = parse_error("(module \"abc");
+    assert_eq!(err.kind(), ErrorKind::Lex);
+    assert!(err.lex_error().is_some());
+    assert!(err.expected().is_empty());
+    assert_eq!(err.notes().len(), 0);
+}
+
+#[test]
+fn parse_errors_list_expected_tokens() {
+    let err = parse_error("(module (func (param i32 i64) (result 1)))");
+    assert_eq!(err.kind(), ErrorKind::Parse);
+    assert_eq!(err.span().offset(), 38);
+    assert!(err.expected().contains(&"`i32`"), "{:?}", err.expected());
+
+    let err = parse_error("(module (func) x)");
+    assert_eq!(err.kind(), ErrorKind::Parse);
+    assert_eq!(err.expected(), ["left paren"]);
+}
+
+#[test]
+fn resolve_errors_have_notes() {
+    let err = parse_error(
+        r#"
+            (component
+              (import "a" (func $f))
+              (start $f)
+              (start $f)
+            )
+        "#,
+    );
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert!(err.expected().is_empty());
+    let notes = err.notes().collect::<Vec<_>>();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].1, "previous start function here");
+}
+
+#[test]
+fn duplicate_names_point_at_previous_definition() {
+    let err = parse_error("(module (global $g i32 (i32.const 0)) (global $g i32 (i32.const 1)))");
+    assert_eq!(err.message(), "duplicate global identifier");
+    assert_eq!(err.span().offset(), 46);
+    let notes = err.notes().collect::<Vec<_>>();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].0.offset(), 16);
+    assert_eq!(notes[0].1, "previous definition here");
+}
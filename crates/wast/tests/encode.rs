@@ -0,0 +1,402 @@
+use wast::parser::{self, ParseBuffer};
+use wast::Wat;
+
+const WAT: &str = r#"
+    (module $m
+        (type (func (param i32) (result i32)))
+        (import "a" "b" (func $f (type 0)))
+        (memory 1)
+        (func (export "g") (param i32) (result i32)
+            local.get 0
+            call $f)
+        (data (i32.const 0) "hello")
+        (@custom "c" "xyz")
+    )
+"#;
+
+fn parse_and<T>(wat: &str, f: impl FnOnce(&mut Wat<'_>) -> T) -> T {
+    let buf = ParseBuffer::new(wat).unwrap();
+    let mut wat = parser::parse::<Wat>(&buf).unwrap();
+    f(&mut wat)
+}
+
+#[test]
+fn encode_into_and_to_match_encode() {
+    let expected = parse_and(WAT, |wat| wat.encode().unwrap());
+
+    let mut wasm = b"prefix".to_vec();
+    parse_and(WAT, |wat| wat.encode_into(&mut wasm).unwrap());
+    assert_eq!(&wasm[..6], b"prefix");
+    assert_eq!(&wasm[6..], &expected[..]);
+
+    let mut written = Vec::new();
+    parse_and(WAT, |wat| wat.encode_to(&mut written).unwrap());
+    assert_eq!(written, expected);
+}
+
+#[test]
+fn encode_to_writes_one_item_at_a_time() {
+    // Each function body is written out on its own rather than as part of a
+    // buffered code section.
+    let body = "(i32.add (local.get 0) (i32.const 1))".repeat(200);
+    let wat =
+        format!("(module (func (param i32) (result i32) {body}) (func (param i32) {body} drop))");
+    let expected = parse_and(&wat, |wat| wat.encode().unwrap());
+
+    struct Writes(Vec<Vec<u8>>);
+    impl std::io::Write for Writes {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.push(bytes.to_vec());
+            Ok(bytes.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut writes = Writes(Vec::new());
+    parse_and(&wat, |wat| wat.encode_to(&mut writes).unwrap());
+    assert_eq!(writes.0.concat(), expected);
+    let largest = writes.0.iter().map(|w| w.len()).max().unwrap();
+    assert!(largest < 1500, "{largest}");
+}
+
+#[test]
+fn encode_to_reports_errors() {
+    let err = parse_and("(module (func call $missing))", |wat| {
+        wat.encode_to(Vec::new()).unwrap_err()
+    });
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let inner = err
+        .get_ref()
+        .unwrap()
+        .downcast_ref::<wast::Error>()
+        .unwrap();
+    assert!(inner.message().contains("unknown func"), "{}", inner);
+
+    struct Full;
+    impl std::io::Write for Full {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::WriteZero.into())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+    let err = parse_and(WAT, |wat| wat.encode_to(Full).unwrap_err());
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn offsets() -> anyhow::Result<()> {
+    let text = r#"
+        (module
+            (memory 1)
+            (func $f (param i32) (result i32)
+                (i32.add (local.get 0) (i32.const 1)))
+            (func (local i64)
+                (block
+                    (br_if 0 (i32.const 0)))
+                (if (i32.const 1) (then nop) (else unreachable)))
+            (data (i32.const 0) "a")
+            (data "bc")
+        )
+    "#;
+    let mut buf = ParseBuffer::new(text)?;
+    buf.track_instr_spans(true);
+    let mut module = match parser::parse::<Wat>(&buf)? {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    let (wasm, offsets) = module.encode_with_offsets()?;
+
+    let mut funcs = Vec::new();
+    let mut instrs = Vec::new();
+    let mut data = Vec::new();
+    for payload in wasmparser::Parser::new(0).parse_all(&wasm) {
+        match payload? {
+            wasmparser::Payload::CodeSectionEntry(body) => {
+                funcs.push(body.range().start);
+                let mut body_instrs = Vec::new();
+                for op in body.get_operators_reader()?.into_iter_with_offsets() {
+                    body_instrs.push(op?.1);
+                }
+                // Skip the function's implicit `end`.
+                body_instrs.pop();
+                instrs.extend(body_instrs);
+            }
+            wasmparser::Payload::DataSection(reader) => {
+                for segment in reader {
+                    data.push(segment?.range.start);
+                }
+            }
+            _ => {}
+        }
+    }
+    let offsets_of = |items: &[(wast::token::Span, usize)]| {
+        items.iter().map(|(_, offset)| *offset).collect::<Vec<_>>()
+    };
+    assert_eq!(offsets_of(&offsets.funcs), funcs);
+    assert_eq!(offsets_of(&offsets.instrs), instrs);
+    assert_eq!(offsets_of(&offsets.data), data);
+
+    // The token at `span`, which is either a keyword or a `)`.
+    let text_at = |span: wast::token::Span| {
+        let rest = &text[span.offset()..];
+        let len = rest.find(|c: char| c.is_whitespace() || c == ')').unwrap();
+        rest[..len.max(1)].to_string()
+    };
+    let instr_text = offsets
+        .instrs
+        .iter()
+        .map(|(span, _)| text_at(*span))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        instr_text,
+        [
+            "local.get",
+            "i32.const",
+            "i32.add",
+            "block",
+            "i32.const",
+            "br_if",
+            ")",
+            "i32.const",
+            "if",
+            "nop",
+            "else",
+            "unreachable",
+            ")",
+        ]
+    );
+    assert_eq!(text_at(offsets.funcs[0].0), "func");
+    assert_eq!(text_at(offsets.data[1].0), "data");
+
+    // Without tracking, only functions and data segments have offsets.
+    let buf = ParseBuffer::new(text)?;
+    let mut module = match parser::parse::<Wat>(&buf)? {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    let (untracked, untracked_offsets) = module.encode_with_offsets()?;
+    assert_eq!(untracked, wasm);
+    assert!(untracked_offsets.instrs.is_empty());
+    assert_eq!(untracked_offsets.funcs, offsets.funcs);
+    assert_eq!(untracked_offsets.data, offsets.data);
+    Ok(())
+}
+
+#[test]
+fn source_map() -> anyhow::Result<()> {
+    let text = "(module (func nop))\n";
+    let mut buf = ParseBuffer::new(text)?;
+    buf.track_instr_spans(true);
+    let mut module = match parser::parse::<Wat>(&buf)? {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    let (_, offsets) = module.encode_with_offsets()?;
+    // The function's body is at offset 22 and `nop` at offset 23, mapped to
+    // columns 9 and 14 of the first line.
+    assert_eq!(
+        offsets.source_map(text, "a \"b\".wat"),
+        r#"{"version":3,"sources":["a \"b\".wat"],"sourcesContent":["(module (func nop))\n"],"names":[],"mappings":"sBAAS,CAAK"}"#
+    );
+    Ok(())
+}
+
+#[test]
+fn custom_page_sizes() {
+    let wasm = parse_and(
+        r#"
+        (module
+            (import "" "" (memory 1 (pagesize 1)))
+            (memory i64 0 2 (pagesize 65536)))
+        "#,
+        |wat| wat.encode().unwrap(),
+    );
+    let mut memories = Vec::new();
+    for payload in wasmparser::Parser::new(0).parse_all(&wasm) {
+        match payload.unwrap() {
+            wasmparser::Payload::ImportSection(s) => memories.push(s.range()),
+            wasmparser::Payload::MemorySection(s) => memories.push(s.range()),
+            _ => {}
+        }
+    }
+    // The limits' flags have 0x08 set and are followed by log2 of the size.
+    assert_eq!(&wasm[memories[0].clone()], b"\x01\0\0\x02\x08\x01\x00");
+    assert_eq!(&wasm[memories[1].clone()], b"\x01\x0d\x00\x02\x10");
+}
+
+#[test]
+fn encode_sections() {
+    let encode_sections = |wat: &str| {
+        parse_and(wat, |wat| match wat {
+            Wat::Module(m) => m.encode_sections().unwrap(),
+            Wat::Component(_) => unreachable!(),
+        })
+    };
+    let sections = encode_sections(WAT);
+    let ids = sections.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    assert_eq!(ids, [1, 2, 3, 5, 7, 10, 11, 0, 0]);
+
+    // Reassembling the sections gives back the whole module, and the code
+    // section can be swapped for that of a module with different bodies.
+    let assemble = |sections: &[(u8, Vec<u8>)]| {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        for (id, contents) in sections {
+            // All of the sections here are smaller than 128 bytes, so their
+            // size is a single byte.
+            wasm.push(*id);
+            wasm.push(u8::try_from(contents.len()).unwrap());
+            wasm.extend_from_slice(contents);
+        }
+        wasm
+    };
+    assert_eq!(
+        assemble(&sections),
+        parse_and(WAT, |wat| wat.encode().unwrap())
+    );
+
+    let other = WAT.replace("local.get 0", "i32.const 1");
+    let mut spliced = encode_sections(WAT);
+    let code = encode_sections(&other).remove(5);
+    assert_eq!(code.0, 10);
+    spliced[5] = code;
+    assert_eq!(
+        assemble(&spliced),
+        parse_and(&other, |wat| wat.encode().unwrap())
+    );
+}
+
+#[test]
+fn data_count_for_gc_instructions() {
+    let has_data_count = |wat: &str| {
+        let wasm = parse_and(wat, |wat| wat.encode().unwrap());
+        let found = wasmparser::Parser::new(0)
+            .parse_all(&wasm)
+            .any(|p| matches!(p.unwrap(), wasmparser::Payload::DataCountSection { .. }));
+        found
+    };
+    assert!(has_data_count(
+        r#"
+        (module
+            (type $a (array (mut i8)))
+            (func (param (ref $a))
+                (array.init_data $a $d
+                    (local.get 0) (i32.const 0) (i32.const 0) (i32.const 0)))
+            (data $d ""))
+        "#
+    ));
+    assert!(has_data_count(
+        r#"
+        (module
+            (type $a (array i8))
+            (global (ref $a) (array.new_data $a $d (i32.const 0) (i32.const 0)))
+            (data $d ""))
+        "#
+    ));
+    assert!(!has_data_count(r#"(module (func) (data ""))"#));
+}
+
+#[test]
+fn nested_components() {
+    let inner = r#"
+        (component
+            (import "f" (func))
+            (core module (func (export "g")))
+        )
+    "#;
+    let outer = format!("(component {inner} {inner})");
+    let inner = parse_and(inner, |wat| wat.encode().unwrap());
+    let outer = parse_and(&outer, |wat| wat.encode().unwrap());
+
+    // Each nested component is embedded as-is, with its size as a prefix.
+    let mut nested = Vec::new();
+    for payload in wasmparser::Parser::new(0).parse_all(&outer) {
+        if let wasmparser::Payload::ComponentSection { range, .. } = payload.unwrap() {
+            nested.push(range);
+        }
+    }
+    assert_eq!(nested.len(), 2);
+    for range in nested {
+        assert_eq!(&outer[range.clone()], &inner[..]);
+        assert_eq!(usize::from(outer[range.start - 1]), inner.len());
+    }
+    wasmparser::Validator::new_with_features(wasmparser::WasmFeatures {
+        component_model: true,
+        ..Default::default()
+    })
+    .validate_all(&outer)
+    .unwrap();
+}
+
+#[test]
+fn custom_page_sizes_in_component_types() {
+    let wasm = parse_and(
+        r#"
+        (component
+            (core type (module (import "" "" (memory 1 (pagesize 1))))))
+        "#,
+        |wat| wat.encode().unwrap(),
+    );
+    // The memory type keeps its page size rather than silently dropping it.
+    let memory = b"\x02\x08\x01\x00";
+    assert!(wasm.windows(memory.len()).any(|w| w == memory));
+}
+
+#[test]
+fn gc_heap_types_in_component_types() {
+    let wasm = parse_and(
+        r#"
+        (component
+            (core type (func (param (ref null none) (ref nofunc) (ref null noextern))
+                             (result (ref any) (ref null eq) (ref i31)))))
+        "#,
+        |wat| wat.encode().unwrap(),
+    );
+    // Each heap type is encoded with its `ref`/`ref null` prefix.
+    let ty = b"\x60\x03\x63\x71\x64\x73\x63\x72\x03\x64\x6e\x63\x6d\x64\x6c";
+    assert!(wasm.windows(ty.len()).any(|w| w == ty));
+}
+
+#[test]
+fn gc_types_use_final_encoding() {
+    let wasm = parse_and(
+        r#"
+        (module
+            (rec (type (struct)) (type (array i32)))
+            (type $a (sub (func)))
+            (type (sub final $a (func (param (ref null struct) (ref i31) nullref)))))
+        "#,
+        |wat| wat.encode().unwrap(),
+    );
+    let types = b"\x01\x17\x03\
+        \x4e\x02\x5f\x00\x5e\x7f\x00\
+        \x50\x00\x60\x00\x00\
+        \x4f\x01\x02\x60\x03\x6b\x64\x6c\x71\x00";
+    assert!(wasm.windows(types.len()).any(|w| w == types));
+}
+
+#[test]
+fn typed_function_references_validate() {
+    let wasm = parse_and(
+        r#"
+        (module
+            (type $t (func))
+            (func (param (ref null $t))
+                (block
+                    local.get 0
+                    br_on_null 0
+                    call_ref $t)
+                local.get 0
+                ref.as_non_null
+                call_ref $t))
+        "#,
+        |wat| wat.encode().unwrap(),
+    );
+    let mut validator = wasmparser::Validator::new_with_features(wasmparser::WasmFeatures {
+        function_references: true,
+        ..Default::default()
+    });
+    validator.validate_all(&wasm).unwrap();
+}
@@ -0,0 +1,64 @@
+use wast::parser::{self, ParseBuffer};
+use wast::token::Span;
+use wast::{Location, LocationComments, Wat};
+
+#[test]
+fn parses_location_comments() {
+    assert_eq!(Location::from_comment("(;@0;)"), Some(Location::Offset(0)));
+    assert_eq!(
+        Location::from_comment("(;@1a2b  ;)"),
+        Some(Location::Offset(0x1a2b))
+    );
+    assert_eq!(
+        Location::from_comment("(;@ foo.wat:12 ;)"),
+        Some(Location::Line {
+            file: "foo.wat",
+            line: 12
+        })
+    );
+    assert_eq!(Location::from_comment("(;@;)"), None);
+    assert_eq!(Location::from_comment("(;@xyz;)"), None);
+    assert_eq!(Location::from_comment("(;@:12;)"), None);
+    assert_eq!(Location::from_comment("(; @12 ;)"), None);
+    assert_eq!(Location::from_comment(";; @12"), None);
+}
+
+#[test]
+fn printed_offsets_round_trip() -> anyhow::Result<()> {
+    let wasm = wat::parse_str(
+        r#"
+            (module
+                (func (export "f") (param i32) (result i32)
+                    local.get 0
+                    i32.const 1
+                    i32.add))
+        "#,
+    )?;
+    let mut printer = wasmprinter::Printer::new();
+    printer.print_offsets(true);
+    let text = printer.print(&wasm)?;
+
+    // The printed text, location comments and all, parses back to the same
+    // binary.
+    let buf = ParseBuffer::new(&text)?;
+    let mut wat = parser::parse::<Wat>(&buf)?;
+    assert_eq!(wat.encode()?, wasm);
+
+    // And each instruction maps back to its offset in the original binary.
+    let comments = LocationComments::new(&text)?;
+    let mut reader = wasmparser::Parser::new(0).parse_all(&wasm);
+    let body = loop {
+        if let wasmparser::Payload::CodeSectionEntry(body) = reader.next().unwrap()? {
+            break body;
+        }
+    };
+    let mut ops = body.get_operators_reader()?;
+    ops.read()?;
+    let (_, offset) = ops.read_with_offset()?;
+    let i32_const = text.find("i32.const 1").unwrap();
+    assert_eq!(
+        comments.get(Span::from_offset(i32_const)),
+        Some(Location::Offset(offset))
+    );
+    Ok(())
+}
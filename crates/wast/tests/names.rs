@@ -0,0 +1,196 @@
+use wast::component::ComponentNameKind;
+use wast::core::{FuncKind, Instruction, ModuleField, ModuleKind, NameKind};
+use wast::parser::{self, ParseBuffer};
+use wast::token::Index;
+use wast::Wat;
+
+#[test]
+fn core_names() -> anyhow::Result<()> {
+    let buf = ParseBuffer::new(
+        r#"
+        (module
+            (type $ty (func))
+            (import "" "f" (func $imported))
+            (func $f)
+            (table $t1 1 funcref)
+            (table $t2 1 funcref)
+            (memory $m 1)
+            (global $g i32 (i32.const 0))
+            (tag $e)
+            (data $d "")
+            (elem $el func $f)
+        )
+        "#,
+    )?;
+    let mut module = match parser::parse::<Wat>(&buf)? {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    let names = module.resolve()?;
+
+    assert_eq!(names.index_of("ty", NameKind::Type), Some(0));
+    assert_eq!(names.index_of("imported", NameKind::Func), Some(0));
+    assert_eq!(names.index_of("f", NameKind::Func), Some(1));
+    assert_eq!(names.index_of("t2", NameKind::Table), Some(1));
+    assert_eq!(names.index_of("m", NameKind::Memory), Some(0));
+    assert_eq!(names.index_of("g", NameKind::Global), Some(0));
+    assert_eq!(names.index_of("e", NameKind::Tag), Some(0));
+    assert_eq!(names.index_of("d", NameKind::Data), Some(0));
+    assert_eq!(names.index_of("el", NameKind::Elem), Some(0));
+    assert_eq!(names.index_of("f", NameKind::Table), None);
+    assert_eq!(names.index_of("missing", NameKind::Func), None);
+
+    // Identifiers from the module's own parse buffer can be resolved too.
+    let global_id = match &module.kind {
+        ModuleKind::Text(fields) => fields
+            .iter()
+            .find_map(|f| match f {
+                ModuleField::Global(g) => g.id,
+                _ => None,
+            })
+            .unwrap(),
+        ModuleKind::Binary(_) => unreachable!(),
+    };
+    let mut idx = Index::Id(global_id);
+    assert_eq!(names.resolve(&mut idx, NameKind::Global)?, 0);
+    assert!(matches!(idx, Index::Num(0, _)));
+    assert!(names
+        .resolve(&mut Index::Id(global_id), NameKind::Func)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn component_names() -> anyhow::Result<()> {
+    let buf = ParseBuffer::new(
+        r#"
+        (component
+            (import "a" (func $a))
+            (core module $m)
+            (core instance $i (instantiate $m))
+            (type $t (func))
+            (component $c)
+            (instance $inst (instantiate $c))
+            (core func $lowered (canon lower (func $a)))
+            (export $b "b" (func $a))
+        )
+        "#,
+    )?;
+    let mut component = match parser::parse::<Wat>(&buf)? {
+        Wat::Component(c) => c,
+        Wat::Module(_) => unreachable!(),
+    };
+    let names = component.resolve()?;
+
+    assert_eq!(names.index_of("a", ComponentNameKind::Func), Some(0));
+    assert_eq!(names.index_of("b", ComponentNameKind::Func), Some(1));
+    assert_eq!(names.index_of("m", ComponentNameKind::CoreModule), Some(0));
+    assert_eq!(
+        names.index_of("i", ComponentNameKind::CoreInstance),
+        Some(0)
+    );
+    // The type of `$a` is expanded to a type definition before `$t`.
+    assert_eq!(names.index_of("t", ComponentNameKind::Type), Some(1));
+    assert_eq!(names.index_of("c", ComponentNameKind::Component), Some(0));
+    assert_eq!(names.index_of("inst", ComponentNameKind::Instance), Some(0));
+    assert_eq!(
+        names.index_of("lowered", ComponentNameKind::CoreFunc),
+        Some(0)
+    );
+    assert_eq!(names.index_of("a", ComponentNameKind::CoreFunc), None);
+    Ok(())
+}
+
+#[test]
+fn names_without_resolving() -> anyhow::Result<()> {
+    let text = r#"
+        (module
+            (func $f (import "" "f"))
+            (memory $m (data "abc"))
+            (table $t funcref (elem $f))
+            (data $d "")
+            (elem $e func $f)
+            (func $g (export "g") call $f)
+        )
+    "#;
+    let buf = ParseBuffer::new(text)?;
+    let module = match parser::parse::<Wat>(&buf)? {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    let names = module.names()?;
+    assert_eq!(names.index_of("g", NameKind::Func), Some(1));
+    // The inline data and elements are segments of their own.
+    assert_eq!(names.index_of("d", NameKind::Data), Some(1));
+    assert_eq!(names.index_of("e", NameKind::Elem), Some(1));
+
+    // The AST still refers to `$f` by name, which can be resolved on demand.
+    let fields = match &module.kind {
+        ModuleKind::Text(fields) => fields,
+        ModuleKind::Binary(_) => unreachable!(),
+    };
+    assert_eq!(fields.len(), 6);
+    let call = fields
+        .iter()
+        .find_map(|f| match f {
+            ModuleField::Func(f) => match &f.kind {
+                FuncKind::Inline { expression, .. } => match expression.instrs.first() {
+                    Some(Instruction::Call(idx)) => Some(*idx),
+                    _ => None,
+                },
+                FuncKind::Import(_) => None,
+            },
+            _ => None,
+        })
+        .unwrap();
+    assert!(matches!(call, Index::Id(_)));
+    assert_eq!(names.index(&call, NameKind::Func)?, 0);
+    assert!(names.index(&call, NameKind::Global).is_err());
+
+    // The same indices are assigned when resolving.
+    let mut module = module;
+    let resolved = module.resolve()?;
+    for (name, kind) in [("d", NameKind::Data), ("e", NameKind::Elem)] {
+        assert_eq!(resolved.index_of(name, kind), names.index_of(name, kind));
+    }
+    Ok(())
+}
+
+#[test]
+fn implicit_types() -> anyhow::Result<()> {
+    let text = r#"
+        (module
+            (import "" "f" (func (param i32)))
+            (func (param i64) (result i64)
+                (block (result i32 i32)
+                    unreachable)
+                unreachable)
+            (func (type $t) (param i64))
+            (func (param i32) (result i32 i32)
+                (block (result i32 i32)
+                    unreachable))
+            (type $t (func (param i64)))
+        )
+    "#;
+    let resolve = || -> anyhow::Result<Vec<u32>> {
+        let buf = ParseBuffer::new(text)?;
+        let mut module = match parser::parse::<Wat>(&buf)? {
+            Wat::Module(m) => m,
+            Wat::Component(_) => unreachable!(),
+        };
+        Ok(module.resolve()?.implicit_types().to_vec())
+    };
+    // `(param i64)` refers to `$t` and the block types are shared, so only the
+    // four other signatures are added, in order, after `$t`.
+    assert_eq!(resolve()?, [1, 2, 3, 4]);
+    assert_eq!(resolve()?, [1, 2, 3, 4]);
+    Ok(())
+}
+
+#[test]
+fn tag_names_round_trip() -> anyhow::Result<()> {
+    let wasm = wat::parse_str("(module (tag $mytag))")?;
+    let text = wasmprinter::print_bytes(&wasm)?;
+    assert!(text.contains("(tag $mytag"), "{text}");
+    Ok(())
+}
@@ -0,0 +1,7 @@
+(module
+  (type $t (func (param i32) (result i32 i32)))
+  (func (param i32) (result i64 i64)
+    (if (type $t) (param i32) (result i64 i64)
+      (local.get 0)
+      (then unreachable)
+      (else unreachable))))
@@ -0,0 +1,5 @@
+(component
+  (import "a" (func $f))
+  (start $f)
+  (start $f)
+)
@@ -0,0 +1,3 @@
+(component
+  (value $v u32 1)
+)
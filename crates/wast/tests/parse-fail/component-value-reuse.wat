@@ -0,0 +1,5 @@
+(component
+  (import "a" (value $v string))
+  (export "b" (value $v))
+  (export "c" (value $v))
+)
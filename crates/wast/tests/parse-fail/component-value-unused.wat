@@ -0,0 +1,9 @@
+(component
+  (component $c
+    (import "a" (value $a string))
+    (export "b" (value $a))
+  )
+  (import "b" (value $v string))
+  (import "c" (value string))
+  (instance (instantiate $c (with "a" (value $v))))
+)
@@ -0,0 +1 @@
+(module (import "a" (func)))
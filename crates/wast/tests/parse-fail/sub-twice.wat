@@ -0,0 +1,3 @@
+(module
+  (type $a (sub (func)))
+  (sub $a (type (sub (func)))))
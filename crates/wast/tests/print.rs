@@ -0,0 +1,169 @@
+use wast::core::Printer;
+use wast::parser::{self, ParseBuffer};
+use wast::Wat;
+
+/// Asserts that `wat` prints, in both flat and folded form, to text which
+/// encodes to the same binary as `wat` itself.
+fn assert_roundtrip(wat: &str) {
+    let expected = wat::parse_str(wat).unwrap();
+    let buf = ParseBuffer::new(wat).unwrap();
+    let module = match parser::parse::<Wat>(&buf).unwrap() {
+        Wat::Module(module) => module,
+        Wat::Component(_) => unreachable!(),
+    };
+    for fold in [false, true] {
+        let text = Printer::new().fold_instructions(fold).print_module(&module);
+        let actual = wat::parse_str(&text).unwrap_or_else(|e| panic!("{e}\n{text}"));
+        assert_eq!(expected, actual, "printed text differs:\n{text}");
+    }
+}
+
+/// Asserts that the component `wat` prints to text which encodes to the same
+/// binary as `wat` itself.
+fn assert_component_roundtrip(wat: &str) {
+    let expected = wat::parse_str(wat).unwrap();
+    let buf = ParseBuffer::new(wat).unwrap();
+    let component = match parser::parse::<Wat>(&buf).unwrap() {
+        Wat::Component(component) => component,
+        Wat::Module(_) => unreachable!(),
+    };
+    let text = Printer::new().print_component(&component);
+    let actual = wat::parse_str(&text).unwrap_or_else(|e| panic!("{e}\n{text}"));
+    assert_eq!(expected, actual, "printed text differs:\n{text}");
+}
+
+#[test]
+fn roundtrip_fields() {
+    assert_roundtrip(
+        r#"
+        (module $m
+            (type $t (func (param i32) (result i32)))
+            (rec
+                (type $s (sub (struct (field $x (mut i32)) (field i8))))
+                (type (sub final $s (struct (field $z (mut i32)) (field i8) (field f64)))))
+            (type (array (mut i16)))
+            (import "a" "b" (func $imp (param i32)))
+            (import "a" "g" (global $ig (mut i64)))
+            (func $i2 (import "a" "c") (type $t))
+            (memory $mem (export "mem") 1 2)
+            (memory i64 (data "\00\01"))
+            (memory 0 1 shared (pagesize 1))
+            (data (i32.const 8) "hi\00\ff" "\"\\")
+            (data $passive "x")
+            (global $g (mut i32) (i32.const 0))
+            (table 2 funcref)
+            (table $t2 externref (elem (ref.null extern)))
+            (elem (i32.const 0) $f $f)
+            (elem declare func $f)
+            (elem (table $t2) (offset i32.const 0) externref (item ref.null extern))
+            (tag $e (param i32))
+            (start $f)
+            (export "g" (global $g))
+            (func $f (@name "the function"))
+            (@custom "hello" (before first) "world")
+            (@producers (language "wat" "1.0"))
+            (@dylink.0 (mem-info (memory 4 2)) (export-info "g" binding-weak 0x8000))
+        )
+        "#,
+    );
+}
+
+#[test]
+fn roundtrip_instructions() {
+    assert_roundtrip(
+        r#"
+        (module
+            (memory 1)
+            (table 1 funcref)
+            (func $f (export "f") (param $x i32) (param i32 i64) (result i32)
+                (local $y i32) (local f32 f64)
+                (if (result i32) (local.get $x)
+                    (then (i32.const 1))
+                    (else (block $b (result i32) (br $b (i32.const 2)))))
+                (loop $l (br_if $l (i32.const 0)))
+                (block (br_table 0 0 0 (i32.const 0)))
+                (drop (call_indirect (param i32) (result i32) (i32.const 0) (i32.const 0)))
+                f32.const nan:0x1 drop
+                f64.const -inf drop
+                f32.const 0.1 drop
+                f64.const -0x1.8p-3 drop
+                i32.const 0 i32.load offset=4 align=1 drop
+                i32.const 0 v128.load8_lane 3 drop
+                v128.const i32x4 1 -2 3 4
+                v128.const f32x4 1.5 nan -inf 0
+                i8x16.shuffle 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+                drop
+                (select (result i32) (i32.const 0) (i32.const 1) (i32.const 1))
+                (memory.copy (i32.const 0) (i32.const 0) (i32.const 0))
+                unreachable
+                end)
+        )
+        "#,
+    );
+}
+
+#[test]
+fn roundtrip_component() {
+    assert_component_roundtrip(
+        r#"
+        (component $c
+            (type $r (record (field "a" u32) (field "b" string)))
+            (type $v (variant (case $x "x") (case "y" u8 (refines $x))))
+            (type (list $r))
+            (type (tuple u8 (option s64) (result) (result u8 (error string))))
+            (type (flags "a" "b"))
+            (type (enum "c" "d"))
+            (type (union u8 float64))
+            (type $f (func (param "x" u32) (result string)))
+            (type (func (result "a" u8) (result "b" char)))
+            (type $i (instance
+                (type $t u8)
+                (alias outer $c $r (type))
+                (export "f" (func (type $f)))
+                (export "t" (type (eq $t)))))
+            (type (component
+                (import "i" (instance (type $i)))
+                (export "v" (value u32))))
+            (core type $ct (func (param i32)))
+            (core type (module
+                (type $t (func))
+                (import "a" "b" (func (type $t)))
+                (export "c" (func (type 0)))))
+            (import "f" (func $f1 (type $f)))
+            (import "v" (value $val bool))
+            (import "m" (core module $m))
+            (import "n" "https://example.com" (instance $inst (export "g" (func))))
+            (core module $m2
+                (memory (export "mem") 1)
+                (func (export "realloc") (param i32 i32 i32 i32) (result i32) unreachable)
+                (func (export "lift") (param i32 i32) unreachable))
+            (core instance $ci (instantiate $m2))
+            (core instance (instantiate $m (with "a" (instance $ci))
+                (with "b" (instance (export "f" (func $ci "lift"))))))
+            (core instance (export "m" (memory $ci "mem")))
+            (alias core export $ci "mem" (core memory $mem))
+            (alias export $inst "g" (func $g))
+            (core func $lowered (canon lower (func $f1) string-encoding=utf16
+                (memory $mem) (realloc (func $ci "realloc"))))
+            (core func (alias core export $ci "lift"))
+            (func $lifted (export "lifted") (type $f)
+                (canon lift (core func $ci "lift") (memory $mem)))
+            (func (type $f) (canon lift (core func $ci "lift")
+                string-encoding=latin1+utf16 (memory $mem)))
+            (core func (canon lower (func $inst "g")))
+            (func (alias export $inst "g"))
+            (component $nested
+                (import "x" (func))
+                (alias outer $c $r (type)))
+            (instance (instantiate $nested (with "x" (func $g))))
+            (instance (export "g" (func $g)))
+            (start $f1 (value $val) (result (value $out)))
+            (export "out" (value $out))
+            (export $e "r" (type $r))
+            (export "nested" (component $nested))
+            (export "m" (core module $m))
+            (@custom "hello" "world")
+        )
+        "#,
+    );
+}
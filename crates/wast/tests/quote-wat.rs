@@ -0,0 +1,64 @@
+use wast::parser::{self, ParseBuffer};
+use wast::{QuoteWat, Wast, WastDirective, WastExecute, WatKind};
+
+#[test]
+fn kinds_in_all_directives() -> anyhow::Result<()> {
+    let buf = ParseBuffer::new(
+        r#"
+        (module $m)
+        (module binary "\00asm" "\01\00\00\00")
+        (module quote "(func)")
+        (component $c)
+        (component binary "\00asm" "\0d\00\01\00")
+        (component quote "(core module)")
+        (assert_unlinkable (module quote "(import \"a\" \"b\" (func))") "unknown import")
+        (assert_unlinkable (component binary "\00asm" "\0d\00\01\00") "unknown import")
+        (assert_trap (module binary "\00asm" "\01\00\00\00") "unreachable")
+        (assert_trap (component quote "") "unreachable")
+        (assert_invalid (component binary "\00asm" "\0d\00\01\00") "type mismatch")
+        (assert_malformed (component quote "(") "unexpected token")
+        "#,
+    )?;
+    let mut wast = parser::parse::<Wast>(&buf)?;
+    let mut kinds = Vec::new();
+    for directive in wast.directives.iter_mut() {
+        let module = match directive {
+            WastDirective::Wat(module)
+            | WastDirective::AssertUnlinkable { module, .. }
+            | WastDirective::AssertTrap {
+                exec: WastExecute::Wat(module),
+                ..
+            }
+            | WastDirective::AssertInvalid { module, .. }
+            | WastDirective::AssertMalformed { module, .. } => module,
+            _ => unreachable!(),
+        };
+        kinds.push((
+            module.kind(),
+            module.id().map(|id| id.name()),
+            matches!(module, QuoteWat::Wat(_)),
+        ));
+        if let QuoteWat::Wat(_) = module {
+            assert!(module.encode()?.starts_with(b"\0asm"));
+        }
+    }
+    use WatKind::*;
+    assert_eq!(
+        kinds,
+        [
+            (Module, Some("m"), true),
+            (Module, None, true),
+            (Module, None, false),
+            (Component, Some("c"), true),
+            (Component, None, true),
+            (Component, None, false),
+            (Module, None, false),
+            (Component, None, true),
+            (Module, None, true),
+            (Component, None, false),
+            (Component, None, true),
+            (Component, None, false),
+        ]
+    );
+    Ok(())
+}
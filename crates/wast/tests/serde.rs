@@ -0,0 +1,50 @@
+#![cfg(feature = "serde")]
+
+use serde_json::json;
+use wast::parser::{self, ParseBuffer};
+use wast::{Wast, Wat};
+
+#[test]
+fn module_to_json() -> anyhow::Result<()> {
+    let buf = ParseBuffer::new("(module $m (func $f (export \"f\") (result i32) i32.const 7))")?;
+    let wat = parser::parse::<Wat>(&buf)?;
+    let json = serde_json::to_value(&wat)?;
+
+    let module = &json["Module"];
+    assert_eq!(module["id"]["name"], "m");
+    let func = &module["kind"]["Text"][0]["Func"];
+    assert_eq!(func["id"]["name"], "f");
+    assert_eq!(func["exports"]["names"], json!(["f"]));
+    assert_eq!(func["ty"]["inline"]["results"], json!(["I32"]));
+    assert_eq!(
+        func["kind"]["Inline"]["expression"]["instrs"],
+        json!([{ "I32Const": 7 }])
+    );
+    Ok(())
+}
+
+#[test]
+fn wast_to_json() -> anyhow::Result<()> {
+    let buf = ParseBuffer::new(
+        r#"
+        (component (core module $m))
+        (assert_return (invoke "f" (i32.const 1)) (i32.const 2))
+        "#,
+    )?;
+    let wast = parser::parse::<Wast>(&buf)?;
+    let json = serde_json::to_value(&wast)?;
+
+    let component = &json["directives"][0]["Wat"]["Wat"]["Component"];
+    assert_eq!(
+        component["kind"]["Text"][0]["CoreModule"]["id"]["name"],
+        "m"
+    );
+    let assert = &json["directives"][1]["AssertReturn"];
+    assert_eq!(assert["exec"]["Invoke"]["name"], "f");
+    assert_eq!(
+        assert["exec"]["Invoke"]["args"],
+        json!([{ "Core": { "I32": 1 } }])
+    );
+    assert_eq!(assert["results"], json!([{ "Core": { "I32": 2 } }]));
+    Ok(())
+}
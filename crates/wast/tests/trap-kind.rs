@@ -0,0 +1,50 @@
+use wast::TrapKind;
+
+#[test]
+fn spec_messages() {
+    let cases = [
+        ("out of bounds memory access", TrapKind::OutOfBoundsMemory),
+        ("out of bounds table access", TrapKind::OutOfBoundsTable),
+        ("unreachable", TrapKind::Unreachable),
+        ("call stack exhausted", TrapKind::StackOverflow),
+        (
+            "indirect call type mismatch",
+            TrapKind::IndirectCallTypeMismatch,
+        ),
+        ("undefined element", TrapKind::UndefinedElement),
+        ("uninitialized element 2", TrapKind::UninitializedElement),
+        ("integer divide by zero", TrapKind::IntegerDivideByZero),
+        ("integer overflow", TrapKind::IntegerOverflow),
+        (
+            "invalid conversion to integer",
+            TrapKind::InvalidConversionToInteger,
+        ),
+        ("null reference", TrapKind::NullReference),
+        ("unaligned atomic", TrapKind::UnalignedAtomic),
+        ("expected shared memory", TrapKind::ExpectedSharedMemory),
+    ];
+    for (message, kind) in cases {
+        assert_eq!(TrapKind::classify(message), Some(kind), "{message}");
+    }
+}
+
+#[test]
+fn engine_messages() {
+    assert!(TrapKind::messages_match(
+        "out of bounds memory access",
+        "wasm trap: Memory out of bounds",
+    ));
+    assert!(TrapKind::messages_match(
+        "unaligned atomic",
+        "wasm trap: misaligned memory access",
+    ));
+    assert!(TrapKind::messages_match(
+        "call stack exhausted",
+        "wasm trap: call stack exhausted",
+    ));
+    assert!(!TrapKind::messages_match(
+        "unreachable",
+        "out of bounds memory access",
+    ));
+    assert!(!TrapKind::messages_match("some message", "another message"));
+}
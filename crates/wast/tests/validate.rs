@@ -0,0 +1,58 @@
+#![cfg(feature = "validate")]
+
+use wasmparser::WasmFeatures;
+use wast::parser::{self, ParseBuffer};
+use wast::Wat;
+
+fn encode_validated(text: &str, track_instr_spans: bool) -> Result<Vec<u8>, wast::Error> {
+    let mut buf = ParseBuffer::new(text)?;
+    buf.track_instr_spans(track_instr_spans);
+    let mut module = match parser::parse::<Wat>(&buf)? {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    module.encode_validated(WasmFeatures::default())
+}
+
+#[test]
+fn valid() {
+    let wasm = encode_validated("(module (func (result i32) i32.const 1))", false).unwrap();
+    assert_eq!(
+        wasm,
+        wat::parse_str("(module (func (result i32) i32.const 1))").unwrap()
+    );
+}
+
+#[test]
+fn errors_point_at_text() {
+    let text = "(module\n  (func (result i32)\n    nop\n    i64.const 1))";
+
+    let err = encode_validated(text, true).unwrap_err();
+    assert!(err.message().contains("type mismatch"), "{}", err);
+    assert_eq!(err.span().linecol_in(text), (3, 4));
+
+    // Without instruction spans the function is blamed instead.
+    let err = encode_validated(text, false).unwrap_err();
+    assert_eq!(err.span().linecol_in(text), (1, 3));
+}
+
+#[test]
+fn features() {
+    let text = "(module (func (result i32 i32) i32.const 0 i32.const 0))";
+    assert!(encode_validated(text, false).is_ok());
+
+    let buf = ParseBuffer::new(text).unwrap();
+    let mut module = match parser::parse::<Wat>(&buf).unwrap() {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    let err = module
+        .encode_validated(WasmFeatures {
+            multi_value: false,
+            ..WasmFeatures::default()
+        })
+        .unwrap_err();
+    assert!(err.message().contains("multi-value"), "{}", err);
+    // Errors outside of functions are reported at the `module` keyword.
+    assert_eq!(err.span().offset(), 1);
+}
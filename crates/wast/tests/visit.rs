@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use wast::core::VisitInstruction;
+use wast::core::{FoldedInstruction, FuncKind, Instruction, ModuleField, ModuleKind};
+use wast::parser::{self, ParseBuffer};
+use wast::token::Index;
+use wast::Wat;
+
+const WAT: &str = r#"
+    (module
+        (memory $m0 1)
+        (memory $m1 1)
+        (func $a)
+        (func $b
+            (i32.store $m1 (i32.const 0) (i32.load (i32.const 4)))
+            (memory.copy $m0 $m1 (i32.const 0) (i32.const 0) (i32.const 0))
+            call $a
+            (if (i32.const 1)
+                (then call $a (br 0))
+                (else call $b))
+            (block $l (loop (br_if $l (i32.const 0))))
+        )
+    )
+"#;
+
+fn with_body<T>(wat: &str, resolve: bool, f: impl FnOnce(&wast::core::Expression<'_>) -> T) -> T {
+    let buf = ParseBuffer::new(wat).unwrap();
+    let mut module = match parser::parse::<Wat>(&buf).unwrap() {
+        Wat::Module(m) => m,
+        Wat::Component(_) => unreachable!(),
+    };
+    if resolve {
+        module.resolve().unwrap();
+    }
+    let fields = match &module.kind {
+        ModuleKind::Text(fields) => fields,
+        ModuleKind::Binary(_) => unreachable!(),
+    };
+    let body = fields
+        .iter()
+        .rev()
+        .find_map(|f| match f {
+            ModuleField::Func(f) => match &f.kind {
+                FuncKind::Inline { expression, .. } => Some(expression),
+                FuncKind::Import(_) => None,
+            },
+            _ => None,
+        })
+        .unwrap();
+    f(body)
+}
+
+#[test]
+fn visit_indices() {
+    #[derive(Default)]
+    struct Counts {
+        instrs: usize,
+        calls: HashMap<u32, usize>,
+        memories: Vec<u32>,
+        labels: Vec<u32>,
+    }
+
+    impl<'a> VisitInstruction<'a> for Counts {
+        fn visit_instruction(&mut self, _instr: &Instruction<'a>) {
+            self.instrs += 1;
+        }
+        fn visit_func(&mut self, instr: &Instruction<'a>, func: &Index<'a>) {
+            assert!(matches!(instr, Instruction::Call(_)));
+            if let Index::Num(n, _) = func {
+                *self.calls.entry(*n).or_insert(0) += 1;
+            }
+        }
+        fn visit_memory(&mut self, _instr: &Instruction<'a>, memory: &Index<'a>) {
+            if let Index::Num(n, _) = memory {
+                self.memories.push(*n);
+            }
+        }
+        fn visit_label(&mut self, _instr: &Instruction<'a>, label: &Index<'a>) {
+            if let Index::Num(n, _) = label {
+                self.labels.push(*n);
+            }
+        }
+    }
+
+    let counts = with_body(WAT, true, |body| {
+        let mut counts = Counts::default();
+        body.visit(&mut counts);
+        assert_eq!(counts.instrs, body.instrs.len());
+        counts
+    });
+    assert_eq!(counts.calls, HashMap::from([(0, 2), (1, 1)]));
+    assert_eq!(counts.memories, [0, 1, 0, 1]);
+    assert_eq!(counts.labels, [0, 1]);
+}
+
+#[test]
+fn visit_types() {
+    #[derive(Default)]
+    struct Types(Vec<String>);
+
+    impl<'a> VisitInstruction<'a> for Types {
+        fn visit_type(&mut self, _instr: &Instruction<'a>, ty: &Index<'a>) {
+            if let Index::Id(id) = ty {
+                self.0.push(id.name().to_string());
+            }
+        }
+    }
+
+    const WAT: &str = r#"
+        (module
+            (type $f (func))
+            (type $s (struct))
+            (type $a (array i8))
+            (func
+                (block $b (param (ref null $s)) (result (ref $f))
+                    unreachable)
+                (select (result (ref null $a)) (ref.null $a) (ref.null $a) (i32.const 0))
+                (call_indirect (type $f) (param (ref $s)) (result (ref null $a)))
+                (let (result (ref $f)) (local (ref $s)) unreachable)
+            )
+        )
+    "#;
+    let types = with_body(WAT, false, |body| {
+        let mut types = Types::default();
+        body.visit(&mut types);
+        types.0
+    });
+    assert_eq!(types, ["s", "f", "a", "a", "a", "f", "s", "a", "f", "s"]);
+}
+
+#[test]
+fn fold() {
+    fn names(instrs: &[FoldedInstruction<'_, '_>]) -> Vec<String> {
+        instrs
+            .iter()
+            .map(|i| {
+                let name = format!("{:?}", i.instr);
+                let name = name.split('(').next().unwrap().to_string();
+                if i.body.is_empty() && i.arms.is_empty() {
+                    return name;
+                }
+                let mut ret = format!("{}[{}]", name, names(&i.body).join(" "));
+                for (_, arm) in i.arms.iter() {
+                    ret.push_str(&format!("[{}]", names(arm).join(" ")));
+                }
+                assert!(i.end.is_some());
+                ret
+            })
+            .collect()
+    }
+
+    with_body(WAT, false, |body| {
+        assert_eq!(
+            names(&body.fold()),
+            [
+                "I32Const",
+                "I32Const",
+                "I32Load",
+                "I32Store",
+                "I32Const",
+                "I32Const",
+                "I32Const",
+                "MemoryCopy",
+                "Call",
+                "I32Const",
+                "If[Call Br][Call]",
+                "Block[Loop[I32Const BrIf]]",
+            ]
+        );
+    });
+}
@@ -0,0 +1,109 @@
+use wast::parser::{self, ParseBuffer};
+use wast::{Wast, WastDirective, WastModules};
+
+#[test]
+fn directives() -> anyhow::Result<()> {
+    let buf = ParseBuffer::new(
+        r#"
+        (module $a (global (export "g") i32 (i32.const 1)))
+        (assert_return (get "g") (i32.const 1))
+        (module quote "(func)")
+        (assert_return (get $a "g") (i32.const 1))
+        (assert_trap (module (start 0) (func unreachable)) "unreachable")
+        (assert_invalid (module (func (result i32))) "type mismatch")
+        (component $c)
+        (register "a" $a)
+        (register "c")
+        (assert_trap (invoke $a "f") "unreachable")
+        (assert_exhaustion (invoke "f") "call stack exhausted")
+        "#,
+    )?;
+    let wast = parser::parse::<Wast>(&buf)?;
+    let mut modules = WastModules::new();
+    let refs = wast
+        .directives
+        .iter()
+        .map(|d| modules.directive(d))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        refs,
+        [
+            Some(0),
+            Some(0),
+            Some(1),
+            Some(0),
+            None,
+            None,
+            Some(2),
+            Some(0),
+            Some(2),
+            Some(0),
+            Some(2),
+        ]
+    );
+    assert_eq!(modules.registered("a"), Some(0));
+    assert_eq!(modules.registered("c"), Some(2));
+    assert_eq!(modules.registered("b"), None);
+    Ok(())
+}
+
+#[test]
+fn threads() -> anyhow::Result<()> {
+    let buf = ParseBuffer::new(
+        r#"
+        (module $mem (memory (export "m") 1 1 shared))
+        (module $other)
+        (thread $t (shared (module $mem))
+            (register "mem" $mem)
+            (module $user)
+            (invoke $mem "run")
+            (invoke "run"))
+        (wait $t)
+        "#,
+    )?;
+    let wast = parser::parse::<Wast>(&buf)?;
+    let mut modules = WastModules::new();
+    for directive in &wast.directives[..2] {
+        modules.directive(directive)?;
+    }
+    let thread = match &wast.directives[2] {
+        WastDirective::Thread(t) => t,
+        _ => unreachable!(),
+    };
+    assert_eq!(modules.directive(&wast.directives[2])?, None);
+    let mut thread_modules = modules.thread(thread)?;
+    let refs = thread
+        .directives
+        .iter()
+        .map(|d| thread_modules.directive(d))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(refs, [Some(0), Some(1), Some(0), Some(1)]);
+    assert_eq!(thread_modules.registered("mem"), Some(0));
+    assert_eq!(modules.registered("mem"), None);
+    Ok(())
+}
+
+#[test]
+fn errors() -> anyhow::Result<()> {
+    let message = |wast: &str| -> anyhow::Result<String> {
+        let buf = ParseBuffer::new(wast)?;
+        let wast = parser::parse::<Wast>(&buf)?;
+        let mut modules = WastModules::new();
+        for directive in wast.directives.iter() {
+            if let Err(e) = modules.directive(directive) {
+                return Ok(e.message());
+            }
+        }
+        panic!("no error")
+    };
+    assert_eq!(message("(invoke \"f\")")?, "no module has been defined");
+    assert_eq!(
+        message("(module $a) (register \"b\" $b)")?,
+        "unknown module `$b`"
+    );
+    assert_eq!(
+        message("(module) (assert_return (get $x \"g\"))")?,
+        "unknown module `$x`"
+    );
+    Ok(())
+}
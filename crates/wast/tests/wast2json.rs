@@ -0,0 +1,63 @@
+use wast::parser::{self, ParseBuffer};
+use wast::Wast;
+
+#[test]
+fn commands() -> anyhow::Result<()> {
+    let text = r#"
+        (module $m
+          (func (export "add") (param i32 i32) (result i32)
+            local.get 0
+            local.get 1
+            i32.add)
+          (global (export "g") f32 (f32.const 1)))
+        (register "m" $m)
+        (assert_return (invoke "add" (i32.const -1) (i32.const 1)) (i32.const 0))
+        (assert_return (get $m "g") (f32.const nan:canonical))
+        (assert_trap (invoke $m "add" (i64.const 1)) "unreachable")
+        (invoke "add" (v128.const i16x8 0 1 2 3 4 5 6 -1))
+        (assert_malformed (module quote "(func") "unexpected end")
+        (assert_invalid (module (func (result i32))) "type mismatch")
+    "#;
+    let buf = ParseBuffer::new(text)?;
+    let mut wast = parser::parse::<Wast>(&buf)?;
+    let json = wast.to_json(text, "tests/simple.wast")?;
+
+    assert_eq!(
+        json.json,
+        r#"{"source_filename": "tests/simple.wast",
+ "commands": [
+  {"type": "module", "line": 2, "name": "$m", "filename": "simple.0.wasm"}, 
+  {"type": "register", "line": 8, "name": "$m", "as": "m"}, 
+  {"type": "assert_return", "line": 9, "action": {"type": "invoke", "field": "add", "args": [{"type": "i32", "value": "4294967295"}, {"type": "i32", "value": "1"}]}, "expected": [{"type": "i32", "value": "0"}]}, 
+  {"type": "assert_return", "line": 10, "action": {"type": "get", "module": "$m", "field": "g"}, "expected": [{"type": "f32", "value": "nan:canonical"}]}, 
+  {"type": "assert_trap", "line": 11, "action": {"type": "invoke", "module": "$m", "field": "add", "args": [{"type": "i64", "value": "1"}]}, "text": "unreachable", "expected": []}, 
+  {"type": "action", "line": 12, "action": {"type": "invoke", "field": "add", "args": [{"type": "v128", "lane_type": "i16", "value": ["0", "1", "2", "3", "4", "5", "6", "65535"]}]}, "expected": []}, 
+  {"type": "assert_malformed", "line": 13, "filename": "simple.1.wat", "text": "unexpected end", "module_type": "text"}, 
+  {"type": "assert_invalid", "line": 14, "filename": "simple.2.wasm", "text": "type mismatch", "module_type": "binary"}]}
+"#
+    );
+
+    let names = json
+        .files
+        .iter()
+        .map(|(n, _)| n.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["simple.0.wasm", "simple.1.wat", "simple.2.wasm"]);
+    assert!(json.files[0].1.starts_with(b"\0asm"));
+    assert_eq!(json.files[1].1, b"(func ");
+    wasmparser::Validator::new().validate_all(&json.files[0].1)?;
+    Ok(())
+}
+
+#[test]
+fn unsupported() -> anyhow::Result<()> {
+    let text = "(thread $t (invoke \"f\")) (wait $t)";
+    let buf = ParseBuffer::new(text)?;
+    let mut wast = parser::parse::<Wast>(&buf)?;
+    let err = wast.to_json(text, "t.wast").unwrap_err();
+    assert_eq!(
+        err.message(),
+        "the wast2json format doesn't support `thread` and `wait` directives"
+    );
+    Ok(())
+}
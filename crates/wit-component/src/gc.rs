@@ -318,17 +318,18 @@ impl<'a> Module<'a> {
                     self.funcs[next_code_index].def = Definition::Local(body);
                     next_code_index += 1;
                 }
+                Payload::SkippedCodeSectionChunk { .. } => {}
 
                 // Ignore all custom sections except for the `name` and
                 // `producers` sections which we parse, but ignore errors within.
-                Payload::CustomSection(s) => {
-                    if s.name() == "name" {
-                        drop(self.parse_name_section(&s));
-                    }
-                    if s.name() == "producers" {
-                        drop(self.parse_producers_section(&s));
-                    }
+                Payload::CustomSection(s) => self.parse_custom_section(&s),
+                // A streamed custom section is read whole out of `wasm` when it
+                // starts, so its chunks are ignored.
+                Payload::CustomSectionStart { range, .. } => {
+                    let s = CustomSectionReader::new(&wasm[range.clone()], range.start)?;
+                    self.parse_custom_section(&s);
                 }
+                Payload::CustomSectionChunk { .. } => {}
 
                 // sections that shouldn't appear in the specially-crafted core wasm
                 // adapter self we're processing
@@ -361,6 +362,15 @@ impl<'a> Module<'a> {
         Ok(())
     }
 
+    fn parse_custom_section(&mut self, section: &CustomSectionReader<'a>) {
+        if section.name() == "name" {
+            drop(self.parse_name_section(section));
+        }
+        if section.name() == "producers" {
+            drop(self.parse_producers_section(section));
+        }
+    }
+
     fn parse_name_section(&mut self, section: &CustomSectionReader<'a>) -> Result<()> {
         let section = NameSectionReader::new(section.data(), section.data_offset());
         for s in section {
@@ -566,6 +576,7 @@ impl<'a> Module<'a> {
                 maximum: mem.ty.maximum,
                 shared: mem.ty.shared,
                 memory64: mem.ty.memory64,
+                page_size_log2: None,
             };
             match &mem.def {
                 Definition::Import(m, n) => {
@@ -36,7 +36,7 @@ use crate::validation::BARE_FUNC_MODULE_NAME;
 use crate::{DecodedWasm, StringEncoding};
 use anyhow::{bail, Context, Result};
 use indexmap::IndexMap;
-use wasm_encoder::Encode;
+use wasm_encoder::{Encode, Section};
 use wasm_metadata::Producers;
 use wasmparser::BinaryReader;
 use wit_parser::{Document, Package, Resolve, World, WorldId, WorldItem};
@@ -186,6 +186,33 @@ pub fn encode(
     Ok(ret)
 }
 
+/// Embeds the metadata for `world` within the core wasm module `wasm` as a
+/// `component-type` custom section, as created by [`encode`].
+///
+/// Before the section is appended the exports of `wasm` are validated to
+/// match the functions that `world` exports, returning an error if a function
+/// is missing or has the wrong signature. The imports of `wasm` aren't
+/// validated since they may be satisfied by adapters when the component is
+/// created.
+pub fn embed(
+    wasm: &mut Vec<u8>,
+    resolve: &Resolve,
+    world: WorldId,
+    encoding: StringEncoding,
+) -> Result<()> {
+    crate::validation::validate_module_exports(wasm, resolve, world)
+        .context("module does not implement the exports of the world")?;
+
+    let encoded = encode(resolve, world, encoding, None)?;
+    let section = wasm_encoder::CustomSection {
+        name: "component-type",
+        data: &encoded,
+    };
+    wasm.push(section.id());
+    section.encode(wasm);
+    Ok(())
+}
+
 impl Bindgen {
     fn decode(data: &[u8]) -> Result<Bindgen> {
         let mut reader = BinaryReader::new(data);
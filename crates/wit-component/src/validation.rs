@@ -204,6 +204,48 @@ pub fn validate_module<'a>(
     Ok(ret)
 }
 
+/// Validates that the core wasm module in `bytes` exports functions with the
+/// expected signatures for everything that `world` exports.
+///
+/// Unlike `validate_module` this doesn't look at the imports of the module,
+/// which may not be known to `world` when they're to be satisfied later on by
+/// adapter modules.
+pub fn validate_module_exports(bytes: &[u8], resolve: &Resolve, world: WorldId) -> Result<()> {
+    let mut validator = Validator::new();
+    let mut types = None;
+    let mut export_funcs = IndexMap::new();
+
+    for payload in Parser::new(0).parse_all(bytes) {
+        let payload = payload?;
+        if let ValidPayload::End(tys) = validator.payload(&payload)? {
+            types = Some(tys);
+            break;
+        }
+
+        match payload {
+            Payload::Version { encoding, .. } if encoding != Encoding::Module => {
+                bail!("data is not a WebAssembly module");
+            }
+            Payload::ExportSection(s) => {
+                for export in s {
+                    let export = export?;
+                    if export.kind == ExternalKind::Func && !is_canonical_function(export.name) {
+                        export_funcs.insert(export.name, export.index);
+                    }
+                }
+            }
+            _ => continue,
+        }
+    }
+
+    let types = types.unwrap();
+    for (name, item) in resolve.worlds[world].exports.iter() {
+        validate_exported_item(resolve, item, name, &export_funcs, &types)?;
+    }
+
+    Ok(())
+}
+
 /// Validation information from an "adapter module" which is distinct from a
 /// "main module" validated above.
 ///
@@ -64,7 +64,10 @@ fn component_encoding_via_flags() -> Result<()> {
             }
         };
 
-        let wat = wasmprinter::print_bytes(&bytes)?;
+        // The producers section records the version of this crate, so it's
+        // elided from the expectation to avoid churn on every release.
+        let wat = wasmprinter::print_bytes(&bytes)?
+            .replace(env!("CARGO_PKG_VERSION"), "$CARGO_PKG_VERSION");
         assert_output(&wat, &component_path)?;
         let (doc, resolve) = match wit_component::decode("component", &bytes)? {
             DecodedWasm::WitPackage(..) => unreachable!(),
@@ -5,6 +5,10 @@
     (import "old" "nargs" (func (;0;) (type 0)))
     (func (;1;) (type 1))
     (export "the_entrypoint" (func 1))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+      (processed-by "my-fake-bindgen" "123.45")
+    )
   )
   (core module (;1;)
     (type (;0;) (func))
@@ -32,12 +36,18 @@
     (table (;0;) 1 1 funcref)
     (export "0" (func $adapt-old-nargs))
     (export "$imports" (table 0))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
   )
   (core module (;3;)
     (type (;0;) (func (result i32)))
     (import "" "0" (func (;0;) (type 0)))
     (import "" "$imports" (table (;0;) 1 1 funcref))
     (elem (;0;) (i32.const 0) func 0)
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
   )
   (core instance (;0;) (instantiate 2))
   (alias core export 0 "0" (core func (;0;)))
@@ -69,5 +79,8 @@
   (type (;0;) (func (param "nargs" u32)))
   (alias core export 4 "entrypoint" (core func (;3;)))
   (func (;0;) (type 0) (canon lift (core func 3)))
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
   (export (;1;) "entrypoint" (func 0))
 )
\ No newline at end of file
@@ -18,6 +18,10 @@
     (memory (;0;) 1)
     (export "bar" (func 1))
     (export "memory" (memory 0))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+      (processed-by "my-fake-bindgen" "123.45")
+    )
   )
   (core module (;1;)
     (type (;0;) (func (param i32 i32)))
@@ -52,6 +56,9 @@
     (export "0" (func $indirect-$root-foo))
     (export "1" (func $indirect-adapter-imports-foo))
     (export "$imports" (table 0))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
   )
   (core module (;3;)
     (type (;0;) (func (param i32 i32)))
@@ -59,6 +66,9 @@
     (import "" "1" (func (;1;) (type 0)))
     (import "" "$imports" (table (;0;) 2 2 funcref))
     (elem (;0;) (i32.const 0) func 0 1)
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
   )
   (core instance (;0;) (instantiate 2))
   (alias core export 0 "0" (core func (;0;)))
@@ -98,5 +108,8 @@
   (export (;3;) "bar" (func 2))
   (alias core export 4 "adapter-bar" (core func (;6;)))
   (func (;4;) (type 1) (canon lift (core func 6) (memory 0) (realloc 2) string-encoding=utf8))
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
   (export (;5;) "adapter-bar" (func 4))
 )
\ No newline at end of file
@@ -11,6 +11,10 @@
     (import "old" "log" (func (;0;) (type 0)))
     (memory (;0;) 1)
     (export "memory" (memory 0))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+      (processed-by "my-fake-bindgen" "123.45")
+    )
   )
   (core module (;1;)
     (type (;0;) (func (param i32 i32)))
@@ -35,6 +39,9 @@
     (export "0" (func $indirect-new-log))
     (export "1" (func $adapt-old-log))
     (export "$imports" (table 0))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
   )
   (core module (;3;)
     (type (;0;) (func (param i32 i32)))
@@ -42,6 +49,9 @@
     (import "" "1" (func (;1;) (type 0)))
     (import "" "$imports" (table (;0;) 2 2 funcref))
     (elem (;0;) (i32.const 0) func 0 1)
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+    )
   )
   (core instance (;0;) (instantiate 2))
   (alias core export 0 "1" (core func (;0;)))
@@ -65,6 +75,9 @@
   (alias export 0 "log" (func (;0;)))
   (core func (;2;) (canon lower (func 0) (memory 0) string-encoding=utf8))
   (alias core export 4 "log" (core func (;3;)))
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
   (core instance (;5;)
     (export "$imports" (table 0))
     (export "0" (func 2))
@@ -1,4 +1,12 @@
 (component
-  (core module (;0;))
+  (core module (;0;)
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+      (processed-by "my-fake-bindgen" "123.45")
+    )
+  )
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
   (core instance (;0;) (instantiate 0))
 )
\ No newline at end of file
@@ -17,6 +17,10 @@
     (export "cabi_realloc" (func 0))
     (export "a" (func 1))
     (export "cabi_post_a" (func 2))
+    (@producers
+      (processed-by "wit-component" "$CARGO_PKG_VERSION")
+      (processed-by "my-fake-bindgen" "123.45")
+    )
   )
   (core instance (;0;) (instantiate 0))
   (alias core export 0 "memory" (core memory (;0;)))
@@ -25,5 +29,8 @@
   (alias core export 0 "a" (core func (;1;)))
   (alias core export 0 "cabi_post_a" (core func (;2;)))
   (func (;0;) (type 0) (canon lift (core func 1) (memory 0) string-encoding=utf8 (post-return 2)))
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
   (export (;1;) "a" (func 0))
 )
\ No newline at end of file
@@ -52,7 +52,10 @@ fn run_test(path: &Path, is_dir: bool) -> Result<()> {
     // convert that binary wasm to textual wasm, then assert it matches the
     // expectation.
     let wasm = wit_component::encode(&resolve, package)?;
-    let wat = wasmprinter::print_bytes(&wasm)?;
+    // The producers section records the version of this crate, so it's
+    // elided from the expectation to avoid churn on every release.
+    let wat =
+        wasmprinter::print_bytes(&wasm)?.replace(env!("CARGO_PKG_VERSION"), "$CARGO_PKG_VERSION");
     assert_output(&path.with_extension("wat"), &wat)?;
     wasmparser::Validator::new_with_features(features)
         .validate_all(&wasm)
@@ -85,7 +88,8 @@ fn run_test(path: &Path, is_dir: bool) -> Result<()> {
     // matches the prior wasm.
     let wasm2 = wit_component::encode(resolve, decoded.package())?;
     if wasm != wasm2 {
-        let wat2 = wasmprinter::print_bytes(&wasm)?;
+        let wat2 = wasmprinter::print_bytes(&wasm2)?
+            .replace(env!("CARGO_PKG_VERSION"), "$CARGO_PKG_VERSION");
         assert_eq!(wat, wat2, "document did not roundtrip correctly");
     }
 
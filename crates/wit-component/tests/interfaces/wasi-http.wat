@@ -91,5 +91,8 @@
       (export (;0;) "proxy" "pkg:/proxy/proxy" (component (type 0)))
     )
   )
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
   (export (;5;) "proxy" "pkg:/proxy" (type 4))
 )
\ No newline at end of file
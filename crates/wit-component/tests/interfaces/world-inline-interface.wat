@@ -16,5 +16,8 @@
       (export (;0;) "has-inline" "pkg:/world-inline-interface/has-inline" (component (type 0)))
     )
   )
+  (@producers
+    (processed-by "wit-component" "$CARGO_PKG_VERSION")
+  )
   (export (;1;) "world-inline-interface" "pkg:/world-inline-interface" (type 0))
 )
\ No newline at end of file
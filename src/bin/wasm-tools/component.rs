@@ -4,7 +4,6 @@ use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use wasm_encoder::{Encode, Section};
 use wasm_tools::Output;
 use wit_component::{ComponentEncoder, DecodedWasm, DocumentPrinter, StringEncoding};
 use wit_parser::{PackageId, Resolve, UnresolvedPackage};
@@ -123,7 +122,8 @@ impl NewOpts {
 ///
 /// This metadata describe the imports and exports of a core wasm module with a
 /// WIT package's `world`. The metadata will be used when creating a full
-/// component.
+/// component. The exports of the core wasm module are validated to match the
+/// functions exported from the `world` before the metadata is embedded.
 ///
 /// Note that this subcommand may not be required most of the time since most
 /// language tooling will already embed this metadata in the final wasm binary
@@ -182,21 +182,14 @@ impl EmbedOpts {
         let (resolve, id) = parse_wit(&self.wit)?;
         let world = resolve.select_world(id, self.world.as_deref())?;
 
-        let encoded = wit_component::metadata::encode(
+        let mut wasm = wasm.unwrap_or_else(|| wit_component::dummy_module(&resolve, world));
+        wit_component::metadata::embed(
+            &mut wasm,
             &resolve,
             world,
             self.encoding.unwrap_or(StringEncoding::UTF8),
-            None,
         )?;
 
-        let section = wasm_encoder::CustomSection {
-            name: "component-type",
-            data: &encoded,
-        };
-        let mut wasm = wasm.unwrap_or_else(|| wit_component::dummy_module(&resolve, world));
-        wasm.push(section.id());
-        section.encode(&mut wasm);
-
         self.io.output(Output::Wasm {
             bytes: &wasm,
             wat: false,
@@ -64,6 +64,7 @@ impl Opts {
                 Name::Table(names) => new_section.tables(&self.name_map(names)?),
                 Name::Element(names) => new_section.elements(&self.name_map(names)?),
                 Name::Data(names) => new_section.data(&self.name_map(names)?),
+                Name::Tag(names) => new_section.tags(&self.name_map(names)?),
                 Name::Local(names) => new_section.locals(&self.indirect_name_map(names)?),
                 Name::Label(names) => new_section.labels(&self.indirect_name_map(names)?),
                 Name::Unknown { .. } => bail!("unknown name section"),
@@ -250,6 +250,14 @@ impl<'a> Dump<'a> {
                     }
                     self.print_ops(body.get_operators_reader()?)?;
                 }
+                Payload::SkippedCodeSectionChunk { range } => {
+                    self.print_byte_header()?;
+                    for _ in 0..NBYTES {
+                        write!(self.dst, "---")?;
+                    }
+                    writeln!(self.dst, "-| ... {} bytes skipped", range.len())?;
+                    self.cur += range.len();
+                }
 
                 // Component sections
                 Payload::ModuleSection { range, .. } => {
@@ -429,6 +437,48 @@ impl<'a> Dump<'a> {
                                 iter.original_position(),
                             )?;
                         }
+                    } else if c.name() == "producers" {
+                        let reader = ProducersSectionReader::new(c.data(), c.data_offset())?;
+                        self.print_iter(reader, |me, _end, field| {
+                            write!(me.state, "field {:?}", field.name)?;
+                            me.print(field.values.range().start)?;
+                            me.print_iter(field.values, |me, end, value| {
+                                write!(me.state, "{:?}", value)?;
+                                me.print(end)
+                            })
+                        })?;
+                    } else if c.name() == "target_features" {
+                        let reader = TargetFeaturesSectionReader::new(c.data(), c.data_offset())?;
+                        self.print_iter(reader, |me, end, feature| {
+                            write!(me.state, "{:?}", feature)?;
+                            me.print(end)
+                        })?;
+                    } else if c.name() == "metadata.code.branch_hint" {
+                        let reader = BranchHintSectionReader::new(c.data(), c.data_offset())?;
+                        self.print_iter(reader, |me, _end, func| {
+                            write!(me.state, "func {}", func.func)?;
+                            me.print(func.hints.range().start)?;
+                            me.print_iter(func.hints, |me, end, hint| {
+                                write!(me.state, "{:?}", hint)?;
+                                me.print(end)
+                            })
+                        })?;
+                    } else if c.name() == "linking" {
+                        let reader = LinkingSectionReader::new(c.data(), c.data_offset())?;
+                        let mut iter = reader.subsections();
+                        write!(self.state, "version: {}", reader.version())?;
+                        self.print(iter.original_position())?;
+                        while let Some(section) = iter.next() {
+                            self.print_linking_section(section?, iter.original_position())?;
+                        }
+                    } else if c.name().starts_with("reloc.") {
+                        let reader = RelocSectionReader::new(c.data(), c.data_offset())?;
+                        write!(self.state, "relocations for section: {}", reader.section_index())?;
+                        self.print(reader.entries().range().start)?;
+                        self.print_iter(reader.entries(), |me, end, entry| {
+                            write!(me.state, "{:?}", entry)?;
+                            me.print(end)
+                        })?;
                     } else {
                         self.print_byte_header()?;
                         for _ in 0..NBYTES {
@@ -438,6 +488,25 @@ impl<'a> Dump<'a> {
                         self.cur += c.data().len();
                     }
                 }
+                Payload::CustomSectionStart {
+                    name,
+                    data_offset,
+                    range,
+                    ..
+                } => {
+                    write!(self.state, "custom section")?;
+                    self.print(range.start)?;
+                    write!(self.state, "name: {:?}", name)?;
+                    self.print(data_offset)?;
+                }
+                Payload::CustomSectionChunk { data, .. } => {
+                    self.print_byte_header()?;
+                    for _ in 0..NBYTES {
+                        write!(self.dst, "---")?;
+                    }
+                    writeln!(self.dst, "-| ... {} bytes of data", data.len())?;
+                    self.cur += data.len();
+                }
                 Payload::UnknownSection {
                     id,
                     range,
@@ -500,6 +569,7 @@ impl<'a> Dump<'a> {
             Name::Global(n) => self.print_name_map("global", n)?,
             Name::Element(n) => self.print_name_map("element", n)?,
             Name::Data(n) => self.print_name_map("data", n)?,
+            Name::Tag(n) => self.print_name_map("tag", n)?,
             Name::Unknown { ty, range, .. } => {
                 write!(self.state, "unknown names: {}", ty)?;
                 self.print(range.start)?;
@@ -509,6 +579,37 @@ impl<'a> Dump<'a> {
         Ok(())
     }
 
+    fn print_linking_section(&mut self, linking: Linking<'_>, end: usize) -> Result<()> {
+        match linking {
+            Linking::SegmentInfo(s) => self.section(s, "segment info", |me, end, segment| {
+                write!(me.state, "{:?}", segment)?;
+                me.print(end)
+            })?,
+            Linking::InitFuncs(s) => self.section(s, "init funcs", |me, end, func| {
+                write!(me.state, "{:?}", func)?;
+                me.print(end)
+            })?,
+            Linking::ComdatInfo(s) => self.section(s, "comdat info", |me, _end, comdat| {
+                write!(me.state, "comdat {:?} flags: {}", comdat.name, comdat.flags)?;
+                me.print(comdat.symbols.range().start)?;
+                me.print_iter(comdat.symbols, |me, end, symbol| {
+                    write!(me.state, "{:?}", symbol)?;
+                    me.print(end)
+                })
+            })?,
+            Linking::SymbolTable(s) => self.section(s, "symbol table", |me, end, symbol| {
+                write!(me.state, "{:?}", symbol)?;
+                me.print(end)
+            })?,
+            Linking::Unknown { ty, range, .. } => {
+                write!(self.state, "unknown linking subsection: {}", ty)?;
+                self.print(range.start)?;
+                self.print(end)?;
+            }
+        }
+        Ok(())
+    }
+
     fn print_custom_component_name_section(
         &mut self,
         name: ComponentName<'_>,
@@ -525,6 +626,7 @@ impl<'a> Dump<'a> {
             ComponentName::CoreTables(n) => self.print_name_map("core table", n)?,
             ComponentName::CoreGlobals(n) => self.print_name_map("core global", n)?,
             ComponentName::CoreMemories(n) => self.print_name_map("core memory", n)?,
+            ComponentName::CoreTags(n) => self.print_name_map("core tag", n)?,
             ComponentName::CoreInstances(n) => self.print_name_map("core instance", n)?,
             ComponentName::CoreModules(n) => self.print_name_map("core module", n)?,
             ComponentName::CoreTypes(n) => self.print_name_map("core type", n)?,
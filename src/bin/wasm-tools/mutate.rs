@@ -1,6 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
-use wasm_mutate::ErrorKind;
+use sha2::{Digest, Sha256};
+use std::collections::{HashSet, VecDeque};
+use std::ffi::{OsStr, OsString};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+use wasm_mutate::{ErrorKind, WasmMutate};
 
 /// A WebAssembly test case mutator.
 ///
@@ -17,6 +23,12 @@ use wasm_mutate::ErrorKind;
 ///
 /// $ wasm-mutate ./input.wasm --seed 1234 -o output.wasm
 ///
+/// Repeatedly mutate a seed module, run `./engine.sh run` on every mutation,
+/// and save mutations which make it exit with a non-zero status into the
+/// `crashes` directory:
+///
+/// $ wasm-mutate ./input.wasm --fuzz ./engine.sh run --crashes crashes --corpus corpus --iterations 100000
+///
 /// ## Exit Codes
 ///
 /// * 0: Success
@@ -46,6 +58,44 @@ pub struct Opts {
 
     #[clap(flatten)]
     wasm_mutate: wasm_mutate::WasmMutate<'static>,
+
+    /// Run a fuzzing loop instead of producing a single mutation.
+    ///
+    /// The given program is invoked with any arguments following it and then
+    /// the path to each mutated Wasm module as its last argument. Modules for
+    /// which the command exits with a non-zero status are saved to the
+    /// `--crashes` directory while all other modules are added to the corpus
+    /// and mutated further.
+    #[clap(
+        long,
+        value_name = "COMMAND",
+        num_args = 1..,
+        requires_all = ["corpus", "crashes"]
+    )]
+    fuzz: Vec<OsString>,
+
+    /// Directory of the corpus used with `--fuzz`.
+    ///
+    /// Any `*.wasm` files already in this directory are used as additional
+    /// seeds, and new interesting-but-passing mutations are written to it.
+    #[clap(long, requires = "fuzz")]
+    corpus: Option<PathBuf>,
+
+    /// Directory to save modules which make the `--fuzz` command fail.
+    #[clap(long, requires = "fuzz")]
+    crashes: Option<PathBuf>,
+
+    /// Number of mutations to run with `--fuzz`.
+    #[clap(long, requires = "fuzz", default_value_t = 1000)]
+    iterations: u64,
+
+    /// Maximum number of entries kept in the corpus with `--fuzz`.
+    ///
+    /// Once the corpus is full the oldest entries are evicted, and the files
+    /// this run wrote for them are deleted from the corpus directory. The
+    /// input module itself is never evicted.
+    #[clap(long, requires = "fuzz", default_value_t = 1000)]
+    max_corpus_size: usize,
 }
 
 impl Opts {
@@ -59,6 +109,10 @@ impl Opts {
         // anyway.
         let input_wasm = Box::leak(input_wasm.into_boxed_slice());
 
+        if let Some((program, args)) = self.fuzz.split_first() {
+            return self.fuzz(program, args, input_wasm);
+        }
+
         let mut output_wasms =
             unwrap_wasm_mutate_result(self.wasm_mutate.run(input_wasm)).take(100);
         let wasm = loop {
@@ -85,6 +139,142 @@ impl Opts {
 
         Ok(())
     }
+
+    fn fuzz(&self, program: &OsStr, args: &[OsString], input_wasm: &[u8]) -> Result<()> {
+        // Both directories are required by clap whenever `--fuzz` is given.
+        let corpus_dir = self.corpus.as_deref().unwrap();
+        let crashes_dir = self.crashes.as_deref().unwrap();
+        for dir in [corpus_dir, crashes_dir] {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create directory: {}", dir.display()))?;
+        }
+
+        // The input module is always available to mutate, and everything else
+        // lives in `corpus` which is bounded by `--max-corpus-size`.
+        let mut corpus = VecDeque::new();
+        for entry in std::fs::read_dir(corpus_dir)
+            .with_context(|| format!("failed to read corpus: {}", corpus_dir.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("wasm") {
+                continue;
+            }
+            let wasm = std::fs::read(&path)
+                .with_context(|| format!("failed to read: {}", path.display()))?;
+            corpus.push_back(CorpusEntry {
+                digest: digest(&wasm),
+                wasm,
+                written: None,
+            });
+        }
+        let mut seen = corpus.iter().map(|e| e.digest.clone()).collect::<HashSet<_>>();
+        seen.insert(digest(input_wasm));
+        self.evict(&mut corpus, &mut seen)?;
+        log::info!("fuzzing with {} corpus entries", corpus.len() + 1);
+
+        let base_seed = self.wasm_mutate.get_seed();
+        let mut crashes = 0;
+        for iteration in 0..self.iterations {
+            let seed = base_seed.wrapping_add(iteration);
+            let index = (iteration % (corpus.len() as u64 + 1)) as usize;
+            let input = match index.checked_sub(1) {
+                Some(i) => &corpus[i].wasm,
+                None => input_wasm,
+            };
+
+            let mut mutate: WasmMutate<'_> = self.wasm_mutate.clone();
+            mutate.seed(seed);
+            let wasm = match mutate.run(input).and_then(|mut i| i.next().transpose()) {
+                Ok(Some(wasm)) => wasm,
+                Ok(None) => continue,
+                Err(e) => {
+                    log::debug!("seed {seed} failed to mutate: {e}");
+                    continue;
+                }
+            };
+
+            let digest = digest(&wasm);
+            if !seen.insert(digest.clone()) {
+                continue;
+            }
+
+            let tmp = NamedTempFile::new().context("failed to create a temporary file")?;
+            std::fs::write(tmp.path(), &wasm).with_context(|| {
+                format!("failed to write to temporary file: {}", tmp.path().display())
+            })?;
+            let status = std::process::Command::new(program)
+                .args(args)
+                .arg(tmp.path())
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .with_context(|| {
+                    format!("failed to run command '{}'", Path::new(program).display())
+                })?;
+
+            // Note that a child killed by a signal on unix does not exit
+            // successfully, so it's saved as a crash as well.
+            let (dir, name) = if status.success() {
+                (corpus_dir, format!("{digest}.wasm"))
+            } else {
+                crashes += 1;
+                (crashes_dir, format!("crash-{digest}.wasm"))
+            };
+            let path = dir.join(name);
+            std::fs::write(&path, &wasm)
+                .with_context(|| format!("failed to write: {}", path.display()))?;
+            if status.success() {
+                corpus.push_back(CorpusEntry {
+                    wasm,
+                    digest,
+                    written: Some(path),
+                });
+                self.evict(&mut corpus, &mut seen)?;
+            } else {
+                eprintln!("seed {seed}: command failed ({status}), saved {}", path.display());
+            }
+        }
+
+        eprintln!(
+            "ran {} iterations, found {crashes} crashes, corpus has {} entries",
+            self.iterations,
+            corpus.len() + 1
+        );
+        Ok(())
+    }
+
+    /// Drops the oldest entries of `corpus` until it, together with the input
+    /// module, fits within `--max-corpus-size`.
+    fn evict(&self, corpus: &mut VecDeque<CorpusEntry>, seen: &mut HashSet<String>) -> Result<()> {
+        let max = self.max_corpus_size.saturating_sub(1);
+        while corpus.len() > max {
+            let entry = corpus.pop_front().unwrap();
+            seen.remove(&entry.digest);
+            if let Some(path) = &entry.written {
+                std::fs::remove_file(path)
+                    .with_context(|| format!("failed to remove: {}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct CorpusEntry {
+    wasm: Vec<u8>,
+    digest: String,
+    /// The file written for this entry by this run, if any.
+    written: Option<PathBuf>,
+}
+
+/// Returns the hex-encoded sha256 digest of `wasm`, which names the files
+/// written by `--fuzz` consistently across runs and toolchains.
+fn digest(wasm: &[u8]) -> String {
+    let mut ret = String::new();
+    for byte in Sha256::digest(wasm) {
+        write!(ret, "{byte:02x}").unwrap();
+    }
+    ret
 }
 
 fn unwrap_wasm_mutate_result<T>(result: wasm_mutate::Result<T>) -> T {
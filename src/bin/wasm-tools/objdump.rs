@@ -42,7 +42,7 @@ impl Opts {
                 CodeSectionStart { range, count, .. } => {
                     printer.section_raw(range, count, "code")?
                 }
-                CodeSectionEntry(_) => {}
+                CodeSectionEntry(_) | SkippedCodeSectionChunk { .. } => {}
 
                 ModuleSection { range, .. } => {
                     printer.section_raw(range, 1, "module")?;
@@ -71,6 +71,13 @@ impl Opts {
                     &format!("custom {:?}", c.name()),
                 )?,
 
+                CustomSectionStart {
+                    name,
+                    data_offset,
+                    range,
+                    ..
+                } => printer.section_raw(data_offset..range.end, 1, &format!("custom {name:?}"))?,
+                CustomSectionChunk { .. } => {}
                 UnknownSection { .. } => {}
 
                 End(_) => printer.end()?,
@@ -1,5 +1,8 @@
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use wasm_encoder::{CustomSection, Encode, SectionId};
 
 /// Parse the WebAssembly text format.
 ///
@@ -13,15 +16,100 @@ pub struct Opts {
     /// Output the text format of WebAssembly instead of the binary format.
     #[clap(short = 't', long)]
     wat: bool,
+
+    /// Write a source map to this path which maps the code offsets of the
+    /// binary back to the input text.
+    ///
+    /// A `sourceMappingURL` custom section with the map's URL is appended to
+    /// the binary so that browsers can find the map. Only core modules in the
+    /// text format are supported.
+    #[clap(long, value_name = "PATH", conflicts_with = "wat")]
+    source_map: Option<PathBuf>,
+
+    /// The URL of the source map to record in the binary, which defaults to
+    /// the file name of `--source-map`.
+    #[clap(long, value_name = "URL", requires = "source_map")]
+    source_map_url: Option<String>,
 }
 
 impl Opts {
     pub fn run(&self) -> Result<()> {
-        let binary = self.io.parse_input_wasm()?;
+        let binary = match &self.source_map {
+            Some(path) => self.parse_with_source_map(path)?,
+            None => self.io.parse_input_wasm()?,
+        };
         self.io.output(wasm_tools::Output::Wasm {
             bytes: &binary,
             wat: self.wat,
         })?;
         Ok(())
     }
+
+    fn parse_with_source_map(&self, map_path: &Path) -> Result<Vec<u8>> {
+        self.io.init_logger();
+        let (bytes, source) = match self.io.input_path() {
+            Some(path) if path != Path::new("-") => {
+                let bytes = std::fs::read(path)
+                    .with_context(|| format!("failed to read `{}`", path.display()))?;
+                (bytes, path.to_path_buf())
+            }
+            _ => {
+                let mut bytes = Vec::new();
+                std::io::stdin()
+                    .read_to_end(&mut bytes)
+                    .context("failed to read <stdin>")?;
+                (bytes, PathBuf::from("<stdin>"))
+            }
+        };
+        if bytes.starts_with(b"\0asm") {
+            bail!("source maps can only be generated from the text format");
+        }
+        let text = String::from_utf8(bytes)
+            .with_context(|| format!("`{}` is not valid utf-8", source.display()))?;
+
+        let (mut wasm, offsets) = encode_with_offsets(&text).map_err(|mut e| {
+            e.set_path(&source);
+            e.set_text(&text);
+            e
+        })?;
+
+        let source_name = source
+            .file_name()
+            .map(|s| s.to_string_lossy())
+            .unwrap_or_default();
+        let map = offsets.source_map(&text, &source_name);
+        std::fs::write(map_path, map)
+            .with_context(|| format!("failed to write `{}`", map_path.display()))?;
+
+        let url = match &self.source_map_url {
+            Some(url) => url.clone(),
+            None => match map_path.file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => bail!("source map path `{}` has no file name", map_path.display()),
+            },
+        };
+        // The map's URL is appended in a custom section, which doesn't change
+        // the offsets of anything before it.
+        let mut data = Vec::new();
+        url.as_str().encode(&mut data);
+        wasm.push(SectionId::Custom as u8);
+        CustomSection {
+            name: "sourceMappingURL",
+            data: &data,
+        }
+        .encode(&mut wasm);
+        Ok(wasm)
+    }
+}
+
+fn encode_with_offsets(text: &str) -> Result<(Vec<u8>, wast::core::Offsets), wast::Error> {
+    let mut buf = wast::parser::ParseBuffer::new(text)?;
+    buf.track_instr_spans(true);
+    match wast::parser::parse::<wast::Wat>(&buf)? {
+        wast::Wat::Module(mut module) => module.encode_with_offsets(),
+        wast::Wat::Component(component) => Err(wast::Error::new(
+            component.span,
+            "source maps can only be generated for core modules".to_string(),
+        )),
+    }
 }
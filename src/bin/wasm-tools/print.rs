@@ -1,5 +1,6 @@
 use anyhow::Result;
 use clap::Parser;
+use std::io::Write;
 
 /// Print the textual form of a WebAssembly binary.
 #[derive(Parser)]
@@ -11,6 +12,40 @@ pub struct Opts {
     /// as comments for debugging.
     #[clap(short, long)]
     print_offsets: bool,
+
+    /// Whether or not to print function bodies as folded s-expressions, such
+    /// as `(i32.add (local.get 0) (i32.const 1))`.
+    #[clap(short, long)]
+    fold_instructions: bool,
+
+    /// Ignore the `name` section and refer to all items by index.
+    #[clap(long, conflicts_with = "synthesize_names")]
+    no_names: bool,
+
+    /// Give items without a name in the `name` section synthesized names such
+    /// as `$#func12`.
+    #[clap(long)]
+    synthesize_names: bool,
+
+    /// Print only the "skeleton" of the module, omitting function bodies and
+    /// annotating the size of each section.
+    #[clap(long)]
+    skeleton: bool,
+
+    /// Print only the function with this index.
+    #[clap(long, value_name = "INDEX", conflicts_with = "skeleton")]
+    function: Option<u32>,
+
+    /// Print custom sections which are otherwise omitted as `@custom`
+    /// annotations with a hexdump of their contents.
+    #[clap(long)]
+    hexdump_custom_sections: bool,
+
+    /// Print the custom section with this name as a `@custom` annotation with
+    /// a hexdump of its contents, even if it would otherwise be printed in a
+    /// structured form.
+    #[clap(long, value_name = "NAME")]
+    hexdump_section: Vec<String>,
 }
 
 impl Opts {
@@ -18,8 +53,23 @@ impl Opts {
         let wasm = self.io.parse_input_wasm()?;
         let mut printer = wasmprinter::Printer::new();
         printer.print_offsets(self.print_offsets);
-        let wat = printer.print(&wasm)?;
-        self.io.output(wasm_tools::Output::Wat(&wat))?;
+        printer.fold_instructions(self.fold_instructions);
+        if self.no_names {
+            printer.name_style(wasmprinter::NameStyle::Indices);
+        } else if self.synthesize_names {
+            printer.name_style(wasmprinter::NameStyle::Synthesized);
+        }
+        printer.skeleton(self.skeleton);
+        printer.hexdump_unknown_custom_sections(self.hexdump_custom_sections);
+        for section in &self.hexdump_section {
+            printer.hexdump_custom_section(section);
+        }
+        let mut output = self.io.output_writer()?;
+        match self.function {
+            Some(index) => output.write_all(printer.print_function(&wasm, index)?.as_bytes())?,
+            None => printer.print_to(&wasm, wasmprinter::PrintIoWrite(&mut output))?,
+        }
+        output.flush()?;
         Ok(())
     }
 }
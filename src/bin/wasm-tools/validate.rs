@@ -1,8 +1,16 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use rayon::prelude::*;
+use std::io::Read;
 use std::mem;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
-use wasmparser::{FuncValidatorAllocations, Parser, ValidPayload, Validator, WasmFeatures};
+use wasmparser::{
+    ElementItems, FromReader, FuncValidatorAllocations, Operator, Parser, Payload, SectionLimited,
+    ValidPayload, Validator, WasmFeatures,
+};
+use wast::parser::{self, ParseBuffer};
+use wast::token::Span;
+use wast::{Wast, WastDirective, WastExecute};
 
 /// Validate a WebAssembly binary
 ///
@@ -22,6 +30,10 @@ use wasmparser::{FuncValidatorAllocations, Parser, ValidPayload, Validator, Wasm
 ///
 /// # Validate `mvp.wasm` without any Wasm feature proposals enabled.
 /// $ wasm-tools validate --features=-all mvp.wasm
+///
+/// # Validate all modules in the `simd.wast` script, checking that those in
+/// # `assert_invalid` and `assert_malformed` directives are rejected.
+/// $ wasm-tools validate --features simd --wast simd.wast
 /// ```
 #[derive(clap::Parser)]
 pub struct Opts {
@@ -33,12 +45,34 @@ pub struct Opts {
     #[clap(long, short = 'f', value_parser = parse_features)]
     features: Option<WasmFeatures>,
 
+    /// Treat the input as a `*.wast` script and validate every module and
+    /// component within it.
+    ///
+    /// Modules in `module` directives, and others expected to be valid such
+    /// as in `assert_unlinkable`, must encode and validate successfully.
+    /// Modules in `assert_malformed` directives must instead fail to encode
+    /// from the text format or to decode from the binary format, and those in
+    /// `assert_invalid` directives must decode but fail to validate. The
+    /// directives within `thread`s are checked too. Each module that doesn't
+    /// meet its expectation is reported, and directives that require
+    /// executing wasm are skipped.
+    #[clap(long)]
+    wast: bool,
+
     #[clap(flatten)]
     io: wasm_tools::InputOutput,
 }
 
 impl Opts {
     pub fn run(&self) -> Result<()> {
+        if self.wast {
+            return self.run_wast();
+        }
+        let wasm = self.io.parse_input_wasm()?;
+        self.validate(&wasm)
+    }
+
+    fn validate(&self, wasm: &[u8]) -> Result<()> {
         // Note that here we're copying the contents of
         // `Validator::validate_all`, but the end is followed up with a parallel
         // iteration over the functions to validate instead of a synchronous
@@ -51,10 +85,9 @@ impl Opts {
         // validated later.
         let mut validator = Validator::new_with_features(self.features.unwrap_or_default());
         let mut functions_to_validate = Vec::new();
-        let wasm = self.io.parse_input_wasm()?;
 
         let start = Instant::now();
-        for payload in Parser::new(0).parse_all(&wasm) {
+        for payload in Parser::new(0).parse_all(wasm) {
             match validator.payload(&payload?)? {
                 ValidPayload::Ok | ValidPayload::Parser(_) | ValidPayload::End(_) => {}
                 ValidPayload::Func(validator, body) => {
@@ -82,6 +115,249 @@ impl Opts {
         log::info!("functions validated in {:?}", start.elapsed());
         Ok(())
     }
+
+    fn run_wast(&self) -> Result<()> {
+        self.io.init_logger();
+        let (path, contents) = match self.io.input_path() {
+            Some(path) if path != Path::new("-") => {
+                let contents = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read {path:?}"))?;
+                (path.to_path_buf(), contents)
+            }
+            _ => {
+                let mut contents = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut contents)
+                    .context("failed to read <stdin>")?;
+                (PathBuf::from("<stdin>"), contents)
+            }
+        };
+        let adjust = |mut e: wast::Error| {
+            e.set_path(&path);
+            e.set_text(&contents);
+            e
+        };
+        let buf = ParseBuffer::new(&contents).map_err(adjust)?;
+        let wast = parser::parse::<Wast>(&buf).map_err(adjust)?;
+
+        let mut summary = WastSummary::default();
+        let location = |span: Span| {
+            let (line, col) = span.linecol_in(&contents);
+            format!("{}:{}:{}", path.display(), line + 1, col + 1)
+        };
+        self.check_wast_directives(wast.directives, &adjust, &location, &mut summary);
+
+        let WastSummary {
+            checked,
+            malformed,
+            invalid,
+            mismatches,
+        } = summary;
+        let summary = format!(
+            "{malformed} were malformed, {invalid} failed to validate and {} were valid",
+            checked - malformed - invalid
+        );
+        if mismatches > 0 {
+            bail!(
+                "{mismatches} of {checked} modules in {} did not meet their expectations \
+                 ({summary})",
+                path.display()
+            );
+        }
+        log::info!("{checked} modules met their expectations ({summary})");
+        Ok(())
+    }
+
+    /// Checks the modules of each of `directives` against what the directive
+    /// expects of them, recursing into the directives of threads.
+    fn check_wast_directives(
+        &self,
+        directives: Vec<WastDirective<'_>>,
+        adjust: &dyn Fn(wast::Error) -> wast::Error,
+        location: &dyn Fn(Span) -> String,
+        summary: &mut WastSummary,
+    ) {
+        for directive in directives {
+            let span = directive.span();
+            let (expected, mut module) = match directive {
+                WastDirective::Wat(module)
+                | WastDirective::AssertUnlinkable { module, .. }
+                | WastDirective::AssertTrap {
+                    exec: WastExecute::Wat(module),
+                    ..
+                }
+                | WastDirective::AssertException {
+                    exec: WastExecute::Wat(module),
+                    ..
+                } => (Outcome::Valid, module),
+                WastDirective::AssertMalformed { module, .. } => (Outcome::Malformed, module),
+                WastDirective::AssertInvalid { module, .. } => (Outcome::Invalid, module),
+                WastDirective::Thread(thread) => {
+                    self.check_wast_directives(thread.directives, adjust, location, summary);
+                    continue;
+                }
+                _ => continue,
+            };
+            summary.checked += 1;
+
+            // Modules which fail to encode from the text format, or whose
+            // binary fails to decode, are malformed, while those which decode
+            // but fail validation are invalid.
+            let (outcome, error) = match module.encode() {
+                Err(e) => (Outcome::Malformed, Some(adjust(e).into())),
+                Ok(wasm) => match decode(&wasm) {
+                    Err(e) => (Outcome::Malformed, Some(e)),
+                    Ok(()) => match self.validate(&wasm) {
+                        Err(e) => (Outcome::Invalid, Some(e)),
+                        Ok(()) => (Outcome::Valid, None),
+                    },
+                },
+            };
+            match outcome {
+                Outcome::Malformed => summary.malformed += 1,
+                Outcome::Invalid => summary.invalid += 1,
+                Outcome::Valid => {}
+            }
+            if outcome == expected {
+                continue;
+            }
+
+            summary.mismatches += 1;
+            let location = location(span);
+            match error {
+                Some(e) => eprintln!(
+                    "error: {location}: expected {} module, but it was {}: {e:#}\n",
+                    expected.describe(),
+                    outcome.describe(),
+                ),
+                None => eprintln!(
+                    "error: {location}: expected {} module, but it validated\n",
+                    expected.describe(),
+                ),
+            }
+        }
+    }
+}
+
+/// Counts of the modules checked by `validate --wast`.
+#[derive(Default)]
+struct WastSummary {
+    checked: u32,
+    malformed: u32,
+    invalid: u32,
+    mismatches: u32,
+}
+
+/// What happened, or is expected to happen, when a module in a `*.wast`
+/// script is encoded and validated.
+#[derive(Clone, Copy, PartialEq)]
+enum Outcome {
+    Valid,
+    Malformed,
+    Invalid,
+}
+
+impl Outcome {
+    fn describe(&self) -> &'static str {
+        match self {
+            Outcome::Valid => "a valid",
+            Outcome::Malformed => "a malformed",
+            Outcome::Invalid => "an invalid",
+        }
+    }
+}
+
+/// Decodes all of `wasm` without validating it, so that malformed binaries
+/// can be told apart from invalid ones.
+///
+/// This includes the checks of the binary format which wasmparser only does
+/// while validating, such as that the function and code sections have the
+/// same number of entries.
+fn decode(wasm: &[u8]) -> Result<()> {
+    fn items<'a, T: FromReader<'a>>(reader: SectionLimited<'a, T>) -> Result<Vec<T>> {
+        Ok(reader.into_iter().collect::<wasmparser::Result<_>>()?)
+    }
+
+    let mut functions = 0;
+    let mut bodies = 0;
+    let mut data_count = None;
+    let mut data = 0;
+    let mut uses_data_count = false;
+    for payload in Parser::new(0).parse_all(wasm) {
+        match payload? {
+            Payload::Version { .. } => {
+                functions = 0;
+                bodies = 0;
+                data_count = None;
+                data = 0;
+                uses_data_count = false;
+            }
+            Payload::TypeSection(s) => drop(items(s)?),
+            Payload::ImportSection(s) => drop(items(s)?),
+            Payload::FunctionSection(s) => functions = items(s)?.len(),
+            Payload::TableSection(s) => drop(items(s)?),
+            Payload::MemorySection(s) => drop(items(s)?),
+            Payload::TagSection(s) => drop(items(s)?),
+            Payload::GlobalSection(s) => drop(items(s)?),
+            Payload::ExportSection(s) => drop(items(s)?),
+            Payload::DataCountSection { count, .. } => data_count = Some(count),
+            Payload::DataSection(s) => data = items(s)?.len(),
+            Payload::ElementSection(s) => {
+                for element in items(s)? {
+                    match element.items {
+                        ElementItems::Functions(r) => drop(items(r)?),
+                        ElementItems::Expressions(r) => drop(items(r)?),
+                    }
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                bodies += 1;
+                let mut locals = 0u32;
+                for local in body.get_locals_reader()? {
+                    let (count, _) = local?;
+                    locals = match locals.checked_add(count) {
+                        Some(n) => n,
+                        None => bail!("too many locals"),
+                    };
+                }
+                for op in body.get_operators_reader()? {
+                    match op? {
+                        Operator::BrTable { targets } => {
+                            for target in targets.targets() {
+                                target?;
+                            }
+                        }
+                        Operator::MemoryInit { .. } | Operator::DataDrop { .. } => {
+                            uses_data_count = true;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Payload::InstanceSection(s) => drop(items(s)?),
+            Payload::CoreTypeSection(s) => drop(items(s)?),
+            Payload::ComponentInstanceSection(s) => drop(items(s)?),
+            Payload::ComponentAliasSection(s) => drop(items(s)?),
+            Payload::ComponentTypeSection(s) => drop(items(s)?),
+            Payload::ComponentCanonicalSection(s) => drop(items(s)?),
+            Payload::ComponentImportSection(s) => drop(items(s)?),
+            Payload::ComponentExportSection(s) => drop(items(s)?),
+            Payload::UnknownSection { id, .. } => bail!("malformed section id: {id}"),
+            Payload::End(_) => {
+                if functions != bodies {
+                    bail!("function and code section have inconsistent lengths");
+                }
+                if matches!(data_count, Some(count) if count as usize != data) {
+                    bail!("data count and data section have inconsistent lengths");
+                }
+                if uses_data_count && data_count.is_none() {
+                    bail!("data count section required");
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
 }
 
 fn parse_features(arg: &str) -> Result<WasmFeatures> {
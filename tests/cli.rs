@@ -22,6 +22,12 @@
 //! where a `|` will execute the first subcommand and pipe its stdout into the
 //! stdin of the next command.
 //!
+//! An argument of `%tmpdir` is replaced with the path to a fresh temporary
+//! directory which is removed once the test finishes.
+//!
+//! An argument of `%wasm-tools` is replaced with the path to the `wasm-tools`
+//! executable itself, for subcommands which run another program.
+//!
 //! Use `BLESS=1` in the environment to auto-update expectation files. Be sure
 //! to look at the diff!
 
@@ -87,6 +93,7 @@ fn run_test(test: &Path, bless: bool) -> Result<()> {
         .next()
         .ok_or_else(|| anyhow!("no line found with `;; RUN: ` directive"))?;
 
+    let tmpdir = tempfile::TempDir::new()?;
     let mut cmd = wasm_tools_exe();
     let mut stdin = None;
     for arg in line.split_whitespace() {
@@ -96,6 +103,10 @@ fn run_test(test: &Path, bless: bool) -> Result<()> {
             cmd = wasm_tools_exe();
         } else if arg == "%" {
             cmd.arg(test);
+        } else if arg == "%wasm-tools" {
+            cmd.arg(env!("CARGO_BIN_EXE_wasm-tools"));
+        } else if let Some(rest) = arg.strip_prefix("%tmpdir") {
+            cmd.arg(format!("{}{rest}", tmpdir.path().display()));
         } else {
             cmd.arg(arg);
         }
@@ -0,0 +1,12 @@
+;; RUN: dump %
+
+(module
+  (func (param i32)
+    local.get 0
+    (@metadata.code.branch_hint "\00")
+    if
+    end
+    local.get 0
+    (@metadata.code.branch_hint "\01")
+    br_if 0)
+)
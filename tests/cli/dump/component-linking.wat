@@ -12,9 +12,10 @@
   (component $c
     (import "a" (core module))
     (import "b" (func))
-    (import "c" (value string))
+    (import "c" (value $v string))
     (import "d" (instance))
     (import "e" (component))
+    (export "c" (value $v))
   )
 
   (instance (instantiate $c
@@ -0,0 +1,14 @@
+;; RUN: dump %
+
+(module
+  (func call 0)
+  (memory 1)
+  (data "x")
+  (@custom "linking"
+    "\02"
+    "\05\05\01\01d\02\01"
+    "\06\03\01\01\00"
+    "\07\07\01\01c\00\01\01\00"
+    "\08\0d\02\00\00\00\01f\01\00\01d\00\00\01")
+  (@custom "reloc.CODE" "\03\01\00\04\00")
+)
@@ -0,0 +1,8 @@
+;; RUN: dump %
+
+(module
+  (@producers
+    (language "wat" "1.0")
+    (processed-by "wasm-tools" "1.0"))
+  (@custom "target_features" "\02+\0bbulk-memory-\07atomics")
+)
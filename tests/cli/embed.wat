@@ -0,0 +1,10 @@
+;; RUN: component embed tests/cli/embed.wit % | component new | component wit
+
+(module
+  (import "$root" "log" (func (param i32 i32)))
+  (memory (export "memory") 1)
+  (func (export "run") (param i32) (result i32)
+    local.get 0)
+  (func (export "cabi_realloc") (param i32 i32 i32 i32) (result i32)
+    unreachable)
+)
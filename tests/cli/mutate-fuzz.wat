@@ -0,0 +1,10 @@
+;; RUN: mutate % --seed 0 --fuzz %wasm-tools validate --corpus %tmpdir/corpus --crashes %tmpdir/crashes --iterations 20 --max-corpus-size 4
+
+(module
+  (func (export "f") (param i32) (result i32)
+    local.get 0
+    i32.const 1
+    i32.add)
+  (func (export "g") (result i64)
+    i64.const 42)
+)
@@ -0,0 +1,6 @@
+;; RUN: parse % --source-map /dev/null --source-map-url foo.wasm.map | objdump
+
+(module
+  (func (param i32) (result i32)
+    local.get 0)
+)
@@ -0,0 +1,30 @@
+;; RUN: print --fold-instructions %
+
+(module
+  (func $add (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add
+  )
+  (func (param i32) (result i32)
+    local.get 0
+    if (result i32)
+      local.get 0
+      i32.const 1
+      call $add
+    else
+      block
+        i32.const 0
+        i32.const 2
+        i32.store
+        br 0
+      end
+      i32.const 0
+    end
+    loop
+      local.get 0
+      i32.eqz
+      br_if 0
+    end
+  )
+)
@@ -0,0 +1,14 @@
+;; RUN: print --function 1 %
+
+(module
+  (import "m" "f" (func $imported (param i32)))
+  (func $add (param i32 i32) (result i32)
+    local.get 0
+    local.get 1
+    i32.add
+  )
+  (func (export "run")
+    i32.const 1
+    call $imported
+  )
+)
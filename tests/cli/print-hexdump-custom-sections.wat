@@ -0,0 +1,11 @@
+;; RUN: print --hexdump-custom-sections --hexdump-section producers %
+
+(module
+  (@custom "first" (before first) "a")
+  (type (func))
+  (@custom "after-type" (after type) "0123456789abcdef0123456789abcdef\00\01\ff\"\\")
+  (func)
+  (@custom "after-code" (after code) "")
+  (data "x")
+  (@producers (language "C" "11"))
+)
@@ -0,0 +1,20 @@
+;; RUN: print --synthesize-names %
+
+(module
+  (import "m" "f" (func $imported (param i32)))
+  (type (func))
+  (func $named (param $x i32) (param i32) (local f32)
+    block
+      local.get 1
+      call $imported
+    end
+    call 2
+  )
+  (func)
+  (table 1 funcref)
+  (memory 1)
+  (global i32 (i32.const 0))
+  (tag)
+  (elem (i32.const 0) func $named)
+  (data (i32.const 0) "x")
+)
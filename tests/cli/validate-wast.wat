@@ -0,0 +1,28 @@
+;; RUN: validate --wast %
+
+(module
+  (func (export "f") (result i32) i32.const 0))
+(assert_return (invoke "f") (i32.const 0))
+
+(assert_invalid
+  (module (func (result i32)))
+  "type mismatch")
+(assert_malformed
+  (module quote "(func")
+  "unexpected end of input")
+(assert_unlinkable
+  (module (import "missing" "f" (func)))
+  "unknown import")
+(assert_malformed
+  (module binary
+    "\00asm" "\01\00\00\00"
+    "\01\04\01\60\00\00"
+    "\03\02\01\00")
+  "function and code section have inconsistent lengths")
+
+(thread $T
+  (module (func (export "g")))
+  (assert_invalid
+    (module (func (result i64)))
+    "type mismatch"))
+(wait $T)
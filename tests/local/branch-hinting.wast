@@ -0,0 +1,41 @@
+(module
+  (import "" "" (func))
+  (func $f (param i32) (result i32)
+    local.get 0
+    (@metadata.code.branch_hint "\00")
+    if (result i32)
+      i32.const 1
+    else
+      block $b
+        local.get 0
+        (@metadata.code.branch_hint "\01")
+        br_if $b
+      end
+      i32.const 0
+    end)
+  (func (param i32)
+    (@metadata.code.branch_hint "\01")
+    (if (local.get 0)
+      (then
+        (@metadata.code.branch_hint "\00")
+        (br_if 0 (local.get 0))))))
+
+(assert_malformed
+  (module quote
+    "(func (param i32)"
+    "  (@metadata.code.branch_hint \"\\02\")"
+    "  (if (local.get 0) (then)))")
+  "invalid value for branch hint")
+
+(assert_malformed
+  (module quote
+    "(func"
+    "  (@metadata.code.branch_hint \"\\01\")"
+    "  nop)")
+  "branch hints are only allowed on `if` and `br_if`")
+
+(assert_malformed
+  (module quote
+    "(func"
+    "  (@metadata.code.branch_hint \"\\01\"))")
+  "branch hint must be followed by an instruction")
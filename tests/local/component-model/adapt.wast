@@ -285,3 +285,25 @@
     (func (export "foo") (canon lift (core func $f)))
   )
   "unknown core func: failed to find name `$f`")
+
+(assert_invalid
+  (component
+    (import "a" (func $f (param "x" string)))
+    (core func (canon lower (func $f) (memory $m)))
+  )
+  "unknown core memory: failed to find name `$m`")
+
+(assert_invalid
+  (component
+    (import "a" (func $f (param "x" string)))
+    (core func (canon lower (func $f) (realloc $r)))
+  )
+  "unknown core func: failed to find name `$r`")
+
+(assert_invalid
+  (component
+    (core module $m (func (export "foo")))
+    (core instance $i (instantiate $m))
+    (func (export "foo") (canon lift (core func $i "foo") (post-return $p)))
+  )
+  "unknown core func: failed to find name `$p`")
@@ -287,3 +287,81 @@
   ;; inline alias injection sugar works for component references
   (instance (instantiate (component $i "x")))
 )
+
+(component
+  (import "a" (core module $libc
+    (export "memory" (memory 1))
+    (export "table" (table 0 funcref))
+    (export "func" (func))
+    (export "global" (global i32))
+    (export "tag" (tag))
+  ))
+  (core instance $libc (instantiate $libc))
+  (core memory $mem (alias core export $libc "memory"))
+  (core table $tbl (alias core export $libc "table"))
+  (core func $func (alias core export $libc "func"))
+  (core global $global (alias core export $libc "global"))
+  (core tag $tag (alias core export $libc "tag"))
+  (alias core export $libc "tag" (core tag $tag2))
+
+  (import "x" (core module $needs_libc
+    (import "" "memory" (memory 1))
+    (import "" "table" (table 0 funcref))
+    (import "" "func" (func))
+    (import "" "global" (global i32))
+    (import "" "tag" (tag))
+    (import "" "tag2" (tag))
+  ))
+
+  (core instance (instantiate $needs_libc (with "" (instance
+    (export "memory" (memory $mem))
+    (export "table" (table $tbl))
+    (export "func" (func $func))
+    (export "global" (global $global))
+    (export "tag" (tag $tag))
+    (export "tag2" (tag $tag2))
+  ))))
+)
+
+;; explicit export aliases of every sort
+(component
+  (import "a" (instance $i
+    (type $u u8)
+    (export "m" (core module))
+    (export "f" (func))
+    (export "v" (value s32))
+    (export "t" (type (eq $u)))
+    (export "c" (component))
+    (export "i" (instance))
+  ))
+  (alias export $i "m" (core module $m))
+  (alias export $i "f" (func $f))
+  (alias export $i "v" (value $v))
+  (alias export $i "t" (type $t))
+  (alias export $i "c" (component $c))
+  (alias export $i "i" (instance $i2))
+  (export "m" (core module $m))
+  (export "f" (func $f))
+  (export "v" (value $v))
+  (export "c" (component $c))
+  (export "i" (instance $i2))
+)
+
+;; outer aliases through several levels of nesting
+(component $A
+  (core module $m)
+  (component $c)
+  (type $t u8)
+  (component $B
+    (type $t2 string)
+    (component
+      (alias outer $A $m (core module))
+      (alias outer $A $c (component))
+      (alias outer $A $t (type $t))
+      (alias outer $B $t2 (type))
+      (import "a" (func (param "x" $t)))
+      ;; names in enclosing components are aliased implicitly
+      (import "b" (func (param "x" $t2)))
+    )
+  )
+)
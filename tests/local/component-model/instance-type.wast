@@ -231,3 +231,28 @@
       ))
     )))
   "type index 0 is not a function type")
+
+;; identifiers of exports may be bound within the item's signature
+(component $C
+  (type $r (record (field "x" u32)))
+  (import "a" (instance $a
+    (type $u u32)
+    (export "u" (type $u' (eq $u)))
+    (export "f" (func (param "u" $u')))
+    (export "r" (type $r' (eq $r)))
+    (export "i" (instance
+      (export "t" (type $t (eq $r')))
+      (export "g" (func (param "t" $t) (result (list $t))))
+    ))
+  ))
+  (import "b" (component
+    (import "i" (instance $i (export "f" (func (param "r" $r)))))
+    (export "r" (type $r2 (eq $r)))
+    (export "e" (func (result $r2)))
+  ))
+)
+
+(assert_malformed
+  (component quote
+    "(type (instance (export $a \"a\" (func $b))))")
+  "export has more than one identifier")
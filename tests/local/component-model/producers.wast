@@ -0,0 +1,7 @@
+(component
+  (core module
+    (@producers (processed-by "clang" "16.0.0"))
+    (@target_features (used "bulk-memory")))
+  (@producers (language "Rust" "1.70"))
+  (@producers (processed-by "rustc" "1.70") (language "Rust" "1.71"))
+)
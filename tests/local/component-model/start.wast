@@ -10,7 +10,8 @@
   (component
     (import "a" (func $f (param "p" string)))
     (import "b" (value $v string))
-    (start $f (value $v) (value $v))
+    (import "c" (value $v2 string))
+    (start $f (value $v) (value $v2))
   )
   "start function requires 1 arguments")
 
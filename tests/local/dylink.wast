@@ -0,0 +1,22 @@
+(module
+  (@dylink.0
+    (mem-info (memory 16 2) (table 1 0))
+    (needed "libc.so" "libm.so")
+    (export-info "foo" binding-weak visibility-hidden)
+    (export-info "bar" tls 0x10000)
+    (import-info "env" "baz" undefined)
+    (import-info "GOT.mem" "qux" binding-local))
+  (func)
+)
+
+(module
+  (@dylink.0
+    (mem-info (table 2 1))
+    (needed)))
+
+(assert_malformed
+  (module quote "(@dylink.0 (export-info \"foo\" weak))")
+  "expected a symbol flag")
+(assert_malformed
+  (module quote "(@dylink.0 (needed-libs))")
+  "expected one of")
@@ -61,3 +61,34 @@
   (module
     (func block try catch_all rethrow 1 end end))
   "target was not a `catch` block")
+
+;; Labels and tags named on `try`, `catch`, `rethrow` and `delegate` in both
+;; the flat and folded forms.
+(module
+  (tag $e (param i32))
+  (func (result i32)
+    try $outer (result i32)
+      try $inner (result i32)
+        i32.const 1
+        throw $e
+      catch $e
+      catch_all
+        rethrow $inner
+      end $inner
+    catch $e
+      try $d
+        rethrow $outer
+      delegate $outer
+    catch_all
+      i32.const 0
+    end $outer)
+  (func
+    (try $t
+      (do (throw $e (i32.const 1)))
+      (catch $e drop (rethrow $t))
+      (catch_all (rethrow $t))))
+  (func
+    (try $t
+      (do (try (do nop) (delegate $t)))
+      (delegate 0)))
+)
@@ -0,0 +1,37 @@
+(module
+  (import "" "g" (global $g i32))
+  (import "" "h" (global $h i64))
+  (memory 1)
+  (table 10 funcref)
+  (func $f)
+
+  (global i32 (i32.add (global.get $g) (i32.const 1)))
+  (global i64 (i64.mul (i64.sub (global.get $h) (i64.const 2)) (i64.const 3)))
+  (global i32 global.get $g i32.const 4 i32.mul)
+
+  (data (i32.add (global.get $g) (i32.const 8)) "abc")
+  (data (offset (i32.sub (global.get $g) (i32.const 8))) "abc")
+  (data (i32.const 1 i32.const 2 i32.add) "abc")
+
+  (elem (i32.add (global.get $g) (i32.const 1)) $f)
+  (elem (offset (i32.mul (global.get $g) (i32.const 2))) func $f)
+  (elem (table 0) (offset global.get $g i32.const 3 i32.add) func $f)
+  (elem (i32.const 1 i32.const 2 i32.sub) func $f)
+)
+
+(module
+  (import "" "base" (global $base i64))
+  (memory i64 1)
+  (data (i64.add (global.get $base) (i64.mul (i64.const 2) (i64.const 8))) "x")
+)
+
+(assert_invalid
+  (module
+    (global i32 (i32.add (i32.const 1) (i64.const 2))))
+  "type mismatch")
+
+(assert_invalid
+  (module
+    (memory 1)
+    (data (i32.div_u (i32.const 4) (i32.const 2)) "x"))
+  "constant expression required")
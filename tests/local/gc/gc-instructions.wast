@@ -0,0 +1,72 @@
+(module
+  (type $s (struct (field (mut i32)) (field i8)))
+  (type $a (array (mut i32)))
+  (type $b (array (mut i8)))
+  (type $f (array (mut funcref)))
+  (data $d "abcd")
+  (elem $e func)
+
+  (func (param (ref $s)) (result i32)
+    i32.const 1
+    i32.const 2
+    struct.new $s
+    drop
+    struct.new_default $s
+    drop
+    local.get 0
+    i32.const 3
+    struct.set $s 0
+    local.get 0
+    struct.get_s $s 1
+    drop
+    local.get 0
+    struct.get_u $s 1
+    drop
+    local.get 0
+    struct.get $s 0)
+
+  (func (param (ref $a) (ref $b))
+    (array.new $a (i32.const 0) (i32.const 1))
+    (array.new_default $a (i32.const 1))
+    (array.new_fixed $a 2 (i32.const 1) (i32.const 2))
+    (array.new_data $b $d (i32.const 0) (i32.const 4))
+    drop
+    drop
+    drop
+    drop
+    (array.set $a (local.get 0) (i32.const 0) (array.get $a (local.get 0) (i32.const 0)))
+    (drop (array.get_s $b (local.get 1) (i32.const 0)))
+    (drop (array.get_u $b (local.get 1) (i32.const 0)))
+    (drop (array.len (local.get 0)))
+    (array.fill $a (local.get 0) (i32.const 0) (i32.const 7) (i32.const 1))
+    (array.copy $a $a (local.get 0) (i32.const 0) (local.get 0) (i32.const 0) (i32.const 1))
+    (array.init_data $b $d (local.get 1) (i32.const 0) (i32.const 0) (i32.const 4))
+    (array.init_elem $f $e
+      (array.new_elem $f $e (i32.const 0) (i32.const 0))
+      (i32.const 0) (i32.const 0) (i32.const 0)))
+
+  (func (param anyref) (result i32)
+    (i31.get_s (ref.i31 (i32.const 1)))
+    (i31.get_u (ref.i31 (i32.const 1)))
+    drop
+    drop
+    (drop (ref.test (ref $s) (local.get 0)))
+    (drop (ref.test (ref null $s) (local.get 0)))
+    (drop (ref.test i31ref (local.get 0)))
+    (drop (ref.cast (ref $s) (local.get 0)))
+    (drop (ref.cast (ref null any) (local.get 0)))
+    (drop (ref.cast structref (local.get 0)))
+    (drop (extern.convert_any (local.get 0)))
+    (drop (any.convert_extern (ref.null extern)))
+    (drop (extern.externalize (local.get 0)))
+    (drop (extern.internalize (ref.null extern)))
+    (drop (i31.new (i32.const 0)))
+    i32.const 0)
+
+  (func (param anyref) (result anyref)
+    (block $l (result anyref)
+      (br_on_cast $l anyref (ref $s) (local.get 0))
+      (br_on_cast_fail $l anyref (ref null i31) (local.get 0))
+      drop
+      ref.null any))
+)
@@ -0,0 +1,39 @@
+(module
+  (type $a (sub (func)))
+  (type $b (sub final $a (func)))
+  (type $c (sub $a (func)))
+  (type (sub final (struct)))
+  (type (sub (array i8)))
+  (rec
+    (type $r1 (sub (struct (field (ref null $r2)))))
+    (type $r2 (sub $r1 (struct (field (ref null $r2)) (field i32))))
+  )
+  (rec
+    (type $d (sub $a (func (param (ref $d)))))
+  )
+
+  ;; The older form of subtyping is still accepted.
+  (sub $a (type (func)))
+
+  ;; Non-final types aren't used for inline type annotations.
+  (func (type $a))
+  (func)
+)
+
+(module
+  (func
+    (param nullref nullfuncref nullexternref)
+    (param (ref none) (ref nofunc) (ref noextern))
+    (param (ref null none) (ref null nofunc) (ref null noextern)))
+  (func
+    ref.null none
+    drop
+    ref.null nofunc
+    drop
+    ref.null noextern
+    drop)
+)
+
+(assert_malformed
+  (module quote "(type (sub $a $b (func)))")
+  "expected `(`")
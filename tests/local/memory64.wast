@@ -130,3 +130,16 @@
 (assert_invalid
   (module (memory 1) (data (i64.const 0) ".."))
   "type mismatch")
+
+;; limits, offsets, and data segment offsets beyond the range of a u32
+(module
+  (memory i64 0x1_0000_0000 0x1_0000_0001)
+  (func
+    i64.const 0 i32.load offset=0xffff_ffff_ffff_ffff drop
+    i64.const 0 i64.const 0 i64.store offset=0x1_0000_0000)
+  (data (i64.const 0x1_0000_0000) "..")
+)
+(module
+  (memory i64 (data "abc"))
+  (func (result i64) memory.size)
+)
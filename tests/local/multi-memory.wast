@@ -224,3 +224,28 @@
   (data (memory $m) (i32.const 0) "...")
 )
 
+
+;; abbreviations for defining memories alongside other memories, and memory
+;; indices given by number as well as by name
+(module
+  (import "" "m" (memory $i 1))
+  (memory $a (export "a") 1)
+  (memory $b (data "hello" "world"))
+  (memory $c i64 (data "x"))
+  (data (memory $b) (i32.const 1) "b")
+  (data (memory 3) (i64.const 1) "c")
+  (data $seg "")
+  (func
+    i32.const 0 i32.load $b offset=4 align=2 drop
+    i64.const 0 i32.load 3 offset=0x1_0000_0000 drop
+    i32.const 0 v128.const i64x2 0 0 v128.load8_lane $b offset=1 0 drop
+    i32.const 0 v128.const i64x2 0 0 v128.store8_lane 2 offset=1 align=1 15
+    i32.const 0 i32.const 0 i32.const 0 memory.init $b $seg
+    i32.const 0 i32.const 0 i32.const 0 memory.init 2 $seg
+    i32.const 0 i32.const 0 i32.const 0 memory.copy $b $i
+    i32.const 0 i32.const 0 i32.const 0 memory.copy 1 0
+    i32.const 0 i32.const 0 i32.const 0 memory.fill 2
+    memory.size 3 drop
+    i64.const 1 memory.grow $c drop
+  )
+)
@@ -0,0 +1,32 @@
+(module
+  (@producers
+    (language "wat" "1.0")
+    (processed-by "wasm-tools" "1.2.3")
+    (language "C" "")
+    (sdk "an sdk \"quoted\"" "4"))
+  (@target_features
+    (used "simd128")
+    (disallowed "atomics")
+    (required "mutable-globals"))
+  (func)
+)
+
+(module
+  (@producers)
+  (@target_features)
+)
+
+(module
+  (@producers
+    (language "wat" "1.0")
+    (language "wat" "1.1"))
+  (func)
+  (@producers
+    (processed-by "wasm-tools" "1.2.3")
+    (language "C" "11"))
+  (@producers (processed-by "wasm-tools" "2.0.0"))
+)
+
+(assert_malformed
+  (module quote "(@target_features (sometimes \"simd128\"))")
+  "expected one of")
@@ -0,0 +1,18 @@
+(module
+  (type $t (func (param i32) (result i32)))
+  (table $a 1 funcref)
+  (table $b 1 funcref)
+  (func $f (type $t)
+    local.get 0
+    return_call $f)
+  (func (type $t)
+    local.get 0
+    i32.const 0
+    return_call_indirect $b (type $t))
+  (func (param i32) (result i32)
+    (return_call_indirect $a (param i32) (result i32)
+      (local.get 0) (i32.const 0)))
+  (func (param i32) (result i32)
+    (return_call_ref $t (local.get 0) (ref.func $f)))
+  (elem declare func $f)
+)
@@ -7,3 +7,41 @@
     )
   )
   "must always specify maximum alignment")
+
+(module $Mem
+  (memory (export "shared") 1 1 shared)
+)
+
+(thread $T1 (shared (module $Mem))
+  (register "mem" $Mem)
+  (module
+    (memory (import "mem" "shared") 1 10 shared)
+    (func (export "run")
+      (i32.atomic.store (i32.const 0) (i32.const 1))
+      (drop (memory.atomic.notify (i32.const 0) (i32.const 1)))
+    )
+  )
+  (invoke "run")
+)
+
+(thread $T2 (shared (module $Mem))
+  (register "mem" $Mem)
+  (module
+    (memory (import "mem" "shared") 1 1 shared)
+    (func (export "run") (result i32)
+      (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const -1))
+    )
+  )
+  (assert_return (invoke "run") (either (i32.const 0) (i32.const 1)))
+)
+
+(wait $T1)
+(wait $T2)
+
+(thread $T3
+  (module
+    (func (export "run"))
+  )
+  (invoke "run")
+)
+(wait $T3)